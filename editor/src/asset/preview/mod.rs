@@ -20,7 +20,10 @@ use crate::{
             widget::WidgetBuilder, HorizontalAlignment, UserInterface, VerticalAlignment,
         },
         material::{shader::Shader, Material, MaterialResource, PropertyValue},
-        renderer::framework::gpu_texture::{GpuTextureKind, PixelKind},
+        renderer::{
+            framework::gpu_texture::{GpuTextureKind, PixelKind},
+            QualitySettings,
+        },
         resource::{
             curve::CurveResourceState,
             model::{Model, ModelResourceExtension},
@@ -276,11 +279,48 @@ impl AssetPreviewGenerator for SoundPreview {
     }
 }
 
+/// Pixels of an image rendered by the renderer.
+pub struct RenderedPixels {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Pixels of the image in RGBA8 format. Rows are stored from bottom to top, as OpenGL does.
+    pub pixels: Vec<u8>,
+}
+
+impl RenderedPixels {
+    fn into_preview_texture(self) -> Option<AssetPreviewTexture> {
+        TextureResource::from_bytes(
+            TextureKind::Rectangle {
+                width: self.width,
+                height: self.height,
+            },
+            TexturePixelKind::RGBA8,
+            self.pixels,
+            ResourceKind::Embedded,
+        )
+        .map(|texture| AssetPreviewTexture {
+            texture,
+            // OpenGL was designed by mathematicians.
+            flip_y: true,
+        })
+    }
+}
+
 fn render_scene_to_texture(
     engine: &mut Engine,
     scene: &mut Scene,
     rt_size: Vector2<f32>,
 ) -> Option<AssetPreviewTexture> {
+    render_scene_to_pixels(engine, scene, rt_size).and_then(RenderedPixels::into_preview_texture)
+}
+
+fn render_scene_to_pixels(
+    engine: &mut Engine,
+    scene: &mut Scene,
+    rt_size: Vector2<f32>,
+) -> Option<RenderedPixels> {
     let GraphicsContext::Initialized(ref mut graphics_context) = engine.graphics_context else {
         Log::warn("Cannot render an asset preview when the renderer is not initialized!");
         return None;
@@ -348,25 +388,72 @@ fn render_scene_to_texture(
             .scene_data_map
             .remove(&temp_handle);
 
-        TextureResource::from_bytes(
-            TextureKind::Rectangle {
-                width: width as u32,
-                height: height as u32,
-            },
-            TexturePixelKind::RGBA8,
+        Some(RenderedPixels {
+            width: width as u32,
+            height: height as u32,
             pixels,
-            ResourceKind::Embedded,
-        )
-        .map(|texture| AssetPreviewTexture {
-            texture,
-            // OpenGL was designed by mathematicians.
-            flip_y: true,
         })
     } else {
         None
     }
 }
 
+/// Renders the given node (with all its descendants) in isolation and returns the pixels of the
+/// image of the given size. The node is copied into a temporary scene with neutral lighting setup
+/// (soft ambient light only) and rendered with screen-space ambient occlusion, regardless of the
+/// current quality settings of the renderer. The background of the resulting image is transparent,
+/// transparent materials are rendered on top of it using their own alpha. It is used to create
+/// thumbnails for model and surface data assets.
+pub fn render_isolated_node_to_pixels(
+    engine: &mut Engine,
+    scene: &Scene,
+    node: Handle<Node>,
+    size: Vector2<f32>,
+) -> Option<RenderedPixels> {
+    let mut thumbnail_scene = Scene::new();
+    thumbnail_scene.rendering_options.ambient_lighting_color = Color::opaque(180, 180, 180);
+    thumbnail_scene.rendering_options.clear_color = Some(Color::TRANSPARENT);
+
+    scene.graph.copy_node(
+        node,
+        &mut thumbnail_scene.graph,
+        &mut |_, _| true,
+        &mut |_, _| {},
+        &mut |_, _, _| {},
+    );
+
+    // Force SSAO for the thumbnail and restore previous settings after rendering.
+    let mut prev_quality_settings = None;
+    if let GraphicsContext::Initialized(ref mut graphics_context) = engine.graphics_context {
+        let settings = graphics_context.renderer.get_quality_settings();
+        if !settings.use_ssao {
+            Log::verify(
+                graphics_context
+                    .renderer
+                    .set_quality_settings(&QualitySettings {
+                        use_ssao: true,
+                        ..settings
+                    }),
+            );
+            prev_quality_settings = Some(settings);
+        }
+    }
+
+    let pixels = render_scene_to_pixels(engine, &mut thumbnail_scene, size);
+
+    if let Some(prev_quality_settings) = prev_quality_settings {
+        if let GraphicsContext::Initialized(ref mut graphics_context) = engine.graphics_context {
+            Log::verify(
+                graphics_context
+                    .renderer
+                    .set_quality_settings(&prev_quality_settings),
+            );
+        }
+    }
+
+    pixels
+}
+
 pub struct ModelPreview;
 
 impl AssetPreviewGenerator for ModelPreview {
@@ -390,9 +477,9 @@ impl AssetPreviewGenerator for ModelPreview {
     ) -> Option<AssetPreviewTexture> {
         let model = resource.try_cast::<Model>()?;
         let mut scene = Scene::new();
-        scene.rendering_options.ambient_lighting_color = Color::opaque(180, 180, 180);
-        model.instantiate(&mut scene);
-        render_scene_to_texture(engine, &mut scene, Vector2::new(128.0, 128.0))
+        let root = model.instantiate(&mut scene);
+        render_isolated_node_to_pixels(engine, &scene, root, Vector2::new(128.0, 128.0))
+            .and_then(RenderedPixels::into_preview_texture)
     }
 
     fn simple_icon(
@@ -429,11 +516,11 @@ impl AssetPreviewGenerator for SurfaceDataPreview {
     ) -> Option<AssetPreviewTexture> {
         let surface = resource.try_cast::<SurfaceData>()?;
         let mut scene = Scene::new();
-        scene.rendering_options.ambient_lighting_color = Color::opaque(180, 180, 180);
-        MeshBuilder::new(BaseBuilder::new())
+        let mesh = MeshBuilder::new(BaseBuilder::new())
             .with_surfaces(vec![SurfaceBuilder::new(surface.clone()).build()])
             .build(&mut scene.graph);
-        render_scene_to_texture(engine, &mut scene, Vector2::new(128.0, 128.0))
+        render_isolated_node_to_pixels(engine, &scene, mesh, Vector2::new(128.0, 128.0))
+            .and_then(RenderedPixels::into_preview_texture)
     }
 
     fn simple_icon(
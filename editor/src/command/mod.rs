@@ -125,9 +125,11 @@ impl CommandTrait for CommandGroup {
     fn name(&mut self, context: &dyn CommandContext) -> String {
         if self.custom_name.is_empty() {
             let mut name = String::from("Command group: ");
-            for cmd in self.commands.iter_mut() {
+            for (i, cmd) in self.commands.iter_mut().enumerate() {
+                if i > 0 {
+                    name.push_str(", ");
+                }
                 name.push_str(&cmd.name(context));
-                name.push_str(", ");
             }
             name
         } else {
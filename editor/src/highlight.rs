@@ -207,6 +207,7 @@ impl SceneRenderPass for HighlightRenderPass {
                 storage: &mut render_bundle_storage,
                 graph: &ctx.scene.graph,
                 render_pass_name: &render_pass_name,
+                viewport: ctx.viewport,
             };
 
             for &root_node_handle in self.nodes_to_highlight.iter() {
@@ -283,6 +284,7 @@ impl SceneRenderPass for HighlightRenderPass {
                         |mut program_binding| {
                             apply_material(MaterialContext {
                                 material,
+                                material_overrides: &bundle.material_overrides,
                                 program_binding: &mut program_binding,
                                 texture_cache: ctx.texture_cache,
                                 world_matrix: &instance.world_transform,
@@ -290,6 +292,7 @@ impl SceneRenderPass for HighlightRenderPass {
                                 wvp_matrix: &(view_projection * instance.world_transform),
                                 bone_matrices: &instance.bone_matrices,
                                 use_skeletal_animation: bundle.is_skinned,
+                                use_instancing: false,
                                 camera_position: &ctx.camera.global_position(),
                                 camera_up_vector: &camera_up,
                                 camera_side_vector: &camera_side,
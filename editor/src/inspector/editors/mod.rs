@@ -5,13 +5,17 @@ use crate::fyrox::scene::mesh::BatchingMode;
 use crate::fyrox::{
     asset::{manager::ResourceManager, Resource},
     core::{
+        algebra::Vector2,
+        color::Color,
         futures::executor::block_on,
+        math::Rect,
         parking_lot::Mutex,
         pool::{ErasedHandle, Handle},
     },
     gui,
     gui::inspector::editors::{
-        bit::BitFieldPropertyEditorDefinition, collection::VecCollectionPropertyEditorDefinition,
+        array::ArrayPropertyEditorDefinition, bit::BitFieldPropertyEditorDefinition,
+        collection::VecCollectionPropertyEditorDefinition,
         enumeration::EnumPropertyEditorDefinition, inherit::InheritablePropertyEditorDefinition,
         inspectable::InspectablePropertyEditorDefinition, PropertyEditorDefinitionContainer,
     },
@@ -20,7 +24,7 @@ use crate::fyrox::{
         shader::{Shader, ShaderResource},
         MaterialResource,
     },
-    renderer::framework::state::PolygonFillMode,
+    renderer::{bundle::SortingMode, framework::state::PolygonFillMode},
     resource::{
         curve::{CurveResource, CurveResourceState},
         model::{MaterialSearchOptions, Model, ModelResource},
@@ -41,7 +45,10 @@ use crate::fyrox::{
             CuboidShape, CylinderShape, GeometrySource, HeightfieldShape, InteractionGroups,
             SegmentShape, TriangleShape, TrimeshShape,
         },
-        dim2,
+        dim2::{
+            self,
+            rectangle::{NineSlice, RectangleWrapMode},
+        },
         graph::physics::CoefficientCombineRule,
         joint::*,
         light::{
@@ -210,6 +217,8 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.insert(InheritablePropertyEditorDefinition::<Handle<Node>>::new());
     container.register_inheritable_vec_collection::<Handle<Node>>();
+    container.register_inheritable_vec_collection::<Vector2<f32>>();
+    container.register_inheritable_vec_collection::<Color>();
     container.insert(NodeHandlePropertyEditorDefinition::new(
         sender.clone(),
         EntityKind::SceneNode,
@@ -247,6 +256,20 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(EnumPropertyEditorDefinition::<LodGroup>::new_optional());
     container.insert(InheritablePropertyEditorDefinition::<Option<LodGroup>>::new());
 
+    container.insert(EnumPropertyEditorDefinition::<Rect<f32>>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<Option<Rect<f32>>>::new());
+    container.insert(InspectablePropertyEditorDefinition::<RectangleWrapMode>::new());
+    container.insert(EnumPropertyEditorDefinition::<RectangleWrapMode>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<RectangleWrapMode>,
+    >::new());
+    container.insert(InspectablePropertyEditorDefinition::<NineSlice>::new());
+    container.insert(EnumPropertyEditorDefinition::<NineSlice>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<Option<NineSlice>>::new());
+    container.insert(ArrayPropertyEditorDefinition::<Color, 4>::new());
+    container.insert(EnumPropertyEditorDefinition::<[Color; 4]>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<Option<[Color; 4]>>::new());
+
     {
         use crate::fyrox::scene::animation::spritesheet::prelude::*;
         container.register_inheritable_enum::<Status, _>();
@@ -395,6 +418,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<SortingMode, _>();
 
     container.insert(EnumPropertyEditorDefinition::<Vec<ScriptRecord>>::new_optional());
     container.insert(VecCollectionPropertyEditorDefinition::<ScriptRecord>::new());
@@ -459,7 +483,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     >::new());
 
     container.insert(InspectablePropertyEditorDefinition::<ParticleSystemRng>::new());
-    container.insert(EnumPropertyEditorDefinition::<PolygonFillMode>::new());
+    container.register_inheritable_enum::<PolygonFillMode, _>();
 
     container.insert(EnumPropertyEditorDefinition::<MipFilter>::new());
 
@@ -5,7 +5,7 @@ use crate::fyrox::{
     gui::inspector::{CollectionChanged, FieldKind, PropertyChanged},
     scene::{node::Node, terrain::Terrain},
 };
-use crate::scene::commands::{GameSceneContext, RevertSceneNodePropertyCommand};
+use crate::scene::commands::{GameSceneContext, RevertInheritableCommand};
 use crate::{
     scene::commands::terrain::{AddTerrainLayerCommand, DeleteTerrainLayerCommand},
     Command,
@@ -52,9 +52,9 @@ impl SceneNodePropertyChangedHandler {
             if args.is_inheritable() {
                 // Prevent reverting property value if there's no parent resource.
                 if node.resource().is_some() {
-                    Some(Command::new(RevertSceneNodePropertyCommand::new(
-                        args.path(),
+                    Some(Command::new(RevertInheritableCommand::new(
                         handle,
+                        args.path(),
                     )))
                 } else {
                     None
@@ -1,25 +1,36 @@
 use crate::fyrox::{
     core::pool::Handle,
     gui::{menu::MenuItemMessage, message::UiMessage, BuildContext, UiNode},
-    scene::{base::BaseBuilder, dim2::rectangle::RectangleBuilder, node::Node},
+    scene::{
+        base::BaseBuilder, dim2::polygon::PolygonBuilder, dim2::rectangle::RectangleBuilder,
+        node::Node,
+    },
 };
 use crate::menu::create_menu_item;
 
 pub struct Dim2Menu {
     pub menu: Handle<UiNode>,
     create_sprite: Handle<UiNode>,
+    create_polygon: Handle<UiNode>,
 }
 
 impl Dim2Menu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let create_sprite;
+        let create_polygon;
 
         let menu = create_menu_item(
             "2D",
-            vec![{
-                create_sprite = create_menu_item("Rectangle (2D Sprite)", vec![], ctx);
-                create_sprite
-            }],
+            vec![
+                {
+                    create_sprite = create_menu_item("Rectangle (2D Sprite)", vec![], ctx);
+                    create_sprite
+                },
+                {
+                    create_polygon = create_menu_item("Polygon (2D)", vec![], ctx);
+                    create_polygon
+                },
+            ],
             ctx,
         );
 
@@ -27,6 +38,7 @@ impl Dim2Menu {
             menu,
 
             create_sprite,
+            create_polygon,
         }
     }
 
@@ -36,6 +48,10 @@ impl Dim2Menu {
                 let node =
                     RectangleBuilder::new(BaseBuilder::new().with_name("Sprite (2D)")).build_node();
                 Some(node)
+            } else if message.destination() == self.create_polygon {
+                let node =
+                    PolygonBuilder::new(BaseBuilder::new().with_name("Polygon (2D)")).build_node();
+                Some(node)
             } else {
                 None
             }
@@ -5,11 +5,16 @@ use crate::fyrox::{
 };
 use crate::{command::CommandTrait, scene::commands::GameSceneContext};
 
+/// Sets a value of any property (float, color, texture, etc.) of a material. The previous value of
+/// the property is captured on execution and restored on revert. The type of the new value must
+/// match the type of the property, otherwise the command does nothing.
 #[derive(Debug)]
 pub struct SetMaterialPropertyValueCommand {
     material: MaterialResource,
     name: ImmutableString,
+    // The new value before the execution and the old value after it.
     value: PropertyValue,
+    executed: bool,
 }
 use crate::command::CommandContext;
 
@@ -21,26 +26,74 @@ fn try_save(material: &MaterialResource) {
     }
 }
 
+/// Executes every given command and saves every affected material once, no matter how many
+/// commands change it.
+pub fn execute_batch(commands: &mut [SetMaterialPropertyValueCommand]) {
+    for command in commands.iter_mut() {
+        command.execute_unsaved();
+    }
+    save_batch(commands);
+}
+
+/// Reverts every given command in reverse order (so properties, that were changed by multiple
+/// commands, get their original values back) and saves every affected material once.
+pub fn revert_batch(commands: &mut [SetMaterialPropertyValueCommand]) {
+    for command in commands.iter_mut().rev() {
+        command.revert_unsaved();
+    }
+    save_batch(commands);
+}
+
+fn save_batch(commands: &[SetMaterialPropertyValueCommand]) {
+    let mut saved = Vec::<&MaterialResource>::new();
+    for command in commands {
+        if !saved.contains(&&command.material) {
+            try_save(&command.material);
+            saved.push(&command.material);
+        }
+    }
+}
+
 impl SetMaterialPropertyValueCommand {
     pub fn new(material: MaterialResource, name: ImmutableString, value: PropertyValue) -> Self {
         Self {
             material,
             name,
             value,
+            executed: false,
         }
     }
 
-    fn swap(&mut self) {
+    fn swap(&mut self) -> Result<(), String> {
         let mut material = self.material.data_ref();
 
-        let old_value = material.property_ref(&self.name).unwrap().clone();
+        let old_value = material
+            .property_ref(&self.name)
+            .cloned()
+            .ok_or_else(|| format!("Material has no property {}!", self.name))?;
 
         material
-            .set_property(&self.name, std::mem::replace(&mut self.value, old_value))
-            .unwrap();
+            .set_property(&self.name, self.value.clone())
+            .map_err(|e| e.to_string())?;
 
-        drop(material);
-        try_save(&self.material);
+        self.value = old_value;
+
+        Ok(())
+    }
+
+    fn execute_unsaved(&mut self) {
+        match self.swap() {
+            Ok(()) => self.executed = true,
+            Err(err) => Log::err(format!("Unable to set material property: {err}")),
+        }
+    }
+
+    fn revert_unsaved(&mut self) {
+        // Nothing to revert, if the command failed to execute.
+        if self.executed {
+            Log::verify(self.swap());
+            self.executed = false;
+        }
     }
 }
 
@@ -50,11 +103,17 @@ impl CommandTrait for SetMaterialPropertyValueCommand {
     }
 
     fn execute(&mut self, _: &mut dyn CommandContext) {
-        self.swap();
+        self.execute_unsaved();
+        if self.executed {
+            try_save(&self.material);
+        }
     }
 
     fn revert(&mut self, _: &mut dyn CommandContext) {
-        self.swap();
+        if self.executed {
+            self.revert_unsaved();
+            try_save(&self.material);
+        }
     }
 }
 
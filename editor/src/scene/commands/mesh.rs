@@ -1,29 +1,85 @@
 use crate::command::CommandContext;
 use crate::fyrox::{
-    core::{pool::Handle, sstorage::ImmutableString},
+    core::{log::Log, pool::Handle, sstorage::ImmutableString},
+    graph::SceneGraph,
     material::{shader::SamplerFallback, PropertyValue},
+    renderer::framework::state::PolygonFillMode,
     resource::texture::TextureResource,
     scene::{mesh::Mesh, node::Node},
 };
-use crate::{command::CommandTrait, scene::commands::GameSceneContext};
+use crate::{
+    command::CommandTrait,
+    scene::commands::{
+        material::{self, SetMaterialPropertyValueCommand},
+        GameSceneContext,
+    },
+};
 
+/// Sets the diffuse texture of every surface of every given mesh in a single undoable step.
+/// Every surface is changed by a separate [`SetMaterialPropertyValueCommand`], that captures the
+/// previous texture of the surface and restores it on revert. Every affected material is saved
+/// once per execution or revert. Nodes, that are not meshes, are ignored.
 #[derive(Debug)]
-enum TextureSet {
-    Single(TextureResource),
-    Multiple(Vec<Option<TextureResource>>),
+pub struct SetMeshTextureBatchCommand {
+    nodes: Vec<Handle<Node>>,
+    texture: TextureResource,
+    // Commands for every surface of every node, in the same order as the nodes.
+    commands: Vec<SetMaterialPropertyValueCommand>,
 }
 
+impl SetMeshTextureBatchCommand {
+    pub fn new(nodes: Vec<Handle<Node>>, texture: TextureResource) -> Self {
+        Self {
+            nodes,
+            texture,
+            commands: Default::default(),
+        }
+    }
+}
+
+impl CommandTrait for SetMeshTextureBatchCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        format!("Set Texture ({} Meshes)", self.nodes.len())
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let game_scene = context.get_mut::<GameSceneContext>();
+        self.commands = self
+            .nodes
+            .iter()
+            .filter_map(|&node| game_scene.scene.graph.try_get_of_type::<Mesh>(node))
+            .flat_map(|mesh| mesh.surfaces())
+            .map(|surface| {
+                SetMaterialPropertyValueCommand::new(
+                    surface.material().clone(),
+                    ImmutableString::new("diffuseTexture"),
+                    PropertyValue::Sampler {
+                        value: Some(self.texture.clone()),
+                        fallback: SamplerFallback::White,
+                    },
+                )
+            })
+            .collect();
+        material::execute_batch(&mut self.commands);
+    }
+
+    fn revert(&mut self, _context: &mut dyn CommandContext) {
+        material::revert_batch(&mut self.commands);
+        self.commands.clear();
+    }
+}
+
+/// Sets the diffuse texture of every surface of a single mesh. It is a thin wrapper over
+/// [`SetMeshTextureBatchCommand`].
 #[derive(Debug)]
 pub struct SetMeshTextureCommand {
-    node: Handle<Node>,
-    set: TextureSet,
+    batch: SetMeshTextureBatchCommand,
 }
 
 impl SetMeshTextureCommand {
     pub fn new(node: Handle<Node>, texture: TextureResource) -> Self {
         Self {
-            node,
-            set: TextureSet::Single(texture),
+            batch: SetMeshTextureBatchCommand::new(vec![node], texture),
         }
     }
 }
@@ -34,77 +90,344 @@ impl CommandTrait for SetMeshTextureCommand {
     }
 
     fn execute(&mut self, context: &mut dyn CommandContext) {
-        let context = context.get_mut::<GameSceneContext>();
-        if let TextureSet::Single(texture) = &self.set {
-            let mesh: &mut Mesh = context.scene.graph[self.node].as_mesh_mut();
-            let old_set = mesh
-                .surfaces_mut()
-                .iter()
-                .map(|s| {
-                    s.material()
-                        .data_ref()
-                        .property_ref(&ImmutableString::new("diffuseTexture"))
-                        .and_then(|p| {
-                            if let PropertyValue::Sampler { value, .. } = p {
-                                value.clone()
-                            } else {
-                                None
-                            }
-                        })
-                })
-                .collect();
-            for surface in mesh.surfaces_mut() {
-                surface
-                    .material()
-                    .data_ref()
-                    .set_property(
-                        &ImmutableString::new("diffuseTexture"),
-                        PropertyValue::Sampler {
-                            value: Some(texture.clone()),
-                            fallback: SamplerFallback::White,
-                        },
-                    )
-                    .unwrap();
-            }
-            self.set = TextureSet::Multiple(old_set);
-        } else {
-            unreachable!()
+        self.batch.execute(context)
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.batch.revert(context)
+    }
+}
+
+/// Sets a value of a property of the material of a surface of a mesh. It is a thin wrapper over
+/// [`SetMaterialPropertyValueCommand`], that fetches the material from the surface.
+#[derive(Debug)]
+pub struct SetSurfaceMaterialPropertyCommand {
+    node: Handle<Node>,
+    surface_index: usize,
+    name: ImmutableString,
+    value: PropertyValue,
+    command: Option<SetMaterialPropertyValueCommand>,
+}
+
+impl SetSurfaceMaterialPropertyCommand {
+    pub fn new(
+        node: Handle<Node>,
+        surface_index: usize,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) -> Self {
+        Self {
+            node,
+            surface_index,
+            name,
+            value,
+            command: None,
         }
     }
+}
+
+impl CommandTrait for SetSurfaceMaterialPropertyCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        format!("Set Material Property {}", self.name)
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        let game_scene = context.get_mut::<GameSceneContext>();
+        let Some(surface) = game_scene
+            .scene
+            .graph
+            .try_get_of_type::<Mesh>(self.node)
+            .and_then(|mesh| mesh.surfaces().get(self.surface_index))
+        else {
+            Log::err(format!(
+                "Unable to set material property: mesh {} has no surface with index {}!",
+                self.node, self.surface_index
+            ));
+            return;
+        };
+
+        let mut command = SetMaterialPropertyValueCommand::new(
+            surface.material().clone(),
+            self.name.clone(),
+            self.value.clone(),
+        );
+        command.execute(context);
+        self.command = Some(command);
+    }
 
     fn revert(&mut self, context: &mut dyn CommandContext) {
+        if let Some(mut command) = self.command.take() {
+            command.revert(context);
+        }
+    }
+}
+
+/// Toggles a surface of a mesh between solid and wireframe display modes. It is used to inspect
+/// individual parts of complex meshes.
+#[derive(Debug)]
+pub struct ToggleSurfaceWireframeCommand {
+    node: Handle<Node>,
+    surface_index: usize,
+}
+
+impl ToggleSurfaceWireframeCommand {
+    pub fn new(node: Handle<Node>, surface_index: usize) -> Self {
+        Self {
+            node,
+            surface_index,
+        }
+    }
+
+    fn toggle(&mut self, context: &mut dyn CommandContext) {
         let context = context.get_mut::<GameSceneContext>();
-        if let TextureSet::Multiple(set) = &self.set {
-            let mesh: &mut Mesh = context.scene.graph[self.node].as_mesh_mut();
-            let new_value = mesh.surfaces_mut()[0]
-                .material()
-                .data_ref()
-                .property_ref(&ImmutableString::new("diffuseTexture"))
-                .and_then(|p| {
-                    if let PropertyValue::Sampler { value, .. } = p {
-                        value.clone()
-                    } else {
-                        None
-                    }
-                })
-                .unwrap();
-            assert_eq!(mesh.surfaces_mut().len(), set.len());
-            for (surface, old_texture) in mesh.surfaces_mut().iter_mut().zip(set) {
-                surface
-                    .material()
-                    .data_ref()
-                    .set_property(
-                        &ImmutableString::new("diffuseTexture"),
-                        PropertyValue::Sampler {
-                            value: old_texture.clone(),
-                            fallback: SamplerFallback::White,
-                        },
-                    )
-                    .unwrap();
-            }
-            self.set = TextureSet::Single(new_value);
-        } else {
-            unreachable!()
+        let Some(surface) = context
+            .scene
+            .graph
+            .try_get_mut_of_type::<Mesh>(self.node)
+            .and_then(|mesh| mesh.surfaces_mut().get_mut(self.surface_index))
+        else {
+            Log::err(format!(
+                "Unable to toggle wireframe: mesh {} has no surface with index {}!",
+                self.node, self.surface_index
+            ));
+            return;
+        };
+
+        let new_mode = match surface.polygon_fill_mode() {
+            PolygonFillMode::Fill => PolygonFillMode::Line,
+            _ => PolygonFillMode::Fill,
+        };
+        surface.set_polygon_fill_mode(new_mode);
+    }
+}
+
+impl CommandTrait for ToggleSurfaceWireframeCommand {
+    fn name(&mut self, _context: &dyn CommandContext) -> String {
+        "Toggle Surface Wireframe".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        self.toggle(context)
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.toggle(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::{CommandGroup, CommandTrait},
+        fyrox::{
+            asset::untyped::ResourceKind,
+            core::{
+                algebra::{Matrix4, Vector3},
+                color::Color,
+                pool::Handle,
+                sstorage::ImmutableString,
+            },
+            material::PropertyValue,
+            renderer::framework::state::PolygonFillMode,
+            resource::texture::{TextureResource, TextureResourceExtension},
+            scene::{
+                base::BaseBuilder,
+                mesh::{
+                    surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+                    Mesh, MeshBuilder,
+                },
+                node::Node,
+                pivot::PivotBuilder,
+                Scene,
+            },
+        },
+        scene::commands::{
+            mesh::{
+                SetMeshTextureBatchCommand, SetMeshTextureCommand,
+                SetSurfaceMaterialPropertyCommand, ToggleSurfaceWireframeCommand,
+            },
+            GameSceneContext,
+        },
+    };
+
+    fn make_mesh(scene: &mut Scene) -> Handle<Node> {
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut scene.graph)
+    }
+
+    fn diffuse_texture(scene: &Scene, node: Handle<Node>) -> Option<TextureResource> {
+        let mesh: &Mesh = scene.graph[node].as_mesh();
+        let material = mesh.surfaces()[0].material().data_ref();
+        match material.property_ref(&ImmutableString::new("diffuseTexture")) {
+            Some(PropertyValue::Sampler { value, .. }) => value.clone(),
+            _ => None,
         }
     }
+
+    #[test]
+    fn test_set_mesh_texture_group() {
+        let mut scene = Scene::new();
+        let a = make_mesh(&mut scene);
+        let b = make_mesh(&mut scene);
+
+        let old_a = diffuse_texture(&scene, a);
+        let old_b = diffuse_texture(&scene, b);
+
+        let texture = TextureResource::new_render_target(1, 1);
+        let mut group = CommandGroup::default();
+        group.push(SetMeshTextureCommand::new(a, texture.clone()));
+        group.push(SetMeshTextureCommand::new(b, texture.clone()));
+
+        GameSceneContext::exec_with_scene(&mut scene, |ctx| {
+            group.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), Some(texture.clone()));
+            assert_eq!(diffuse_texture(scene, b), Some(texture.clone()));
+
+            group.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), old_a);
+            assert_eq!(diffuse_texture(scene, b), old_b);
+        });
+    }
+
+    #[test]
+    fn test_set_mesh_texture_batch_ignores_non_meshes() {
+        let mut scene = Scene::new();
+        let a = make_mesh(&mut scene);
+        let b = make_mesh(&mut scene);
+        let pivot = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+
+        let old_a = diffuse_texture(&scene, a);
+        let old_b = diffuse_texture(&scene, b);
+
+        let texture = TextureResource::new_render_target(1, 1);
+        let mut batch = SetMeshTextureBatchCommand::new(vec![a, pivot, b], texture.clone());
+
+        GameSceneContext::exec_with_scene(&mut scene, |ctx| {
+            batch.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), Some(texture.clone()));
+            assert_eq!(diffuse_texture(scene, b), Some(texture.clone()));
+
+            batch.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), old_a);
+            assert_eq!(diffuse_texture(scene, b), old_b);
+        });
+    }
+
+    #[test]
+    fn test_set_mesh_texture_batch() {
+        let mut scene = Scene::new();
+        let a = make_mesh(&mut scene);
+        let b = make_mesh(&mut scene);
+
+        let old_a = diffuse_texture(&scene, a);
+        let old_b = diffuse_texture(&scene, b);
+
+        let texture = TextureResource::new_render_target(1, 1);
+        let mut batch = SetMeshTextureBatchCommand::new(vec![a, b], texture.clone());
+
+        GameSceneContext::exec_with_scene(&mut scene, |ctx| {
+            assert_eq!(batch.name(ctx), "Set Texture (2 Meshes)");
+
+            batch.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), Some(texture.clone()));
+            assert_eq!(diffuse_texture(scene, b), Some(texture.clone()));
+
+            batch.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), old_a);
+            assert_eq!(diffuse_texture(scene, b), old_b);
+
+            // Redo must work as well.
+            batch.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_texture(scene, a), Some(texture.clone()));
+        });
+    }
+
+    fn diffuse_color(scene: &Scene, node: Handle<Node>) -> Option<Color> {
+        let mesh: &Mesh = scene.graph[node].as_mesh();
+        let material = mesh.surfaces()[0].material().data_ref();
+        match material.property_ref(&ImmutableString::new("diffuseColor")) {
+            Some(PropertyValue::Color(color)) => Some(*color),
+            _ => None,
+        }
+    }
+
+    fn emission_strength(scene: &Scene, node: Handle<Node>) -> Option<Vector3<f32>> {
+        let mesh: &Mesh = scene.graph[node].as_mesh();
+        let material = mesh.surfaces()[0].material().data_ref();
+        match material.property_ref(&ImmutableString::new("emissionStrength")) {
+            Some(PropertyValue::Vector3(strength)) => Some(*strength),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_set_material_property() {
+        let mut scene = Scene::new();
+        let mesh = make_mesh(&mut scene);
+
+        let old_color = diffuse_color(&scene, mesh);
+        let old_strength = emission_strength(&scene, mesh);
+
+        let mut set_color = SetSurfaceMaterialPropertyCommand::new(
+            mesh,
+            0,
+            ImmutableString::new("diffuseColor"),
+            PropertyValue::Color(Color::RED),
+        );
+        // Wrong type, must be rejected and leave the material untouched.
+        let mut set_strength = SetSurfaceMaterialPropertyCommand::new(
+            mesh,
+            0,
+            ImmutableString::new("emissionStrength"),
+            PropertyValue::Float(2.0),
+        );
+
+        GameSceneContext::exec_with_scene(&mut scene, |ctx| {
+            set_color.execute(ctx);
+            set_strength.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_color(scene, mesh), Some(Color::RED));
+            assert_eq!(emission_strength(scene, mesh), old_strength);
+
+            set_strength.revert(ctx);
+            set_color.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(diffuse_color(scene, mesh), old_color);
+            assert_eq!(emission_strength(scene, mesh), old_strength);
+        });
+    }
+
+    #[test]
+    fn test_toggle_surface_wireframe() {
+        let mut scene = Scene::new();
+        let mesh = make_mesh(&mut scene);
+
+        let fill_mode =
+            |scene: &Scene| scene.graph[mesh].as_mesh().surfaces()[0].polygon_fill_mode();
+        assert_eq!(fill_mode(&scene), PolygonFillMode::Fill);
+
+        let mut toggle = ToggleSurfaceWireframeCommand::new(mesh, 0);
+
+        GameSceneContext::exec_with_scene(&mut scene, |ctx| {
+            toggle.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(fill_mode(scene), PolygonFillMode::Line);
+
+            toggle.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(fill_mode(scene), PolygonFillMode::Fill);
+        });
+    }
 }
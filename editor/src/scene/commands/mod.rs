@@ -1,6 +1,9 @@
 use crate::fyrox::{
     asset::manager::ResourceManager,
-    core::{log::Log, pool::Handle, reflect::prelude::*, type_traits::prelude::*},
+    core::{
+        log::Log, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
+        variable::VariableFlags,
+    },
     engine::SerializationContext,
     graph::{BaseSceneGraph, SceneGraphNode},
     scene::{graph::SubGraph, node::Node, Scene},
@@ -70,6 +73,30 @@ impl GameSceneContext {
     }
 }
 
+#[cfg(test)]
+impl GameSceneContext {
+    /// Executes the given closure with a context of the given scene, the rest of the context is
+    /// filled with default values. Used to test commands.
+    pub fn exec_with_scene<F>(scene: &mut Scene, func: F)
+    where
+        F: FnOnce(&mut dyn CommandContext),
+    {
+        let mut selection = Selection::default();
+        let mut scene_content_root = scene.graph.get_root();
+        let mut clipboard = Clipboard::default();
+        Self::exec(
+            &mut selection,
+            scene,
+            &mut scene_content_root,
+            &mut clipboard,
+            MessageSender(std::sync::mpsc::channel().0),
+            ResourceManager::new(Arc::new(crate::fyrox::core::task::TaskPool::new())),
+            Arc::new(SerializationContext::new()),
+            |ctx| func(ctx),
+        );
+    }
+}
+
 impl CommandContext for GameSceneContext {}
 
 pub fn selection_to_delete(editor_selection: &Selection, game_scene: &GameScene) -> GraphSelection {
@@ -280,40 +307,75 @@ impl CommandTrait for PasteCommand {
     }
 }
 
+/// Reverts an inheritable property of a scene node to the value of its parent (the value from the
+/// resource the node was instantiated from) and clears its modified flag. The command remembers the
+/// current value and the flags of the variable, so it could be fully undone.
 #[derive(Debug)]
-pub struct RevertSceneNodePropertyCommand {
+pub struct RevertInheritableCommand {
+    node: Handle<Node>,
     path: String,
-    handle: Handle<Node>,
     value: Option<Box<dyn Reflect>>,
+    flags: Option<VariableFlags>,
 }
 
-impl RevertSceneNodePropertyCommand {
-    pub fn new(path: String, handle: Handle<Node>) -> Self {
+impl RevertInheritableCommand {
+    pub fn new(node: Handle<Node>, path: String) -> Self {
         Self {
+            node,
             path,
-            handle,
             value: None,
+            flags: None,
         }
     }
+
+    fn property_flags(&self, node: &Node) -> Option<VariableFlags> {
+        let mut flags = None;
+        node.as_reflect(&mut |node| {
+            node.resolve_path(&self.path, &mut |result| {
+                if let Ok(field) = result {
+                    field.as_inheritable_variable(&mut |inheritable| {
+                        flags = inheritable.map(|inheritable| inheritable.flags());
+                    })
+                }
+            })
+        });
+        flags
+    }
+
+    fn set_property_flags(&self, node: &mut Node, flags: VariableFlags) {
+        node.as_reflect_mut(&mut |node| {
+            node.resolve_path_mut(&self.path, &mut |result| {
+                if let Ok(field) = result {
+                    field.as_inheritable_variable_mut(&mut |inheritable| {
+                        if let Some(inheritable) = inheritable {
+                            inheritable.set_flags(flags);
+                        }
+                    })
+                }
+            })
+        });
+    }
 }
 
-impl CommandTrait for RevertSceneNodePropertyCommand {
+impl CommandTrait for RevertInheritableCommand {
     fn name(&mut self, _context: &dyn CommandContext) -> String {
         format!("Revert {} Property", self.path)
     }
 
     fn execute(&mut self, context: &mut dyn CommandContext) {
         let context = context.get_mut::<GameSceneContext>();
-        let child = &mut context.scene.graph[self.handle];
-        self.value = child.revert_inheritable_property(&self.path);
+        let node = &mut context.scene.graph[self.node];
+        self.flags = self.property_flags(node);
+        self.value = node.revert_inheritable_property(&self.path);
     }
 
     fn revert(&mut self, context: &mut dyn CommandContext) {
         let context = context.get_mut::<GameSceneContext>();
+        let node = &mut context.scene.graph[self.node];
         // If the property was modified, then simply set it to previous value to make it modified again.
         if let Some(old_value) = self.value.take() {
             let mut old_value = Some(old_value);
-            context.scene.graph[self.handle].as_reflect_mut(&mut |node| {
+            node.as_reflect_mut(&mut |node| {
                 node.set_field_by_path(&self.path, old_value.take().unwrap(), &mut |result| {
                     if result.is_err() {
                         Log::err(format!(
@@ -322,7 +384,120 @@ impl CommandTrait for RevertSceneNodePropertyCommand {
                         ))
                     }
                 });
-            })
+            });
+            // Setting the value marks the variable as modified, restore the exact flags it had
+            // before the command was executed.
+            if let Some(flags) = self.flags.take() {
+                self.set_property_flags(node, flags);
+            }
         }
     }
 }
+
+/// Reverts an inheritable property of a scene node to the value from its parent resource.
+#[deprecated(note = "use `RevertInheritableCommand` instead")]
+#[derive(Debug)]
+pub struct RevertSceneNodePropertyCommand(RevertInheritableCommand);
+
+#[allow(deprecated)]
+impl RevertSceneNodePropertyCommand {
+    pub fn new(path: String, handle: Handle<Node>) -> Self {
+        Self(RevertInheritableCommand::new(handle, path))
+    }
+}
+
+#[allow(deprecated)]
+impl CommandTrait for RevertSceneNodePropertyCommand {
+    fn name(&mut self, context: &dyn CommandContext) -> String {
+        self.0.name(context)
+    }
+
+    fn execute(&mut self, context: &mut dyn CommandContext) {
+        self.0.execute(context)
+    }
+
+    fn revert(&mut self, context: &mut dyn CommandContext) {
+        self.0.revert(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        command::CommandTrait,
+        fyrox::{
+            asset::{untyped::ResourceKind, Resource},
+            core::{pool::Handle, variable::VariableFlags},
+            graph::NodeMapping,
+            resource::model::{Model, ModelResourceExtension},
+            scene::{node::Node, Scene},
+        },
+        scene::commands::{GameSceneContext, RevertInheritableCommand},
+    };
+
+    // Creates a scene with an instance of a model resource and returns the root of the instance.
+    fn make_instance(scene: &mut Scene) -> Handle<Node> {
+        let model = Resource::new_ok(
+            ResourceKind::Embedded,
+            Model::new(NodeMapping::UseNames, Scene::new()),
+        );
+        model.instantiate(scene)
+    }
+
+    const PATH: &str = "base.visibility";
+
+    // Returns the visibility of the node and whether it is marked as modified.
+    fn visibility(scene: &Scene, node: Handle<Node>) -> (bool, bool) {
+        let node_ref = &scene.graph[node];
+        let flags = RevertInheritableCommand::new(node, PATH.to_string())
+            .property_flags(node_ref)
+            .unwrap();
+        (
+            node_ref.visibility(),
+            flags.contains(VariableFlags::MODIFIED),
+        )
+    }
+
+    fn test_revert_visibility(
+        mut command: impl CommandTrait,
+        scene: &mut Scene,
+        node: Handle<Node>,
+    ) {
+        GameSceneContext::exec_with_scene(scene, |ctx| {
+            command.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(visibility(scene, node), (true, false));
+
+            command.revert(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(visibility(scene, node), (false, true));
+
+            // Redo must work as well.
+            command.execute(ctx);
+            let scene = &*ctx.get_mut::<GameSceneContext>().scene;
+            assert_eq!(visibility(scene, node), (true, false));
+        });
+    }
+
+    #[test]
+    fn test_revert_inheritable_property() {
+        let mut scene = Scene::new();
+        let node = make_instance(&mut scene);
+        scene.graph[node].set_visibility(false);
+        assert_eq!(visibility(&scene, node), (false, true));
+
+        let command = RevertInheritableCommand::new(node, PATH.to_string());
+        test_revert_visibility(command, &mut scene, node);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_revert_scene_node_property() {
+        let mut scene = Scene::new();
+        let node = make_instance(&mut scene);
+        scene.graph[node].set_visibility(false);
+
+        let command = super::RevertSceneNodePropertyCommand::new(PATH.to_string(), node);
+        test_revert_visibility(command, &mut scene, node);
+    }
+}
@@ -368,7 +368,7 @@ impl EditorSceneEntry {
         settings: &Settings,
     ) {
         self.controller
-            .on_drop(handle, screen_bounds, engine, settings)
+            .on_drop(handle, screen_bounds, &self.selection, engine, settings)
     }
 }
 
@@ -74,6 +74,7 @@ pub trait SceneController: 'static {
         &mut self,
         handle: Handle<UiNode>,
         screen_bounds: Rect<f32>,
+        editor_selection: &Selection,
         engine: &mut Engine,
         settings: &Settings,
     );
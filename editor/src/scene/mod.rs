@@ -21,7 +21,7 @@ use crate::{
             reflect::Reflect,
             visitor::Visitor,
         },
-        engine::{Engine, SerializationContext},
+        engine::{Engine, GraphicsContext, SerializationContext},
         fxhash::FxHashSet,
         graph::{BaseSceneGraph, SceneGraph},
         gui::{
@@ -66,8 +66,9 @@ use crate::{
     scene::{
         clipboard::Clipboard,
         commands::{
-            graph::AddModelCommand, mesh::SetMeshTextureCommand, ChangeSelectionCommand,
-            GameSceneContext,
+            graph::AddModelCommand,
+            mesh::{SetMeshTextureBatchCommand, SetMeshTextureCommand},
+            ChangeSelectionCommand, GameSceneContext,
         },
         controller::SceneController,
         selector::HierarchyNode,
@@ -653,6 +654,7 @@ impl SceneController for GameScene {
         &mut self,
         handle: Handle<UiNode>,
         screen_bounds: Rect<f32>,
+        editor_selection: &Selection,
         engine: &mut Engine,
         settings: &Settings,
     ) {
@@ -707,11 +709,35 @@ impl SceneController for GameScene {
                         let texture = tex.clone();
                         let mut texture = texture.state();
                         if texture.data().is_some() {
-                            let node = &mut engine.scenes[self.scene].graph[result.node];
-
-                            if node.is_mesh() {
-                                self.sender
-                                    .do_command(SetMeshTextureCommand::new(result.node, tex));
+                            let graph = &engine.scenes[self.scene].graph;
+
+                            if graph[result.node].is_mesh() {
+                                // Apply the texture to every selected mesh at once, if the texture
+                                // was dropped on one of them.
+                                let selected_meshes = editor_selection
+                                    .as_graph()
+                                    .filter(|selection| selection.contains(result.node))
+                                    .map(|selection| {
+                                        selection
+                                            .nodes()
+                                            .iter()
+                                            .cloned()
+                                            .filter(|&node| {
+                                                graph.try_get(node).is_some_and(|n| n.is_mesh())
+                                            })
+                                            .collect::<Vec<_>>()
+                                    })
+                                    .unwrap_or_default();
+
+                                if selected_meshes.len() > 1 {
+                                    self.sender.do_command(SetMeshTextureBatchCommand::new(
+                                        selected_meshes,
+                                        tex,
+                                    ));
+                                } else {
+                                    self.sender
+                                        .do_command(SetMeshTextureCommand::new(result.node, tex));
+                                }
                             }
                         }
                     }
@@ -975,6 +1001,12 @@ impl SceneController for GameScene {
             Message::FocusObject(handle) => {
                 let scene = &mut engine.scenes[self.scene];
                 self.camera_controller.fit_object(scene, *handle);
+                // The camera jumps to the object, so the history of the previous view is invalid.
+                if let GraphicsContext::Initialized(ref mut graphics_context) =
+                    engine.graphics_context
+                {
+                    graphics_context.renderer.reset_ssao_history();
+                }
                 false
             }
             Message::SyncNodeHandleName { view, handle } => {
@@ -21,7 +21,10 @@ use crate::{
             window::{WindowBuilder, WindowMessage, WindowTitle},
             HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
         },
-        renderer::{CsmSettings, QualitySettings, ShadowMapPrecision},
+        renderer::{
+            AoApplication, CsmSettings, PointShadowPcfKernel, QualitySettings, ShadowMapPrecision,
+            SsaoDepthFilter,
+        },
     },
     inspector::editors::make_property_editors_container,
     message::MessageSender,
@@ -210,6 +213,9 @@ impl SettingsData {
         container.insert(InspectablePropertyEditorDefinition::<GraphicsSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<SelectionSettings>::new());
         container.insert(EnumPropertyEditorDefinition::<ShadowMapPrecision>::new());
+        container.insert(EnumPropertyEditorDefinition::<SsaoDepthFilter>::new());
+        container.insert(EnumPropertyEditorDefinition::<AoApplication>::new());
+        container.insert(EnumPropertyEditorDefinition::<PointShadowPcfKernel>::new());
         container.insert(EnumPropertyEditorDefinition::<ScriptEditor>::new());
         container.insert(InspectablePropertyEditorDefinition::<DebuggingSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CsmSettings>::new());
@@ -213,6 +213,7 @@ impl SceneController for UiScene {
         &mut self,
         handle: Handle<UiNode>,
         _screen_bounds: Rect<f32>,
+        _editor_selection: &Selection,
         _engine: &mut Engine,
         _settings: &Settings,
     ) {
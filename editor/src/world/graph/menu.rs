@@ -3,7 +3,7 @@ use crate::{
     fyrox::{
         asset::untyped::UntypedResource,
         core::{algebra::Vector2, algebra::Vector3, pool::Handle, reflect::Reflect, scope_profile},
-        graph::BaseSceneGraph,
+        graph::{BaseSceneGraph, SceneGraph},
         gui::{
             file_browser::FileSelectorMessage,
             menu::{ContextMenuBuilder, MenuItemBuilder, MenuItemContent, MenuItemMessage},
@@ -14,6 +14,7 @@ use crate::{
             window::WindowMessage,
             BuildContext, RcUiNodeHandle, UiNode,
         },
+        scene::mesh::Mesh,
     },
     make_save_file_selector,
     menu::{create::CreateEntityMenu, create_menu_item, create_menu_item_shortcut},
@@ -24,7 +25,9 @@ use crate::{
                 AddNodeCommand, LinkNodesCommand, MoveNodeCommand, ReplaceNodeCommand,
                 SetGraphRootCommand, SetNodeTransformCommand,
             },
-            make_delete_selection_command, RevertSceneNodePropertyCommand,
+            make_delete_selection_command,
+            mesh::ToggleSurfaceWireframeCommand,
+            RevertInheritableCommand,
         },
         controller::SceneController,
         GameScene, Selection,
@@ -50,6 +53,7 @@ pub struct SceneNodeContextMenu {
     make_root: Handle<UiNode>,
     open_asset: Handle<UiNode>,
     reset_inheritable_properties: Handle<UiNode>,
+    toggle_wireframe: Handle<UiNode>,
 }
 
 impl WorldViewerItemContextMenu for SceneNodeContextMenu {
@@ -74,6 +78,20 @@ fn resource_path_of_first_selected_node(
     None
 }
 
+fn is_any_mesh_selected(
+    editor_selection: &Selection,
+    game_scene: &GameScene,
+    engine: &Engine,
+) -> bool {
+    editor_selection.as_graph().is_some_and(|graph_selection| {
+        let graph = &engine.scenes[game_scene.scene].graph;
+        graph_selection
+            .nodes
+            .iter()
+            .any(|node| graph.try_get_of_type::<Mesh>(*node).is_some())
+    })
+}
+
 impl SceneNodeContextMenu {
     pub fn new(ctx: &mut BuildContext) -> Self {
         let delete_selection;
@@ -83,6 +101,7 @@ impl SceneNodeContextMenu {
         let make_root;
         let open_asset;
         let reset_inheritable_properties;
+        let toggle_wireframe;
 
         let (create_child_entity_menu, create_child_entity_menu_root_items) =
             CreateEntityMenu::new(ctx);
@@ -148,6 +167,10 @@ impl SceneNodeContextMenu {
                             reset_inheritable_properties =
                                 create_menu_item("Reset Inheritable Properties", vec![], ctx);
                             reset_inheritable_properties
+                        })
+                        .with_child({
+                            toggle_wireframe = create_menu_item("Toggle Wireframe", vec![], ctx);
+                            toggle_wireframe
                         }),
                 )
                 .build(ctx),
@@ -172,6 +195,7 @@ impl SceneNodeContextMenu {
             make_root,
             open_asset,
             reset_inheritable_properties,
+            toggle_wireframe,
             create_parent_entity_menu,
         }
     }
@@ -360,9 +384,9 @@ impl SceneNodeContextMenu {
                                         val.as_inheritable_variable(&mut |inheritable| {
                                             if inheritable.is_some() {
                                                 commands.push(Command::new(
-                                                    RevertSceneNodePropertyCommand::new(
-                                                        path.to_string(),
+                                                    RevertInheritableCommand::new(
                                                         *node_handle,
+                                                        path.to_string(),
                                                     ),
                                                 ));
                                             }
@@ -374,6 +398,26 @@ impl SceneNodeContextMenu {
                         }
                         sender.do_command(CommandGroup::from(commands));
                     }
+                } else if message.destination() == self.toggle_wireframe {
+                    if let Some(graph_selection) = editor_selection.as_graph() {
+                        let graph = &engine.scenes[game_scene.scene].graph;
+                        let mut commands = Vec::new();
+                        for node_handle in graph_selection.nodes.iter() {
+                            if let Some(mesh) = graph.try_get_of_type::<Mesh>(*node_handle) {
+                                for surface_index in 0..mesh.surfaces().len() {
+                                    commands.push(Command::new(
+                                        ToggleSurfaceWireframeCommand::new(
+                                            *node_handle,
+                                            surface_index,
+                                        ),
+                                    ));
+                                }
+                            }
+                        }
+                        if !commands.is_empty() {
+                            sender.do_command(CommandGroup::from(commands));
+                        }
+                    }
                 }
             } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data()
             {
@@ -403,6 +447,15 @@ impl SceneNodeContextMenu {
                             )
                             .map_or(false, |p| utils::is_native_scene(&p)),
                         ));
+
+                    engine
+                        .user_interfaces
+                        .first()
+                        .send_message(WidgetMessage::enabled(
+                            self.toggle_wireframe,
+                            MessageDirection::ToWidget,
+                            is_any_mesh_selected(editor_selection, game_scene, engine),
+                        ));
                 }
             } else if let Some(FileSelectorMessage::Commit(path)) = message.data() {
                 if message.destination() == self.save_as_prefab_dialog {
@@ -621,6 +621,44 @@ impl Color {
         Self::from_rgba(r, g, b, self.a)
     }
 
+    /// Converts the color from sRGB color space to linear color space using exact sRGB transfer
+    /// function (unlike [`Self::srgb_to_linear`], which uses `2.2` gamma approximation). Alpha
+    /// channel is left unchanged.
+    #[must_use]
+    #[inline]
+    pub fn srgb_to_linear_exact(self) -> Self {
+        fn convert(c: u8) -> u8 {
+            let c = c as f32 / 255.0;
+            let linear = if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            };
+            (linear.clamp(0.0, 1.0) * 255.0).round() as u8
+        }
+
+        Self::from_rgba(convert(self.r), convert(self.g), convert(self.b), self.a)
+    }
+
+    /// Converts the color from linear color space to sRGB color space using exact sRGB transfer
+    /// function (unlike [`Self::linear_to_srgb`], which uses `2.2` gamma approximation). Alpha
+    /// channel is left unchanged.
+    #[must_use]
+    #[inline]
+    pub fn linear_to_srgb_exact(self) -> Self {
+        fn convert(c: u8) -> u8 {
+            let c = c as f32 / 255.0;
+            let srgb = if c <= 0.003_130_8 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            };
+            (srgb.clamp(0.0, 1.0) * 255.0).round() as u8
+        }
+
+        Self::from_rgba(convert(self.r), convert(self.g), convert(self.b), self.a)
+    }
+
     #[inline]
     pub fn as_frgba(self) -> Vector4<f32> {
         Vector4::new(
@@ -978,6 +1016,43 @@ mod test {
         assert_eq!(color.linear_to_srgb(), Color::opaque(99, 99, 99));
     }
 
+    #[test]
+    fn test_color_srgb_to_linear_exact() {
+        assert_eq!(Color::BLACK.srgb_to_linear_exact(), Color::BLACK);
+        assert_eq!(Color::WHITE.srgb_to_linear_exact(), Color::WHITE);
+        assert_eq!(Color::BLACK.linear_to_srgb_exact(), Color::BLACK);
+        assert_eq!(Color::WHITE.linear_to_srgb_exact(), Color::WHITE);
+
+        assert_eq!(
+            Color::from_rgba(100, 200, 10, 77).srgb_to_linear_exact(),
+            Color::from_rgba(32, 147, 1, 77)
+        );
+        assert_eq!(
+            Color::from_rgba(32, 147, 1, 77).linear_to_srgb_exact(),
+            Color::from_rgba(99, 200, 13, 77)
+        );
+
+        // Linear -> sRGB -> linear round trip is accurate, because sRGB has higher precision in
+        // dark tones.
+        for i in 0..=255 {
+            let color = Color::from_rgba(i, i, i, i);
+            let round_trip = color.linear_to_srgb_exact().srgb_to_linear_exact();
+            assert_eq!(round_trip.a, i);
+            assert!((round_trip.r as i32 - i as i32).abs() <= 1);
+        }
+
+        // sRGB -> linear -> sRGB round trip loses precision in dark tones only, because of 8-bit
+        // storage of linear values.
+        for i in 0..=255 {
+            let color = Color::from_rgba(i, i, i, 255 - i);
+            let round_trip = color.srgb_to_linear_exact().linear_to_srgb_exact();
+            assert_eq!(round_trip.a, 255 - i);
+            if i >= 64 {
+                assert!((round_trip.r as i32 - i as i32).abs() <= 1);
+            }
+        }
+    }
+
     #[test]
     fn test_color_as_frgba() {
         assert_eq!(Color::BLACK.as_frgba(), Vector4::new(0.0, 0.0, 0.0, 1.0));
@@ -21,6 +21,30 @@ impl_reflect!(
     pub struct Rect<T: Debug> {}
 );
 
+impl<T> crate::TypeUuidProvider for Rect<T>
+where
+    T: crate::TypeUuidProvider + Debug,
+{
+    fn type_uuid() -> Uuid {
+        crate::combine_uuids(
+            uuid::uuid!("3d3b4b5e-9c4e-4b2e-8f0b-2c6a7d1f5e91"),
+            T::type_uuid(),
+        )
+    }
+}
+
+impl<T> crate::TypeUuidProvider for crate::algebra::Vector2<T>
+where
+    T: crate::TypeUuidProvider + Scalar,
+{
+    fn type_uuid() -> Uuid {
+        crate::combine_uuids(
+            uuid::uuid!("b6a2d1c4-5f3e-4e8a-9c7d-0e1f2a3b4c5d"),
+            T::type_uuid(),
+        )
+    }
+}
+
 impl<T> Visit for Rect<T>
 where
     T: NumAssign + Scalar + Visit + PartialOrd + Copy + 'static,
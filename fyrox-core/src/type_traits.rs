@@ -71,6 +71,18 @@ impl<T: TypeUuidProvider> TypeUuidProvider for Vec<T> {
     }
 }
 
+impl<T: TypeUuidProvider, const N: usize> TypeUuidProvider for [T; N] {
+    fn type_uuid() -> Uuid {
+        combine_uuids(
+            combine_uuids(
+                uuid::uuid!("2c5ae5a7-4f53-4e2c-9d0c-0b7e54d2f6a1"),
+                Uuid::from_u128(N as u128),
+            ),
+            T::type_uuid(),
+        )
+    }
+}
+
 #[inline]
 pub fn combine_uuids(a: Uuid, b: Uuid) -> Uuid {
     let mut combined_bytes = a.into_bytes();
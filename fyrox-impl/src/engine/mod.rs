@@ -1874,6 +1874,14 @@ impl Engine {
 
                         let scene_handle = context.scenes.add(scene);
 
+                        // History of temporal effects belongs to the previous view, it is invalid
+                        // for the new scene.
+                        if let GraphicsContext::Initialized(ref mut graphics_context) =
+                            context.graphics_context
+                        {
+                            graphics_context.renderer.reset_ssao_history();
+                        }
+
                         // Notify plugins about newly loaded scene.
                         if self.plugins_enabled {
                             for plugin in self.plugins.iter_mut() {
@@ -21,7 +21,7 @@ use crate::{
     material::shader::{PropertyKind, SamplerFallback, ShaderResource, ShaderResourceExtension},
     resource::texture::{Texture, TextureResource},
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHasher};
 use fyrox_resource::state::ResourceState;
 use fyrox_resource::untyped::ResourceKind;
 use lazy_static::lazy_static;
@@ -29,6 +29,7 @@ use std::error::Error;
 use std::{
     any::Any,
     fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
     ops::Deref,
     path::Path,
     sync::Arc,
@@ -300,6 +301,130 @@ impl Default for PropertyValue {
     }
 }
 
+/// A set of property values, that override the values of a shared material for a single object
+/// (for example, a rectangle) without cloning the material. The renderer applies the overrides on
+/// top of the material properties right before drawing, so objects with the same material and the
+/// same set of overrides are still batched together, while objects with different overrides are
+/// drawn using separate draw calls.
+///
+/// # Supported types
+///
+/// Only simple values could be used as overrides: [`PropertyValue::Float`], [`PropertyValue::Int`],
+/// [`PropertyValue::UInt`], [`PropertyValue::Vector2`], [`PropertyValue::Vector3`],
+/// [`PropertyValue::Vector4`], [`PropertyValue::Matrix2`], [`PropertyValue::Matrix3`],
+/// [`PropertyValue::Matrix4`], [`PropertyValue::Bool`] and [`PropertyValue::Color`]. Arrays and
+/// samplers are not supported, use a separate material for them.
+///
+/// # Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::sstorage::ImmutableString,
+/// #     material::{MaterialPropertyOverrides, PropertyValue},
+/// # };
+/// let mut overrides = MaterialPropertyOverrides::default();
+/// assert!(overrides.set(ImmutableString::new("dissolveAmount"), PropertyValue::Float(0.5)));
+/// ```
+#[derive(Debug, Clone, Default, Visit, Reflect)]
+pub struct MaterialPropertyOverrides {
+    properties: FxHashMap<ImmutableString, PropertyValue>,
+}
+
+impl MaterialPropertyOverrides {
+    /// Returns `true` if the given value could be used as an override. See the type docs for the
+    /// list of supported types.
+    pub fn is_supported(value: &PropertyValue) -> bool {
+        matches!(
+            value,
+            PropertyValue::Float(_)
+                | PropertyValue::Int(_)
+                | PropertyValue::UInt(_)
+                | PropertyValue::Vector2(_)
+                | PropertyValue::Vector3(_)
+                | PropertyValue::Vector4(_)
+                | PropertyValue::Matrix2(_)
+                | PropertyValue::Matrix3(_)
+                | PropertyValue::Matrix4(_)
+                | PropertyValue::Bool(_)
+                | PropertyValue::Color(_)
+        )
+    }
+
+    /// Sets a new value of a property with the given name. Returns `false` and does nothing if the
+    /// type of the value is not supported. The value must have the same type as the property in
+    /// the shader, otherwise the override will be ignored by the renderer.
+    pub fn set(&mut self, name: ImmutableString, value: PropertyValue) -> bool {
+        if Self::is_supported(&value) {
+            self.properties.insert(name, value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns a value of the override with the given name (if any).
+    pub fn get(&self, name: &ImmutableString) -> Option<&PropertyValue> {
+        self.properties.get(name)
+    }
+
+    /// Removes the override with the given name and returns its value (if any).
+    pub fn remove(&mut self, name: &ImmutableString) -> Option<PropertyValue> {
+        self.properties.remove(name)
+    }
+
+    /// Removes every override.
+    pub fn clear(&mut self) {
+        self.properties.clear()
+    }
+
+    /// Returns an iterator over every override.
+    pub fn iter(&self) -> impl Iterator<Item = (&ImmutableString, &PropertyValue)> {
+        self.properties.iter()
+    }
+
+    /// Returns total amount of overrides.
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Returns `true` if there are no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Calculates a hash of the overrides, that does not depend on the order of insertion. Equal
+    /// sets of overrides have equal hashes, it is used by the renderer to batch objects.
+    pub fn content_hash(&self) -> u64 {
+        fn write_floats(hasher: &mut FxHasher, floats: &[f32]) {
+            for float in floats {
+                hasher.write_u32(float.to_bits());
+            }
+        }
+
+        self.properties.iter().fold(0, |acc, (name, value)| {
+            let mut hasher = FxHasher::default();
+            name.hash(&mut hasher);
+            std::mem::discriminant(value).hash(&mut hasher);
+            match value {
+                PropertyValue::Float(v) => write_floats(&mut hasher, &[*v]),
+                PropertyValue::Vector2(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Vector3(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Vector4(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Matrix2(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Matrix3(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Matrix4(v) => write_floats(&mut hasher, v.as_slice()),
+                PropertyValue::Int(v) => hasher.write_i32(*v),
+                PropertyValue::UInt(v) => hasher.write_u32(*v),
+                PropertyValue::Bool(v) => hasher.write_u8(*v as u8),
+                PropertyValue::Color(v) => hasher.write(&[v.r, v.g, v.b, v.a]),
+                // Unsupported types cannot be added to the overrides.
+                _ => (),
+            }
+            acc ^ hasher.finish()
+        })
+    }
+}
+
 /// Material defines a set of values for a shader. Materials usually contains textures (diffuse,
 /// normal, height, emission, etc. maps), numerical values (floats, integers), vectors, booleans,
 /// matrices and arrays of each type, except textures. Each parameter can be changed in runtime
@@ -852,6 +977,24 @@ impl Material {
         )
     }
 
+    /// Sets the strength of the normal map. It is a shortcut for [`Self::set_property`] method with
+    /// `normalIntensity` property, that is supported by the standard shaders. `1.0` (default) means
+    /// that the normal map is used as is, `0.0` - the normal map is ignored. See
+    /// [shader module docs](self::shader) for more info.
+    pub fn set_normal_intensity(&mut self, intensity: f32) -> Result<(), MaterialError> {
+        self.set_property(
+            &ImmutableString::new("normalIntensity"),
+            PropertyValue::Float(intensity),
+        )
+    }
+
+    /// Returns the strength of the normal map, or [`None`] if the shader of the material does not
+    /// have `normalIntensity` property.
+    pub fn normal_intensity(&self) -> Option<f32> {
+        self.property_ref(&ImmutableString::new("normalIntensity"))
+            .and_then(|value| value.as_float())
+    }
+
     /// Adds missing properties with default values, removes non-existent properties. Does not modify any existing
     /// properties. This method has limited usage, that is mostly related to shader hot reloading. Returns `true`
     /// if the syncing was successful, `false` - if the shader resource is not loaded.
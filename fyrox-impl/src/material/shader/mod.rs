@@ -136,6 +136,12 @@
 //! about each rendered object. These textures then are used for physically-based lighting.
 //! Use this pass when you want the standard lighting to work with your objects.
 //!
+//! - DepthPrepass - An optional depth-only pass, that is used to fill the depth buffer before the
+//! GBuffer pass, when the depth prepass is enabled (see `Renderer::set_depth_prepass`). It must
+//! compute exactly the same vertex positions as the GBuffer pass (declare `invariant gl_Position;`
+//! in both passes) and discard the same fragments (for example, by alpha test), otherwise the
+//! object will have holes. Objects without this pass are rendered without the prepass.
+//!
 //! - Forward - A pass that draws an object directly in render target. This pass is very
 //! limiting, it does not support lighting, shadows, etc. It should be only used to render
 //! translucent objects.
@@ -157,6 +163,7 @@
 //! | fyrox_worldViewProjection  | `mat4`       | Local-to-clip-space transform.                                                                                    |
 //! | fyrox_boneMatrices         | `sampler2D`  | Array of bone matrices packed into a texture. Use `S_FetchMatrix` built-in method to fetch a matrix by its index. |
 //! | fyrox_useSkeletalAnimation | `bool`       | Whether skinned meshes is rendering or not.                                                                       |
+//! | fyrox_useInstancing        | `bool`       | Whether instanced 2D rectangles are rendering or not (see `RectangleInstance` for attribute locations).           |
 //! | fyrox_cameraPosition       | `vec3`       | Position of the camera.                                                                                           |
 //! | fyrox_usePOM               | `bool`       | Whether to use parallax mapping or not.                                                                           |
 //! | fyrox_lightPosition        | `vec3`       | Light position.                                                                                                   |
@@ -227,6 +234,26 @@
 //!
 //! Usually you don't need to get this shader manually, using of [Material::standard](super::Material::standard)
 //! is enough.
+//!
+//! ## Ambient occlusion factor
+//!
+//! Standard shader (and its two-sided version) has `aoFactor` property in `[0; 1]` range, that defines
+//! how much of screen-space ambient occlusion a surface receives. `1.0` (default) means full ambient
+//! occlusion, `0.0` - no ambient occlusion at all. It could be useful for materials like skin or foliage,
+//! that look too dark with full ambient occlusion. The value is written to the alpha channel of the
+//! material texture of the G-Buffer, so custom shaders should write `1.0` there to receive full ambient
+//! occlusion.
+//! Standard terrain shader always receives full ambient occlusion: its base layer (marked by `isBaseLayer`
+//! property, which is set by the terrain automatically) writes `1.0` and ignores its mask, while the
+//! masks of upper layers are used only as blending weights.
+//!
+//! ## Normal intensity
+//!
+//! Standard shaders (including the terrain one) have `normalIntensity` property, that scales the
+//! strength of the normal map. `1.0` (default) means that the normal map is used as is, values less
+//! than one make the details softer (`0.0` - the normal map is ignored), values greater than one
+//! make them stronger. The scaled normal is written to the G-Buffer, so it affects every light
+//! source. See [Material::set_normal_intensity](super::Material::set_normal_intensity).
 
 use crate::{
     asset::{io::ResourceIo, untyped::ResourceKind, Resource, ResourceData, SHADER_RESOURCE_UUID},
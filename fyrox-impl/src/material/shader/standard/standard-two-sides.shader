@@ -59,6 +59,14 @@
             name: "parallaxScale",
             kind: Float(0.08),
         ),
+        (
+            name: "normalIntensity",
+            kind: Float(1.0),
+        ),
+        (
+            name: "aoFactor",
+            kind: Float(1.0),
+        ),
     ],
 
     passes: [
@@ -100,6 +108,9 @@
                 uniform bool fyrox_useSkeletalAnimation;
                 uniform sampler2D fyrox_boneMatrices;
 
+                // Must match the DepthPrepass pass, otherwise "equal" depth test fails.
+                invariant gl_Position;
+
                 out vec3 position;
                 out vec3 normal;
                 out vec2 texCoord;
@@ -183,6 +194,8 @@
                 uniform vec4 diffuseColor;
                 uniform float parallaxCenter;
                 uniform float parallaxScale;
+                uniform float normalIntensity;
+                uniform float aoFactor;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
@@ -223,13 +236,16 @@
                     }
                     outColor.a = 1.0;
 
-                    vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                    vec3 n = normalize(texture(normalTexture, tc).xyz * 2.0 - 1.0);
+                    n.xy *= normalIntensity;
+                    outNormal = vec4(normalize(tangentSpace * n) * 0.5 + 0.5, 1.0);
 
                     outMaterial.x = texture(metallicTexture, tc).r;
                     outMaterial.y = texture(roughnessTexture, tc).r;
                     outMaterial.z = texture(aoTexture, tc).r;
-                    outMaterial.a = 1.0;
+                    // Alpha channel stores the amount of screen-space ambient occlusion that
+                    // the surface receives.
+                    outMaterial.a = aoFactor;
 
                     outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
                     outAmbient.a = 1.0;
@@ -238,6 +254,93 @@
                 }
                 "#,
         ),
+        (
+            name: "DepthPrepass",
+            draw_parameters: DrawParameters(
+                cull_face: None,
+                color_write: ColorMask(
+                    red: false,
+                    green: false,
+                    blue: false,
+                    alpha: false,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                // Must match the GBuffer pass, otherwise "equal" depth test of the GBuffer pass fails.
+                invariant gl_Position;
+
+                uniform mat4 fyrox_worldViewProjection;
+                uniform bool fyrox_useSkeletalAnimation;
+                uniform sampler2D fyrox_boneMatrices;
+
+                out vec2 texCoord;
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        int i0 = int(boneIndices.x);
+                        int i1 = int(boneIndices.y);
+                        int i2 = int(boneIndices.z);
+                        int i3 = int(boneIndices.w);
+
+                        mat4 m0 = S_FetchMatrix(fyrox_boneMatrices, i0);
+                        mat4 m1 = S_FetchMatrix(fyrox_boneMatrices, i1);
+                        mat4 m2 = S_FetchMatrix(fyrox_boneMatrices, i2);
+                        mat4 m3 = S_FetchMatrix(fyrox_boneMatrices, i3);
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                    }
+
+                    texCoord = vertexTexCoord;
+
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                uniform sampler2D diffuseTexture;
+                uniform vec2 texCoordScale;
+                uniform vec4 diffuseColor;
+
+                in vec2 texCoord;
+
+                void main()
+                {
+                    // Only the alpha test of the GBuffer pass is needed, depth is written automatically.
+                    if ((diffuseColor * texture(diffuseTexture, texCoord * texCoordScale)).a < 0.5) {
+                        discard;
+                    }
+                }
+                "#,
+        ),
         (
             name: "Forward",
             draw_parameters: DrawParameters(
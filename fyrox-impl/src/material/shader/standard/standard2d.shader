@@ -46,20 +46,41 @@
                 layout(location = 0) in vec3 vertexPosition;
                 layout(location = 1) in vec2 vertexTexCoord;
                 layout(location = 2) in vec4 vertexColor;
+                layout(location = 3) in vec4 vertexClipRect;
+                layout(location = 4) in vec2 vertexWrapMode;
+
+                // Per-instance attributes of instanced rectangles, the matrix occupies locations 5-8.
+                layout(location = 5) in mat4 instanceWorldMatrix;
+                layout(location = 9) in vec4 instanceColor;
+                layout(location = 10) in vec4 instanceUvRect;
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_viewProjectionMatrix;
+                uniform bool fyrox_useInstancing;
 
                 out vec2 texCoord;
                 out vec4 color;
                 out vec3 fragmentPosition;
+                flat out vec4 clipRect;
+                flat out vec2 wrapMode;
 
                 void main()
                 {
-                    texCoord = vertexTexCoord;
-                    fragmentPosition = (fyrox_worldMatrix * vec4(vertexPosition, 1.0)).xyz;
-                    gl_Position = fyrox_worldViewProjection * vec4(vertexPosition, 1.0);
-                    color = vertexColor;
+                    clipRect = vertexClipRect;
+                    wrapMode = vertexWrapMode;
+                    if (fyrox_useInstancing) {
+                        vec4 worldPosition = instanceWorldMatrix * vec4(vertexPosition, 1.0);
+                        texCoord = instanceUvRect.xy + vertexTexCoord * instanceUvRect.zw;
+                        fragmentPosition = worldPosition.xyz;
+                        gl_Position = fyrox_viewProjectionMatrix * worldPosition;
+                        color = vertexColor * instanceColor;
+                    } else {
+                        texCoord = vertexTexCoord;
+                        fragmentPosition = (fyrox_worldMatrix * vec4(vertexPosition, 1.0)).xyz;
+                        gl_Position = fyrox_worldViewProjection * vec4(vertexPosition, 1.0);
+                        color = vertexColor;
+                    }
                 }
                "#,
 
@@ -79,9 +100,39 @@
                 in vec2 texCoord;
                 in vec4 color;
                 in vec3 fragmentPosition;
+                flat in vec4 clipRect;
+                flat in vec2 wrapMode;
+
+                // Wraps texture coordinate according to TextureWrapMode index, negative mode leaves
+                // the coordinate as is.
+                float wrapTexCoord(float coord, float mode)
+                {
+                    if (mode < 0.0) {
+                        return coord;
+                    } else if (mode < 0.5) {
+                        // Repeat
+                        return fract(coord);
+                    } else if (mode < 2.5) {
+                        // ClampToEdge, ClampToBorder
+                        return clamp(coord, 0.0, 1.0);
+                    } else if (mode < 3.5) {
+                        // MirroredRepeat
+                        float m = mod(coord, 2.0);
+                        return m > 1.0 ? 2.0 - m : m;
+                    } else {
+                        // MirrorClampToEdge
+                        return clamp(abs(coord), 0.0, 1.0);
+                    }
+                }
 
                 void main()
                 {
+                    // Clip rect is stored as (min_x, min_y, max_x, max_y) in world space.
+                    if (fragmentPosition.x < clipRect.x || fragmentPosition.y < clipRect.y ||
+                        fragmentPosition.x > clipRect.z || fragmentPosition.y > clipRect.w) {
+                        discard;
+                    }
+
                     vec3 lighting = fyrox_ambientLightColor.xyz;
                     for(int i = 0; i < fyrox_lightCount; ++i) {
                         // "Unpack" light parameters.
@@ -102,7 +153,11 @@
                         lighting += lightColor * (distanceAttenuation * directionalAttenuation);
                     }
 
-                    FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(texture(diffuseTexture, texCoord));
+                    // Use gradients of the original coordinates to prevent seams at wrapping boundaries.
+                    vec2 wrappedTexCoord = vec2(wrapTexCoord(texCoord.x, wrapMode.x), wrapTexCoord(texCoord.y, wrapMode.y));
+                    vec4 diffuseColor = textureGrad(diffuseTexture, wrappedTexCoord, dFdx(texCoord), dFdy(texCoord));
+
+                    FragColor = vec4(lighting, 1.0) * color * S_SRGBToLinear(diffuseColor);
                 }
                "#,
         )
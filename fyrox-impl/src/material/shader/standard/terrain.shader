@@ -71,6 +71,14 @@
             name: "parallaxScale",
             kind: Float(0.08),
         ),
+        (
+            name: "normalIntensity",
+            kind: Float(1.0),
+        ),
+        (
+            name: "isBaseLayer",
+            kind: Bool(false),
+        ),
     ],
 
     passes: [
@@ -172,6 +180,8 @@
                 uniform vec4 diffuseColor;
                 uniform float parallaxCenter;
                 uniform float parallaxScale;
+                uniform float normalIntensity;
+                uniform bool isBaseLayer;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
@@ -206,8 +216,9 @@
 
                     outColor = diffuseColor * texture(diffuseTexture, tc);
 
-                    vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
-                    outNormal = vec4(normalize(tangentSpace * n.xyz) * 0.5 + 0.5, 1.0);
+                    vec3 n = normalize(texture(normalTexture, tc).xyz * 2.0 - 1.0);
+                    n.xy *= normalIntensity;
+                    outNormal = vec4(normalize(tangentSpace * n) * 0.5 + 0.5, 1.0);
 
                     outMaterial.x = texture(metallicTexture, tc).r;
                     outMaterial.y = texture(roughnessTexture, tc).r;
@@ -224,7 +235,11 @@
                     outColor.a = mask;
                     outAmbient.a = mask;
                     outNormal.a = mask;
-                    outMaterial.a = mask;
+                    // Alpha channel of the material texture stores the amount of screen-space ambient
+                    // occlusion that the surface receives, the terrain receives full occlusion. Upper
+                    // layers still need the mask as blending weight, but the base layer is opaque and
+                    // writes 1.0, so the alpha stays 1.0 after Max blending of the upper layers.
+                    outMaterial.a = isBaseLayer ? 1.0 : mask;
                 }
                 "#,
         ),
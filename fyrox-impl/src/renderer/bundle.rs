@@ -3,20 +3,28 @@
 use crate::scene::node::RdcControlFlow;
 use crate::{
     core::{
-        algebra::{Matrix4, Vector3},
-        math::frustum::Frustum,
+        algebra::{Matrix4, Point3, Vector2, Vector3},
+        math::{frustum::Frustum, Rect},
         pool::Handle,
+        reflect::prelude::*,
         sstorage::ImmutableString,
+        type_traits::prelude::*,
+        value_as_u8_slice,
+        visitor::prelude::*,
     },
     graph::BaseSceneGraph,
-    material::MaterialResource,
-    renderer::{cache::TimeToLive, framework::geometry_buffer::ElementRange},
+    material::{MaterialPropertyOverrides, MaterialResource},
+    renderer::{
+        cache::TimeToLive,
+        framework::{geometry_buffer::ElementRange, state::PolygonFillMode},
+    },
     scene::{
+        dim2::rectangle::{RectangleInstance, RectangleVertex},
         graph::Graph,
         mesh::{
             buffer::{
                 BytesStorage, TriangleBuffer, TriangleBufferRefMut, VertexAttributeDescriptor,
-                VertexBuffer, VertexBufferRefMut,
+                VertexBuffer, VertexBufferRefMut, VertexTrait,
             },
             surface::{SurfaceData, SurfaceResource},
             RenderPath,
@@ -31,6 +39,7 @@ use std::{
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
 };
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 /// Observer info contains all the data, that describes an observer. It could be a real camera, light source's
 /// "virtual camera" that is used for shadow mapping, etc.
@@ -45,6 +54,40 @@ pub struct ObserverInfo {
     pub view_matrix: Matrix4<f32>,
     /// Projection matrix of the observer.
     pub projection_matrix: Matrix4<f32>,
+    /// Viewport of the observer in pixels.
+    pub viewport: Rect<i32>,
+}
+
+/// Defines how a scene node calculates its sorting index, which is used to define the order of
+/// rendering of transparent objects.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    TypeUuidProvider,
+)]
+#[type_uuid(id = "4a5a1a3e-94cf-4a5e-9c0b-3c4bd8e0f2d7")]
+pub enum SortingMode {
+    /// Objects are sorted by their distance to the observer, far objects are rendered first.
+    #[default]
+    Distance,
+    /// Objects are sorted by the layer first (objects with lower layer are rendered first), and then
+    /// by Y coordinate in world space (objects with higher Y coordinate are rendered first). This
+    /// mode is useful for isometric 2D games, where objects that are "higher" on the screen are
+    /// further from the observer.
+    IsometricY {
+        /// Sorting layer of the object.
+        layer: u16,
+    },
 }
 
 /// Render context is used to collect render data from the scene nodes. It provides all required information about
@@ -71,6 +114,8 @@ pub struct RenderContext<'a> {
     pub graph: &'a Graph,
     /// A name of the render pass for which the context was created for.
     pub render_pass_name: &'a ImmutableString,
+    /// Viewport of the observer in pixels. Could be used to snap vertices to pixel grid.
+    pub viewport: Rect<i32>,
 }
 
 impl<'a> RenderContext<'a> {
@@ -86,6 +131,78 @@ impl<'a> RenderContext<'a> {
                 .z
                 * granularity) as u64
     }
+
+    /// Calculates sorting index that sorts objects by the given layer first and then by Y coordinate
+    /// of the given point in world space. Objects with lower layer are rendered first, objects on the
+    /// same layer with higher Y coordinate are rendered first. See [`SortingMode::IsometricY`] for
+    /// more info.
+    pub fn calculate_isometric_sorting_index(
+        &self,
+        layer: u16,
+        global_position: Vector3<f32>,
+    ) -> u64 {
+        const Y_BITS: u32 = 48;
+        const Y_MASK: i64 = (1 << Y_BITS) - 1;
+        let granularity = 1000.0;
+        let y_key = ((-global_position.y * granularity) as i64 + (1 << (Y_BITS - 1)))
+            .clamp(0, Y_MASK) as u64;
+        ((layer as u64) << Y_BITS) | y_key
+    }
+
+    /// Converts an explicit integer sort key to a sorting index. Objects with lower keys are rendered
+    /// first. Unlike [`Self::calculate_sorting_index`], the result does not depend on the position of
+    /// the observer, so the order is stable. See [`Self::push_with_sort_key`] for more info.
+    ///
+    /// # Limitations
+    ///
+    /// Sorting indices of every mode share the same range of values, so the relative order of the
+    /// objects with sort keys and the objects sorted by [`Self::calculate_sorting_index`] or
+    /// [`Self::calculate_isometric_sorting_index`] is not meaningful. For example, the key `0`
+    /// maps to `2^63`, which is a valid distance-based index as well. Use sort keys for every
+    /// object, that must be ordered relative to the keyed objects.
+    pub fn sorting_index_from_key(key: i64) -> u64 {
+        // Flip the sign bit, so negative keys go before positive ones.
+        (key as u64) ^ (1 << 63)
+    }
+
+    /// Adds the given triangles to the storage using an explicit integer sort key. Objects with
+    /// lower keys are rendered first, triangles with equal keys are rendered in the order they
+    /// were pushed. It is mostly useful for 2D games, where the drawing order must be deterministic
+    /// and must not depend on floating-point distance to the camera. Triangles with different keys
+    /// are never merged in the same bundle. See [`BundleParameters::with_sort_key`] for more info.
+    pub fn push_with_sort_key(
+        &mut self,
+        key: i64,
+        layout: &[VertexAttributeDescriptor],
+        material: &MaterialResource,
+        material_overrides: &MaterialPropertyOverrides,
+        render_path: RenderPath,
+        node_handle: Handle<Node>,
+        func: &mut dyn FnMut(VertexBufferRefMut, TriangleBufferRefMut),
+    ) {
+        self.storage.push_triangles(
+            layout,
+            &BundleParameters::new(material, render_path, 0)
+                .with_material_overrides(material_overrides)
+                .with_sort_key(key),
+            node_handle,
+            func,
+        )
+    }
+
+    /// Calculates sorting index of the given point using the given sorting mode.
+    pub fn calculate_sorting_index_with_mode(
+        &self,
+        mode: SortingMode,
+        global_position: Vector3<f32>,
+    ) -> u64 {
+        match mode {
+            SortingMode::Distance => self.calculate_sorting_index(global_position),
+            SortingMode::IsometricY { layer } => {
+                self.calculate_isometric_sorting_index(layer, global_position)
+            }
+        }
+    }
 }
 
 /// Persistent identifier marks drawing data, telling the renderer that the data is the same, no matter from which
@@ -128,6 +245,23 @@ pub struct SurfaceInstanceData {
     pub node_handle: Handle<Node>,
 }
 
+/// A set of rectangle instances that share the same material, it is drawn in a single instanced
+/// draw call. See [`RenderDataBundleStorageTrait::push_rectangle_instances`] for more info.
+pub struct RectangleInstanceBatch {
+    /// A material that is shared across all instances.
+    pub material: MaterialResource,
+    /// A set of instances.
+    pub instances: Vec<RectangleInstance>,
+    sort_index: u64,
+}
+
+impl RectangleInstanceBatch {
+    /// Returns the sort index of the batch, that defines the order of rendering.
+    pub fn sort_index(&self) -> u64 {
+        self.sort_index
+    }
+}
+
 /// A set of surface instances that share the same vertex/index data and a material.
 pub struct RenderDataBundle {
     /// A pointer to shared surface data.
@@ -145,9 +279,22 @@ pub struct RenderDataBundle {
     pub render_path: RenderPath,
     /// A decal layer index of the bundle.
     pub decal_layer_index: u8,
+    /// A set of material property values, that are applied on top of the material properties. It
+    /// is shared across all instances, see [`MaterialPropertyOverrides`] docs for more info.
+    pub material_overrides: MaterialPropertyOverrides,
+    /// Defines how polygons of the bundle are rasterized. It is applied on top of the polygon fill
+    /// mode of a render pass, only if it is not [`PolygonFillMode::Fill`].
+    pub polygon_fill_mode: PolygonFillMode,
     sort_index: u64,
 }
 
+impl RenderDataBundle {
+    /// Returns the sort index of the bundle, that defines the order of rendering.
+    pub fn sort_index(&self) -> u64 {
+        self.sort_index
+    }
+}
+
 impl Debug for RenderDataBundle {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -159,16 +306,111 @@ impl Debug for RenderDataBundle {
     }
 }
 
+/// A set of parameters, that defines a bundle for the geometry that is added to a bundle storage
+/// (see [`RenderDataBundleStorageTrait`]). Geometry with equal parameters is merged in the same
+/// bundle, if at least one parameter is different, a separate bundle is used.
+#[derive(Copy, Clone, Debug)]
+pub struct BundleParameters<'a> {
+    /// A material that is shared across all instances of the bundle.
+    pub material: &'a MaterialResource,
+    /// A set of material property values, that are applied on top of the material properties.
+    /// See [`MaterialPropertyOverrides`] docs for more info.
+    pub material_overrides: Option<&'a MaterialPropertyOverrides>,
+    /// A render path of the bundle.
+    pub render_path: RenderPath,
+    /// A decal layer index of the bundle.
+    pub decal_layer_index: u8,
+    /// A sort index of the bundle, that defines the order of rendering. See
+    /// [`RenderContext::calculate_sorting_index`] for more info.
+    pub sort_index: u64,
+    /// If `true`, geometry with different sort indices is put in separate bundles, so the sort
+    /// index defines the drawing order of every piece of geometry, even if it shares all other
+    /// parameters. Otherwise the sort index of the bundle is taken from the first piece of
+    /// geometry added to it.
+    pub separate_by_sort_index: bool,
+    /// Defines how polygons of the bundle are rasterized. It is mostly used for inspection, for
+    /// example to show specific surfaces of a mesh in wireframe mode.
+    pub polygon_fill_mode: PolygonFillMode,
+}
+
+impl<'a> BundleParameters<'a> {
+    /// Creates a new set of parameters with the given material, render path and sort index. The
+    /// rest of the parameters have their default values: no material property overrides, decal
+    /// layer `0`, no separation by the sort index and [`PolygonFillMode::Fill`].
+    pub fn new(material: &'a MaterialResource, render_path: RenderPath, sort_index: u64) -> Self {
+        Self {
+            material,
+            material_overrides: None,
+            render_path,
+            decal_layer_index: 0,
+            sort_index,
+            separate_by_sort_index: false,
+            polygon_fill_mode: PolygonFillMode::Fill,
+        }
+    }
+
+    /// Sets the material property overrides of the bundle. Empty overrides are the same as no
+    /// overrides.
+    pub fn with_material_overrides(
+        mut self,
+        material_overrides: &'a MaterialPropertyOverrides,
+    ) -> Self {
+        self.material_overrides = Some(material_overrides);
+        self
+    }
+
+    /// Sets the decal layer index of the bundle.
+    pub fn with_decal_layer_index(mut self, decal_layer_index: u8) -> Self {
+        self.decal_layer_index = decal_layer_index;
+        self
+    }
+
+    /// Sets an explicit integer sort key (see [`RenderContext::sorting_index_from_key`]) instead of
+    /// the sort index. Geometry with different keys is put in separate bundles, so the key defines
+    /// the drawing order of every piece of geometry, even if it shares a material with other
+    /// geometry.
+    pub fn with_sort_key(mut self, sort_key: i64) -> Self {
+        self.sort_index = RenderContext::sorting_index_from_key(sort_key);
+        self.separate_by_sort_index = true;
+        self
+    }
+
+    /// Defines whether geometry with different sort indices is put in separate bundles. See
+    /// [`Self::separate_by_sort_index`] for more info.
+    pub fn with_separation_by_sort_index(mut self, separate_by_sort_index: bool) -> Self {
+        self.separate_by_sort_index = separate_by_sort_index;
+        self
+    }
+
+    /// Sets the polygon fill mode of the bundle.
+    pub fn with_polygon_fill_mode(mut self, polygon_fill_mode: PolygonFillMode) -> Self {
+        self.polygon_fill_mode = polygon_fill_mode;
+        self
+    }
+
+    fn write_hash(&self, hasher: &mut impl Hasher) {
+        hasher.write_u64(self.material.key());
+        hasher.write_u8(self.decal_layer_index);
+        hasher.write_u32(self.render_path as u32);
+        if let Some(material_overrides) = self.material_overrides {
+            if !material_overrides.is_empty() {
+                hasher.write_u64(material_overrides.content_hash());
+            }
+        }
+        if self.separate_by_sort_index {
+            hasher.write_u64(self.sort_index);
+        }
+        if self.polygon_fill_mode != PolygonFillMode::Fill {
+            hasher.write_u32(self.polygon_fill_mode as u32);
+        }
+    }
+}
+
 /// A trait for an entity that can collect render data.
 pub trait RenderDataBundleStorageTrait {
     /// Adds a new mesh to the bundle storage using the given set of vertices and triangles. This
-    /// method automatically creates a render bundle according to a hash of the following parameters:
-    ///
-    /// - Material
-    /// - Vertex Type
-    /// - Render Path
-    /// - Skinning
-    /// - Decal Layer Index
+    /// method automatically creates a render bundle according to a hash of the vertex type and
+    /// the given bundle parameters (see [`BundleParameters`]).
     ///
     /// If one of these parameters is different, then a new bundle will be created and used to store
     /// the given vertices and indices. If an appropriate bundle exists, the the method will store
@@ -179,35 +421,92 @@ pub trait RenderDataBundleStorageTrait {
     /// This method is used to reduce amount of draw calls of underlying GAPI, by merging small
     /// portions of data into one big block that shares drawing parameters and can be rendered in
     /// a single draw call. The vertices in this case should be pre-processed by applying world
-    /// transform to them. This is so-called dynamic batching.
+    /// transform to them (so the bundles are never skinned). This is so-called dynamic batching.
     ///
     /// Do not use this method if you have a mesh with lots of vertices and triangles, because
     /// pre-processing them on CPU could take more time than rendering them directly on GPU one-by-one.
     fn push_triangles(
         &mut self,
         layout: &[VertexAttributeDescriptor],
-        material: &MaterialResource,
-        render_path: RenderPath,
-        decal_layer_index: u8,
-        sort_index: u64,
-        is_skinned: bool,
+        params: &BundleParameters,
         node_handle: Handle<Node>,
         func: &mut dyn FnMut(VertexBufferRefMut, TriangleBufferRefMut),
     );
 
     /// Adds a new surface instance to the storage. The method will automatically put the instance
-    /// in the appropriate bundle. Bundle selection is done using the material, surface data, render
-    /// path, decal layer index, skinning flag. If only one of these parameters is different, then
-    /// the surface instance will be put in a separate bundle.
+    /// in the appropriate bundle. Bundle selection is done using the surface data, skinning flag
+    /// (an instance is skinned if it has bone matrices) and the given bundle parameters (see
+    /// [`BundleParameters`]). If only one of these parameters is different, then the surface
+    /// instance will be put in a separate bundle.
     fn push(
         &mut self,
         data: &SurfaceResource,
-        material: &MaterialResource,
-        render_path: RenderPath,
-        decal_layer_index: u8,
-        sort_index: u64,
+        params: &BundleParameters,
         instance_data: SurfaceInstanceData,
     );
+
+    /// Adds a batch of rectangle instances to the storage. Every instance is a unit quad (see
+    /// [`RectangleInstance::unit_quad`]) with its own world transform, color and texture
+    /// coordinates rectangle. Instances that share the material and the sort index are put in the
+    /// same batch, which is rendered using a single instanced draw call. It is much faster than
+    /// [`Self::push_triangles`] for large amounts of rectangles (for example, for particle-like
+    /// effects), because there is no need to generate vertices on CPU.
+    ///
+    /// Instanced batches are rendered in the forward pass, interleaved with regular bundles by the
+    /// sort index. `Forward` render pass of the material's shader is used with `fyrox_useInstancing`
+    /// built-in uniform set to `true`, the pass receives [`RectangleVertex`] attributes of the unit
+    /// quad at locations `0..=4` and [`RectangleInstance`] attributes at locations `5..=10`. The
+    /// standard 2D shader supports instancing out of the box, batches with materials, which shaders
+    /// do not declare `fyrox_useInstancing` uniform, are skipped.
+    ///
+    /// Default implementation transforms the instances on CPU and adds them using
+    /// [`Self::push_triangles`], storages that support instancing must override this method.
+    fn push_rectangle_instances(
+        &mut self,
+        material: &MaterialResource,
+        sort_index: u64,
+        node_handle: Handle<Node>,
+        instances: &[RectangleInstance],
+    ) {
+        let quad = RectangleInstance::unit_quad();
+        let triangles = RectangleInstance::unit_quad_triangles();
+
+        self.push_triangles(
+            RectangleVertex::layout(),
+            &BundleParameters::new(material, RenderPath::Forward, sort_index),
+            node_handle,
+            &mut |mut vertex_buffer, mut triangle_buffer| {
+                for instance in instances {
+                    let start_vertex_index = vertex_buffer.vertex_count();
+
+                    for vertex in quad.iter() {
+                        let vertex = RectangleVertex {
+                            position: instance
+                                .world_transform
+                                .transform_point(&Point3::from(vertex.position))
+                                .coords,
+                            tex_coord: instance.uv_rect.xy()
+                                + vertex.tex_coord.component_mul(&Vector2::new(
+                                    instance.uv_rect.z,
+                                    instance.uv_rect.w,
+                                )),
+                            color: instance.color,
+                            ..*vertex
+                        };
+
+                        vertex_buffer
+                            .push_vertex_raw(value_as_u8_slice(&vertex))
+                            .unwrap();
+                    }
+
+                    triangle_buffer.push_triangles_iter_with_offset(
+                        start_vertex_index,
+                        triangles.iter().cloned(),
+                    );
+                }
+            },
+        )
+    }
 }
 
 /// Bundle storage handles bundle generation for a scene before rendering. It is used to optimize
@@ -217,6 +516,9 @@ pub struct RenderDataBundleStorage {
     bundle_map: FxHashMap<u64, usize>,
     /// A sorted list of bundles.
     pub bundles: Vec<RenderDataBundle>,
+    rectangle_batch_map: FxHashMap<u64, usize>,
+    /// A sorted list of instanced rectangle batches.
+    pub rectangle_batches: Vec<RectangleInstanceBatch>,
 }
 
 impl RenderDataBundleStorage {
@@ -233,6 +535,8 @@ impl RenderDataBundleStorage {
         let mut storage = Self {
             bundle_map: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher::default()),
             bundles: Vec::with_capacity(capacity),
+            rectangle_batch_map: Default::default(),
+            rectangle_batches: Default::default(),
         };
 
         let mut lod_filter = vec![true; graph.capacity() as usize];
@@ -270,6 +574,7 @@ impl RenderDataBundleStorage {
             storage: &mut storage,
             graph,
             render_pass_name: &render_pass_name,
+            viewport: observer_info.viewport,
         };
 
         let mut stack = Vec::with_capacity(capacity / 4);
@@ -288,21 +593,18 @@ impl RenderDataBundleStorage {
         storage
     }
 
-    /// Sorts the bundles by their respective sort index.
+    /// Sorts the bundles (and instanced rectangle batches) by their respective sort index. Bundles
+    /// with equal sort index keep the order in which they were created.
     pub fn sort(&mut self) {
-        self.bundles.sort_unstable_by_key(|b| b.sort_index);
+        self.bundles.sort_by_key(|b| b.sort_index);
+        self.rectangle_batches.sort_by_key(|b| b.sort_index);
     }
 }
 
 impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
     /// Adds a new mesh to the bundle storage using the given set of vertices and triangles. This
-    /// method automatically creates a render bundle according to a hash of the following parameters:
-    ///
-    /// - Material
-    /// - Vertex Type
-    /// - Render Path
-    /// - Skinning
-    /// - Decal Layer Index
+    /// method automatically creates a render bundle according to a hash of the vertex type and
+    /// the given bundle parameters (see [`BundleParameters`]).
     ///
     /// If one of these parameters is different, then a new bundle will be created and used to store
     /// the given vertices and indices. If an appropriate bundle exists, the the method will store
@@ -320,20 +622,13 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
     fn push_triangles(
         &mut self,
         layout: &[VertexAttributeDescriptor],
-        material: &MaterialResource,
-        render_path: RenderPath,
-        decal_layer_index: u8,
-        sort_index: u64,
-        is_skinned: bool,
+        params: &BundleParameters,
         node_handle: Handle<Node>,
         func: &mut dyn FnMut(VertexBufferRefMut, TriangleBufferRefMut),
     ) {
         let mut hasher = FxHasher::default();
-        hasher.write_u64(material.key());
         layout.hash(&mut hasher);
-        hasher.write_u8(if is_skinned { 1 } else { 0 });
-        hasher.write_u8(decal_layer_index);
-        hasher.write_u32(render_path as u32);
+        params.write_hash(&mut hasher);
         let key = hasher.finish();
 
         let bundle = if let Some(&bundle_index) = self.bundle_map.get(&key) {
@@ -362,7 +657,7 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
             let persistent_identifier = PersistentIdentifier::new_combined(&data, node_handle, 0);
             self.bundles.push(RenderDataBundle {
                 data,
-                sort_index,
+                sort_index: params.sort_index,
                 instances: vec![
                     // Each bundle must have at least one instance to be rendered.
                     SurfaceInstanceData {
@@ -375,10 +670,12 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
                         node_handle,
                     },
                 ],
-                material: material.clone(),
-                is_skinned,
-                render_path,
-                decal_layer_index,
+                material: params.material.clone(),
+                is_skinned: false,
+                render_path: params.render_path,
+                decal_layer_index: params.decal_layer_index,
+                material_overrides: params.material_overrides.cloned().unwrap_or_default(),
+                polygon_fill_mode: params.polygon_fill_mode,
                 // Temporary buffer lives one frame.
                 time_to_live: TimeToLive(0.0),
             });
@@ -394,26 +691,22 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
         func(vertex_buffer, triangle_buffer);
     }
 
-    /// Adds a new surface instance to the storage. The method will automatically put the instance in the appropriate
-    /// bundle. Bundle selection is done using the material, surface data, render path, decal layer index, skinning flag.
-    /// If only one of these parameters is different, then the surface instance will be put in a separate bundle.
+    /// Adds a new surface instance to the storage. The method will automatically put the instance
+    /// in the appropriate bundle. Bundle selection is done using the surface data, skinning flag and
+    /// the given bundle parameters (see [`BundleParameters`]). If only one of these parameters is
+    /// different, then the surface instance will be put in a separate bundle.
     fn push(
         &mut self,
         data: &SurfaceResource,
-        material: &MaterialResource,
-        render_path: RenderPath,
-        decal_layer_index: u8,
-        sort_index: u64,
+        params: &BundleParameters,
         instance_data: SurfaceInstanceData,
     ) {
         let is_skinned = !instance_data.bone_matrices.is_empty();
 
         let mut hasher = FxHasher::default();
-        hasher.write_u64(material.key());
         hasher.write_u64(data.key());
         hasher.write_u8(if is_skinned { 1 } else { 0 });
-        hasher.write_u8(decal_layer_index);
-        hasher.write_u32(render_path as u32);
+        params.write_hash(&mut hasher);
         let key = hasher.finish();
 
         let bundle = if let Some(&bundle_index) = self.bundle_map.get(&key) {
@@ -422,12 +715,14 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
             self.bundle_map.insert(key, self.bundles.len());
             self.bundles.push(RenderDataBundle {
                 data: data.clone(),
-                sort_index,
+                sort_index: params.sort_index,
                 instances: Default::default(),
-                material: material.clone(),
+                material: params.material.clone(),
                 is_skinned,
-                render_path,
-                decal_layer_index,
+                render_path: params.render_path,
+                decal_layer_index: params.decal_layer_index,
+                material_overrides: params.material_overrides.cloned().unwrap_or_default(),
+                polygon_fill_mode: params.polygon_fill_mode,
                 time_to_live: Default::default(),
             });
             self.bundles.last_mut().unwrap()
@@ -435,4 +730,122 @@ impl RenderDataBundleStorageTrait for RenderDataBundleStorage {
 
         bundle.instances.push(instance_data)
     }
+
+    fn push_rectangle_instances(
+        &mut self,
+        material: &MaterialResource,
+        sort_index: u64,
+        _node_handle: Handle<Node>,
+        instances: &[RectangleInstance],
+    ) {
+        let mut hasher = FxHasher::default();
+        hasher.write_u64(material.key());
+        hasher.write_u64(sort_index);
+        let key = hasher.finish();
+
+        let batch = if let Some(&batch_index) = self.rectangle_batch_map.get(&key) {
+            self.rectangle_batches.get_mut(batch_index).unwrap()
+        } else {
+            self.rectangle_batch_map
+                .insert(key, self.rectangle_batches.len());
+            self.rectangle_batches.push(RectangleInstanceBatch {
+                material: material.clone(),
+                instances: Vec::with_capacity(instances.len()),
+                sort_index,
+            });
+            self.rectangle_batches.last_mut().unwrap()
+        };
+
+        batch.instances.extend_from_slice(instances)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{pool::Handle, sstorage::ImmutableString},
+        material::{Material, MaterialPropertyOverrides, MaterialResource, PropertyValue},
+        renderer::{
+            bundle::{
+                BundleParameters, RenderContext, RenderDataBundleStorage,
+                RenderDataBundleStorageTrait,
+            },
+            framework::state::PolygonFillMode,
+        },
+        scene::{
+            dim2::rectangle::{RectangleInstance, RectangleVertex},
+            mesh::{buffer::VertexTrait, RenderPath},
+        },
+    };
+
+    #[test]
+    fn test_sorting_index_from_key() {
+        let keys = [i64::MIN, -100, -1, 0, 1, 100, i64::MAX];
+        for pair in keys.windows(2) {
+            assert!(
+                RenderContext::sorting_index_from_key(pair[0])
+                    < RenderContext::sorting_index_from_key(pair[1])
+            );
+        }
+    }
+
+    #[test]
+    fn test_push_triangles_with_parameters() {
+        let material = MaterialResource::new_ok(Default::default(), Material::standard_2d());
+        let mut overrides = MaterialPropertyOverrides::default();
+        overrides.set(ImmutableString::new("dissolve"), PropertyValue::Float(0.5));
+
+        let base = BundleParameters::new(&material, RenderPath::Forward, 0);
+        let variants = [
+            base,
+            base.with_material_overrides(&overrides),
+            base.with_polygon_fill_mode(PolygonFillMode::Line),
+            base.with_decal_layer_index(1),
+            base.with_sort_key(-1),
+            base.with_sort_key(1),
+        ];
+
+        let mut storage = RenderDataBundleStorage::default();
+        for _ in 0..2 {
+            for params in variants.iter() {
+                storage.push_triangles(
+                    RectangleVertex::layout(),
+                    params,
+                    Handle::NONE,
+                    &mut |_, _| {},
+                );
+            }
+        }
+        storage.sort();
+
+        assert_eq!(storage.bundles.len(), variants.len());
+        assert!(storage
+            .bundles
+            .iter()
+            .any(|bundle| bundle.material_overrides.content_hash() == overrides.content_hash()));
+        assert!(storage
+            .bundles
+            .iter()
+            .any(|bundle| bundle.polygon_fill_mode == PolygonFillMode::Line));
+    }
+
+    #[test]
+    fn test_push_rectangle_instances() {
+        let material_a = MaterialResource::new_ok(Default::default(), Material::standard_2d());
+        let material_b = MaterialResource::new_ok(Default::default(), Material::standard_2d());
+
+        let mut storage = RenderDataBundleStorage::default();
+        let instances = [RectangleInstance::default(); 3];
+        storage.push_rectangle_instances(&material_a, 1, Handle::NONE, &instances);
+        storage.push_rectangle_instances(&material_b, 0, Handle::NONE, &instances[..1]);
+        storage.push_rectangle_instances(&material_a, 1, Handle::NONE, &instances[..2]);
+        storage.sort();
+
+        assert!(storage.bundles.is_empty());
+        assert_eq!(storage.rectangle_batches.len(), 2);
+        assert_eq!(storage.rectangle_batches[0].material, material_b);
+        assert_eq!(storage.rectangle_batches[0].instances.len(), 1);
+        assert_eq!(storage.rectangle_batches[1].material, material_a);
+        assert_eq!(storage.rectangle_batches[1].instances.len(), 5);
+    }
 }
@@ -17,7 +17,7 @@ use crate::{
         gpu_program::{GpuProgram, UniformLocation},
         state::PipelineState,
     },
-    renderer::RenderPassStatistics,
+    renderer::{frame_ring::FrameRing, RenderPassStatistics},
     scene::{camera::Camera, debug::SceneDrawingContext},
 };
 
@@ -29,7 +29,8 @@ struct Vertex {
 
 /// See module docs.
 pub struct DebugRenderer {
-    geometry: GeometryBuffer,
+    // One copy per frame in flight.
+    geometry: FrameRing<GeometryBuffer>,
     vertices: Vec<Vertex>,
     line_indices: Vec<[u32; 2]>,
     shader: DebugShader,
@@ -54,34 +55,52 @@ impl DebugShader {
     }
 }
 
-impl DebugRenderer {
-    pub(crate) fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
-        let geometry = GeometryBufferBuilder::new(ElementKind::Line)
-            .with_buffer_builder(
-                BufferBuilder::new::<Vertex>(GeometryBufferKind::DynamicDraw, None)
-                    .with_attribute(AttributeDefinition {
-                        location: 0,
-                        divisor: 0,
-                        kind: AttributeKind::Float3,
-                        normalized: false,
-                    })
-                    .with_attribute(AttributeDefinition {
-                        location: 1,
-                        kind: AttributeKind::UnsignedByte4,
-                        normalized: true,
-                        divisor: 0,
-                    }),
-            )
-            .build(state)?;
+fn make_geometry(state: &PipelineState) -> Result<GeometryBuffer, FrameworkError> {
+    GeometryBufferBuilder::new(ElementKind::Line)
+        .with_buffer_builder(
+            BufferBuilder::new::<Vertex>(GeometryBufferKind::DynamicDraw, None)
+                .with_attribute(AttributeDefinition {
+                    location: 0,
+                    divisor: 0,
+                    kind: AttributeKind::Float3,
+                    normalized: false,
+                })
+                .with_attribute(AttributeDefinition {
+                    location: 1,
+                    kind: AttributeKind::UnsignedByte4,
+                    normalized: true,
+                    divisor: 0,
+                }),
+        )
+        .build(state)
+}
 
+impl DebugRenderer {
+    pub(crate) fn new(
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<Self, FrameworkError> {
         Ok(Self {
-            geometry,
+            geometry: FrameRing::new(frames_in_flight, || make_geometry(state))?,
             shader: DebugShader::new(state)?,
             vertices: Default::default(),
             line_indices: Default::default(),
         })
     }
 
+    pub(crate) fn set_frames_in_flight(
+        &mut self,
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<(), FrameworkError> {
+        self.geometry
+            .resize(frames_in_flight, || make_geometry(state))
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.geometry.advance();
+    }
+
     pub(crate) fn render(
         &mut self,
         state: &PipelineState,
@@ -111,11 +130,12 @@ impl DebugRenderer {
             self.line_indices.push([i, i + 1]);
             i += 2;
         }
-        self.geometry.set_buffer_data(state, 0, &self.vertices);
-        self.geometry.bind(state).set_lines(&self.line_indices);
+        let geometry = self.geometry.current_mut();
+        geometry.set_buffer_data(state, 0, &self.vertices);
+        geometry.bind(state).set_lines(&self.line_indices);
 
         statistics += framebuffer.draw(
-            &self.geometry,
+            geometry,
             state,
             viewport,
             &self.shader.program,
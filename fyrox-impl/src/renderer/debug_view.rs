@@ -0,0 +1,374 @@
+//! Debug views allows you to see intermediate results of the renderer (G-Buffer contents, SSAO,
+//! etc.) instead of the final frame. See [`DebugView`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Vector2, color::Color, math::Rect, reflect::prelude::*, scope_profile,
+        sstorage::ImmutableString, uuid_provider,
+    },
+    renderer::{
+        bundle::RenderDataBundleStorage,
+        cache::shader::ShaderCache,
+        framework::{
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BlendParameters, DrawParameters, FrameBuffer,
+            },
+            geometry_buffer::{
+                BufferBuilder, DrawCallStatistics, ElementKind, ElementRange, GeometryBuffer,
+                GeometryBufferBuilder, GeometryBufferKind,
+            },
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            state::{BlendFactor, BlendFunc, PipelineState},
+        },
+        make_viewport_matrix, GeometryCache, RenderPassStatistics, GBUFFER_PASS_NAME,
+    },
+    scene::{
+        camera::{Camera, Projection},
+        dim2::rectangle::RectangleInstance,
+        mesh::buffer::VertexBuffer,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, rc::Rc};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// Debug view defines what will be shown instead of the final frame of a scene. It is useful to
+/// tune rendering parameters (for example, SSAO radius) or to find issues with content (broken
+/// normal maps, etc.). Debug geometry and custom LDR render passes are still drawn on top of
+/// the debug view.
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum DebugView {
+    /// Scene is rendered normally.
+    #[default]
+    None,
+    /// Blurred screen space ambient occlusion map. Keep in mind that the map is updated only if
+    /// SSAO is enabled in quality settings.
+    Ssao,
+    /// World-space normals from G-Buffer, packed in `[0; 1]` range.
+    Normals,
+    /// Linearized depth from G-Buffer, where black is the near clipping plane and white is the
+    /// far clipping plane.
+    Depth,
+    /// Albedo (diffuse color) from G-Buffer, without any lighting.
+    Albedo,
+    /// Overdraw heatmap, that shows how many times each pixel was drawn. Every surface of the
+    /// scene (including instanced rectangles) is drawn additively without depth test, so the count
+    /// includes fragments that will be rejected by depth test later. Black means no overdraw,
+    /// blue-green-yellow-red colors are used for increasing amount of overdraw and white means
+    /// [`MAX_OVERDRAW`] draws and more. It is useful to find fill-rate problems, especially with
+    /// transparent surfaces and many overlapping 2D rectangles.
+    ///
+    /// Shaders of the materials are not used, so skinned meshes are drawn in their bind pose and
+    /// fragments, that are discarded by materials (alpha test, transparent parts of sprites, etc.),
+    /// are counted as well. Such fragments still cost fill-rate, because they are shaded.
+    Overdraw,
+    /// Raw screen space ambient occlusion map, before it is blurred. It is useful to tune radius
+    /// and bias of SSAO. Keep in mind that the map is updated only if SSAO is enabled in quality
+    /// settings.
+    SsaoRaw,
+}
+
+/// Amount of overdraw, that is shown as white color by [`DebugView::Overdraw`].
+pub const MAX_OVERDRAW: f32 = 16.0;
+
+uuid_provider!(DebugView = "0e2c7a4d-4c5b-4f25-9a8e-6f3d2b1c7e90");
+
+pub(crate) struct DebugViewShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    source_texture: UniformLocation,
+    mode: UniformLocation,
+    depth_range: UniformLocation,
+    max_overdraw: UniformLocation,
+}
+
+impl DebugViewShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/debug_view_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "DebugViewShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            source_texture: program
+                .uniform_location(state, &ImmutableString::new("sourceTexture"))?,
+            mode: program.uniform_location(state, &ImmutableString::new("mode"))?,
+            depth_range: program.uniform_location(state, &ImmutableString::new("depthRange"))?,
+            max_overdraw: program.uniform_location(state, &ImmutableString::new("maxOverdraw"))?,
+            program,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        state: &PipelineState,
+        view: DebugView,
+        texture: Rc<RefCell<GpuTexture>>,
+        camera: &Camera,
+        framebuffer: &mut FrameBuffer,
+        viewport: Rect<i32>,
+        quad: &GeometryBuffer,
+    ) -> Result<DrawCallStatistics, FrameworkError> {
+        let mode = match view {
+            DebugView::None | DebugView::Normals | DebugView::Albedo => 0,
+            DebugView::Ssao | DebugView::SsaoRaw => 1,
+            DebugView::Depth => match camera.projection() {
+                Projection::Perspective(_) => 2,
+                // Depth is already linear for orthographic projection.
+                Projection::Orthographic(_) => 1,
+            },
+            DebugView::Overdraw => 3,
+        };
+        let depth_range = Vector2::new(camera.projection().z_near(), camera.projection().z_far());
+
+        framebuffer.draw(
+            quad,
+            state,
+            viewport,
+            &self.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.wvp_matrix, &make_viewport_matrix(viewport))
+                    .set_texture(&self.source_texture, &texture)
+                    .set_i32(&self.mode, mode)
+                    .set_vector2(&self.depth_range, &depth_range)
+                    .set_f32(&self.max_overdraw, MAX_OVERDRAW);
+            },
+        )
+    }
+}
+
+/// Counts how many times each pixel is drawn, the counters are then shown as a heatmap by
+/// [`DebugViewShader`]. See [`DebugView::Overdraw`] for more info.
+pub(crate) struct OverdrawRenderer {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    framebuffer: Option<FrameBuffer>,
+    rectangle_quad: GeometryBuffer,
+}
+
+fn make_rectangle_quad(state: &PipelineState) -> Result<GeometryBuffer, FrameworkError> {
+    let quad = VertexBuffer::new(4, RectangleInstance::unit_quad().to_vec())
+        .map_err(|e| FrameworkError::Custom(e.to_string()))?;
+
+    let geometry = GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(BufferBuilder::from_vertex_buffer(
+            &quad,
+            GeometryBufferKind::StaticDraw,
+        ))
+        .build(state)?;
+
+    geometry
+        .bind(state)
+        .set_triangles(&RectangleInstance::unit_quad_triangles());
+
+    Ok(geometry)
+}
+
+fn make_counter_framebuffer(
+    state: &PipelineState,
+    width: usize,
+    height: usize,
+) -> Result<FrameBuffer, FrameworkError> {
+    // Half-precision floats are used, because blending with 32-bit floats is not supported
+    // everywhere. They're precise enough to count up to 2048.
+    let counter = GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle { width, height },
+        PixelKind::R16F,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+
+    FrameBuffer::new(
+        state,
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: Rc::new(RefCell::new(counter)),
+        }],
+    )
+}
+
+impl OverdrawRenderer {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/overdraw_fs.glsl");
+        let vertex_source = include_str!("shaders/overdraw_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "OverdrawShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            program,
+            framebuffer: None,
+            rectangle_quad: make_rectangle_quad(state)?,
+        })
+    }
+
+    /// Draws every surface and every instanced rectangle of the given bundle storage into the
+    /// counter texture of the viewport size and returns the texture.
+    pub fn render(
+        &mut self,
+        state: &PipelineState,
+        bundle_storage: &RenderDataBundleStorage,
+        camera: &Camera,
+        viewport: Rect<i32>,
+        geom_cache: &mut GeometryCache,
+        shader_cache: &mut ShaderCache,
+    ) -> Result<(RenderPassStatistics, Rc<RefCell<GpuTexture>>), FrameworkError> {
+        scope_profile!();
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let width = viewport.w().max(1) as usize;
+        let height = viewport.h().max(1) as usize;
+
+        let is_size_matches = self.framebuffer.as_ref().is_some_and(|framebuffer| {
+            matches!(
+                framebuffer.color_attachments()[0].texture.borrow().kind(),
+                GpuTextureKind::Rectangle { width: w, height: h } if w == width && h == height
+            )
+        });
+        if !is_size_matches {
+            self.framebuffer = Some(make_counter_framebuffer(state, width, height)?);
+        }
+        let framebuffer = self.framebuffer.as_mut().unwrap();
+
+        let counter_viewport = Rect::new(0, 0, width as i32, height as i32);
+
+        framebuffer.clear(
+            state,
+            counter_viewport,
+            Some(Color::TRANSPARENT),
+            None,
+            None,
+        );
+
+        let view_projection = camera.view_projection_matrix();
+
+        for bundle in bundle_storage.bundles.iter() {
+            let mut material_state = bundle.material.state();
+            let Some(material) = material_state.data() else {
+                continue;
+            };
+
+            // Respect face culling of the material, otherwise back faces will be counted too.
+            let Some(cull_face) = shader_cache.get(state, material.shader()).and_then(|set| {
+                set.render_passes
+                    .get(&GBUFFER_PASS_NAME)
+                    .or_else(|| set.render_passes.get(&ImmutableString::new("Forward")))
+                    .map(|pass| pass.draw_params.cull_face)
+            }) else {
+                continue;
+            };
+
+            let Some(geometry) = geom_cache.get(state, &bundle.data, bundle.time_to_live) else {
+                continue;
+            };
+
+            let draw_params = DrawParameters {
+                cull_face,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: Some(BlendParameters {
+                    func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                    ..Default::default()
+                }),
+                stencil_op: Default::default(),
+            };
+
+            for instance in bundle.instances.iter() {
+                statistics += framebuffer.draw(
+                    geometry,
+                    state,
+                    counter_viewport,
+                    &self.program,
+                    &draw_params,
+                    instance.element_range,
+                    |mut program_binding| {
+                        program_binding.set_matrix4(
+                            &self.wvp_matrix,
+                            &(view_projection * instance.world_transform),
+                        );
+                    },
+                )?;
+            }
+        }
+
+        // Instanced rectangles are drawn one by one, it is slower than instancing, but it does not
+        // matter for a debug view. Rectangles are never culled.
+        let draw_params = DrawParameters {
+            cull_face: None,
+            color_write: Default::default(),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: false,
+            blend: Some(BlendParameters {
+                func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                ..Default::default()
+            }),
+            stencil_op: Default::default(),
+        };
+        for batch in bundle_storage.rectangle_batches.iter() {
+            for instance in batch.instances.iter() {
+                statistics += framebuffer.draw(
+                    &self.rectangle_quad,
+                    state,
+                    counter_viewport,
+                    &self.program,
+                    &draw_params,
+                    ElementRange::Full,
+                    |mut program_binding| {
+                        program_binding.set_matrix4(
+                            &self.wvp_matrix,
+                            &(view_projection * instance.world_transform),
+                        );
+                    },
+                )?;
+            }
+        }
+
+        Ok((
+            statistics,
+            framebuffer.color_attachments()[0].texture.clone(),
+        ))
+    }
+}
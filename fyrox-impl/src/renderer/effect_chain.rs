@@ -0,0 +1,275 @@
+//! Effect chain is an ordered list of post effects, that are applied to the final (low dynamic
+//! range) frame of a scene one after another. It is an extension point for user-defined effects,
+//! the only built-in effect that goes through it is FXAA. See [`EffectChain`] docs for more info.
+
+use crate::{
+    core::math::Rect,
+    renderer::{
+        blit_pixels,
+        flat_shader::FlatShader,
+        framework::{
+            error::FrameworkError, framebuffer::FrameBuffer, geometry_buffer::GeometryBuffer,
+            gpu_texture::GpuTexture, state::PipelineState,
+        },
+        QualitySettings, RenderPassStatistics,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A context for a post effect.
+pub struct PostEffectContext<'a> {
+    /// A pipeline state that is used as a wrapper to underlying graphics API.
+    pub state: &'a PipelineState,
+
+    /// A unit quad that could be used to draw full screen effects.
+    pub quad: &'a GeometryBuffer,
+
+    /// A viewport of the camera.
+    pub viewport: Rect<i32>,
+
+    /// Current quality settings of the renderer.
+    pub quality_settings: &'a QualitySettings,
+
+    /// A texture with the output of previous effect in the chain (or the scene frame, if the
+    /// effect is the first one).
+    pub input: Rc<RefCell<GpuTexture>>,
+
+    /// A frame buffer the effect must render its result to. Its contents is undefined, so the
+    /// effect must overwrite every pixel in the viewport.
+    pub output: &'a mut FrameBuffer,
+}
+
+/// A post effect is a full screen effect, that takes a frame and produces a new one.
+pub trait PostEffect {
+    /// Returns a name of the effect, it could be used to find the effect in a chain.
+    fn name(&self) -> &str;
+
+    /// Returns `true` if the effect should be applied with the given quality settings. Disabled
+    /// effects are skipped and do not consume ping-pong targets.
+    fn is_enabled(&self, _quality_settings: &QualitySettings) -> bool {
+        true
+    }
+
+    /// Applies the effect to [`PostEffectContext::input`] and writes the result to
+    /// [`PostEffectContext::output`].
+    fn apply(&mut self, ctx: PostEffectContext) -> Result<RenderPassStatistics, FrameworkError>;
+}
+
+/// Effect chain is an ordered list of post effects, each effect takes the output of previous one
+/// as its input. The chain uses two frame buffers (ping-pong targets) and swaps them after every
+/// effect, so no effect ever reads and writes the same texture.
+///
+/// The chain is applied to the low dynamic range frame of each scene, right after tone mapping.
+/// By default, the chain contains FXAA only (named `FXAA`), which is enabled by
+/// [`QualitySettings::fxaa`].
+///
+/// # Scope
+///
+/// The chain does not control the order of every built-in post-process, the rest of them have
+/// fixed places in the pipeline and do not implement [`PostEffect`]:
+///
+/// - Screen-space ambient occlusion is not a full screen pass over the frame, its occlusion map
+///   is applied to the lighting while the lights are accumulated.
+/// - Bloom and tone mapping (with the color grading look-up table) work with the high dynamic
+///   range frame, which is not available to the chain, and hold per-scene state (bloom targets
+///   and luminance history).
+///
+/// This means, that an effect added to the chain always runs after tone mapping and color grading
+/// and could not be placed between them.
+#[derive(Default)]
+pub struct EffectChain {
+    effects: Vec<Box<dyn PostEffect>>,
+}
+
+impl EffectChain {
+    /// Adds a new effect to the end of the chain.
+    pub fn add<E: PostEffect + 'static>(&mut self, effect: E) -> &mut Self {
+        self.effects.push(Box::new(effect));
+        self
+    }
+
+    /// Inserts a new effect at the given position in the chain. Panics if `index > len`.
+    pub fn insert<E: PostEffect + 'static>(&mut self, index: usize, effect: E) -> &mut Self {
+        self.effects.insert(index, Box::new(effect));
+        self
+    }
+
+    /// Removes an effect at the given position in the chain and returns it. Panics if the index
+    /// is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Box<dyn PostEffect> {
+        self.effects.remove(index)
+    }
+
+    /// Returns a position of the first effect with the given name.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.effects.iter().position(|effect| effect.name() == name)
+    }
+
+    /// Removes every effect from the chain.
+    pub fn clear(&mut self) {
+        self.effects.clear()
+    }
+
+    /// Returns an iterator over the effects in the order of their application.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn PostEffect> {
+        self.effects.iter().map(|effect| &**effect)
+    }
+
+    /// Returns an iterator over the effects, that are enabled with the given quality settings, in
+    /// the order of their application.
+    pub fn enabled<'a>(
+        &'a self,
+        quality_settings: &'a QualitySettings,
+    ) -> impl Iterator<Item = &'a dyn PostEffect> {
+        self.iter()
+            .filter(move |effect| effect.is_enabled(quality_settings))
+    }
+
+    /// Returns total amount of effects in the chain.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns `true` if the chain has no effects.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Runs every enabled effect of the chain. The contents of `frame` is used as the input of
+    /// the first effect and the output of the last effect is written back to it, `temp` is
+    /// used as the second ping-pong target.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn run(
+        &mut self,
+        state: &PipelineState,
+        quad: &GeometryBuffer,
+        flat_shader: &FlatShader,
+        viewport: Rect<i32>,
+        quality_settings: &QualitySettings,
+        frame: &mut FrameBuffer,
+        temp: &mut FrameBuffer,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let mut statistics = RenderPassStatistics::default();
+
+        let mut source = &mut *frame;
+        let mut destination = &mut *temp;
+        let mut applied = 0;
+
+        for effect in self
+            .effects
+            .iter_mut()
+            .filter(|effect| effect.is_enabled(quality_settings))
+        {
+            statistics += effect.apply(PostEffectContext {
+                state,
+                quad,
+                viewport,
+                quality_settings,
+                input: source.color_attachments()[0].texture.clone(),
+                output: &mut *destination,
+            })?;
+
+            std::mem::swap(&mut source, &mut destination);
+            applied += 1;
+        }
+
+        // The result ended up in the temporary target, copy it back to the frame.
+        if applied % 2 != 0 {
+            statistics += blit_pixels(
+                state,
+                destination,
+                source.color_attachments()[0].texture.clone(),
+                flat_shader,
+                viewport,
+                quad,
+            )?;
+        }
+
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::{
+        effect_chain::{EffectChain, PostEffect, PostEffectContext},
+        framework::error::FrameworkError,
+        QualitySettings, RenderPassStatistics,
+    };
+
+    struct TestEffect {
+        name: &'static str,
+        requires_fxaa: bool,
+    }
+
+    impl TestEffect {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                requires_fxaa: false,
+            }
+        }
+    }
+
+    impl PostEffect for TestEffect {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn is_enabled(&self, quality_settings: &QualitySettings) -> bool {
+            !self.requires_fxaa || quality_settings.fxaa
+        }
+
+        fn apply(
+            &mut self,
+            _ctx: PostEffectContext,
+        ) -> Result<RenderPassStatistics, FrameworkError> {
+            unreachable!()
+        }
+    }
+
+    fn names<'a>(effects: impl Iterator<Item = &'a dyn PostEffect>) -> Vec<&'a str> {
+        effects.map(|effect| effect.name()).collect()
+    }
+
+    #[test]
+    fn test_effect_chain_order() {
+        let mut chain = EffectChain::default();
+        chain.add(TestEffect::new("A")).add(TestEffect::new("C"));
+        chain.insert(1, TestEffect::new("B"));
+        chain.insert(0, TestEffect::new("Z"));
+        assert_eq!(names(chain.iter()), ["Z", "A", "B", "C"]);
+        assert_eq!(chain.position("B"), Some(2));
+        assert_eq!(chain.position("D"), None);
+
+        assert_eq!(chain.remove(0).name(), "Z");
+        assert_eq!(names(chain.iter()), ["A", "B", "C"]);
+        assert_eq!(chain.len(), 3);
+
+        chain.clear();
+        assert!(chain.is_empty());
+    }
+
+    #[test]
+    fn test_effect_chain_skips_disabled_effects() {
+        let mut chain = EffectChain::default();
+        chain
+            .add(TestEffect::new("A"))
+            .add(TestEffect {
+                name: "B",
+                requires_fxaa: true,
+            })
+            .add(TestEffect::new("C"));
+
+        let mut quality_settings = QualitySettings {
+            fxaa: true,
+            ..Default::default()
+        };
+        assert_eq!(names(chain.enabled(&quality_settings)), ["A", "B", "C"]);
+
+        quality_settings.fxaa = false;
+        assert_eq!(names(chain.enabled(&quality_settings)), ["A", "C"]);
+        // Disabled effects are kept in the chain.
+        assert_eq!(chain.len(), 3);
+    }
+}
@@ -8,7 +8,7 @@
 
 use crate::{
     core::{
-        algebra::{Vector2, Vector4},
+        algebra::{Matrix4, Vector2, Vector4},
         color::Color,
         math::{frustum::Frustum, Rect},
         scope_profile,
@@ -16,20 +16,31 @@ use crate::{
     },
     renderer::{
         apply_material,
-        bundle::RenderDataBundleStorage,
+        bundle::{
+            PersistentIdentifier, RectangleInstanceBatch, RenderDataBundle, RenderDataBundleStorage,
+        },
         cache::{shader::ShaderCache, texture::TextureCache},
+        frame_ring::FrameRing,
         framework::{
-            error::FrameworkError, framebuffer::FrameBuffer, gpu_texture::GpuTexture,
-            state::PipelineState,
+            error::FrameworkError,
+            framebuffer::FrameBuffer,
+            geometry_buffer::{
+                BufferBuilder, ElementKind, GeometryBuffer, GeometryBufferBuilder,
+                GeometryBufferKind,
+            },
+            gpu_program::BuiltInUniform,
+            gpu_texture::GpuTexture,
+            state::{PipelineState, PolygonFace, PolygonFillMode},
         },
         storage::MatrixStorageCache,
         GeometryCache, LightData, MaterialContext, QualitySettings, RenderPassStatistics,
     },
     scene::{
         camera::Camera,
+        dim2::rectangle::RectangleInstance,
         graph::Graph,
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
-        mesh::RenderPath,
+        mesh::{buffer::VertexBuffer, RenderPath},
     },
 };
 use fyrox_core::math::Matrix4Ext;
@@ -37,6 +48,14 @@ use std::{cell::RefCell, rc::Rc};
 
 pub(crate) struct ForwardRenderer {
     render_pass_name: ImmutableString,
+    // Unit quad in the first buffer and per-instance data in the second one. One copy per frame
+    // in flight.
+    rectangle_instances_geometry: FrameRing<GeometryBuffer>,
+}
+
+enum ForwardItem<'a> {
+    Bundle(&'a RenderDataBundle),
+    Batch(&'a RectangleInstanceBatch),
 }
 
 pub(crate) struct ForwardRenderContext<'a, 'b> {
@@ -59,15 +78,62 @@ pub(crate) struct ForwardRenderContext<'a, 'b> {
     pub ambient_light: Color,
 }
 
+fn make_rectangle_instances_geometry(
+    state: &PipelineState,
+) -> Result<GeometryBuffer, FrameworkError> {
+    let quad = VertexBuffer::new(4, RectangleInstance::unit_quad().to_vec())
+        .map_err(|e| FrameworkError::Custom(e.to_string()))?;
+    let instances = VertexBuffer::new(0, Vec::<RectangleInstance>::new())
+        .map_err(|e| FrameworkError::Custom(e.to_string()))?;
+
+    let geometry = GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(BufferBuilder::from_vertex_buffer(
+            &quad,
+            GeometryBufferKind::StaticDraw,
+        ))
+        .with_buffer_builder(BufferBuilder::from_vertex_buffer(
+            &instances,
+            GeometryBufferKind::DynamicDraw,
+        ))
+        .build(state)?;
+
+    geometry
+        .bind(state)
+        .set_triangles(&RectangleInstance::unit_quad_triangles());
+
+    Ok(geometry)
+}
+
 impl ForwardRenderer {
-    pub(crate) fn new() -> Self {
-        Self {
+    pub(crate) fn new(
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<Self, FrameworkError> {
+        Ok(Self {
             render_pass_name: ImmutableString::new("Forward"),
-        }
+            rectangle_instances_geometry: FrameRing::new(frames_in_flight, || {
+                make_rectangle_instances_geometry(state)
+            })?,
+        })
+    }
+
+    pub(crate) fn set_frames_in_flight(
+        &mut self,
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<(), FrameworkError> {
+        self.rectangle_instances_geometry
+            .resize(frames_in_flight, || {
+                make_rectangle_instances_geometry(state)
+            })
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        self.rectangle_instances_geometry.advance();
     }
 
     pub(crate) fn render(
-        &self,
+        &mut self,
         args: ForwardRenderContext,
     ) -> Result<RenderPassStatistics, FrameworkError> {
         scope_profile!();
@@ -150,82 +216,194 @@ impl ForwardRenderer {
             }
         }
 
-        for bundle in bundle_storage
+        let pass_fill_mode = state.polygon_fill_mode();
+        let camera_position = camera.global_position();
+        let identity = Matrix4::identity();
+
+        // Instanced rectangle batches are interleaved with the bundles by their sort index, both
+        // lists are already sorted.
+        let mut bundles = bundle_storage
             .bundles
             .iter()
             .filter(|b| b.render_path == RenderPath::Forward)
-        {
-            let mut material_state = bundle.material.state();
+            .peekable();
+        let mut batches = bundle_storage.rectangle_batches.iter().peekable();
 
-            let Some(material) = material_state.data() else {
-                continue;
+        loop {
+            let item = match (bundles.peek(), batches.peek()) {
+                (Some(bundle), Some(batch)) if batch.sort_index() < bundle.sort_index() => {
+                    ForwardItem::Batch(batches.next().unwrap())
+                }
+                (Some(_), _) => ForwardItem::Bundle(bundles.next().unwrap()),
+                (None, Some(_)) => ForwardItem::Batch(batches.next().unwrap()),
+                (None, None) => break,
             };
 
-            let Some(geometry) = geom_cache.get(state, &bundle.data, bundle.time_to_live) else {
-                continue;
-            };
+            match item {
+                ForwardItem::Bundle(bundle) => {
+                    let mut material_state = bundle.material.state();
 
-            let blend_shapes_storage = bundle
-                .data
-                .data_ref()
-                .blend_shapes_container
-                .as_ref()
-                .and_then(|c| c.blend_shape_storage.clone());
-
-            let Some(render_pass) = shader_cache
-                .get(state, material.shader())
-                .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
-            else {
-                continue;
-            };
+                    let Some(material) = material_state.data() else {
+                        continue;
+                    };
 
-            for instance in bundle.instances.iter() {
-                let view_projection = if instance.depth_offset != 0.0 {
-                    let mut projection = camera.projection_matrix();
-                    projection[14] -= instance.depth_offset;
-                    projection * camera.view_matrix()
-                } else {
-                    initial_view_projection
-                };
+                    let Some(geometry) = geom_cache.get(state, &bundle.data, bundle.time_to_live)
+                    else {
+                        continue;
+                    };
+
+                    let blend_shapes_storage = bundle
+                        .data
+                        .data_ref()
+                        .blend_shapes_container
+                        .as_ref()
+                        .and_then(|c| c.blend_shape_storage.clone());
+
+                    let Some(render_pass) =
+                        shader_cache
+                            .get(state, material.shader())
+                            .and_then(|shader_set| {
+                                shader_set.render_passes.get(&self.render_pass_name)
+                            })
+                    else {
+                        continue;
+                    };
+
+                    if bundle.polygon_fill_mode != PolygonFillMode::Fill {
+                        state.set_polygon_fill_mode(
+                            PolygonFace::FrontAndBack,
+                            bundle.polygon_fill_mode,
+                        );
+                    }
+
+                    for instance in bundle.instances.iter() {
+                        let view_projection = if instance.depth_offset != 0.0 {
+                            let mut projection = camera.projection_matrix();
+                            projection[14] -= instance.depth_offset;
+                            projection * camera.view_matrix()
+                        } else {
+                            initial_view_projection
+                        };
+
+                        statistics += framebuffer.draw(
+                            geometry,
+                            state,
+                            viewport,
+                            &render_pass.program,
+                            &render_pass.draw_params,
+                            instance.element_range,
+                            |mut program_binding| {
+                                apply_material(MaterialContext {
+                                    material,
+                                    material_overrides: &bundle.material_overrides,
+                                    program_binding: &mut program_binding,
+                                    texture_cache,
+                                    world_matrix: &instance.world_transform,
+                                    view_projection_matrix: &view_projection,
+                                    wvp_matrix: &(view_projection * instance.world_transform),
+                                    bone_matrices: &instance.bone_matrices,
+                                    use_skeletal_animation: bundle.is_skinned,
+                                    use_instancing: false,
+                                    camera_position: &camera_position,
+                                    camera_up_vector: &camera_up,
+                                    camera_side_vector: &camera_side,
+                                    z_near: camera.projection().z_near(),
+                                    z_far: camera.projection().z_far(),
+                                    use_pom: quality_settings.use_parallax_mapping,
+                                    light_position: &Default::default(),
+                                    blend_shapes_storage: blend_shapes_storage.as_ref(),
+                                    blend_shapes_weights: &instance.blend_shapes_weights,
+                                    normal_dummy: &normal_dummy,
+                                    white_dummy: &white_dummy,
+                                    black_dummy: &black_dummy,
+                                    volume_dummy: &volume_dummy,
+                                    matrix_storage,
+                                    persistent_identifier: instance.persistent_identifier,
+                                    light_data: Some(&light_data),
+                                    ambient_light,
+                                    scene_depth: Some(&scene_depth),
+                                });
+                            },
+                        )?;
+                    }
+
+                    if bundle.polygon_fill_mode != PolygonFillMode::Fill {
+                        state.set_polygon_fill_mode(PolygonFace::FrontAndBack, pass_fill_mode);
+                    }
+                }
+                ForwardItem::Batch(batch) => {
+                    if batch.instances.is_empty() {
+                        continue;
+                    }
+
+                    let mut material_state = batch.material.state();
+
+                    let Some(material) = material_state.data() else {
+                        continue;
+                    };
+
+                    let Some(render_pass) =
+                        shader_cache
+                            .get(state, material.shader())
+                            .and_then(|shader_set| {
+                                shader_set.render_passes.get(&self.render_pass_name)
+                            })
+                    else {
+                        continue;
+                    };
+
+                    // Shaders without instancing support would draw every instance at the origin.
+                    if render_pass.program.built_in_uniform_locations
+                        [BuiltInUniform::UseInstancing as usize]
+                        .is_none()
+                    {
+                        continue;
+                    }
+
+                    let instances_geometry = self.rectangle_instances_geometry.current_mut();
+                    instances_geometry.set_buffer_data(state, 1, &batch.instances);
 
-                statistics += framebuffer.draw(
-                    geometry,
-                    state,
-                    viewport,
-                    &render_pass.program,
-                    &render_pass.draw_params,
-                    instance.element_range,
-                    |mut program_binding| {
-                        apply_material(MaterialContext {
-                            material,
-                            program_binding: &mut program_binding,
-                            texture_cache,
-                            world_matrix: &instance.world_transform,
-                            view_projection_matrix: &view_projection,
-                            wvp_matrix: &(view_projection * instance.world_transform),
-                            bone_matrices: &instance.bone_matrices,
-                            use_skeletal_animation: bundle.is_skinned,
-                            camera_position: &camera.global_position(),
-                            camera_up_vector: &camera_up,
-                            camera_side_vector: &camera_side,
-                            z_near: camera.projection().z_near(),
-                            z_far: camera.projection().z_far(),
-                            use_pom: quality_settings.use_parallax_mapping,
-                            light_position: &Default::default(),
-                            blend_shapes_storage: blend_shapes_storage.as_ref(),
-                            blend_shapes_weights: &instance.blend_shapes_weights,
-                            normal_dummy: &normal_dummy,
-                            white_dummy: &white_dummy,
-                            black_dummy: &black_dummy,
-                            volume_dummy: &volume_dummy,
-                            matrix_storage,
-                            persistent_identifier: instance.persistent_identifier,
-                            light_data: Some(&light_data),
-                            ambient_light,
-                            scene_depth: Some(&scene_depth),
-                        });
-                    },
-                )?;
+                    statistics += framebuffer.draw_instances(
+                        batch.instances.len(),
+                        instances_geometry,
+                        state,
+                        viewport,
+                        &render_pass.program,
+                        &render_pass.draw_params,
+                        |mut program_binding| {
+                            apply_material(MaterialContext {
+                                material,
+                                material_overrides: &Default::default(),
+                                program_binding: &mut program_binding,
+                                texture_cache,
+                                world_matrix: &identity,
+                                view_projection_matrix: &initial_view_projection,
+                                wvp_matrix: &initial_view_projection,
+                                bone_matrices: &[],
+                                use_skeletal_animation: false,
+                                use_instancing: true,
+                                camera_position: &camera_position,
+                                camera_up_vector: &camera_up,
+                                camera_side_vector: &camera_side,
+                                z_near: camera.projection().z_near(),
+                                z_far: camera.projection().z_far(),
+                                use_pom: quality_settings.use_parallax_mapping,
+                                light_position: &Default::default(),
+                                blend_shapes_storage: None,
+                                blend_shapes_weights: &[],
+                                normal_dummy: &normal_dummy,
+                                white_dummy: &white_dummy,
+                                black_dummy: &black_dummy,
+                                volume_dummy: &volume_dummy,
+                                matrix_storage,
+                                persistent_identifier: PersistentIdentifier(0),
+                                light_data: Some(&light_data),
+                                ambient_light,
+                                scene_depth: Some(&scene_depth),
+                            });
+                        },
+                    );
+                }
             }
         }
 
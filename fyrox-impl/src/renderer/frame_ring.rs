@@ -0,0 +1,82 @@
+//! A ring of per-frame copies of GPU resources, that are updated every frame. See [`FrameRing`]
+//! docs for more info.
+
+/// A ring of copies of a GPU resource (a vertex buffer, a uniform storage, etc.), that is updated
+/// every frame. Every frame uses the next copy, so the renderer never overwrites the data, that is
+/// still used by the frames being processed by the GPU. Overwriting such data forces the video
+/// driver to wait until the GPU finishes the previous frames (implicit synchronization). The amount
+/// of copies must be equal to the amount of frames in flight (see
+/// [`crate::renderer::Renderer::set_frames_in_flight`]).
+pub struct FrameRing<T> {
+    items: Vec<T>,
+    current: usize,
+}
+
+impl<T> FrameRing<T> {
+    /// Creates a new ring with the given amount of copies (at least one), each copy is created by
+    /// the given function.
+    pub fn new<E>(count: usize, mut factory: impl FnMut() -> Result<T, E>) -> Result<Self, E> {
+        let mut items = Vec::with_capacity(count.max(1));
+        for _ in 0..count.max(1) {
+            items.push(factory()?);
+        }
+        Ok(Self { items, current: 0 })
+    }
+
+    /// Changes the amount of copies (at least one). New copies are created by the given function,
+    /// extra copies are destroyed.
+    pub fn resize<E>(
+        &mut self,
+        count: usize,
+        mut factory: impl FnMut() -> Result<T, E>,
+    ) -> Result<(), E> {
+        let count = count.max(1);
+        self.items.truncate(count);
+        while self.items.len() < count {
+            self.items.push(factory()?);
+        }
+        self.current %= count;
+        Ok(())
+    }
+
+    /// Switches the ring to the next copy. Must be called once per frame.
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.items.len();
+    }
+
+    /// Returns the copy of the current frame.
+    pub fn current_mut(&mut self) -> &mut T {
+        &mut self.items[self.current]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrameRing;
+
+    #[test]
+    fn test_frame_ring() {
+        let mut counter = 0;
+        let mut factory = || -> Result<usize, ()> {
+            counter += 1;
+            Ok(counter)
+        };
+
+        let mut ring = FrameRing::new(2, &mut factory).unwrap();
+        assert_eq!(*ring.current_mut(), 1);
+        ring.advance();
+        assert_eq!(*ring.current_mut(), 2);
+        ring.advance();
+        assert_eq!(*ring.current_mut(), 1);
+
+        ring.resize(3, &mut factory).unwrap();
+        ring.advance();
+        ring.advance();
+        assert_eq!(*ring.current_mut(), 3);
+
+        ring.resize(1, &mut factory).unwrap();
+        assert_eq!(*ring.current_mut(), 1);
+        ring.advance();
+        assert_eq!(*ring.current_mut(), 1);
+    }
+}
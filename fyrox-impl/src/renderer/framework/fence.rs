@@ -0,0 +1,72 @@
+use crate::renderer::framework::{error::FrameworkError, state::PipelineState};
+use glow::HasContext;
+use std::rc::Weak;
+
+/// A synchronization primitive, that is signaled when the GPU finishes every command that was
+/// issued before the fence was created.
+pub struct GpuFence {
+    state: Weak<PipelineState>,
+    id: glow::Fence,
+}
+
+impl GpuFence {
+    /// Inserts a new fence in the command stream.
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        unsafe {
+            let id = state
+                .gl
+                .fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .map_err(FrameworkError::Custom)?;
+
+            Ok(Self {
+                state: state.weak(),
+                id,
+            })
+        }
+    }
+
+    /// Returns `true` if the fence is signaled, `false` - otherwise. Unlike [`Self::wait`], this
+    /// method never blocks.
+    pub fn is_signaled(&self, state: &PipelineState) -> bool {
+        unsafe {
+            matches!(
+                state
+                    .gl
+                    .client_wait_sync(self.id, glow::SYNC_FLUSH_COMMANDS_BIT, 0),
+                glow::ALREADY_SIGNALED | glow::CONDITION_SATISFIED
+            )
+        }
+    }
+
+    /// Blocks current thread until the fence is signaled. WebGL does not allow blocking waits, so
+    /// on this platform the method only checks the state of the fence and returns immediately.
+    pub fn wait(&self, state: &PipelineState) {
+        // One second, in nanoseconds.
+        #[cfg(not(target_arch = "wasm32"))]
+        const TIMEOUT: i32 = 1_000_000_000;
+        #[cfg(target_arch = "wasm32")]
+        const TIMEOUT: i32 = 0;
+
+        unsafe {
+            loop {
+                let result =
+                    state
+                        .gl
+                        .client_wait_sync(self.id, glow::SYNC_FLUSH_COMMANDS_BIT, TIMEOUT);
+                if result != glow::TIMEOUT_EXPIRED || TIMEOUT == 0 {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for GpuFence {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            unsafe {
+                state.gl.delete_sync(self.id);
+            }
+        }
+    }
+}
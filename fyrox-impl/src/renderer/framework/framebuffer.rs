@@ -2,13 +2,20 @@ use crate::{
     core::{color::Color, math::Rect, reflect::prelude::*, scope_profile, visitor::prelude::*},
     renderer::framework::{
         error::FrameworkError,
+        fence::GpuFence,
         geometry_buffer::{DrawCallStatistics, ElementRange, GeometryBuffer},
         gpu_program::{GpuProgram, GpuProgramBinding},
-        gpu_texture::{CubeMapFace, GpuTexture, GpuTextureKind, PixelElementKind},
-        state::{BlendEquation, BlendFunc, ColorMask, PipelineState, StencilFunc, StencilOp},
+        gpu_texture::{
+            image_2d_size_bytes, CubeMapFace, GpuTexture, GpuTextureKind, PixelElementKind,
+            PixelKind,
+        },
+        state::{
+            BlendEquation, BlendFunc, BlitFilter, ColorMask, GpuMemoryCategory, PipelineState,
+            StencilFunc, StencilOp,
+        },
     },
 };
-use glow::HasContext;
+use glow::{HasContext, PixelPackData};
 use serde::{Deserialize, Serialize};
 use std::rc::Weak;
 use std::{cell::RefCell, rc::Rc};
@@ -20,6 +27,194 @@ pub enum AttachmentKind {
     Depth,
 }
 
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum DepthStencilClear {
+    DepthStencil { depth: f32, stencil: i32 },
+    Depth(f32),
+    Stencil(i32),
+    Nothing,
+}
+
+/// Defines which parts of a depth (or depth-stencil) attachment of the given kind must be
+/// cleared.
+fn depth_stencil_clear(
+    kind: AttachmentKind,
+    depth: Option<f32>,
+    stencil: Option<i32>,
+) -> DepthStencilClear {
+    match kind {
+        AttachmentKind::Color => unreachable!("depth cannot be color!"),
+        AttachmentKind::DepthStencil => match (depth, stencil) {
+            (Some(depth), Some(stencil)) => DepthStencilClear::DepthStencil { depth, stencil },
+            (Some(depth), None) => DepthStencilClear::Depth(depth),
+            (None, Some(stencil)) => DepthStencilClear::Stencil(stencil),
+            (None, None) => DepthStencilClear::Nothing,
+        },
+        AttachmentKind::Depth => match depth {
+            Some(depth) => DepthStencilClear::Depth(depth),
+            None => DepthStencilClear::Nothing,
+        },
+    }
+}
+
+/// Calculates `glClear` mask for the default frame buffer.
+fn clear_mask(color: Option<Color>, depth: Option<f32>, stencil: Option<i32>) -> u32 {
+    let mut mask = 0;
+    if color.is_some() {
+        mask |= glow::COLOR_BUFFER_BIT;
+    }
+    if depth.is_some() {
+        mask |= glow::DEPTH_BUFFER_BIT;
+    }
+    if stencil.is_some() {
+        mask |= glow::STENCIL_BUFFER_BIT;
+    }
+    mask
+}
+
+/// Clears the attachment with the given index of the currently bound frame buffer using a clear
+/// function, that matches the pixel format of the attachment.
+unsafe fn clear_color_attachment(
+    state: &PipelineState,
+    index: usize,
+    attachment: &Attachment,
+    color: Color,
+) {
+    match attachment.texture.borrow().pixel_kind().element_kind() {
+        PixelElementKind::Float | PixelElementKind::NormalizedUnsignedInteger => {
+            let fvalues = color.as_frgba();
+            state
+                .gl
+                .clear_buffer_f32_slice(glow::COLOR, index as u32, &fvalues.data.0[0])
+        }
+        PixelElementKind::Integer => {
+            let values = [
+                color.r as i32,
+                color.g as i32,
+                color.b as i32,
+                color.a as i32,
+            ];
+            state
+                .gl
+                .clear_buffer_i32_slice(glow::COLOR, index as u32, &values);
+        }
+        PixelElementKind::UnsignedInteger => {
+            let values = [
+                color.r as u32,
+                color.g as u32,
+                color.b as u32,
+                color.a as u32,
+            ];
+            state
+                .gl
+                .clear_buffer_u32_slice(glow::COLOR, index as u32, &values);
+        }
+    }
+}
+
+/// A reusable pixel buffer object for asynchronous reads of pixels, that are started by
+/// [`FrameBuffer::begin_read_pixels`]. The same buffer is used for every read, it grows if a read
+/// needs more space than it has. Only one read could be pending at a time, starting a new read
+/// discards the pending one.
+pub struct AsyncPixelRead {
+    state: Weak<PipelineState>,
+    buffer: glow::Buffer,
+    capacity: usize,
+    size: usize,
+    fence: Option<GpuFence>,
+}
+
+impl AsyncPixelRead {
+    /// Creates a new empty pixel buffer object. The memory for the pixels is allocated on first
+    /// read.
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        unsafe {
+            Ok(Self {
+                state: state.weak(),
+                buffer: state.gl.create_buffer().map_err(FrameworkError::Custom)?,
+                capacity: 0,
+                size: 0,
+                fence: None,
+            })
+        }
+    }
+
+    /// Returns `true` if there is a read, that was started, but not yet finished.
+    pub fn is_pending(&self) -> bool {
+        self.fence.is_some()
+    }
+
+    /// Returns `true` if the GPU has finished copying the pixels, so they could be fetched without
+    /// a stall.
+    pub fn is_ready(&self, state: &PipelineState) -> bool {
+        self.fence
+            .as_ref()
+            .is_some_and(|fence| fence.is_signaled(state))
+    }
+
+    /// Returns the pixels, if the GPU has finished copying them, or `None` if the read is still
+    /// pending (or there is no read at all). The read is finished after the pixels are returned,
+    /// so the buffer could be used for the next read. This method never blocks. Returns an error
+    /// if the pixels could not be fetched from the buffer.
+    pub fn try_finish_read(
+        &mut self,
+        state: &PipelineState,
+    ) -> Result<Option<Vec<u8>>, FrameworkError> {
+        if !self.is_ready(state) {
+            return Ok(None);
+        }
+
+        self.fence = None;
+
+        let mut data = vec![0; self.size];
+
+        unsafe {
+            state
+                .gl
+                .bind_buffer(glow::PIXEL_PACK_BUFFER, Some(self.buffer));
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let ptr = state.gl.map_buffer_range(
+                    glow::PIXEL_PACK_BUFFER,
+                    0,
+                    self.size as i32,
+                    glow::MAP_READ_BIT,
+                );
+                if ptr.is_null() {
+                    state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+                    return Err(FrameworkError::Custom(
+                        "Unable to map the pixel buffer for reading!".to_string(),
+                    ));
+                }
+                std::ptr::copy_nonoverlapping(ptr, data.as_mut_ptr(), self.size);
+                state.gl.unmap_buffer(glow::PIXEL_PACK_BUFFER);
+            }
+
+            // WebGL does not support buffer mapping.
+            #[cfg(target_arch = "wasm32")]
+            state
+                .gl
+                .get_buffer_sub_data(glow::PIXEL_PACK_BUFFER, 0, &mut data);
+
+            state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+        }
+
+        Ok(Some(data))
+    }
+}
+
+impl Drop for AsyncPixelRead {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            state.register_deallocation(GpuMemoryCategory::Buffer, self.capacity);
+            unsafe {
+                state.gl.delete_buffer(self.buffer);
+            }
+        }
+    }
+}
+
 pub struct Attachment {
     pub kind: AttachmentKind,
     pub texture: Rc<RefCell<GpuTexture>>,
@@ -78,6 +273,50 @@ impl Default for DrawParameters {
     }
 }
 
+impl DrawParameters {
+    /// Returns names of the fields, that differ from the default values (see [`Self::default`]).
+    /// It is intended for debugging purposes only, for example to log why a specific draw call
+    /// behaves unexpectedly.
+    pub fn diff_from_default(&self) -> Vec<&'static str> {
+        let default = Self::default();
+
+        // Destructuring ensures that new fields won't be forgotten here.
+        let Self {
+            cull_face,
+            color_write,
+            depth_write,
+            stencil_test,
+            depth_test,
+            blend,
+            stencil_op,
+        } = self;
+
+        let mut diff = Vec::new();
+        if *cull_face != default.cull_face {
+            diff.push("cull_face");
+        }
+        if *color_write != default.color_write {
+            diff.push("color_write");
+        }
+        if *depth_write != default.depth_write {
+            diff.push("depth_write");
+        }
+        if *stencil_test != default.stencil_test {
+            diff.push("stencil_test");
+        }
+        if *depth_test != default.depth_test {
+            diff.push("depth_test");
+        }
+        if *blend != default.blend {
+            diff.push("blend");
+        }
+        if *stencil_op != default.stencil_op {
+            diff.push("stencil_op");
+        }
+        diff
+    }
+}
+
 unsafe fn set_attachment(state: &PipelineState, gl_attachment_kind: u32, texture: &GpuTexture) {
     match texture.kind() {
         GpuTextureKind::Line { .. } => {
@@ -138,6 +377,10 @@ impl FrameBuffer {
                     AttachmentKind::DepthStencil => glow::DEPTH_STENCIL_ATTACHMENT,
                     AttachmentKind::Depth => glow::DEPTH_ATTACHMENT,
                 };
+                depth_attachment
+                    .texture
+                    .borrow_mut()
+                    .mark_as_render_target(state);
                 set_attachment(
                     state,
                     depth_attachment_kind,
@@ -149,6 +392,10 @@ impl FrameBuffer {
             for (i, color_attachment) in color_attachments.iter().enumerate() {
                 assert_eq!(color_attachment.kind, AttachmentKind::Color);
                 let color_attachment_kind = glow::COLOR_ATTACHMENT0 + i as u32;
+                color_attachment
+                    .texture
+                    .borrow_mut()
+                    .mark_as_render_target(state);
                 set_attachment(
                     state,
                     color_attachment_kind,
@@ -222,6 +469,277 @@ impl FrameBuffer {
         self.fbo
     }
 
+    /// Reads pixels of the first color attachment (or the back buffer) in the given viewport and
+    /// returns them as tightly packed RGBA8 data. Rows are stored from bottom to top, as OpenGL
+    /// does. The color attachment must have normalized fixed point format, floating point
+    /// formats are not supported.
+    pub fn read_rgba8_pixels(&self, state: &PipelineState, viewport: Rect<i32>) -> Vec<u8> {
+        state.set_framebuffer(self.fbo);
+
+        let mut buffer = vec![0; 4 * viewport.w().max(0) as usize * viewport.h().max(0) as usize];
+        unsafe {
+            state.gl.read_pixels(
+                viewport.x(),
+                viewport.y(),
+                viewport.w(),
+                viewport.h(),
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelPackData::Slice(buffer.as_mut_slice()),
+            );
+        }
+        buffer
+    }
+
+    /// Reads pixels of the color attachment with the given index in the given region and returns
+    /// them as tightly packed data in the pixel format of the attachment. Rows are stored from
+    /// bottom to top, as OpenGL does. The back buffer has the only "attachment" with index `0` and
+    /// [`PixelKind::RGBA8`] format.
+    ///
+    /// # Performance
+    ///
+    /// This method is synchronous - it stalls the CPU until the GPU has finished rendering to the
+    /// frame buffer and the data is transferred back. It could easily ruin performance if used
+    /// every frame, so use it sparingly (screenshots, picking on click, etc.).
+    pub fn read_pixels(
+        &self,
+        state: &PipelineState,
+        attachment_index: usize,
+        rect: Rect<i32>,
+    ) -> Result<Vec<u8>, FrameworkError> {
+        scope_profile!();
+
+        let (pixel_kind, read_buffer) = self.read_source(attachment_index, rect)?;
+
+        let mut buffer =
+            vec![0; image_2d_size_bytes(pixel_kind, rect.w() as usize, rect.h() as usize)];
+
+        unsafe {
+            self.issue_read(
+                state,
+                pixel_kind,
+                read_buffer,
+                rect,
+                PixelPackData::Slice(buffer.as_mut_slice()),
+            );
+        }
+
+        Ok(buffer)
+    }
+
+    /// Starts asynchronous read of pixels of the color attachment with the given index in the
+    /// given region into the given pixel buffer. Unlike [`Self::read_pixels`], this method does
+    /// not stall the CPU - the pixels are copied into the pixel buffer object on the GPU side and
+    /// could be fetched later using [`AsyncPixelRead::try_finish_read`], usually a frame or two
+    /// later. It is suitable for continuous readback (GPU picking every frame, etc.), the same
+    /// pixel buffer should be reused for every read. The format of the data is the same as in
+    /// [`Self::read_pixels`].
+    pub fn begin_read_pixels(
+        &self,
+        state: &PipelineState,
+        read: &mut AsyncPixelRead,
+        attachment_index: usize,
+        rect: Rect<i32>,
+    ) -> Result<(), FrameworkError> {
+        scope_profile!();
+
+        let (pixel_kind, read_buffer) = self.read_source(attachment_index, rect)?;
+
+        let size = image_2d_size_bytes(pixel_kind, rect.w() as usize, rect.h() as usize);
+
+        // Discard the pending read (if any), its result will be overwritten.
+        read.fence = None;
+
+        unsafe {
+            state
+                .gl
+                .bind_buffer(glow::PIXEL_PACK_BUFFER, Some(read.buffer));
+            if size > read.capacity {
+                state
+                    .gl
+                    .buffer_data_size(glow::PIXEL_PACK_BUFFER, size as i32, glow::STREAM_READ);
+                state.register_deallocation(GpuMemoryCategory::Buffer, read.capacity);
+                state.register_allocation(GpuMemoryCategory::Buffer, size);
+                read.capacity = size;
+            }
+            self.issue_read(
+                state,
+                pixel_kind,
+                read_buffer,
+                rect,
+                PixelPackData::BufferOffset(0),
+            );
+            state.gl.bind_buffer(glow::PIXEL_PACK_BUFFER, None);
+        }
+
+        read.size = size;
+        read.fence = Some(GpuFence::new(state)?);
+
+        Ok(())
+    }
+
+    fn read_source(
+        &self,
+        attachment_index: usize,
+        rect: Rect<i32>,
+    ) -> Result<(PixelKind, u32), FrameworkError> {
+        let (pixel_kind, read_buffer) = if self.fbo.is_some() {
+            let attachment = self
+                .color_attachments
+                .get(attachment_index)
+                .ok_or_else(|| {
+                    FrameworkError::Custom(format!(
+                        "Frame buffer has no color attachment with index {attachment_index}!"
+                    ))
+                })?;
+            (
+                attachment.texture.borrow().pixel_kind(),
+                glow::COLOR_ATTACHMENT0 + attachment_index as u32,
+            )
+        } else if attachment_index == 0 {
+            (PixelKind::RGBA8, glow::BACK)
+        } else {
+            return Err(FrameworkError::Custom(format!(
+                "Back buffer has no color attachment with index {attachment_index}!"
+            )));
+        };
+
+        if pixel_kind.is_compressed() {
+            return Err(FrameworkError::Custom(format!(
+                "Unable to read pixels of {pixel_kind:?} attachment, compressed formats are not \
+                supported!"
+            )));
+        }
+
+        if rect.w() < 0 || rect.h() < 0 {
+            return Err(FrameworkError::Custom(format!(
+                "Invalid region {rect:?} to read pixels from!"
+            )));
+        }
+
+        Ok((pixel_kind, read_buffer))
+    }
+
+    unsafe fn issue_read(
+        &self,
+        state: &PipelineState,
+        pixel_kind: PixelKind,
+        read_buffer: u32,
+        rect: Rect<i32>,
+        destination: PixelPackData,
+    ) {
+        let descriptor = pixel_kind.pixel_descriptor();
+
+        state.set_framebuffer(self.fbo);
+
+        state.gl.read_buffer(read_buffer);
+        // Rows must be tightly packed, otherwise the buffer could be too small.
+        state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+        state.gl.read_pixels(
+            rect.x(),
+            rect.y(),
+            rect.w(),
+            rect.h(),
+            descriptor.format,
+            descriptor.data_type,
+            destination,
+        );
+        state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+        if self.fbo.is_some() {
+            // Restore the default read buffer of the frame buffer.
+            state.gl.read_buffer(glow::COLOR_ATTACHMENT0);
+        }
+    }
+
+    /// Generates mip levels of the color attachment with the given index from its base level,
+    /// which is useful after rendering to the attachment (reflections, downsampling chains, etc.).
+    /// The texture of the attachment must be created with the desired amount of mip levels and
+    /// with a minification filter, that uses mip mapping, otherwise an error is returned.
+    pub fn generate_mipmaps(
+        &self,
+        state: &PipelineState,
+        attachment_index: usize,
+    ) -> Result<(), FrameworkError> {
+        let attachment = self
+            .color_attachments
+            .get(attachment_index)
+            .ok_or_else(|| {
+                FrameworkError::Custom(format!(
+                    "Frame buffer has no color attachment with index {attachment_index}!"
+                ))
+            })?;
+
+        let mut texture = attachment.texture.borrow_mut();
+
+        let min_filter = texture.minification_filter();
+        if !min_filter.is_using_mip_mapping() {
+            return Err(FrameworkError::Custom(format!(
+                "Unable to generate mip maps for color attachment {attachment_index}, its \
+                minification filter {min_filter:?} does not use mip mapping!"
+            )));
+        }
+
+        if texture.pixel_kind().is_compressed() {
+            return Err(FrameworkError::Custom(format!(
+                "Unable to generate mip maps for color attachment {attachment_index}, compressed \
+                formats are not supported!"
+            )));
+        }
+
+        texture.bind_mut(state, 0).generate_mip_maps();
+
+        Ok(())
+    }
+
+    /// Copies the given region of this frame buffer to the given region of the destination frame
+    /// buffer. If the regions have different sizes, the source region is scaled using the given
+    /// filter. [`BlitFilter::Linear`] can be used only for color buffers, an error is returned if
+    /// it is used to copy depth or stencil values.
+    pub fn blit_to(
+        &self,
+        state: &PipelineState,
+        dest: &FrameBuffer,
+        src_rect: Rect<i32>,
+        dst_rect: Rect<i32>,
+        copy_color: bool,
+        copy_depth: bool,
+        copy_stencil: bool,
+        filter: BlitFilter,
+    ) -> Result<(), FrameworkError> {
+        if filter == BlitFilter::Linear && (copy_depth || copy_stencil) {
+            return Err(FrameworkError::Custom(
+                "Linear filtering can be used only to blit color buffers!".to_string(),
+            ));
+        }
+
+        // Make sure that the cached binding matches the actual one after the blit.
+        state.set_framebuffer(dest.fbo);
+
+        state.blit_framebuffer(
+            self.fbo,
+            dest.fbo,
+            src_rect.x(),
+            src_rect.y(),
+            src_rect.x() + src_rect.w(),
+            src_rect.y() + src_rect.h(),
+            dst_rect.x(),
+            dst_rect.y(),
+            dst_rect.x() + dst_rect.w(),
+            dst_rect.y() + dst_rect.h(),
+            copy_color,
+            copy_depth,
+            copy_stencil,
+            filter,
+        );
+
+        // Blitting binds read and draw frame buffers separately, restore the binding of both.
+        unsafe {
+            state.gl.bind_framebuffer(glow::FRAMEBUFFER, dest.fbo);
+        }
+
+        Ok(())
+    }
+
     pub fn clear(
         &mut self,
         state: &PipelineState,
@@ -238,60 +756,46 @@ impl FrameBuffer {
         unsafe {
             // Special route for default buffer.
             if self.fbo == Default::default() {
-                let mut mask = 0;
-
                 if let Some(color) = color {
                     state.set_color_write(ColorMask::default());
                     state.set_clear_color(color);
-                    mask |= glow::COLOR_BUFFER_BIT;
                 }
                 if let Some(depth) = depth {
                     state.set_depth_write(true);
                     state.set_clear_depth(depth);
-                    mask |= glow::DEPTH_BUFFER_BIT;
                 }
                 if let Some(stencil) = stencil {
                     state.set_stencil_mask(0xFFFF_FFFF);
                     state.set_clear_stencil(stencil);
-                    mask |= glow::STENCIL_BUFFER_BIT;
                 }
 
-                state.gl.clear(mask);
+                state.gl.clear(clear_mask(color, depth, stencil));
             }
 
-            // Custom routes for specific frame buffer attachments.
+            // Custom routes for specific frame buffer attachments. Depth and stencil parts of
+            // packed depth-stencil attachments are cleared independently, so clearing stencil
+            // does not disturb depth and vice versa.
             if let Some(depth_stencil) = self.depth_attachment.as_ref() {
-                state.set_depth_write(true);
-                state.set_stencil_mask(0xFFFF_FFFF);
-
-                match depth_stencil.kind {
-                    AttachmentKind::Color => unreachable!("depth cannot be color!"),
-                    AttachmentKind::DepthStencil => match (depth, stencil) {
-                        (Some(depth), Some(stencil)) => {
-                            state.gl.clear_buffer_depth_stencil(
-                                glow::DEPTH_STENCIL,
-                                0,
-                                depth,
-                                stencil,
-                            );
-                        }
-                        (Some(depth), None) => {
-                            let values = [depth];
-                            state.gl.clear_buffer_f32_slice(glow::DEPTH, 0, &values);
-                        }
-                        (None, Some(stencil)) => {
-                            let values = [stencil];
-                            state.gl.clear_buffer_i32_slice(glow::STENCIL, 0, &values);
-                        }
-                        (None, None) => {
-                            // Nothing to do
-                        }
-                    },
-                    AttachmentKind::Depth => {
-                        if let Some(depth) = depth {
-                            let values = [depth];
-                            state.gl.clear_buffer_f32_slice(glow::DEPTH, 0, &values);
-                        }
+                match depth_stencil_clear(depth_stencil.kind, depth, stencil) {
+                    DepthStencilClear::DepthStencil { depth, stencil } => {
+                        state.set_depth_write(true);
+                        state.set_stencil_mask(0xFFFF_FFFF);
+                        state
+                            .gl
+                            .clear_buffer_depth_stencil(glow::DEPTH_STENCIL, 0, depth, stencil);
+                    }
+                    DepthStencilClear::Depth(depth) => {
+                        state.set_depth_write(true);
+                        let values = [depth];
+                        state.gl.clear_buffer_f32_slice(glow::DEPTH, 0, &values);
+                    }
+                    DepthStencilClear::Stencil(stencil) => {
+                        state.set_stencil_mask(0xFFFF_FFFF);
+                        let values = [stencil];
+                        state.gl.clear_buffer_i32_slice(glow::STENCIL, 0, &values);
+                    }
+                    DepthStencilClear::Nothing => {
+                        // Nothing to do
                     }
                 }
             }
@@ -300,43 +804,44 @@ impl FrameBuffer {
                 state.set_color_write(ColorMask::default());
 
                 for (i, attachment) in self.color_attachments.iter().enumerate() {
-                    match attachment.texture.borrow().pixel_kind().element_kind() {
-                        PixelElementKind::Float | PixelElementKind::NormalizedUnsignedInteger => {
-                            let fvalues = color.as_frgba();
-                            state.gl.clear_buffer_f32_slice(
-                                glow::COLOR,
-                                i as u32,
-                                &fvalues.data.0[0],
-                            )
-                        }
-                        PixelElementKind::Integer => {
-                            let values = [
-                                color.r as i32,
-                                color.g as i32,
-                                color.b as i32,
-                                color.a as i32,
-                            ];
-                            state
-                                .gl
-                                .clear_buffer_i32_slice(glow::COLOR, i as u32, &values);
-                        }
-                        PixelElementKind::UnsignedInteger => {
-                            let values = [
-                                color.r as u32,
-                                color.g as u32,
-                                color.b as u32,
-                                color.a as u32,
-                            ];
-                            state
-                                .gl
-                                .clear_buffer_u32_slice(glow::COLOR, i as u32, &values);
-                        }
-                    }
+                    clear_color_attachment(state, i, attachment, color);
                 }
             }
         }
     }
 
+    /// Clears only the color attachment with the given index, other attachments are left
+    /// untouched. It is useful for multiple render targets setups, where each attachment must be
+    /// cleared with its own value (for example, a normal buffer must be cleared with
+    /// `(0.5, 0.5, 1.0)` color, while an albedo buffer - with black color). Returns an error if
+    /// there is no such attachment. The back buffer has no color attachments, use
+    /// [`Self::clear`] instead.
+    pub fn clear_attachment(
+        &mut self,
+        state: &PipelineState,
+        viewport: Rect<i32>,
+        index: usize,
+        color: Color,
+    ) -> Result<(), FrameworkError> {
+        scope_profile!();
+
+        let attachment = self.color_attachments.get(index).ok_or_else(|| {
+            FrameworkError::Custom(format!(
+                "Frame buffer has no color attachment with index {index}!"
+            ))
+        })?;
+
+        state.set_viewport(viewport);
+        state.set_framebuffer(self.id());
+        state.set_color_write(ColorMask::default());
+
+        unsafe {
+            clear_color_attachment(state, index, attachment, color);
+        }
+
+        Ok(())
+    }
+
     pub fn draw<F: FnOnce(GpuProgramBinding<'_, '_>)>(
         &mut self,
         geometry: &GeometryBuffer,
@@ -400,3 +905,186 @@ impl Drop for FrameBuffer {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::framework::{
+        framebuffer::{
+            clear_mask, depth_stencil_clear, AttachmentKind, BlendParameters, DepthStencilClear,
+            DrawParameters,
+        },
+        state::{BlendFactor, BlendFunc},
+    };
+    #[cfg(target_os = "linux")]
+    use crate::{
+        core::math::Rect,
+        renderer::framework::{
+            framebuffer::{Attachment, FrameBuffer},
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            state::{GlKind, PipelineState, SharedPipelineState},
+        },
+    };
+    #[cfg(target_os = "linux")]
+    use glow::{HasContext, PixelPackData};
+    #[cfg(target_os = "linux")]
+    use glutin::{
+        api::egl::{context::PossiblyCurrentContext, device::Device, display::Display},
+        config::{ConfigSurfaceTypes, ConfigTemplateBuilder},
+        context::{ContextApi, ContextAttributesBuilder, GlProfile, Version},
+        prelude::*,
+    };
+    #[cfg(target_os = "linux")]
+    use std::{cell::RefCell, ffi::CString, rc::Rc};
+
+    #[test]
+    fn test_stencil_only_clear_preserves_depth() {
+        assert_eq!(
+            depth_stencil_clear(AttachmentKind::DepthStencil, None, Some(0)),
+            DepthStencilClear::Stencil(0)
+        );
+        assert_eq!(
+            depth_stencil_clear(AttachmentKind::DepthStencil, Some(1.0), None),
+            DepthStencilClear::Depth(1.0)
+        );
+        assert_eq!(
+            depth_stencil_clear(AttachmentKind::DepthStencil, Some(1.0), Some(0)),
+            DepthStencilClear::DepthStencil {
+                depth: 1.0,
+                stencil: 0
+            }
+        );
+        assert_eq!(
+            depth_stencil_clear(AttachmentKind::Depth, None, Some(0)),
+            DepthStencilClear::Nothing
+        );
+
+        let mask = clear_mask(None, None, Some(0));
+        assert_eq!(mask & glow::DEPTH_BUFFER_BIT, 0);
+        assert_eq!(mask & glow::COLOR_BUFFER_BIT, 0);
+        assert_ne!(mask & glow::STENCIL_BUFFER_BIT, 0);
+    }
+
+    // Creates a pipeline state with an offscreen OpenGL context, that does not need a window or a
+    // display server. Returns `None` if there's no suitable device (on machines without GPU and
+    // software rasterizer, for example). The tests, that need it, are ignored by default and must
+    // be run explicitly using `cargo test -- --ignored` on a machine with such device.
+    #[cfg(target_os = "linux")]
+    fn make_headless_state() -> Option<(SharedPipelineState, PossiblyCurrentContext)> {
+        unsafe {
+            let device = Device::query_devices().ok()?.next()?;
+            let display = Display::with_device(&device, None).ok()?;
+            let template = ConfigTemplateBuilder::new()
+                .with_surface_type(ConfigSurfaceTypes::empty())
+                .build();
+            let config = display.find_configs(template).ok()?.next()?;
+            let attributes = ContextAttributesBuilder::new()
+                .with_profile(GlProfile::Core)
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+                .build(None);
+            let context = display
+                .create_context(&config, &attributes)
+                .ok()?
+                .make_current_surfaceless()
+                .ok()?;
+            let gl = glow::Context::from_loader_function(|s| {
+                display.get_proc_address(&CString::new(s).unwrap())
+            });
+            Some((PipelineState::new(gl, GlKind::OpenGL), context))
+        }
+    }
+
+    // Returns depth and stencil of the first pixel of a frame buffer with D24S8 attachment.
+    #[cfg(target_os = "linux")]
+    fn read_depth_stencil(state: &PipelineState, framebuffer: &FrameBuffer) -> (f32, u8) {
+        let mut bytes = [0; 4];
+        state.set_framebuffer(framebuffer.id());
+        unsafe {
+            state.gl.read_pixels(
+                0,
+                0,
+                1,
+                1,
+                glow::DEPTH_STENCIL,
+                glow::UNSIGNED_INT_24_8,
+                PixelPackData::Slice(&mut bytes),
+            );
+        }
+        let packed = u32::from_ne_bytes(bytes);
+        (
+            (packed >> 8) as f32 / 0xFF_FFFF as f32,
+            (packed & 0xFF) as u8,
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "requires an EGL device with OpenGL 3.3 support"]
+    fn test_clear_depth_and_stencil_independently() {
+        let (state, _context) =
+            make_headless_state().expect("no EGL device with OpenGL 3.3 support");
+
+        let depth_stencil = GpuTexture::new(
+            &state,
+            GpuTextureKind::Rectangle {
+                width: 4,
+                height: 4,
+            },
+            PixelKind::D24S8,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )
+        .unwrap();
+        let mut framebuffer = FrameBuffer::new(
+            &state,
+            Some(Attachment {
+                kind: AttachmentKind::DepthStencil,
+                texture: Rc::new(RefCell::new(depth_stencil)),
+            }),
+            vec![],
+        )
+        .unwrap();
+        let viewport = Rect::new(0, 0, 4, 4);
+
+        framebuffer.clear(&state, viewport, None, Some(0.5), Some(3));
+        let (depth, stencil) = read_depth_stencil(&state, &framebuffer);
+        assert!((depth - 0.5).abs() < 1.0e-4);
+        assert_eq!(stencil, 3);
+
+        // Write masks left by previous passes must not prevent clearing.
+        state.set_depth_write(false);
+        state.set_stencil_mask(0);
+
+        framebuffer.clear(&state, viewport, None, None, Some(7));
+        let (depth, stencil) = read_depth_stencil(&state, &framebuffer);
+        assert!((depth - 0.5).abs() < 1.0e-4);
+        assert_eq!(stencil, 7);
+
+        framebuffer.clear(&state, viewport, None, Some(1.0), None);
+        let (depth, stencil) = read_depth_stencil(&state, &framebuffer);
+        assert!((depth - 1.0).abs() < 1.0e-4);
+        assert_eq!(stencil, 7);
+    }
+
+    #[test]
+    fn test_draw_parameters_diff_from_default() {
+        assert!(DrawParameters::default().diff_from_default().is_empty());
+
+        let params = DrawParameters {
+            cull_face: None,
+            depth_write: false,
+            blend: Some(BlendParameters {
+                func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            params.diff_from_default(),
+            vec!["cull_face", "depth_write", "blend"]
+        );
+    }
+}
@@ -2,7 +2,10 @@ use crate::scene::mesh::surface::SurfaceData;
 use crate::{
     core::array_as_u8_slice,
     core::{math::TriangleDefinition, scope_profile},
-    renderer::framework::{error::FrameworkError, state::PipelineState},
+    renderer::framework::{
+        error::FrameworkError,
+        state::{GpuMemoryCategory, PipelineState},
+    },
     scene::mesh::buffer::{VertexAttributeDataType, VertexBuffer},
 };
 use glow::HasContext;
@@ -15,6 +18,8 @@ struct NativeBuffer {
     kind: GeometryBufferKind,
     element_size: usize,
     size_bytes: usize,
+    // Actual size of the storage of the buffer, it could be larger than the size of the data.
+    allocated_bytes: usize,
     // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
     thread_mark: PhantomData<*const u8>,
 }
@@ -22,6 +27,7 @@ struct NativeBuffer {
 impl Drop for NativeBuffer {
     fn drop(&mut self) {
         if let Some(state) = self.state.upgrade() {
+            state.register_deallocation(GpuMemoryCategory::Buffer, self.allocated_bytes);
             unsafe {
                 state.gl.delete_buffer(self.id);
             }
@@ -35,6 +41,7 @@ pub struct GeometryBuffer {
     buffers: Vec<NativeBuffer>,
     element_buffer_object: glow::Buffer,
     element_count: Cell<usize>,
+    element_buffer_size: Cell<usize>,
     element_kind: ElementKind,
     // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
     thread_mark: PhantomData<*const u8>,
@@ -220,6 +227,14 @@ impl<'a> GeometryBufferBinding<'a> {
         self.state
             .gl
             .buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, data, glow::DYNAMIC_DRAW);
+
+        self.state.register_deallocation(
+            GpuMemoryCategory::Buffer,
+            self.buffer.element_buffer_size.get(),
+        );
+        self.state
+            .register_allocation(GpuMemoryCategory::Buffer, data.len());
+        self.buffer.element_buffer_size.set(data.len());
     }
 
     pub fn draw(&self, element_range: ElementRange) -> Result<DrawCallStatistics, FrameworkError> {
@@ -326,6 +341,10 @@ impl GeometryBuffer {
                 state
                     .gl
                     .buffer_data_u8_slice(glow::ARRAY_BUFFER, array_as_u8_slice(data), usage);
+
+                state.register_deallocation(GpuMemoryCategory::Buffer, buffer.allocated_bytes);
+                state.register_allocation(GpuMemoryCategory::Buffer, size);
+                buffer.allocated_bytes = size;
             } else {
                 state
                     .gl
@@ -363,6 +382,7 @@ impl GeometryBuffer {
 impl Drop for GeometryBuffer {
     fn drop(&mut self) {
         if let Some(state) = self.state.upgrade() {
+            state.register_deallocation(GpuMemoryCategory::Buffer, self.element_buffer_size.get());
             unsafe {
                 self.buffers.clear();
 
@@ -455,12 +475,15 @@ impl BufferBuilder {
             }
         }
 
+        state.register_allocation(GpuMemoryCategory::Buffer, self.data_size);
+
         let native_buffer = NativeBuffer {
             state: state.weak(),
             id: vbo,
             kind: self.kind,
             element_size: self.element_size,
             size_bytes: self.data_size,
+            allocated_bytes: self.data_size,
             thread_mark: Default::default(),
         };
 
@@ -530,6 +553,7 @@ impl GeometryBufferBuilder {
             buffers,
             element_buffer_object: ebo,
             element_count: Cell::new(0),
+            element_buffer_size: Cell::new(0),
             element_kind: self.element_kind,
             thread_mark: PhantomData,
         })
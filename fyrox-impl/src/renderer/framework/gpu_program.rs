@@ -30,6 +30,7 @@ pub enum BuiltInUniform {
     WorldViewProjectionMatrix,
     BoneMatrices,
     UseSkeletalAnimation,
+    UseInstancing,
     CameraPosition,
     CameraUpVector,
     CameraSideVector,
@@ -442,6 +443,8 @@ fn fetch_built_in_uniform_locations(
         fetch_uniform_location(state, program, "fyrox_boneMatrices");
     locations[BuiltInUniform::UseSkeletalAnimation as usize] =
         fetch_uniform_location(state, program, "fyrox_useSkeletalAnimation");
+    locations[BuiltInUniform::UseInstancing as usize] =
+        fetch_uniform_location(state, program, "fyrox_useInstancing");
 
     locations[BuiltInUniform::CameraPosition as usize] =
         fetch_uniform_location(state, program, "fyrox_cameraPosition");
@@ -1,6 +1,9 @@
 use crate::{
-    core::color::Color,
-    renderer::framework::{error::FrameworkError, state::PipelineState},
+    core::{color::Color, math::Rect},
+    renderer::framework::{
+        error::FrameworkError,
+        state::{BlitFilter, GpuMemoryCategory, PipelineState},
+    },
     resource::texture::{
         TextureKind, TextureMagnificationFilter, TextureMinificationFilter, TexturePixelKind,
         TextureWrapMode,
@@ -146,8 +149,8 @@ pub enum PixelElementKind {
 }
 
 pub struct PixelDescriptor {
-    data_type: u32,
-    format: u32,
+    pub(crate) data_type: u32,
+    pub(crate) format: u32,
     internal_format: u32,
     swizzle_mask: Option<[i32; 4]>,
 }
@@ -385,6 +388,9 @@ pub struct GpuTexture {
     r_wrap_mode: WrapMode,
     anisotropy: f32,
     pixel_kind: PixelKind,
+    // Size of the data of every mip level, it is used to track GPU memory usage.
+    size_bytes: usize,
+    is_render_target: bool,
     // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
     thread_mark: PhantomData<*const u8>,
 }
@@ -429,7 +435,7 @@ fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth
     }
 }
 
-fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> usize {
+pub(crate) fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> usize {
     let pixel_count = width * height;
     match pixel_kind {
         PixelKind::RGBA32F => 16 * pixel_count,
@@ -552,6 +558,17 @@ impl MinificationFilter {
     pub fn into_gl_value(self) -> i32 {
         self as i32
     }
+
+    /// Returns `true` if the filter samples mip levels of a texture.
+    pub fn is_using_mip_mapping(self) -> bool {
+        match self {
+            Self::Nearest | Self::Linear => false,
+            Self::NearestMipMapNearest
+            | Self::NearestMipMapLinear
+            | Self::LinearMipMapNearest
+            | Self::LinearMipMapLinear => true,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -628,10 +645,7 @@ impl CubeMapFace {
 impl<'a> TextureBinding<'a> {
     pub fn set_anisotropy(self, anisotropy: f32) -> Self {
         unsafe {
-            let max = self
-                .state
-                .gl
-                .get_parameter_f32(glow::MAX_TEXTURE_MAX_ANISOTROPY_EXT);
+            let max = self.state.capabilities().max_anisotropy;
             self.state.gl.tex_parameter_f32(
                 glow::TEXTURE_2D,
                 glow::TEXTURE_MAX_ANISOTROPY_EXT,
@@ -690,6 +704,17 @@ impl<'a> TextureBinding<'a> {
         self
     }
 
+    /// Generates every mip level of the texture (up to the mip count the texture was created
+    /// with) from its base level.
+    pub fn generate_mip_maps(self) -> Self {
+        unsafe {
+            self.state
+                .gl
+                .generate_mipmap(self.texture.kind.gl_texture_target());
+        }
+        self
+    }
+
     pub fn set_border_color(self, #[allow(unused_variables)] color: Color) -> Self {
         #[cfg(not(target_arch = "wasm32"))]
         unsafe {
@@ -776,6 +801,12 @@ impl<'a> TextureBinding<'a> {
         self.texture.kind = kind;
         self.texture.pixel_kind = pixel_kind;
 
+        let category = self.texture.memory_category();
+        self.state
+            .register_deallocation(category, self.texture.size_bytes);
+        self.state.register_allocation(category, desired_byte_count);
+        self.texture.size_bytes = desired_byte_count;
+
         let target = kind.gl_texture_target();
 
         unsafe {
@@ -1066,6 +1097,8 @@ impl GpuTexture {
                 r_wrap_mode: WrapMode::Repeat,
                 anisotropy: 1.0,
                 pixel_kind,
+                size_bytes: 0,
+                is_render_target: false,
                 thread_mark: PhantomData,
             };
 
@@ -1157,14 +1190,194 @@ impl GpuTexture {
         self.anisotropy
     }
 
+    /// Returns the size of the data of every mip level of the texture in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
+    fn memory_category(&self) -> GpuMemoryCategory {
+        if self.is_render_target {
+            GpuMemoryCategory::RenderTarget
+        } else {
+            GpuMemoryCategory::Texture
+        }
+    }
+
+    /// Marks the texture as a render target (frame buffer attachment), so its memory is counted
+    /// in the respective category of the memory statistics.
+    pub(crate) fn mark_as_render_target(&mut self, state: &PipelineState) {
+        if !self.is_render_target {
+            state.register_deallocation(GpuMemoryCategory::Texture, self.size_bytes);
+            state.register_allocation(GpuMemoryCategory::RenderTarget, self.size_bytes);
+            self.is_render_target = true;
+        }
+    }
+
     pub fn pixel_kind(&self) -> PixelKind {
         self.pixel_kind
     }
+
+    /// Copies the entire main level of detail of the texture to the destination texture. Both
+    /// textures must be rectangle textures of the same size and pixel kind. See
+    /// [`Self::copy_region_to`] for more info.
+    pub fn copy_to(&self, state: &PipelineState, dest: &GpuTexture) -> Result<(), FrameworkError> {
+        let GpuTextureKind::Rectangle { width, height } = self.kind else {
+            return Err(FrameworkError::Custom(
+                "Only rectangle textures can be copied!".to_string(),
+            ));
+        };
+        match dest.kind {
+            GpuTextureKind::Rectangle {
+                width: dest_width,
+                height: dest_height,
+            } if dest_width == width && dest_height == height => self.copy_region_to(
+                state,
+                Rect::new(0, 0, width as i32, height as i32),
+                dest,
+                0,
+                0,
+            ),
+            _ => Err(FrameworkError::Custom(
+                "Destination texture must have the same size as the source texture!".to_string(),
+            )),
+        }
+    }
+
+    /// Copies a region of the main level of detail of the texture to the destination texture at
+    /// the given position. The copy is done entirely on the GPU, without any round trips to the
+    /// CPU. Both textures must be rectangle textures with the same pixel kind, and the region
+    /// must fit in both textures.
+    ///
+    /// The copy is done via `glCopyImageSubData` if it is supported (see
+    /// [`super::state::PipelineCapabilities::copy_image`]), otherwise it falls back to frame
+    /// buffer blitting, which does not support compressed textures.
+    pub fn copy_region_to(
+        &self,
+        state: &PipelineState,
+        region: Rect<i32>,
+        dest: &GpuTexture,
+        dest_x: i32,
+        dest_y: i32,
+    ) -> Result<(), FrameworkError> {
+        let (
+            GpuTextureKind::Rectangle { width, height },
+            GpuTextureKind::Rectangle {
+                width: dest_width,
+                height: dest_height,
+            },
+        ) = (self.kind, dest.kind)
+        else {
+            return Err(FrameworkError::Custom(
+                "Only rectangle textures can be copied!".to_string(),
+            ));
+        };
+
+        if self.pixel_kind != dest.pixel_kind {
+            return Err(FrameworkError::Custom(format!(
+                "Unable to copy texture with {:?} pixels to a texture with {:?} pixels!",
+                self.pixel_kind, dest.pixel_kind
+            )));
+        }
+
+        let fits = |x: i32, y: i32, w: usize, h: usize| {
+            x >= 0
+                && y >= 0
+                && region.w() >= 0
+                && region.h() >= 0
+                && x + region.w() <= w as i32
+                && y + region.h() <= h as i32
+        };
+        if !fits(region.x(), region.y(), width, height)
+            || !fits(dest_x, dest_y, dest_width, dest_height)
+        {
+            return Err(FrameworkError::Custom(format!(
+                "Region {:?} does not fit in the source or the destination texture!",
+                region
+            )));
+        }
+
+        unsafe {
+            if state.capabilities().copy_image {
+                state.gl.copy_image_sub_data(
+                    self.texture,
+                    glow::TEXTURE_2D,
+                    0,
+                    region.x(),
+                    region.y(),
+                    0,
+                    dest.texture,
+                    glow::TEXTURE_2D,
+                    0,
+                    dest_x,
+                    dest_y,
+                    0,
+                    region.w(),
+                    region.h(),
+                    1,
+                );
+            } else {
+                if self.pixel_kind.is_compressed() {
+                    return Err(FrameworkError::Custom(
+                        "Compressed textures cannot be copied on this device!".to_string(),
+                    ));
+                }
+
+                let (attachment, copy_color, copy_depth, copy_stencil) = match self.pixel_kind {
+                    PixelKind::D32F | PixelKind::D16 => {
+                        (glow::DEPTH_ATTACHMENT, false, true, false)
+                    }
+                    PixelKind::D24S8 => (glow::DEPTH_STENCIL_ATTACHMENT, false, true, true),
+                    _ => (glow::COLOR_ATTACHMENT0, true, false, false),
+                };
+
+                let read_fbo = state.gl.create_framebuffer()?;
+                let draw_fbo = state.gl.create_framebuffer()?;
+
+                for (fbo, texture) in [(read_fbo, self.texture), (draw_fbo, dest.texture)] {
+                    state.set_framebuffer(Some(fbo));
+                    state.gl.framebuffer_texture_2d(
+                        glow::FRAMEBUFFER,
+                        attachment,
+                        glow::TEXTURE_2D,
+                        Some(texture),
+                        0,
+                    );
+                }
+
+                state.blit_framebuffer(
+                    Some(read_fbo),
+                    Some(draw_fbo),
+                    region.x(),
+                    region.y(),
+                    region.x() + region.w(),
+                    region.y() + region.h(),
+                    dest_x,
+                    dest_y,
+                    dest_x + region.w(),
+                    dest_y + region.h(),
+                    copy_color,
+                    copy_depth,
+                    copy_stencil,
+                    BlitFilter::Nearest,
+                );
+
+                // Blitting binds read and draw frame buffers directly, reset the binding to keep
+                // the cached state in sync.
+                state.set_framebuffer(None);
+
+                state.gl.delete_framebuffer(read_fbo);
+                state.gl.delete_framebuffer(draw_fbo);
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for GpuTexture {
     fn drop(&mut self) {
         if let Some(state) = self.state.upgrade() {
+            state.register_deallocation(self.memory_category(), self.size_bytes);
             unsafe {
                 state.gl.delete_texture(self.texture);
             }
@@ -1,8 +1,10 @@
 #![allow(missing_docs)] // TODO
 
 pub mod error;
+pub mod fence;
 pub mod framebuffer;
 pub mod geometry_buffer;
 pub mod gpu_program;
 pub mod gpu_texture;
+pub mod screen_texture;
 pub mod state;
@@ -0,0 +1,173 @@
+//! Render target textures, which size tracks the size of the screen. See [`ScreenRelativeTexture`]
+//! docs for more info.
+
+use crate::renderer::framework::{
+    error::FrameworkError,
+    gpu_texture::{GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind},
+    state::PipelineState,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+/// Calculates the size of a screen relative texture for the given frame size and scale. The size
+/// is rounded down and it is never less than one pixel.
+pub fn screen_relative_size(frame_size: (u32, u32), scale: f32) -> (usize, usize) {
+    let dimension = |size: u32| ((size as f32 * scale) as usize).max(1);
+    (dimension(frame_size.0), dimension(frame_size.1))
+}
+
+/// A texture, which size is a fraction (or a multiple) of the size of the screen. The renderer
+/// resizes such textures automatically when the screen size changes, so an effect that uses the
+/// texture does not need to handle resizing by itself.
+///
+/// ## Auto-resize semantics
+///
+/// - The texture is resized in place: the GPU texture object stays the same, so every frame buffer
+///   that uses the texture as an attachment stays valid and needs no re-creation.
+/// - The contents of the texture is undefined after resizing, it must be fully re-rendered.
+/// - The size is calculated by [`screen_relative_size`], every dimension is rounded down and it
+///   is at least one pixel.
+/// - The texture is tracked as long as there is at least one strong reference to it (this handle,
+///   a clone of [`Self::texture`] or a frame buffer attachment). Once every reference is dropped, the
+///   texture is removed from the tracking list.
+/// - Sampling parameters (filtering, wrapping, etc.) are preserved on resize.
+#[derive(Clone)]
+pub struct ScreenRelativeTexture {
+    texture: Rc<RefCell<GpuTexture>>,
+    scale: f32,
+    pixel_kind: PixelKind,
+}
+
+impl ScreenRelativeTexture {
+    /// Returns a shared reference to the GPU texture.
+    pub fn texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.texture
+    }
+
+    /// Returns the scale of the texture relative to the size of the screen.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns pixel format of the texture.
+    pub fn pixel_kind(&self) -> PixelKind {
+        self.pixel_kind
+    }
+}
+
+struct TrackedTexture {
+    texture: Weak<RefCell<GpuTexture>>,
+    scale: f32,
+    pixel_kind: PixelKind,
+}
+
+/// A list of every alive [`ScreenRelativeTexture`], it is used by the renderer to resize the
+/// textures when the screen size changes.
+#[derive(Default)]
+pub struct ScreenRelativeTextureStorage {
+    textures: Vec<TrackedTexture>,
+}
+
+impl ScreenRelativeTextureStorage {
+    /// Creates a new texture with the given scale relative to the given frame size and starts
+    /// tracking it. The texture uses nearest filtering by default.
+    pub fn create(
+        &mut self,
+        state: &PipelineState,
+        frame_size: (u32, u32),
+        scale: f32,
+        pixel_kind: PixelKind,
+    ) -> Result<ScreenRelativeTexture, FrameworkError> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(FrameworkError::Custom(format!(
+                "Scale of a screen relative texture must be a positive number, got {scale}!"
+            )));
+        }
+
+        let (width, height) = screen_relative_size(frame_size, scale);
+        let texture = Rc::new(RefCell::new(GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            pixel_kind,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?));
+
+        self.textures.push(TrackedTexture {
+            texture: Rc::downgrade(&texture),
+            scale,
+            pixel_kind,
+        });
+
+        Ok(ScreenRelativeTexture {
+            texture,
+            scale,
+            pixel_kind,
+        })
+    }
+
+    /// Resizes every alive texture to match the new frame size. Textures, that are no longer used,
+    /// are removed from the storage.
+    pub fn set_frame_size(
+        &mut self,
+        state: &PipelineState,
+        frame_size: (u32, u32),
+    ) -> Result<(), FrameworkError> {
+        self.textures
+            .retain(|tracked| tracked.texture.strong_count() > 0);
+
+        for tracked in self.textures.iter() {
+            let Some(texture) = tracked.texture.upgrade() else {
+                continue;
+            };
+
+            let (width, height) = screen_relative_size(frame_size, tracked.scale);
+            let mut texture = texture.borrow_mut();
+            if let GpuTextureKind::Rectangle {
+                width: current_width,
+                height: current_height,
+            } = texture.kind()
+            {
+                if current_width == width && current_height == height {
+                    continue;
+                }
+            }
+
+            texture.bind_mut(state, 0).set_data(
+                GpuTextureKind::Rectangle { width, height },
+                tracked.pixel_kind,
+                1,
+                None,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the amount of tracked textures.
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    /// Returns `true` if the storage tracks no textures, `false` - otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::framework::screen_texture::screen_relative_size;
+
+    #[test]
+    fn test_screen_relative_size() {
+        assert_eq!(screen_relative_size((1920, 1080), 1.0), (1920, 1080));
+        assert_eq!(screen_relative_size((1921, 1081), 0.5), (960, 540));
+        assert_eq!(screen_relative_size((1, 1), 0.25), (1, 1));
+        assert_eq!(screen_relative_size((100, 50), 2.0), (200, 100));
+    }
+}
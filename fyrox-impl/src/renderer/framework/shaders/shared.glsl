@@ -139,6 +139,53 @@ vec3 S_PBR_CalculateLight(TPBRContext ctx) {
     return (kD * ctx.albedo / PI + specular) * ctx.lightColor * NdotL;
 }
 
+// Calculates physically-correct lighting of a spherical area light using "representative point"
+// method (Brian Karis, "Real Shading in Unreal Engine 4"). Specular term is calculated for the
+// point of the sphere, that is closest to the reflection ray, and its distribution is widened
+// and renormalized according to the solid angle of the sphere. Diffuse term uses the direction
+// to the center of the sphere (ctx.fragmentToLight). `fragmentToLight` is an unnormalized vector
+// to the center of the sphere. Zero radius gives exactly the same result as S_PBR_CalculateLight.
+vec3 S_PBR_CalculateSphereLight(TPBRContext ctx, vec3 fragmentToLight, float sourceRadius) {
+    vec3 F0 = mix(vec3(0.04), ctx.albedo, ctx.metallic);
+
+    vec3 N = ctx.fragmentNormal;
+    vec3 V = ctx.viewVector;
+    vec3 L = ctx.fragmentToLight;
+
+    vec3 R = reflect(-V, N);
+    vec3 centerToRay = dot(fragmentToLight, R) * R - fragmentToLight;
+    vec3 closestPoint = fragmentToLight + centerToRay * clamp(sourceRadius / max(length(centerToRay), 0.0001), 0.0, 1.0);
+    vec3 specularL = normalize(closestPoint);
+    vec3 H = normalize(V + specularL);
+
+    // Energy conservation for the widened distribution.
+    float normalization = 1.0;
+    if (sourceRadius > 0.0) {
+        float alpha = ctx.roughness * ctx.roughness;
+        float alphaPrime = clamp(alpha + sourceRadius / (2.0 * max(length(fragmentToLight), 0.0001)), 0.0, 1.0);
+        normalization = (alpha / alphaPrime) * (alpha / alphaPrime);
+    }
+
+    // Cook-Torrance BRDF
+    float NDF = S_DistributionGGX(N, H, ctx.roughness) * normalization;
+    float G = S_GeometrySmith(N, V, specularL, ctx.roughness);
+    vec3 F = S_FresnelSchlick(max(dot(H, V), 0.0), F0);
+
+    float specularNdotL = max(dot(N, specularL), 0.0);
+
+    vec3 numerator = NDF * G * F;
+    float denominator = 4.0 * max(dot(N, V), 0.0) * specularNdotL + 0.001; // 0.001 to prevent divide by zero.
+    vec3 specular = numerator / denominator;
+
+    vec3 kS = F;
+    vec3 kD = vec3(1.0) - kS;
+    kD *= 1.0 - ctx.metallic;
+
+    float NdotL = max(dot(N, L), 0.0);
+
+    return (kD * ctx.albedo / PI * NdotL + specular * specularNdotL) * ctx.lightColor;
+}
+
 // Returns scatter amount for given parameters.
 // https://cseweb.ucsd.edu/~ravir/papers/singlescat/scattering.pdf
 // https://blog.mmacklin.com/2010/05/29/in-scattering-demo/
@@ -179,51 +226,74 @@ bool S_RaySphereIntersection(vec3 origin, vec3 dir, vec3 center, float radius, o
     return S_SolveQuadraticEq(a, b, c, minT, maxT);
 }
 
+// Fetches distance to the nearest shadow caster in the given direction. If static shadow map is used,
+// the closest distance from both maps is taken.
+float S_PointShadowDistance(
+    vec3 direction,
+    in samplerCube shadowMap,
+    bool useStaticShadowMap,
+    in samplerCube staticShadowMap)
+{
+    float distance = texture(shadowMap, direction).r;
+    if (useStaticShadowMap)
+    {
+        distance = min(distance, texture(staticShadowMap, direction).r);
+    }
+    return distance;
+}
+
 // Calculates point shadow factor where 1.0 - no shadow, 0.0 - fully in shadow.
 // Why value is inversed? To be able to directly multiply color to shadow factor.
 float S_PointShadow(
     bool shadowsEnabled,
     bool softShadows,
+    int pcfKernelRadius,
     float fragmentDistance,
     float shadowBias,
     vec3 toLight,
-    in samplerCube shadowMap)
+    in samplerCube shadowMap,
+    bool useStaticShadowMap,
+    in samplerCube staticShadowMap)
 {
     if (shadowsEnabled)
     {
         float biasedFragmentDistance = fragmentDistance - shadowBias;
 
-        if (softShadows)
+        if (softShadows && pcfKernelRadius > 0)
         {
-            const int samples = 20;
+            // Samples are taken on a square grid in the plane, that is perpendicular to the fetch
+            // direction, the size of the grid is defined by the kernel radius.
+            vec3 direction = -toLight;
+            vec3 up = abs(direction.y) < 0.99 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0);
+            vec3 tangent = normalize(cross(up, direction));
+            vec3 bitangent = cross(direction, tangent);
 
-            const vec3 directions[samples] = vec3[samples] (
-            vec3(1, 1, 1), vec3(1, -1, 1), vec3(-1, -1, 1), vec3(-1, 1, 1),
-            vec3(1, 1, -1), vec3(1, -1, -1), vec3(-1, -1, -1), vec3(-1, 1, -1),
-            vec3(1, 1, 0), vec3(1, -1, 0), vec3(-1, -1, 0), vec3(-1, 1, 0),
-            vec3(1, 0, 1), vec3(-1, 0, 1), vec3(1, 0, -1), vec3(-1, 0, -1),
-            vec3(0, 1, 1), vec3(0, -1, 1), vec3(0, -1, -1), vec3(0, 1, -1)
-            );
-
-            const float diskRadius = 0.0025;
+            const float sampleSpacing = 0.0025;
 
             float accumulator = 0.0;
 
-            for (int i = 0; i < samples; ++i)
+            for (int y = -pcfKernelRadius; y <= pcfKernelRadius; ++y)
             {
-                vec3 fetchDirection = -toLight + directions[i] * diskRadius;
-                float shadowDistanceToLight = texture(shadowMap, fetchDirection).r;
-                if (biasedFragmentDistance > shadowDistanceToLight)
+                for (int x = -pcfKernelRadius; x <= pcfKernelRadius; ++x)
                 {
-                    accumulator += 1.0;
+                    vec3 fetchDirection = direction + (tangent * float(x) + bitangent * float(y)) * sampleSpacing;
+                    float shadowDistanceToLight = S_PointShadowDistance(
+                        fetchDirection, shadowMap, useStaticShadowMap, staticShadowMap);
+                    if (biasedFragmentDistance > shadowDistanceToLight)
+                    {
+                        accumulator += 1.0;
+                    }
                 }
             }
 
-            return clamp(1.0 - accumulator / float(samples), 0.0, 1.0);
+            float kernelSize = float(2 * pcfKernelRadius + 1);
+
+            return clamp(1.0 - accumulator / (kernelSize * kernelSize), 0.0, 1.0);
         }
         else
         {
-            float shadowDistanceToLight = texture(shadowMap, -toLight).r;
+            float shadowDistanceToLight = S_PointShadowDistance(
+                -toLight, shadowMap, useStaticShadowMap, staticShadowMap);
             return biasedFragmentDistance > shadowDistanceToLight ? 0.0 : 1.0;
         }
     } else {
@@ -351,6 +421,19 @@ mat4 S_FetchMatrix(in sampler2D storage, int index) {
     return mat4(col1, col2, col3, col4);
 }
 
+// Interleaved sampling splits a map in subsets of pixels, that are rendered at reduced resolution.
+// The subset with the index `phase` contains every pixel with `(x + y) % 2 == phase` (checkerboard)
+// for the factor 2 and every pixel with `x % 2 + 2 * (y % 2) == phase` for the factor 4. Returns the
+// pixel of the full map, that corresponds to the given pixel of the subset.
+ivec2 S_InterleavedSubsetToPixel(ivec2 subsetPixel, int factor, int phase) {
+    if (factor == 2) {
+        return ivec2(2 * subsetPixel.x + (subsetPixel.y + phase) % 2, subsetPixel.y);
+    } else if (factor == 4) {
+        return 2 * subsetPixel + ivec2(phase % 2, phase / 2);
+    }
+    return subsetPixel;
+}
+
 struct TBlendShapeOffsets {
     vec3 position;
     vec3 normal;
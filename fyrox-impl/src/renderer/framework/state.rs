@@ -1,7 +1,7 @@
 use crate::renderer::PipelineStatistics;
 use crate::{
     core::{color::Color, math::Rect, reflect::prelude::*, visitor::prelude::*},
-    renderer::framework::framebuffer::{CullFace, DrawParameters},
+    renderer::framework::framebuffer::{BlendParameters, CullFace, DrawParameters},
 };
 use fyrox_core::uuid_provider;
 use glow::{Framebuffer, HasContext};
@@ -233,6 +233,9 @@ struct InnerState {
 
     frame_statistics: PipelineStatistics,
     gl_kind: GlKind,
+    verify_state_cache: bool,
+
+    memory_usage: GpuMemoryStats,
 }
 
 impl InnerState {
@@ -265,6 +268,120 @@ impl InnerState {
             frame_statistics: Default::default(),
             blend_equation: Default::default(),
             gl_kind,
+            verify_state_cache: false,
+            memory_usage: Default::default(),
+        }
+    }
+}
+
+/// A set of hardware limits and capabilities of the graphics device. The values are queried once
+/// when the pipeline state is created. Could be used to adapt quality settings to the hardware.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PipelineCapabilities {
+    /// Maximum width and height of a 2D texture.
+    pub max_texture_size: usize,
+    /// Maximum width and height of each face of a cube map texture.
+    pub max_cube_map_texture_size: usize,
+    /// Maximum width, height and depth of a volume texture.
+    pub max_3d_texture_size: usize,
+    /// Maximum amount of color attachments of a single frame buffer.
+    pub max_color_attachments: usize,
+    /// Maximum amount of texture units that could be used in a fragment shader.
+    pub max_texture_image_units: usize,
+    /// Maximum level of anisotropic filtering. `1.0` if anisotropic filtering is not supported.
+    pub max_anisotropy: f32,
+    /// A list of supported compressed texture formats (`GL_COMPRESSED_*` constants).
+    pub compressed_texture_formats: Vec<u32>,
+    /// `true` if the device can copy texture data directly (`glCopyImageSubData`). When it is not
+    /// supported, texture copies fall back to frame buffer blitting.
+    pub copy_image: bool,
+}
+
+impl PipelineCapabilities {
+    fn query(gl: &glow::Context) -> Self {
+        unsafe {
+            let get_usize = |parameter| gl.get_parameter_i32(parameter).max(0) as usize;
+
+            let extensions = gl.supported_extensions();
+            let max_anisotropy = if extensions.contains("GL_EXT_texture_filter_anisotropic")
+                || extensions.contains("GL_ARB_texture_filter_anisotropic")
+            {
+                gl.get_parameter_f32(glow::MAX_TEXTURE_MAX_ANISOTROPY_EXT)
+                    .max(1.0)
+            } else {
+                1.0
+            };
+
+            let version = gl.version();
+            let copy_image = if version.is_embedded {
+                (version.major, version.minor) >= (3, 2)
+                    || extensions.contains("GL_EXT_copy_image")
+                    || extensions.contains("GL_OES_copy_image")
+            } else {
+                (version.major, version.minor) >= (4, 3) || extensions.contains("GL_ARB_copy_image")
+            };
+
+            let mut compressed_texture_formats =
+                vec![0; get_usize(glow::NUM_COMPRESSED_TEXTURE_FORMATS)];
+            if !compressed_texture_formats.is_empty() {
+                gl.get_parameter_i32_slice(
+                    glow::COMPRESSED_TEXTURE_FORMATS,
+                    &mut compressed_texture_formats,
+                );
+            }
+
+            Self {
+                max_texture_size: get_usize(glow::MAX_TEXTURE_SIZE),
+                max_cube_map_texture_size: get_usize(glow::MAX_CUBE_MAP_TEXTURE_SIZE),
+                max_3d_texture_size: get_usize(glow::MAX_3D_TEXTURE_SIZE),
+                max_color_attachments: get_usize(glow::MAX_COLOR_ATTACHMENTS),
+                max_texture_image_units: get_usize(glow::MAX_TEXTURE_IMAGE_UNITS),
+                max_anisotropy,
+                compressed_texture_formats: compressed_texture_formats
+                    .into_iter()
+                    .map(|format| format as u32)
+                    .collect(),
+                copy_image,
+            }
+        }
+    }
+}
+
+/// Category of GPU memory allocations, see [`GpuMemoryStats`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GpuMemoryCategory {
+    /// Textures, that are used only for sampling.
+    Texture,
+    /// Vertex, index and pixel buffers.
+    Buffer,
+    /// Textures, that are attached to frame buffers (G-Buffer, shadow maps, etc.).
+    RenderTarget,
+}
+
+/// Amount of GPU memory (in bytes) allocated by the renderer, grouped by category. The values
+/// are estimations based on the sizes of the allocated resources, the actual memory usage could
+/// be higher, because video drivers may add padding, alignment and internal buffers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GpuMemoryStats {
+    /// Memory used by textures, that are used only for sampling.
+    pub textures: usize,
+    /// Memory used by vertex, index and pixel buffers.
+    pub buffers: usize,
+    /// Memory used by textures, that are attached to frame buffers.
+    pub render_targets: usize,
+}
+
+impl GpuMemoryStats {
+    /// Returns total amount of memory of every category.
+    pub fn total(&self) -> usize {
+        self.textures + self.buffers + self.render_targets
+    }
+
+    fn category_mut(&mut self, category: GpuMemoryCategory) -> &mut usize {
+        match category {
+            GpuMemoryCategory::Texture => &mut self.textures,
+            GpuMemoryCategory::Buffer => &mut self.buffers,
+            GpuMemoryCategory::RenderTarget => &mut self.render_targets,
         }
     }
 }
@@ -273,6 +390,7 @@ pub type SharedPipelineState = Rc<PipelineState>;
 
 pub struct PipelineState {
     pub gl: glow::Context,
+    capabilities: PipelineCapabilities,
     state: RefCell<InnerState>,
     this: RefCell<Option<Weak<PipelineState>>>,
 }
@@ -477,6 +595,20 @@ impl Default for PolygonFillMode {
     }
 }
 
+/// Filter, that is used when a blit operation scales the source region to fit the destination
+/// region.
+#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Default, Eq)]
+#[repr(u32)]
+pub enum BlitFilter {
+    /// Takes the closest pixel of the source region. It is the only filter, that can be used to
+    /// copy depth and stencil values.
+    #[default]
+    Nearest = glow::NEAREST,
+    /// Interpolates the neighbouring pixels of the source region. It is required for correct
+    /// downsampling of color buffers (for example, to half resolution).
+    Linear = glow::LINEAR,
+}
+
 impl PipelineState {
     pub fn new(
         #[allow(unused_mut)] mut context: glow::Context,
@@ -552,6 +684,7 @@ impl PipelineState {
         }
 
         let state = Self {
+            capabilities: PipelineCapabilities::query(&context),
             gl: context,
             state: RefCell::new(InnerState::new(gl_kind)),
             this: Default::default(),
@@ -572,6 +705,32 @@ impl PipelineState {
         self.state.borrow().gl_kind
     }
 
+    /// Returns hardware limits and capabilities of the graphics device.
+    pub fn capabilities(&self) -> &PipelineCapabilities {
+        &self.capabilities
+    }
+
+    /// Returns the amount of GPU memory, that is currently allocated by textures and buffers.
+    pub fn memory_usage(&self) -> GpuMemoryStats {
+        self.state.borrow().memory_usage
+    }
+
+    /// Registers a new allocation of the given size in the memory statistics.
+    pub(crate) fn register_allocation(&self, category: GpuMemoryCategory, size_bytes: usize) {
+        *self.state.borrow_mut().memory_usage.category_mut(category) += size_bytes;
+    }
+
+    /// Removes an allocation of the given size from the memory statistics.
+    pub(crate) fn register_deallocation(&self, category: GpuMemoryCategory, size_bytes: usize) {
+        let mut state = self.state.borrow_mut();
+        let value = state.memory_usage.category_mut(category);
+        *value = value.saturating_sub(size_bytes);
+    }
+
+    pub fn polygon_fill_mode(&self) -> PolygonFillMode {
+        self.state.borrow().polygon_fill_mode
+    }
+
     pub fn set_polygon_fill_mode(
         &self,
         polygon_face: PolygonFace,
@@ -633,6 +792,8 @@ impl PipelineState {
                     self.gl.disable(glow::BLEND);
                 }
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -648,6 +809,8 @@ impl PipelineState {
                     self.gl.disable(glow::DEPTH_TEST);
                 }
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -659,6 +822,8 @@ impl PipelineState {
             unsafe {
                 self.gl.depth_mask(state.depth_write);
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -675,6 +840,8 @@ impl PipelineState {
                     state.color_write.alpha,
                 );
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -690,6 +857,8 @@ impl PipelineState {
                     self.gl.disable(glow::STENCIL_TEST);
                 }
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -699,6 +868,8 @@ impl PipelineState {
             state.cull_face = cull_face;
 
             unsafe { self.gl.cull_face(state.cull_face as u32) }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -714,6 +885,8 @@ impl PipelineState {
                     self.gl.disable(glow::CULL_FACE);
                 }
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -775,6 +948,8 @@ impl PipelineState {
                     state.blend_func.alpha_dfactor as u32,
                 );
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -789,6 +964,8 @@ impl PipelineState {
                     state.blend_equation.alpha as u32,
                 );
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -848,6 +1025,8 @@ impl PipelineState {
                     state.stencil_func.mask,
                 );
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -865,6 +1044,8 @@ impl PipelineState {
 
                 self.gl.stencil_mask(state.stencil_op.write_mask);
             }
+        } else {
+            state.frame_statistics.skipped_state_changes += 1;
         }
     }
 
@@ -924,6 +1105,7 @@ impl PipelineState {
         copy_color: bool,
         copy_depth: bool,
         copy_stencil: bool,
+        filter: BlitFilter,
     ) {
         let mut mask = 0;
         if copy_color {
@@ -949,7 +1131,7 @@ impl PipelineState {
                 dst_x1,
                 dst_y1,
                 mask,
-                glow::NEAREST,
+                filter as u32,
             );
         }
     }
@@ -994,6 +1176,124 @@ impl PipelineState {
         } else {
             self.set_culling(false);
         }
+
+        if self.state.borrow().verify_state_cache {
+            self.verify_state_cache();
+        }
+    }
+
+    /// Enables or disables verification of the internal state cache. When enabled, every call of
+    /// [`Self::apply_draw_parameters`] queries the actual state from OpenGL and panics if it does not
+    /// match the cached one. This is very slow and should only be used for debugging purposes.
+    pub fn set_state_cache_verification(&self, enabled: bool) {
+        self.state.borrow_mut().verify_state_cache = enabled;
+    }
+
+    /// Returns `true` if the state cache verification is enabled, `false` - otherwise.
+    pub fn is_state_cache_verification_enabled(&self) -> bool {
+        self.state.borrow().verify_state_cache
+    }
+
+    /// Compares the cached draw state with the actual state of OpenGL and panics if there's any
+    /// mismatch.
+    pub fn verify_state_cache(&self) {
+        let state = self.state.borrow();
+
+        unsafe {
+            let gl = &self.gl;
+
+            assert_eq!(
+                gl.is_enabled(glow::BLEND),
+                state.blend,
+                "Blend state mismatch!"
+            );
+            assert_eq!(
+                gl.is_enabled(glow::DEPTH_TEST),
+                state.depth_test,
+                "Depth test state mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::DEPTH_WRITEMASK) != 0,
+                state.depth_write,
+                "Depth write state mismatch!"
+            );
+            assert_eq!(
+                gl.is_enabled(glow::STENCIL_TEST),
+                state.stencil_test,
+                "Stencil test state mismatch!"
+            );
+            assert_eq!(
+                gl.is_enabled(glow::CULL_FACE),
+                state.culling,
+                "Culling state mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::CULL_FACE_MODE) as u32,
+                state.cull_face as u32,
+                "Cull face mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::BLEND_SRC_RGB) as u32,
+                state.blend_func.sfactor as u32,
+                "Blend source factor mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::BLEND_DST_RGB) as u32,
+                state.blend_func.dfactor as u32,
+                "Blend destination factor mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::BLEND_SRC_ALPHA) as u32,
+                state.blend_func.alpha_sfactor as u32,
+                "Blend source alpha factor mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::BLEND_DST_ALPHA) as u32,
+                state.blend_func.alpha_dfactor as u32,
+                "Blend destination alpha factor mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::STENCIL_FUNC) as u32,
+                state.stencil_func.func as u32,
+                "Stencil function mismatch!"
+            );
+            assert_eq!(
+                gl.get_parameter_i32(glow::STENCIL_REF) as u32,
+                state.stencil_func.ref_value,
+                "Stencil reference value mismatch!"
+            );
+        }
+    }
+
+    /// Returns draw parameters that are currently applied to the pipeline. The returned value is
+    /// built from the internal state cache, so it does not query anything from the GPU and could be
+    /// used to diagnose unexpected render state (for example, depth test that is still enabled).
+    pub fn current_draw_state(&self) -> DrawParameters {
+        let state = self.state.borrow();
+        DrawParameters {
+            cull_face: if state.culling {
+                Some(state.cull_face)
+            } else {
+                None
+            },
+            color_write: state.color_write,
+            depth_write: state.depth_write,
+            stencil_test: if state.stencil_test {
+                Some(state.stencil_func)
+            } else {
+                None
+            },
+            depth_test: state.depth_test,
+            blend: if state.blend {
+                Some(BlendParameters {
+                    func: state.blend_func,
+                    equation: state.blend_equation,
+                })
+            } else {
+                None
+            },
+            stencil_op: state.stencil_op,
+        }
     }
 
     pub fn pipeline_statistics(&self) -> PipelineStatistics {
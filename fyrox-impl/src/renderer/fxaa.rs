@@ -2,23 +2,19 @@ use crate::renderer::framework::geometry_buffer::ElementRange;
 use crate::{
     core::{
         algebra::{Matrix4, Vector2, Vector3},
-        math::Rect,
         sstorage::ImmutableString,
     },
     renderer::{
+        effect_chain::{PostEffect, PostEffectContext},
         framework::{
             error::FrameworkError,
-            framebuffer::{DrawParameters, FrameBuffer},
-            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            framebuffer::DrawParameters,
             gpu_program::{GpuProgram, UniformLocation},
-            gpu_texture::GpuTexture,
             state::PipelineState,
         },
-        RenderPassStatistics,
+        QualitySettings, RenderPassStatistics,
     },
-    scene::mesh::surface::SurfaceData,
 };
-use std::{cell::RefCell, rc::Rc};
 
 struct FxaaShader {
     pub program: GpuProgram,
@@ -47,28 +43,37 @@ impl FxaaShader {
 
 pub struct FxaaRenderer {
     shader: FxaaShader,
-    quad: GeometryBuffer,
 }
 
 impl FxaaRenderer {
+    pub const NAME: &'static str = "FXAA";
+
     pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
         Ok(Self {
             shader: FxaaShader::new(state)?,
-            quad: GeometryBuffer::from_surface_data(
-                &SurfaceData::make_unit_xy_quad(),
-                GeometryBufferKind::StaticDraw,
-                state,
-            )?,
         })
     }
+}
+
+impl PostEffect for FxaaRenderer {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn is_enabled(&self, quality_settings: &QualitySettings) -> bool {
+        quality_settings.fxaa
+    }
+
+    fn apply(&mut self, ctx: PostEffectContext) -> Result<RenderPassStatistics, FrameworkError> {
+        let PostEffectContext {
+            state,
+            quad,
+            viewport,
+            input,
+            output,
+            ..
+        } = ctx;
 
-    pub(crate) fn render(
-        &self,
-        state: &PipelineState,
-        viewport: Rect<i32>,
-        frame_texture: Rc<RefCell<GpuTexture>>,
-        frame_buffer: &mut FrameBuffer,
-    ) -> Result<RenderPassStatistics, FrameworkError> {
         let mut statistics = RenderPassStatistics::default();
 
         let frame_matrix = Matrix4::new_orthographic(
@@ -84,8 +89,8 @@ impl FxaaRenderer {
             0.0,
         ));
 
-        statistics += frame_buffer.draw(
-            &self.quad,
+        statistics += output.draw(
+            quad,
             state,
             viewport,
             &self.shader.program,
@@ -106,7 +111,7 @@ impl FxaaRenderer {
                         &self.shader.inverse_screen_size,
                         &Vector2::new(1.0 / viewport.w() as f32, 1.0 / viewport.h() as f32),
                     )
-                    .set_texture(&self.shader.screen_texture, &frame_texture);
+                    .set_texture(&self.shader.screen_texture, &input);
             },
         )?;
 
@@ -20,7 +20,7 @@ use crate::{
     renderer::{
         apply_material,
         bundle::RenderDataBundleStorage,
-        cache::shader::ShaderCache,
+        cache::shader::{RenderPassData, ShaderCache},
         framework::{
             error::FrameworkError,
             framebuffer::{
@@ -32,7 +32,9 @@ use crate::{
                 Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
                 PixelKind, WrapMode,
             },
-            state::{BlendFactor, BlendFunc, PipelineState},
+            state::{
+                BlendFactor, BlendFunc, CompareFunc, PipelineState, PolygonFace, PolygonFillMode,
+            },
         },
         gbuffer::decal::DecalShader,
         storage::MatrixStorageCache,
@@ -58,6 +60,7 @@ pub struct GBuffer {
     cube: GeometryBuffer,
     decal_shader: DecalShader,
     render_pass_name: ImmutableString,
+    depth_prepass_name: ImmutableString,
 }
 
 pub(crate) struct GBufferRenderContext<'a, 'b> {
@@ -74,10 +77,61 @@ pub(crate) struct GBufferRenderContext<'a, 'b> {
     pub black_dummy: Rc<RefCell<GpuTexture>>,
     pub volume_dummy: Rc<RefCell<GpuTexture>>,
     pub use_parallax_mapping: bool,
+    pub depth_prepass: bool,
     pub graph: &'b Graph,
     pub matrix_storage: &'a mut MatrixStorageCache,
 }
 
+// Stages of G-Buffer filling. When the depth prepass is enabled, opaque geometry is rendered twice:
+// the first time it fills the depth buffer only, using the depth-only "DepthPrepass" render pass of
+// its shader, the second time it is shaded by the "GBuffer" render pass with depth test set to
+// "equal", so the expensive fragment shaders are executed only once per pixel.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum FillStage {
+    Regular,
+    DepthPrepass,
+    Shading,
+}
+
+impl FillStage {
+    // Returns the render pass, its draw parameters and depth function for the given stage, or
+    // `None` if the bundle must not be rendered at the stage.
+    fn select<'a>(
+        self,
+        gbuffer_pass: &'a RenderPassData,
+        depth_pass: Option<&'a RenderPassData>,
+    ) -> Option<(&'a RenderPassData, DrawParameters, CompareFunc)> {
+        // Only the geometry, that writes depth and does not blend with the background, could be
+        // rendered in the prepass. The shader must also provide a depth-only pass for it.
+        let params = &gbuffer_pass.draw_params;
+        let depth_pass = depth_pass
+            .filter(|_| params.depth_write && params.depth_test && params.blend.is_none());
+
+        match self {
+            FillStage::Regular => Some((gbuffer_pass, params.clone(), CompareFunc::default())),
+            FillStage::DepthPrepass => depth_pass.map(|depth_pass| {
+                (
+                    depth_pass,
+                    depth_pass.draw_params.clone(),
+                    CompareFunc::default(),
+                )
+            }),
+            FillStage::Shading => Some(if depth_pass.is_some() {
+                (
+                    gbuffer_pass,
+                    DrawParameters {
+                        depth_write: false,
+                        ..params.clone()
+                    },
+                    CompareFunc::Equal,
+                )
+            } else {
+                (gbuffer_pass, params.clone(), CompareFunc::default())
+            }),
+        }
+    }
+}
+
 impl GBuffer {
     pub fn new(state: &PipelineState, width: usize, height: usize) -> Result<Self, FrameworkError> {
         scope_profile!();
@@ -227,6 +281,7 @@ impl GBuffer {
             )?,
             decal_framebuffer,
             render_pass_name: ImmutableString::new("GBuffer"),
+            depth_prepass_name: ImmutableString::new("DepthPrepass"),
         })
     }
 
@@ -274,6 +329,7 @@ impl GBuffer {
             texture_cache,
             shader_cache,
             use_parallax_mapping,
+            depth_prepass,
             white_dummy,
             normal_dummy,
             black_dummy,
@@ -299,11 +355,21 @@ impl GBuffer {
         let camera_up = inv_view.up();
         let camera_side = inv_view.side();
 
-        for bundle in bundle_storage
-            .bundles
-            .iter()
-            .filter(|b| b.render_path == RenderPath::Deferred)
-        {
+        let pass_fill_mode = state.polygon_fill_mode();
+
+        let stages: &[FillStage] = if depth_prepass {
+            &[FillStage::DepthPrepass, FillStage::Shading]
+        } else {
+            &[FillStage::Regular]
+        };
+
+        for (stage, bundle) in stages.iter().flat_map(|stage| {
+            bundle_storage
+                .bundles
+                .iter()
+                .filter(|b| b.render_path == RenderPath::Deferred)
+                .map(move |bundle| (*stage, bundle))
+        }) {
             let mut material_state = bundle.material.state();
 
             let Some(material) = material_state.data() else {
@@ -321,12 +387,25 @@ impl GBuffer {
                 .as_ref()
                 .and_then(|c| c.blend_shape_storage.clone());
 
-            let Some(render_pass) = shader_cache
-                .get(state, material.shader())
-                .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
-            else {
+            let Some(shader_set) = shader_cache.get(state, material.shader()) else {
+                continue;
+            };
+
+            let Some(gbuffer_pass) = shader_set.render_passes.get(&self.render_pass_name) else {
+                continue;
+            };
+
+            let Some((render_pass, draw_params, depth_func)) = stage.select(
+                gbuffer_pass,
+                shader_set.render_passes.get(&self.depth_prepass_name),
+            ) else {
                 continue;
             };
+            state.set_depth_func(depth_func);
+
+            if bundle.polygon_fill_mode != PolygonFillMode::Fill {
+                state.set_polygon_fill_mode(PolygonFace::FrontAndBack, bundle.polygon_fill_mode);
+            }
 
             for instance in bundle.instances.iter() {
                 let apply_uniforms = |mut program_binding: GpuProgramBinding| {
@@ -340,6 +419,7 @@ impl GBuffer {
 
                     apply_material(MaterialContext {
                         material,
+                        material_overrides: &bundle.material_overrides,
                         program_binding: &mut program_binding,
                         texture_cache,
                         matrix_storage,
@@ -348,6 +428,7 @@ impl GBuffer {
                         wvp_matrix: &(view_projection * instance.world_transform),
                         bone_matrices: &instance.bone_matrices,
                         use_skeletal_animation: bundle.is_skinned,
+                        use_instancing: false,
                         camera_position: &camera.global_position(),
                         camera_up_vector: &camera_up,
                         camera_side_vector: &camera_side,
@@ -373,13 +454,19 @@ impl GBuffer {
                     state,
                     viewport,
                     &render_pass.program,
-                    &render_pass.draw_params,
+                    &draw_params,
                     instance.element_range,
                     apply_uniforms,
                 )?;
             }
+
+            if bundle.polygon_fill_mode != PolygonFillMode::Fill {
+                state.set_polygon_fill_mode(PolygonFace::FrontAndBack, pass_fill_mode);
+            }
         }
 
+        state.set_depth_func(CompareFunc::default());
+
         let inv_view_proj = initial_view_projection.try_inverse().unwrap_or_default();
         let depth = self.depth();
         let decal_mask = self.decal_mask_texture();
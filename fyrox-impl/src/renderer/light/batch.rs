@@ -0,0 +1,311 @@
+//! Tiled batching of point lights. Instead of drawing every point light separately, the screen is
+//! split into tiles and every tile stores a list of the lights that could affect it. Then all the
+//! lights are rendered in a single full screen pass, every pixel loops over the lights of its tile.
+//!
+//! ## Motivation
+//!
+//! Why it uses textures instead of SSBO? See [`crate::renderer::storage::MatrixStorage`] docs, the
+//! reasons are the same.
+
+use crate::{
+    core::algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
+    renderer::framework::{
+        error::FrameworkError,
+        gpu_texture::{
+            GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+        },
+        state::PipelineState,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Size of a screen tile in pixels.
+pub const TILE_SIZE: usize = 32;
+
+// Maximum width of the storage textures, the rest of the data goes to the next rows.
+const STORAGE_WIDTH: usize = 1024;
+
+/// A point light, that will be rendered in a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchedPointLight {
+    /// Position of the light in world coordinates.
+    pub position: Vector3<f32>,
+    /// Radius of the light with the scale of the light node applied.
+    pub radius: f32,
+    /// Radius of the light source with the scale of the light node applied.
+    pub source_radius: f32,
+    /// Linear color of the light, multiplied by its intensity.
+    pub color: Vector3<f32>,
+}
+
+/// Calculates a range of tiles (inclusive), that is covered by the bounding box of the given
+/// sphere. Returns `None` if the sphere is off-screen.
+fn tile_range(
+    position: Vector3<f32>,
+    radius: f32,
+    view_projection: &Matrix4<f32>,
+    frame_size: Vector2<usize>,
+    tile_count: Vector2<usize>,
+) -> Option<(Vector2<usize>, Vector2<usize>)> {
+    let mut min = Vector2::repeat(f32::MAX);
+    let mut max = Vector2::repeat(-f32::MAX);
+    for x in [-1.0, 1.0] {
+        for y in [-1.0, 1.0] {
+            for z in [-1.0, 1.0] {
+                let corner = position + Vector3::new(x, y, z).scale(radius);
+                let clip_space = view_projection * Point3::from(corner).to_homogeneous();
+                if clip_space.w <= f32::EPSILON {
+                    // The camera is inside (or too close to) the bounds of the light, projection
+                    // is unreliable here so the light affects the entire screen.
+                    return Some((Vector2::zeros(), tile_count.map(|count| count - 1)));
+                }
+                let ndc = clip_space.xy().scale(1.0 / clip_space.w);
+                min = min.inf(&ndc);
+                max = max.sup(&ndc);
+            }
+        }
+    }
+
+    if max.x < -1.0 || max.y < -1.0 || min.x > 1.0 || min.y > 1.0 {
+        return None;
+    }
+
+    // Tiles are counted from the bottom-left corner of the screen, the same way as `gl_FragCoord`.
+    let to_tile = |ndc: f32, size: usize, count: usize| {
+        ((((ndc.clamp(-1.0, 1.0) + 1.0) * 0.5 * size as f32) as usize) / TILE_SIZE).min(count - 1)
+    };
+
+    Some((
+        Vector2::new(
+            to_tile(min.x, frame_size.x, tile_count.x),
+            to_tile(min.y, frame_size.y, tile_count.y),
+        ),
+        Vector2::new(
+            to_tile(max.x, frame_size.x, tile_count.x),
+            to_tile(max.y, frame_size.y, tile_count.y),
+        ),
+    ))
+}
+
+/// Per-tile lists of lights. `data` contains a header (offset and count of light indices) for
+/// every tile, followed by the light indices of all tiles.
+#[derive(Default)]
+struct LightGrid {
+    tile_count: Vector2<usize>,
+    tiles: Vec<Vec<u32>>,
+    data: Vec<f32>,
+}
+
+impl LightGrid {
+    fn build(
+        &mut self,
+        lights: &[BatchedPointLight],
+        view_projection: &Matrix4<f32>,
+        frame_size: Vector2<usize>,
+    ) {
+        self.tile_count =
+            frame_size.map(|size| ((size as f32 / TILE_SIZE as f32).ceil() as usize).max(1));
+        let total_tile_count = self.tile_count.x * self.tile_count.y;
+
+        self.tiles.resize_with(total_tile_count, Default::default);
+        for tile in self.tiles.iter_mut() {
+            tile.clear();
+        }
+
+        for (index, light) in lights.iter().enumerate() {
+            if let Some((min, max)) = tile_range(
+                light.position,
+                light.radius,
+                view_projection,
+                frame_size,
+                self.tile_count,
+            ) {
+                for y in min.y..=max.y {
+                    for x in min.x..=max.x {
+                        self.tiles[y * self.tile_count.x + x].push(index as u32);
+                    }
+                }
+            }
+        }
+
+        self.data.clear();
+        let mut offset = 2 * total_tile_count;
+        for tile in self.tiles.iter() {
+            self.data.push(offset as f32);
+            self.data.push(tile.len() as f32);
+            offset += tile.len();
+        }
+        for tile in self.tiles.iter() {
+            self.data.extend(tile.iter().map(|index| *index as f32));
+        }
+    }
+}
+
+fn make_storage_texture(
+    state: &PipelineState,
+    pixel_kind: PixelKind,
+    data: &[u8],
+) -> Result<Rc<RefCell<GpuTexture>>, FrameworkError> {
+    Ok(Rc::new(RefCell::new(GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle {
+            width: 1,
+            height: 1,
+        },
+        pixel_kind,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        Some(data),
+    )?)))
+}
+
+/// Uploads the given data to the texture, the data is padded to fill entire rows of the texture.
+fn upload<T: Copy + Default>(
+    state: &PipelineState,
+    texture: &Rc<RefCell<GpuTexture>>,
+    pixel_kind: PixelKind,
+    data: &mut Vec<T>,
+) -> Result<(), FrameworkError> {
+    let width = data.len().clamp(1, STORAGE_WIDTH);
+    let height = (data.len() as f32 / width as f32).ceil().max(1.0) as usize;
+    data.resize(width * height, Default::default());
+
+    texture.borrow_mut().bind_mut(state, 0).set_data(
+        GpuTextureKind::Rectangle { width, height },
+        pixel_kind,
+        1,
+        Some(crate::core::array_as_u8_slice(data.as_slice())),
+    )?;
+
+    Ok(())
+}
+
+/// A set of point lights, that will be rendered in a single pass. See module docs for more info.
+pub struct PointLightBatch {
+    lights: Vec<BatchedPointLight>,
+    light_data: Vec<Vector4<f32>>,
+    grid: LightGrid,
+    light_texture: Rc<RefCell<GpuTexture>>,
+    grid_texture: Rc<RefCell<GpuTexture>>,
+}
+
+impl PointLightBatch {
+    /// Creates a new empty batch.
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            lights: Default::default(),
+            light_data: Default::default(),
+            grid: Default::default(),
+            light_texture: make_storage_texture(
+                state,
+                PixelKind::RGBA32F,
+                crate::core::array_as_u8_slice(&[Vector4::<f32>::zeros()]),
+            )?,
+            grid_texture: make_storage_texture(state, PixelKind::R32F, &0.0f32.to_ne_bytes())?,
+        })
+    }
+
+    /// Removes every light from the batch.
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    /// Adds a new light to the batch.
+    pub fn push(&mut self, light: BatchedPointLight) {
+        self.lights.push(light);
+    }
+
+    /// Returns `true` if the batch has no lights.
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+
+    /// Returns amount of tiles along both axes, that were used in the last [`Self::upload`] call.
+    pub fn tile_count(&self) -> Vector2<usize> {
+        self.grid.tile_count
+    }
+
+    /// Assigns every light of the batch to screen tiles and uploads the lights and the tiles to
+    /// the textures.
+    pub fn upload(
+        &mut self,
+        state: &PipelineState,
+        view_projection: &Matrix4<f32>,
+        frame_size: Vector2<usize>,
+    ) -> Result<(), FrameworkError> {
+        self.grid.build(&self.lights, view_projection, frame_size);
+
+        self.light_data.clear();
+        for light in self.lights.iter() {
+            self.light_data.push(light.position.push(light.radius));
+            self.light_data.push(light.color.push(light.source_radius));
+        }
+
+        upload(
+            state,
+            &self.light_texture,
+            PixelKind::RGBA32F,
+            &mut self.light_data,
+        )?;
+        upload(
+            state,
+            &self.grid_texture,
+            PixelKind::R32F,
+            &mut self.grid.data,
+        )
+    }
+
+    /// Returns a texture with the data of the lights, every light occupies two texels.
+    pub fn light_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.light_texture
+    }
+
+    /// Returns a texture with per-tile lists of light indices.
+    pub fn grid_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.grid_texture
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector2, Vector3},
+        renderer::light::batch::{BatchedPointLight, LightGrid},
+    };
+
+    fn light(x: f32, y: f32, radius: f32) -> BatchedPointLight {
+        BatchedPointLight {
+            position: Vector3::new(x, y, 0.0),
+            radius,
+            source_radius: 0.0,
+            color: Vector3::repeat(1.0),
+        }
+    }
+
+    #[test]
+    fn test_light_grid() {
+        // Identity matrix maps [-1; 1] range directly to the screen, 64x64 frame gives 2x2 tiles.
+        let lights = [
+            light(-0.5, -0.5, 0.1),
+            light(0.5, 0.5, 0.1),
+            light(0.0, 0.0, 0.1),
+            light(10.0, 10.0, 1.0),
+        ];
+        let mut grid = LightGrid::default();
+        grid.build(&lights, &Matrix4::identity(), Vector2::new(64, 64));
+
+        assert_eq!(grid.tile_count, Vector2::new(2, 2));
+        assert_eq!(grid.tiles, vec![vec![0, 2], vec![2], vec![2], vec![1, 2]]);
+        #[rustfmt::skip]
+        assert_eq!(
+            grid.data,
+            vec![
+                // Headers.
+                8.0, 2.0, 10.0, 1.0, 11.0, 1.0, 12.0, 2.0,
+                // Indices.
+                0.0, 2.0, 2.0, 2.0, 1.0, 2.0,
+            ]
+        );
+    }
+}
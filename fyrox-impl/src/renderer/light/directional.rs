@@ -14,6 +14,7 @@ pub struct DirectionalLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub ao_sampler: UniformLocation,
     pub light_direction: UniformLocation,
     pub light_color: UniformLocation,
     pub inv_view_proj_matrix: UniformLocation,
@@ -52,6 +53,7 @@ impl DirectionalLightShader {
                 .uniform_location(state, &ImmutableString::new("normalTexture"))?,
             material_sampler: program
                 .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            ao_sampler: program.uniform_location(state, &ImmutableString::new("aoSampler"))?,
             light_direction: program
                 .uniform_location(state, &ImmutableString::new("lightDirection"))?,
             light_color: program.uniform_location(state, &ImmutableString::new("lightColor"))?,
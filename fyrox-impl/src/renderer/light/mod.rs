@@ -4,6 +4,7 @@ use crate::{
         algebra::{Matrix4, Point3, Vector2, Vector3},
         color::Color,
         math::{frustum::Frustum, Matrix4Ext, Rect, TriangleDefinition},
+        pool::Handle,
         scope_profile,
     },
     graph::SceneGraph,
@@ -14,7 +15,10 @@ use crate::{
             error::FrameworkError,
             framebuffer::{BlendParameters, CullFace, DrawParameters, FrameBuffer},
             geometry_buffer::{ElementRange, GeometryBuffer, GeometryBufferKind},
-            gpu_texture::GpuTexture,
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            screen_texture::ScreenRelativeTextureStorage,
             state::{
                 BlendFactor, BlendFunc, ColorMask, CompareFunc, PipelineState, StencilAction,
                 StencilFunc, StencilOp,
@@ -22,19 +26,25 @@ use crate::{
         },
         gbuffer::GBuffer,
         light::{
-            ambient::AmbientLightShader, directional::DirectionalLightShader,
-            point::PointLightShader, spot::SpotLightShader,
+            ambient::AmbientLightShader,
+            batch::{BatchedPointLight, PointLightBatch, TILE_SIZE},
+            directional::DirectionalLightShader,
+            point::{BatchedPointLightShader, PointLightShader},
+            spot::SpotLightShader,
         },
         light_volume::LightVolumeRenderer,
         shadow::{
             csm::{CsmRenderContext, CsmRenderer},
-            point::{PointShadowMapRenderContext, PointShadowMapRenderer},
+            point::{
+                PointShadowCache, PointShadowMapRenderContext, PointShadowMapRenderer,
+                ShadowCasterFilter,
+            },
             spot::SpotShadowMapRenderer,
         },
         skybox_shader::SkyboxShader,
-        ssao::ScreenSpaceAmbientOcclusionRenderer,
+        ssao::{PreservedOcclusionMaps, ScreenSpaceAmbientOcclusionRenderer},
         storage::MatrixStorageCache,
-        GeometryCache, QualitySettings, RenderPassStatistics, TextureCache,
+        AoApplication, GeometryCache, QualitySettings, RenderPassStatistics, TextureCache,
     },
     scene::{
         camera::Camera,
@@ -44,20 +54,47 @@ use crate::{
             surface::SurfaceData,
             vertex::SimpleVertex,
         },
+        node::Node,
         Scene,
     },
 };
+use fxhash::FxHashSet;
 use std::{cell::RefCell, rc::Rc};
 
 pub mod ambient;
+pub mod batch;
 pub mod directional;
 pub mod point;
 pub mod spot;
 
+/// Information about a point light that contributed to the last rendered frame. It could be used
+/// to build lighting debug overlays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointLightDebugInfo {
+    /// Handle of the light scene node.
+    pub handle: Handle<Node>,
+    /// Position of the light in world coordinates.
+    pub world_position: Vector3<f32>,
+    /// Position of the light in screen coordinates (in pixels, relative to the top-left corner of
+    /// the frame, the viewport of the camera is taken into account). `None` if the light center
+    /// is behind the camera.
+    pub screen_position: Option<Vector2<f32>>,
+    /// Radius of the light in world units (with the scale of the light node applied).
+    pub radius: f32,
+    /// Color of the light.
+    pub color: Color,
+    /// Intensity of the light, that was used for rendering (the intensity of the light multiplied
+    /// by the point light intensity scale of the renderer).
+    pub intensity: f32,
+    /// `true` if the light has cast shadows in the last frame.
+    pub cast_shadows: bool,
+}
+
 pub struct DeferredLightRenderer {
     pub ssao_renderer: ScreenSpaceAmbientOcclusionRenderer,
     spot_light_shader: SpotLightShader,
     point_light_shader: PointLightShader,
+    batched_point_light_shader: BatchedPointLightShader,
     directional_light_shader: DirectionalLightShader,
     ambient_light_shader: AmbientLightShader,
     quad: GeometryBuffer,
@@ -69,6 +106,12 @@ pub struct DeferredLightRenderer {
     point_shadow_map_renderer: PointShadowMapRenderer,
     csm_renderer: CsmRenderer,
     light_volume: LightVolumeRenderer,
+    point_lights_debug_info: Vec<PointLightDebugInfo>,
+    // 1x1 cube map that is bound instead of a real point shadow map for lights without shadows.
+    // Stores maximum possible distance, so every fragment is considered fully lit.
+    point_shadow_fallback: Rc<RefCell<GpuTexture>>,
+    // Point lights without shadows, that are rendered in a single pass after every other light.
+    point_light_batch: PointLightBatch,
 }
 
 pub(crate) struct DeferredRendererContext<'a> {
@@ -87,11 +130,16 @@ pub(crate) struct DeferredRendererContext<'a> {
     pub black_dummy: Rc<RefCell<GpuTexture>>,
     pub volume_dummy: Rc<RefCell<GpuTexture>>,
     pub matrix_storage: &'a mut MatrixStorageCache,
+    pub point_light_intensity_scale: f32,
+    pub visible_lights: &'a mut FxHashSet<Handle<Node>>,
+    pub point_shadow_cache: &'a mut PointShadowCache,
+    pub ssao_preserved_maps: &'a mut PreservedOcclusionMaps,
 }
 
 impl DeferredLightRenderer {
     pub fn new(
         state: &PipelineState,
+        screen_textures: &mut ScreenRelativeTextureStorage,
         frame_size: (u32, u32),
         settings: &QualitySettings,
     ) -> Result<Self, FrameworkError> {
@@ -133,11 +181,14 @@ impl DeferredLightRenderer {
         Ok(Self {
             ssao_renderer: ScreenSpaceAmbientOcclusionRenderer::new(
                 state,
+                screen_textures,
                 frame_size.0 as usize,
                 frame_size.1 as usize,
+                PixelKind::R32F,
             )?,
             spot_light_shader: SpotLightShader::new(state)?,
             point_light_shader: PointLightShader::new(state)?,
+            batched_point_light_shader: BatchedPointLightShader::new(state)?,
             directional_light_shader: DirectionalLightShader::new(state)?,
             ambient_light_shader: AmbientLightShader::new(state)?,
             quad: GeometryBuffer::from_surface_data(
@@ -189,9 +240,35 @@ impl DeferredLightRenderer {
                 quality_defaults.csm_settings.size,
                 quality_defaults.csm_settings.precision,
             )?,
+            point_lights_debug_info: Default::default(),
+            point_shadow_fallback: Rc::new(RefCell::new(GpuTexture::new(
+                state,
+                GpuTextureKind::Cube {
+                    width: 1,
+                    height: 1,
+                },
+                PixelKind::R32F,
+                MinificationFilter::Nearest,
+                MagnificationFilter::Nearest,
+                1,
+                Some(&f32::MAX.to_ne_bytes().repeat(6)),
+            )?)),
+            point_light_batch: PointLightBatch::new(state)?,
         })
     }
 
+    /// Returns a 1x1 "fully lit" cube map, that is used as a shadow map for point lights that
+    /// do not have a shadow map (shadows are disabled, the light is too far, etc.).
+    pub fn point_shadow_fallback_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        &self.point_shadow_fallback
+    }
+
+    /// Returns information about every point light that passed culling and was rendered in the last
+    /// frame.
+    pub fn point_lights_debug_info(&self) -> &[PointLightDebugInfo] {
+        &self.point_lights_debug_info
+    }
+
     pub fn set_quality_settings(
         &mut self,
         state: &PipelineState,
@@ -233,12 +310,8 @@ impl DeferredLightRenderer {
         state: &PipelineState,
         frame_size: (u32, u32),
     ) -> Result<(), FrameworkError> {
-        self.ssao_renderer = ScreenSpaceAmbientOcclusionRenderer::new(
-            state,
-            frame_size.0 as usize,
-            frame_size.1 as usize,
-        )?;
-        Ok(())
+        self.ssao_renderer
+            .set_frame_size(state, frame_size.0 as usize, frame_size.1 as usize)
     }
 
     pub(crate) fn render(
@@ -250,6 +323,9 @@ impl DeferredLightRenderer {
         let mut pass_stats = RenderPassStatistics::default();
         let mut light_stats = LightingStatistics::default();
 
+        self.point_lights_debug_info.clear();
+        self.point_light_batch.clear();
+
         let DeferredRendererContext {
             state,
             scene,
@@ -266,9 +342,19 @@ impl DeferredLightRenderer {
             black_dummy,
             volume_dummy,
             matrix_storage,
+            point_light_intensity_scale,
+            visible_lights,
+            point_shadow_cache,
+            ssao_preserved_maps,
         } = args;
 
+        point_shadow_cache.remove_unused(&scene.graph);
+        ssao_preserved_maps.remove_unused(&scene.graph);
+        self.point_shadow_map_renderer.remove_unused_cascades();
+
         let viewport = Rect::new(0, 0, gbuffer.width, gbuffer.height);
+        let camera_viewport =
+            camera.viewport_pixels(Vector2::new(gbuffer.width as f32, gbuffer.height as f32));
         let frustum = Frustum::from_view_projection_matrix(camera.view_projection_matrix())
             .unwrap_or_default();
 
@@ -298,6 +384,10 @@ impl DeferredLightRenderer {
                 gbuffer,
                 projection_matrix,
                 camera.view_matrix().basis(),
+                view_projection,
+                settings.ssao_depth_filter,
+                camera.self_handle,
+                ssao_preserved_maps,
             )?;
         }
 
@@ -346,6 +436,14 @@ impl DeferredLightRenderer {
         let gbuffer_material_map = gbuffer.material_texture();
         let gbuffer_ambient_map = gbuffer.ambient_texture();
         let ao_map = self.ssao_renderer.ao_map();
+        let (ambient_ao_map, direct_ao_map) = if settings.use_ssao {
+            match settings.ao_application {
+                AoApplication::All => (&ao_map, &ao_map),
+                AoApplication::AmbientOnly => (&ao_map, &white_dummy),
+            }
+        } else {
+            (&white_dummy, &white_dummy)
+        };
 
         pass_stats += frame_buffer.draw(
             &self.quad,
@@ -373,17 +471,14 @@ impl DeferredLightRenderer {
                         &self.ambient_light_shader.diffuse_texture,
                         &gbuffer_diffuse_map,
                     )
-                    .set_texture(
-                        &self.ambient_light_shader.ao_sampler,
-                        if settings.use_ssao {
-                            &ao_map
-                        } else {
-                            &white_dummy
-                        },
-                    )
+                    .set_texture(&self.ambient_light_shader.ao_sampler, ambient_ao_map)
                     .set_texture(
                         &self.ambient_light_shader.ambient_texture,
                         &gbuffer_ambient_map,
+                    )
+                    .set_texture(
+                        &self.ambient_light_shader.material_texture,
+                        &gbuffer_material_map,
                     );
             },
         )?;
@@ -441,6 +536,8 @@ impl DeferredLightRenderer {
                 continue;
             }
 
+            visible_lights.insert(light_handle);
+
             let b1 = shadows_distance * 0.2;
             let b2 = shadows_distance * 0.4;
             let cascade_index =
@@ -503,10 +600,14 @@ impl DeferredLightRenderer {
                     )?;
 
                     light_stats.spot_shadow_maps_rendered += 1;
-                } else if light.cast::<PointLight>().is_some() {
-                    pass_stats +=
-                        self.point_shadow_map_renderer
-                            .render(PointShadowMapRenderContext {
+                } else if let Some(point) = light.cast::<PointLight>() {
+                    let cache_static_shadows = point.is_caching_static_shadows();
+
+                    if cache_static_shadows {
+                        pass_stats += self.point_shadow_map_renderer.render_static(
+                            point_shadow_cache,
+                            light_handle,
+                            PointShadowMapRenderContext {
                                 state,
                                 graph: &scene.graph,
                                 light_pos: light_position,
@@ -520,7 +621,47 @@ impl DeferredLightRenderer {
                                 black_dummy: black_dummy.clone(),
                                 volume_dummy: volume_dummy.clone(),
                                 matrix_storage,
-                            })?;
+                                caster_filter: ShadowCasterFilter::Static,
+                                shadow_map_size: point.shadow_map_size(),
+                                distance_cascade: 0,
+                                distance_cascade_count: 1,
+                            },
+                        )?;
+                    }
+
+                    // Closer cascades contain only the shadow casters within their far distance,
+                    // because farther casters cannot shadow the fragments that sample them.
+                    let cascade_distances = point.shadow_cascade_distances(light_radius);
+                    let distance_cascade_count = cascade_distances.len();
+                    for (distance_cascade, cascade_radius) in
+                        cascade_distances.into_iter().enumerate()
+                    {
+                        pass_stats +=
+                            self.point_shadow_map_renderer
+                                .render(PointShadowMapRenderContext {
+                                    state,
+                                    graph: &scene.graph,
+                                    light_pos: light_position,
+                                    light_radius: cascade_radius,
+                                    geom_cache: geometry_cache,
+                                    cascade: cascade_index,
+                                    shader_cache,
+                                    texture_cache: textures,
+                                    normal_dummy: normal_dummy.clone(),
+                                    white_dummy: white_dummy.clone(),
+                                    black_dummy: black_dummy.clone(),
+                                    volume_dummy: volume_dummy.clone(),
+                                    matrix_storage,
+                                    caster_filter: if cache_static_shadows {
+                                        ShadowCasterFilter::Dynamic
+                                    } else {
+                                        ShadowCasterFilter::All
+                                    },
+                                    shadow_map_size: point.shadow_map_size(),
+                                    distance_cascade,
+                                    distance_cascade_count,
+                                })?;
+                    }
 
                     light_stats.point_shadow_maps_rendered += 1;
                 } else if let Some(directional) = light.cast::<DirectionalLight>() {
@@ -544,71 +685,78 @@ impl DeferredLightRenderer {
                 };
             }
 
+            // Point lights without shadows are rendered later, all at once.
+            let batched = settings.batched_point_lights
+                && !shadows_enabled
+                && light.cast::<PointLight>().is_some();
+
             // Mark lighted areas in stencil buffer to do light calculations only on them.
 
             let sphere = &self.sphere;
 
-            pass_stats += frame_buffer.draw(
-                sphere,
-                state,
-                viewport,
-                &self.flat_shader.program,
-                &DrawParameters {
-                    cull_face: Some(CullFace::Front),
-                    color_write: ColorMask::all(false),
-                    depth_write: false,
-                    stencil_test: Some(StencilFunc {
-                        func: CompareFunc::Always,
-                        ..Default::default()
-                    }),
-                    stencil_op: StencilOp {
-                        zfail: StencilAction::Incr,
-                        ..Default::default()
+            if !batched {
+                pass_stats += frame_buffer.draw(
+                    sphere,
+                    state,
+                    viewport,
+                    &self.flat_shader.program,
+                    &DrawParameters {
+                        cull_face: Some(CullFace::Front),
+                        color_write: ColorMask::all(false),
+                        depth_write: false,
+                        stencil_test: Some(StencilFunc {
+                            func: CompareFunc::Always,
+                            ..Default::default()
+                        }),
+                        stencil_op: StencilOp {
+                            zfail: StencilAction::Incr,
+                            ..Default::default()
+                        },
+                        depth_test: true,
+                        blend: None,
                     },
-                    depth_test: true,
-                    blend: None,
-                },
-                ElementRange::Full,
-                |mut program_binding| {
-                    program_binding.set_matrix4(
-                        &self.flat_shader.wvp_matrix,
-                        &(view_projection
-                            * Matrix4::new_translation(&light_position)
-                            * Matrix4::new_nonuniform_scaling(&light_radius_vec)),
-                    );
-                },
-            )?;
+                    ElementRange::Full,
+                    |mut program_binding| {
+                        program_binding.set_matrix4(
+                            &self.flat_shader.wvp_matrix,
+                            &(view_projection
+                                * Matrix4::new_translation(&light_position)
+                                * Matrix4::new_nonuniform_scaling(&light_radius_vec)),
+                        );
+                    },
+                )?;
 
-            pass_stats += frame_buffer.draw(
-                sphere,
-                state,
-                viewport,
-                &self.flat_shader.program,
-                &DrawParameters {
-                    cull_face: Some(CullFace::Back),
-                    color_write: ColorMask::all(false),
-                    depth_write: false,
-                    stencil_test: Some(StencilFunc {
-                        func: CompareFunc::Always,
-                        ..Default::default()
-                    }),
-                    stencil_op: StencilOp {
-                        zfail: StencilAction::Decr,
-                        ..Default::default()
+                pass_stats += frame_buffer.draw(
+                    sphere,
+                    state,
+                    viewport,
+                    &self.flat_shader.program,
+                    &DrawParameters {
+                        cull_face: Some(CullFace::Back),
+                        color_write: ColorMask::all(false),
+                        depth_write: false,
+                        stencil_test: Some(StencilFunc {
+                            func: CompareFunc::Always,
+                            ..Default::default()
+                        }),
+                        stencil_op: StencilOp {
+                            zfail: StencilAction::Decr,
+                            ..Default::default()
+                        },
+                        depth_test: true,
+                        blend: None,
                     },
-                    depth_test: true,
-                    blend: None,
-                },
-                ElementRange::Full,
-                |mut program_binding| {
-                    program_binding.set_matrix4(
-                        &self.flat_shader.wvp_matrix,
-                        &(view_projection
-                            * Matrix4::new_translation(&light_position)
-                            * Matrix4::new_nonuniform_scaling(&light_radius_vec)),
-                    );
-                },
-            )?;
+                    ElementRange::Full,
+                    |mut program_binding| {
+                        program_binding.set_matrix4(
+                            &self.flat_shader.wvp_matrix,
+                            &(view_projection
+                                * Matrix4::new_translation(&light_position)
+                                * Matrix4::new_nonuniform_scaling(&light_radius_vec)),
+                        );
+                    },
+                )?;
+            }
 
             let draw_params = DrawParameters {
                 cull_face: None,
@@ -686,6 +834,7 @@ impl DeferredLightRenderer {
                             .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
                             .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
                             .set_texture(&shader.material_sampler, &gbuffer_material_map)
+                            .set_texture(&shader.ao_sampler, direct_ao_map)
                             .set_texture(
                                 &shader.spot_shadow_texture,
                                 &self.spot_shadow_map_renderer.cascade_texture(cascade_index),
@@ -705,44 +854,139 @@ impl DeferredLightRenderer {
 
                 light_stats.point_lights_rendered += 1;
 
-                frame_buffer.draw(
-                    quad,
-                    state,
-                    viewport,
-                    &shader.program,
-                    &draw_params,
-                    ElementRange::Full,
-                    |mut program_binding| {
-                        program_binding
-                            .set_bool(&shader.shadows_enabled, shadows_enabled)
-                            .set_bool(&shader.soft_shadows, settings.point_soft_shadows)
-                            .set_vector3(&shader.light_position, &light_position)
-                            .set_f32(&shader.light_radius, light_radius)
-                            .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
-                            .set_linear_color(
-                                &shader.light_color,
-                                &point_light.base_light_ref().color(),
-                            )
-                            .set_matrix4(&shader.wvp_matrix, &frame_matrix)
-                            .set_vector3(&shader.camera_position, &camera_global_position)
-                            .set_f32(&shader.shadow_bias, point_light.shadow_bias())
-                            .set_f32(
-                                &shader.light_intensity,
-                                point_light.base_light_ref().intensity(),
-                            )
-                            .set_texture(&shader.depth_sampler, &gbuffer_depth_map)
-                            .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
-                            .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
-                            .set_texture(&shader.material_sampler, &gbuffer_material_map)
-                            .set_texture(
-                                &shader.point_shadow_texture,
-                                &self
-                                    .point_shadow_map_renderer
-                                    .cascade_texture(cascade_index),
+                let clip_space_position =
+                    view_projection * Point3::from(light_position).to_homogeneous();
+                let screen_position = if clip_space_position.w > 0.0 {
+                    let ndc = clip_space_position.xy().scale(1.0 / clip_space_position.w);
+                    Some(Vector2::new(
+                        camera_viewport.x() as f32
+                            + (ndc.x + 1.0) * 0.5 * camera_viewport.w() as f32,
+                        camera_viewport.y() as f32
+                            + (1.0 - ndc.y) * 0.5 * camera_viewport.h() as f32,
+                    ))
+                } else {
+                    None
+                };
+
+                self.point_lights_debug_info.push(PointLightDebugInfo {
+                    handle: light_handle,
+                    world_position: light_position,
+                    screen_position,
+                    radius: light_radius,
+                    color: point_light.base_light_ref().color(),
+                    intensity: point_light_intensity_scale
+                        * point_light.base_light_ref().intensity(),
+                    cast_shadows: shadows_enabled,
+                });
+
+                if batched {
+                    self.point_light_batch.push(BatchedPointLight {
+                        position: light_position,
+                        radius: light_radius,
+                        source_radius: light_radius_scale * point_light.source_radius(),
+                        color: point_light
+                            .base_light_ref()
+                            .color()
+                            .srgb_to_linear_f32()
+                            .xyz()
+                            .scale(
+                                point_light_intensity_scale
+                                    * point_light.base_light_ref().intensity(),
+                            ),
+                    });
+
+                    Default::default()
+                } else {
+                    // Make sure that there's always a valid shadow map bound, even if the shadow
+                    // map wasn't rendered for the light.
+                    let cascade_distances = point_light.shadow_cascade_distances(light_radius);
+                    let cascade_count = cascade_distances.len();
+                    // Unused cascades are filled with the last one, the shader never samples them.
+                    let point_shadow_textures = [0, 1, 2].map(|distance_cascade| {
+                        if shadows_enabled {
+                            self.point_shadow_map_renderer.distance_cascade_texture(
+                                state,
+                                cascade_index,
+                                usize::min(distance_cascade, cascade_count - 1),
+                                cascade_count,
+                                point_light.shadow_map_size(),
                             )
-                            .set_f32(&shader.shadow_alpha, shadows_alpha);
-                    },
-                )?
+                        } else {
+                            self.point_shadow_fallback.clone()
+                        }
+                    });
+                    let cascade_splits = Vector2::new(
+                        cascade_distances[0],
+                        cascade_distances[usize::min(1, cascade_count - 1)],
+                    );
+                    let static_shadow_texture = if shadows_enabled {
+                        point_shadow_cache.texture(light_handle)
+                    } else {
+                        None
+                    };
+                    let use_static_shadows = static_shadow_texture.is_some();
+                    let static_shadow_texture =
+                        static_shadow_texture.unwrap_or_else(|| self.point_shadow_fallback.clone());
+
+                    frame_buffer.draw(
+                        quad,
+                        state,
+                        viewport,
+                        &shader.program,
+                        &draw_params,
+                        ElementRange::Full,
+                        |mut program_binding| {
+                            program_binding
+                                .set_bool(&shader.shadows_enabled, shadows_enabled)
+                                .set_bool(&shader.soft_shadows, settings.point_soft_shadows)
+                                .set_i32(
+                                    &shader.pcf_kernel_radius,
+                                    settings.point_shadow_pcf_kernel.radius(),
+                                )
+                                .set_vector3(&shader.light_position, &light_position)
+                                .set_f32(&shader.light_radius, light_radius)
+                                .set_f32(
+                                    &shader.light_source_radius,
+                                    light_radius_scale * point_light.source_radius(),
+                                )
+                                .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
+                                .set_linear_color(
+                                    &shader.light_color,
+                                    &point_light.base_light_ref().color(),
+                                )
+                                .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                                .set_vector3(&shader.camera_position, &camera_global_position)
+                                .set_f32(&shader.shadow_bias, point_light.shadow_bias())
+                                .set_f32(
+                                    &shader.light_intensity,
+                                    point_light_intensity_scale
+                                        * point_light.base_light_ref().intensity(),
+                                )
+                                .set_texture(&shader.depth_sampler, &gbuffer_depth_map)
+                                .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
+                                .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
+                                .set_texture(&shader.material_sampler, &gbuffer_material_map)
+                                .set_texture(&shader.ao_sampler, direct_ao_map)
+                                .set_texture(
+                                    &shader.point_shadow_texture,
+                                    &point_shadow_textures[0],
+                                )
+                                .set_texture(
+                                    &shader.point_shadow_texture1,
+                                    &point_shadow_textures[1],
+                                )
+                                .set_texture(
+                                    &shader.point_shadow_texture2,
+                                    &point_shadow_textures[2],
+                                )
+                                .set_i32(&shader.shadow_cascade_count, cascade_count as i32)
+                                .set_vector2(&shader.shadow_cascade_splits, &cascade_splits)
+                                .set_bool(&shader.use_static_shadows, use_static_shadows)
+                                .set_texture(&shader.static_shadow_texture, &static_shadow_texture)
+                                .set_f32(&shader.shadow_alpha, shadows_alpha);
+                        },
+                    )?
+                }
             } else if let Some(directional) = light.cast::<DirectionalLight>() {
                 let shader = &self.directional_light_shader;
 
@@ -796,6 +1040,7 @@ impl DeferredLightRenderer {
                             .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
                             .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
                             .set_texture(&shader.material_sampler, &gbuffer_material_map)
+                            .set_texture(&shader.ao_sampler, direct_ao_map)
                             .set_matrix4_array(&shader.light_view_proj_matrices, &matrices)
                             .set_texture(
                                 &shader.shadow_cascade0,
@@ -838,6 +1083,52 @@ impl DeferredLightRenderer {
             }
         }
 
+        if !self.point_light_batch.is_empty() {
+            self.point_light_batch.upload(
+                state,
+                &view_projection,
+                Vector2::new(viewport.w() as usize, viewport.h() as usize),
+            )?;
+
+            let shader = &self.batched_point_light_shader;
+            let batch = &self.point_light_batch;
+
+            pass_stats += frame_buffer.draw(
+                &self.quad,
+                state,
+                viewport,
+                &shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: false,
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                },
+                ElementRange::Full,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                        .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
+                        .set_vector3(&shader.camera_position, &camera_global_position)
+                        .set_i32(&shader.tile_size, TILE_SIZE as i32)
+                        .set_i32(&shader.tile_count_x, batch.tile_count().x as i32)
+                        .set_texture(&shader.depth_sampler, &gbuffer_depth_map)
+                        .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
+                        .set_texture(&shader.normal_sampler, &gbuffer_normal_map)
+                        .set_texture(&shader.material_sampler, &gbuffer_material_map)
+                        .set_texture(&shader.ao_sampler, direct_ao_map)
+                        .set_texture(&shader.light_storage, batch.light_texture())
+                        .set_texture(&shader.grid_storage, batch.grid_texture());
+                },
+            )?;
+        }
+
         Ok((pass_stats, light_stats))
     }
 }
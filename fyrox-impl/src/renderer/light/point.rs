@@ -12,11 +12,20 @@ pub struct PointLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub ao_sampler: UniformLocation,
     pub point_shadow_texture: UniformLocation,
+    pub point_shadow_texture1: UniformLocation,
+    pub point_shadow_texture2: UniformLocation,
+    pub shadow_cascade_count: UniformLocation,
+    pub shadow_cascade_splits: UniformLocation,
+    pub static_shadow_texture: UniformLocation,
+    pub use_static_shadows: UniformLocation,
     pub shadows_enabled: UniformLocation,
     pub soft_shadows: UniformLocation,
+    pub pcf_kernel_radius: UniformLocation,
     pub light_position: UniformLocation,
     pub light_radius: UniformLocation,
+    pub light_source_radius: UniformLocation,
     pub light_color: UniformLocation,
     pub inv_view_proj_matrix: UniformLocation,
     pub camera_position: UniformLocation,
@@ -42,13 +51,30 @@ impl PointLightShader {
                 .uniform_location(state, &ImmutableString::new("normalTexture"))?,
             material_sampler: program
                 .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            ao_sampler: program.uniform_location(state, &ImmutableString::new("aoSampler"))?,
             point_shadow_texture: program
                 .uniform_location(state, &ImmutableString::new("pointShadowTexture"))?,
+            point_shadow_texture1: program
+                .uniform_location(state, &ImmutableString::new("pointShadowTexture1"))?,
+            point_shadow_texture2: program
+                .uniform_location(state, &ImmutableString::new("pointShadowTexture2"))?,
+            shadow_cascade_count: program
+                .uniform_location(state, &ImmutableString::new("shadowCascadeCount"))?,
+            shadow_cascade_splits: program
+                .uniform_location(state, &ImmutableString::new("shadowCascadeSplits"))?,
+            static_shadow_texture: program
+                .uniform_location(state, &ImmutableString::new("staticShadowTexture"))?,
+            use_static_shadows: program
+                .uniform_location(state, &ImmutableString::new("useStaticShadows"))?,
             shadows_enabled: program
                 .uniform_location(state, &ImmutableString::new("shadowsEnabled"))?,
             soft_shadows: program.uniform_location(state, &ImmutableString::new("softShadows"))?,
+            pcf_kernel_radius: program
+                .uniform_location(state, &ImmutableString::new("pcfKernelRadius"))?,
             light_position: program.uniform_location(state, &ImmutableString::new("lightPos"))?,
             light_radius: program.uniform_location(state, &ImmutableString::new("lightRadius"))?,
+            light_source_radius: program
+                .uniform_location(state, &ImmutableString::new("lightSourceRadius"))?,
             light_color: program.uniform_location(state, &ImmutableString::new("lightColor"))?,
             inv_view_proj_matrix: program
                 .uniform_location(state, &ImmutableString::new("invViewProj"))?,
@@ -62,3 +88,55 @@ impl PointLightShader {
         })
     }
 }
+
+pub struct BatchedPointLightShader {
+    pub program: GpuProgram,
+    pub wvp_matrix: UniformLocation,
+    pub depth_sampler: UniformLocation,
+    pub color_sampler: UniformLocation,
+    pub normal_sampler: UniformLocation,
+    pub material_sampler: UniformLocation,
+    pub ao_sampler: UniformLocation,
+    pub light_storage: UniformLocation,
+    pub grid_storage: UniformLocation,
+    pub tile_size: UniformLocation,
+    pub tile_count_x: UniformLocation,
+    pub inv_view_proj_matrix: UniformLocation,
+    pub camera_position: UniformLocation,
+}
+
+impl BatchedPointLightShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/deferred_batched_point_light_fs.glsl");
+        let vertex_source = include_str!("../shaders/deferred_light_vs.glsl");
+        let program = GpuProgram::from_source(
+            state,
+            "BatchedPointLightShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            color_sampler: program
+                .uniform_location(state, &ImmutableString::new("colorTexture"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalTexture"))?,
+            material_sampler: program
+                .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            ao_sampler: program.uniform_location(state, &ImmutableString::new("aoSampler"))?,
+            light_storage: program
+                .uniform_location(state, &ImmutableString::new("lightStorage"))?,
+            grid_storage: program.uniform_location(state, &ImmutableString::new("gridStorage"))?,
+            tile_size: program.uniform_location(state, &ImmutableString::new("tileSize"))?,
+            tile_count_x: program.uniform_location(state, &ImmutableString::new("tileCountX"))?,
+            inv_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("invViewProj"))?,
+            camera_position: program
+                .uniform_location(state, &ImmutableString::new("cameraPosition"))?,
+            program,
+        })
+    }
+}
@@ -12,6 +12,7 @@ pub struct SpotLightShader {
     pub color_sampler: UniformLocation,
     pub normal_sampler: UniformLocation,
     pub material_sampler: UniformLocation,
+    pub ao_sampler: UniformLocation,
     pub spot_shadow_texture: UniformLocation,
     pub cookie_enabled: UniformLocation,
     pub cookie_texture: UniformLocation,
@@ -49,6 +50,7 @@ impl SpotLightShader {
                 .uniform_location(state, &ImmutableString::new("normalTexture"))?,
             material_sampler: program
                 .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            ao_sampler: program.uniform_location(state, &ImmutableString::new("aoSampler"))?,
             spot_shadow_texture: program
                 .uniform_location(state, &ImmutableString::new("spotShadowTexture"))?,
             cookie_enabled: program
@@ -16,17 +16,22 @@ pub mod framework;
 pub mod bundle;
 pub mod cache;
 pub mod debug_renderer;
+pub mod debug_view;
+pub mod effect_chain;
+pub mod settings;
 pub mod storage;
 pub mod ui_renderer;
 
 mod bloom;
 mod flat_shader;
 mod forward_renderer;
+mod frame_ring;
 mod fxaa;
 mod gbuffer;
 mod hdr;
 mod light;
 mod light_volume;
+mod screenshot;
 mod shadow;
 mod skybox_shader;
 mod ssao;
@@ -48,21 +53,25 @@ use crate::{
         reflect::prelude::*,
         scope_profile,
         sstorage::ImmutableString,
+        visitor::prelude::*,
     },
-    gui::draw::DrawingContext,
+    gui::{draw::DrawingContext, formatted_text::FormattedText},
     material::{
         shader::{SamplerFallback, Shader, ShaderResource, ShaderResourceExtension},
-        Material, PropertyValue,
+        Material, MaterialPropertyOverrides, PropertyValue,
     },
     renderer::{
         bloom::BloomRenderer,
         bundle::{ObserverInfo, PersistentIdentifier, RenderDataBundleStorage},
         cache::{geometry::GeometryCache, shader::ShaderCache, texture::TextureCache},
         debug_renderer::DebugRenderer,
+        debug_view::{DebugView, DebugViewShader, OverdrawRenderer},
+        effect_chain::EffectChain,
         flat_shader::FlatShader,
         forward_renderer::{ForwardRenderContext, ForwardRenderer},
         framework::{
             error::FrameworkError,
+            fence::GpuFence,
             framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
             geometry_buffer::{
                 DrawCallStatistics, ElementRange, GeometryBuffer, GeometryBufferKind,
@@ -72,19 +81,24 @@ use crate::{
                 Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
                 PixelKind, WrapMode,
             },
-            state::{GlKind, PipelineState, PolygonFace, PolygonFillMode},
+            screen_texture::{ScreenRelativeTexture, ScreenRelativeTextureStorage},
+            state::{
+                BlitFilter, GlKind, GpuMemoryStats, PipelineCapabilities, PipelineState,
+                PolygonFace, PolygonFillMode,
+            },
         },
         fxaa::FxaaRenderer,
         gbuffer::{GBuffer, GBufferRenderContext},
         hdr::HighDynamicRangeRenderer,
         light::{DeferredLightRenderer, DeferredRendererContext},
+        settings::RendererSettings,
         storage::MatrixStorageCache,
         ui_renderer::{UiRenderContext, UiRenderer},
     },
-    resource::texture::{Texture, TextureKind, TextureResource},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
+    resource::texture::{Texture, TextureKind, TextureResource, TextureResourceExtension},
+    scene::{camera::Camera, mesh::surface::SurfaceData, node::Node, Scene, SceneContainer},
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::algebra::Vector4;
 use fyrox_core::uuid_provider;
 use glow::HasContext;
@@ -94,14 +108,26 @@ use glutin::{
     prelude::GlSurface,
     surface::{Surface, WindowSurface},
 };
+use image::RgbaImage;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::any::TypeId;
-use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc, sync::mpsc::Receiver};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, VecDeque},
+    rc::Rc,
+    sync::mpsc::Receiver,
+};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 #[cfg(not(target_arch = "wasm32"))]
 use winit::window::Window;
 
+pub use light::PointLightDebugInfo;
+pub use shadow::point::PointShadowCache;
+pub use ssao::{
+    PreservedOcclusionMaps, SsaoClearMode, SsaoMode, SsaoOptions, DEFAULT_NOISE_SIZE,
+    MAX_NOISE_SIZE,
+};
 pub use stats::*;
 
 lazy_static! {
@@ -132,6 +158,7 @@ pub fn is_shadow_pass(render_pass_name: &str) -> bool {
     Serialize,
     Deserialize,
     Reflect,
+    Visit,
     AsRefStr,
     EnumString,
     VariantNames,
@@ -147,8 +174,119 @@ pub enum ShadowMapPrecision {
 
 uuid_provider!(ShadowMapPrecision = "f9b2755b-248e-46ba-bcab-473eac1acdb8");
 
+/// Filtering that is used by screen space ambient occlusion when it samples the depth buffer.
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum SsaoDepthFilter {
+    /// Depth is fetched from the closest texel. This is the correct option for depth buffers,
+    /// since depth at discontinuities (object silhouettes) is never blended with the background,
+    /// which prevents "halo" artifacts around objects. It may produce slightly blocky occlusion
+    /// on smooth surfaces, since SSAO runs in half resolution.
+    #[default]
+    Nearest,
+    /// Depth is linearly interpolated between neighbouring texels. This gives smoother occlusion
+    /// on continuous surfaces, but at depth discontinuities it produces depth values that do not
+    /// belong to any surface, which results in dark or light "halos" around object edges.
+    Linear,
+}
+
+uuid_provider!(SsaoDepthFilter = "5c1b6e0a-5f3e-4f43-9d1a-2f9b6f9a0c7e");
+
+/// Defines which lighting is modulated by screen space ambient occlusion.
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum AoApplication {
+    /// Ambient occlusion darkens ambient (indirect) lighting only. This is physically correct
+    /// option - occlusion of a point describes how much of the surrounding environment it
+    /// "sees", while direct lights are already occluded by shadows. Corners and crevices lit by
+    /// a light source stay lit. This is the recommended option.
+    #[default]
+    AmbientOnly,
+    /// Ambient occlusion darkens every light (ambient, directional, point and spot). Occluded
+    /// areas look darker and more contrast, even if they are directly lit, which may be desired
+    /// for stylized visuals, but it is not physically correct.
+    All,
+}
+
+uuid_provider!(AoApplication = "b0f2a7c4-3d69-4c5e-8e7a-6a1d2f4c9b13");
+
+/// Size of the kernel of percentage close filtering (PCF) of point shadows. Larger kernels give
+/// softer and less aliased shadow edges, but require more shadow map fetches per pixel.
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum PointShadowPcfKernel {
+    /// Single fetch per pixel, shadow edges are sharp. Same as disabled soft shadows.
+    Taps1x1,
+    /// 9 fetches per pixel, gives slightly smoothed shadow edges.
+    #[default]
+    Taps3x3,
+    /// 25 fetches per pixel, gives the softest shadow edges, but it is the slowest option.
+    Taps5x5,
+}
+
+uuid_provider!(PointShadowPcfKernel = "3e8d4c71-9a2f-4b6e-a1d5-7c0f2b8e6d94");
+
+impl PointShadowPcfKernel {
+    /// Returns the radius of the kernel in samples, excluding the central sample.
+    pub fn radius(self) -> i32 {
+        match self {
+            Self::Taps1x1 => 0,
+            Self::Taps3x3 => 1,
+            Self::Taps5x5 => 2,
+        }
+    }
+}
+
 /// Cascaded-shadow maps settings.
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Visit, Eq)]
 pub struct CsmSettings {
     /// Whether cascaded shadow maps enabled or not.
     pub enabled: bool,
@@ -177,13 +315,18 @@ impl Default for CsmSettings {
 
 /// Quality settings allows you to find optimal balance between performance and
 /// graphics quality.
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Visit)]
 pub struct QualitySettings {
     /// Point shadows
     /// Size of cube map face of shadow map texture in pixels.
     pub point_shadow_map_size: usize,
     /// Use or not percentage close filtering (smoothing) for point shadows.
     pub point_soft_shadows: bool,
+    /// Size of the kernel of percentage close filtering for point shadows, it is used only if
+    /// [`Self::point_soft_shadows`] is enabled.
+    #[serde(default)]
+    #[visit(optional)]
+    pub point_shadow_pcf_kernel: PointShadowPcfKernel,
     /// Point shadows enabled or not.
     pub point_shadows_enabled: bool,
     /// Maximum distance from camera to draw shadows.
@@ -220,11 +363,28 @@ pub struct QualitySettings {
     /// Radius of sampling hemisphere used in SSAO, it defines much ambient
     /// occlusion will be in your scene.
     pub ssao_radius: f32,
+    /// Filtering that is used when SSAO samples the depth buffer. See [`SsaoDepthFilter`] docs
+    /// for more info about artifacts of each option.
+    #[serde(default)]
+    pub ssao_depth_filter: SsaoDepthFilter,
+
+    /// Defines which lighting is darkened by SSAO. See [`AoApplication`] docs for the visual
+    /// difference between the options.
+    #[serde(default)]
+    pub ao_application: AoApplication,
 
     /// Global switch to enable or disable light scattering. Each light can have
     /// its own scatter switch, but this one is able to globally disable scatter.
     pub light_scatter_enabled: bool,
 
+    /// Whether to render point lights without shadows in a single pass or not. When enabled, the
+    /// screen is split into tiles and every pixel processes only the lights that touch its tile,
+    /// it is much faster for scenes with hundreds of lights. Point lights with shadows are always
+    /// rendered one by one. Disable it to fall back to rendering of every light separately.
+    #[serde(default = "default_batched_point_lights")]
+    #[visit(optional)]
+    pub batched_point_lights: bool,
+
     /// Whether to use Fast Approximate AntiAliasing or not.
     pub fxaa: bool,
 
@@ -235,6 +395,10 @@ pub struct QualitySettings {
     pub use_bloom: bool,
 }
 
+fn default_batched_point_lights() -> bool {
+    true
+}
+
 impl Default for QualitySettings {
     fn default() -> Self {
         Self::high()
@@ -242,6 +406,23 @@ impl Default for QualitySettings {
 }
 
 impl QualitySettings {
+    /// Returns a copy of the settings with every texture size clamped to hardware limits of the
+    /// graphics device. See [`Renderer::capabilities`].
+    pub fn clamped_to_capabilities(&self, capabilities: &PipelineCapabilities) -> Self {
+        let mut settings = *self;
+        settings.point_shadow_map_size = settings
+            .point_shadow_map_size
+            .min(capabilities.max_cube_map_texture_size);
+        settings.spot_shadow_map_size = settings
+            .spot_shadow_map_size
+            .min(capabilities.max_texture_size);
+        settings.csm_settings.size = settings
+            .csm_settings
+            .size
+            .min(capabilities.max_texture_size);
+        settings
+    }
+
     /// Highest possible graphics quality. Requires very powerful GPU.
     pub fn ultra() -> Self {
         Self {
@@ -249,6 +430,7 @@ impl QualitySettings {
             point_shadows_distance: 20.0,
             point_shadows_enabled: true,
             point_soft_shadows: true,
+            point_shadow_pcf_kernel: PointShadowPcfKernel::Taps5x5,
             point_shadows_fade_out_range: 1.0,
 
             spot_shadow_map_size: 2048,
@@ -259,9 +441,13 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ssao_depth_filter: SsaoDepthFilter::Nearest,
+            ao_application: AoApplication::AmbientOnly,
 
             light_scatter_enabled: true,
 
+            batched_point_lights: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Full,
             spot_shadow_map_precision: ShadowMapPrecision::Full,
 
@@ -282,6 +468,7 @@ impl QualitySettings {
             point_shadows_distance: 15.0,
             point_shadows_enabled: true,
             point_soft_shadows: true,
+            point_shadow_pcf_kernel: PointShadowPcfKernel::Taps3x3,
             point_shadows_fade_out_range: 1.0,
 
             spot_shadow_map_size: 1024,
@@ -292,9 +479,13 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ssao_depth_filter: SsaoDepthFilter::Nearest,
+            ao_application: AoApplication::AmbientOnly,
 
             light_scatter_enabled: true,
 
+            batched_point_lights: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Full,
             spot_shadow_map_precision: ShadowMapPrecision::Full,
 
@@ -320,6 +511,7 @@ impl QualitySettings {
             point_shadows_distance: 5.0,
             point_shadows_enabled: true,
             point_soft_shadows: false,
+            point_shadow_pcf_kernel: PointShadowPcfKernel::Taps1x1,
             point_shadows_fade_out_range: 1.0,
 
             spot_shadow_map_size: 512,
@@ -330,9 +522,13 @@ impl QualitySettings {
 
             use_ssao: true,
             ssao_radius: 0.5,
+            ssao_depth_filter: SsaoDepthFilter::Nearest,
+            ao_application: AoApplication::AmbientOnly,
 
             light_scatter_enabled: false,
 
+            batched_point_lights: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Half,
             spot_shadow_map_precision: ShadowMapPrecision::Half,
 
@@ -358,6 +554,7 @@ impl QualitySettings {
             point_shadows_distance: 0.0,
             point_shadows_enabled: false,
             point_soft_shadows: false,
+            point_shadow_pcf_kernel: PointShadowPcfKernel::Taps1x1,
             point_shadows_fade_out_range: 1.0,
 
             spot_shadow_map_size: 1,
@@ -368,9 +565,13 @@ impl QualitySettings {
 
             use_ssao: false,
             ssao_radius: 0.5,
+            ssao_depth_filter: SsaoDepthFilter::Nearest,
+            ao_application: AoApplication::AmbientOnly,
 
             light_scatter_enabled: false,
 
+            batched_point_lights: true,
+
             point_shadow_map_precision: ShadowMapPrecision::Half,
             spot_shadow_map_precision: ShadowMapPrecision::Half,
 
@@ -442,6 +643,43 @@ impl Default for Statistics {
     }
 }
 
+/// Progress of shader precompilation, see [`Renderer::precompile_shaders`] for more info.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaderPrecompilationProgress {
+    /// Amount of shaders that were processed (successfully compiled or failed to compile).
+    pub processed: usize,
+    /// Total amount of shaders that were queued for precompilation.
+    pub total: usize,
+}
+
+impl ShaderPrecompilationProgress {
+    /// Returns `true` if every queued shader was processed.
+    pub fn is_finished(&self) -> bool {
+        self.processed >= self.total
+    }
+
+    /// Returns progress of the precompilation in `[0; 1]` range. Could be used to display a
+    /// progress bar on a loading screen.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.processed as f32 / self.total as f32
+        }
+    }
+}
+
+// Maximum amount of time (in seconds) that could be spent on shader precompilation per single
+// cache update. Prevents long freezes when there are lots of shaders in the queue.
+const SHADER_PRECOMPILATION_TIME_BUDGET: f32 = 0.008;
+
+// A single entry of the shader precompilation queue, see `Renderer::precompile_shaders`.
+enum ShaderPrecompilationTask {
+    // Programs of built-in optional effects, that are otherwise compiled on first use.
+    BuiltInPrograms,
+    Shader(ShaderResource),
+}
+
 /// A set of frame buffers, renderers, that contains scene-specific data.
 pub struct AssociatedSceneData {
     /// G-Buffer of the scene.
@@ -466,6 +704,17 @@ pub struct AssociatedSceneData {
 
     /// Rendering statistics for a scene.
     pub statistics: SceneStatistics,
+
+    /// A set of lights that passed culling for at least one camera of the scene during the last
+    /// rendered frame.
+    pub visible_lights: FxHashSet<Handle<Node>>,
+
+    /// Cached shadow maps of static objects for point lights with static shadows caching enabled.
+    pub point_shadow_cache: PointShadowCache,
+
+    /// Raw occlusion maps of every camera of the scene, that are used by
+    /// [`SsaoClearMode::PreserveSkipped`] clear mode of SSAO.
+    pub ssao_preserved_maps: PreservedOcclusionMaps,
 }
 
 impl AssociatedSceneData {
@@ -564,6 +813,9 @@ impl AssociatedSceneData {
             ldr_scene_framebuffer,
             ldr_temp_framebuffer,
             statistics: Default::default(),
+            visible_lights: Default::default(),
+            point_shadow_cache: Default::default(),
+            ssao_preserved_maps: Default::default(),
         })
     }
 
@@ -582,6 +834,7 @@ impl AssociatedSceneData {
             false,
             true,
             true,
+            BlitFilter::Nearest,
         );
     }
 
@@ -655,15 +908,28 @@ pub struct Renderer {
     /// Texture cache with GPU textures.
     pub texture_cache: TextureCache,
     shader_cache: ShaderCache,
+    shader_precompilation_queue: VecDeque<ShaderPrecompilationTask>,
+    shader_precompilation_progress: ShaderPrecompilationProgress,
     geometry_cache: GeometryCache,
     forward_renderer: ForwardRenderer,
-    fxaa_renderer: FxaaRenderer,
+    effect_chain: EffectChain,
+    debug_view: DebugView,
+    debug_view_shader: DebugViewShader,
+    overdraw_renderer: OverdrawRenderer,
+    point_light_intensity_scale: f32,
+    screenshot_requested: bool,
+    screenshot: Option<RgbaImage>,
     texture_event_receiver: Receiver<ResourceEvent>,
     shader_event_receiver: Receiver<ResourceEvent>,
     matrix_storage: MatrixStorageCache,
     // TextureId -> FrameBuffer mapping. This mapping is used for temporal frame buffers
     // like ones used to render UI instances.
     ui_frame_buffers: FxHashMap<u64, FrameBuffer>,
+    frames_in_flight: usize,
+    // Fences of the frames, that were submitted to the GPU but may be not finished yet.
+    frame_fences: VecDeque<GpuFence>,
+    screen_textures: ScreenRelativeTextureStorage,
+    depth_prepass: bool,
     /// Pipeline state.
     pub state: SharedPipelineState,
 }
@@ -899,6 +1165,7 @@ impl<const N: usize> Default for LightData<N> {
 #[allow(missing_docs)] // TODO
 pub struct MaterialContext<'a, 'b, 'c> {
     pub material: &'a Material,
+    pub material_overrides: &'a MaterialPropertyOverrides,
     pub program_binding: &'a mut GpuProgramBinding<'b, 'c>,
     pub texture_cache: &'a mut TextureCache,
     pub matrix_storage: &'a mut MatrixStorageCache,
@@ -910,6 +1177,7 @@ pub struct MaterialContext<'a, 'b, 'c> {
     pub wvp_matrix: &'a Matrix4<f32>,
     pub bone_matrices: &'a [Matrix4<f32>],
     pub use_skeletal_animation: bool,
+    pub use_instancing: bool,
     pub use_pom: bool,
     pub light_position: &'a Vector3<f32>,
     pub blend_shapes_storage: Option<&'a TextureResource>,
@@ -967,6 +1235,9 @@ pub fn apply_material(ctx: MaterialContext) {
         ctx.program_binding
             .set_bool(location, ctx.use_skeletal_animation);
     }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::UseInstancing as usize] {
+        ctx.program_binding.set_bool(location, ctx.use_instancing);
+    }
     if let Some(location) = &built_in_uniforms[BuiltInUniform::CameraPosition as usize] {
         ctx.program_binding
             .set_vector3(location, ctx.camera_position);
@@ -1056,8 +1327,14 @@ pub fn apply_material(ctx: MaterialContext) {
             .set_i32(location, ctx.blend_shapes_weights.len() as i32);
     }
 
-    // Apply material properties.
-    for (name, value) in ctx.material.properties() {
+    // Apply material properties, per-instance overrides are applied last to replace the values of
+    // the material.
+    for (name, value) in ctx
+        .material
+        .properties()
+        .iter()
+        .chain(ctx.material_overrides.iter())
+    {
         if let Some(uniform) = ctx.program_binding.uniform_location(name) {
             match value {
                 PropertyValue::Float(v) => {
@@ -1138,6 +1415,13 @@ pub fn apply_material(ctx: MaterialContext) {
 }
 
 impl Renderer {
+    /// Default amount of frames, that could be processed by the GPU while the CPU prepares the
+    /// next frame. See [`Self::set_frames_in_flight`] for more info.
+    pub const DEFAULT_FRAMES_IN_FLIGHT: usize = 3;
+
+    /// Maximum amount of frames in flight.
+    pub const MAX_FRAMES_IN_FLIGHT: usize = 3;
+
     pub(crate) fn new(
         context: glow::Context,
         frame_size: (u32, u32),
@@ -1174,10 +1458,17 @@ impl Renderer {
             shader_cache.get(&state, &shader);
         }
 
+        let mut screen_textures = ScreenRelativeTextureStorage::default();
+
         Ok(Self {
             backbuffer: FrameBuffer::backbuffer(&state),
             frame_size,
-            deferred_light_renderer: DeferredLightRenderer::new(&state, frame_size, &settings)?,
+            deferred_light_renderer: DeferredLightRenderer::new(
+                &state,
+                &mut screen_textures,
+                frame_size,
+                &settings,
+            )?,
             flat_shader: FlatShader::new(&state)?,
             white_dummy: Rc::new(RefCell::new(GpuTexture::new(
                 &state,
@@ -1264,22 +1555,42 @@ impl Renderer {
                 GeometryBufferKind::StaticDraw,
                 &state,
             )?,
-            ui_renderer: UiRenderer::new(&state)?,
+            ui_renderer: UiRenderer::new(&state, Self::DEFAULT_FRAMES_IN_FLIGHT)?,
             quality_settings: settings,
-            debug_renderer: DebugRenderer::new(&state)?,
+            debug_renderer: DebugRenderer::new(&state, Self::DEFAULT_FRAMES_IN_FLIGHT)?,
             scene_data_map: Default::default(),
             backbuffer_clear_color: Color::BLACK,
             texture_cache: Default::default(),
             geometry_cache: Default::default(),
-            forward_renderer: ForwardRenderer::new(),
+            forward_renderer: ForwardRenderer::new(&state, Self::DEFAULT_FRAMES_IN_FLIGHT)?,
             ui_frame_buffers: Default::default(),
-            fxaa_renderer: FxaaRenderer::new(&state)?,
+            debug_view: DebugView::None,
+            debug_view_shader: DebugViewShader::new(&state)?,
+            overdraw_renderer: OverdrawRenderer::new(&state)?,
+            point_light_intensity_scale: 1.0,
+            frames_in_flight: Self::DEFAULT_FRAMES_IN_FLIGHT,
+            frame_fences: Default::default(),
+            screen_textures,
+            depth_prepass: false,
+            screenshot_requested: false,
+            screenshot: None,
+            effect_chain: {
+                let mut effect_chain = EffectChain::default();
+                effect_chain.add(FxaaRenderer::new(&state)?);
+                effect_chain
+            },
             statistics: Statistics::default(),
             shader_event_receiver,
             texture_event_receiver,
             shader_cache,
+            shader_precompilation_queue: Default::default(),
+            shader_precompilation_progress: Default::default(),
             scene_render_passes: Default::default(),
-            matrix_storage: MatrixStorageCache::new(&state)?,
+            matrix_storage: {
+                let mut matrix_storage = MatrixStorageCache::new(&state)?;
+                matrix_storage.set_frames_in_flight(Self::DEFAULT_FRAMES_IN_FLIGHT);
+                matrix_storage
+            },
             state,
         })
     }
@@ -1315,6 +1626,13 @@ impl Renderer {
         self.statistics
     }
 
+    /// Returns the amount of GPU memory, that is currently allocated by the renderer for textures,
+    /// buffers and render targets. The values are estimations based on the sizes of the resources,
+    /// they do not include memory used internally by video driver.
+    pub fn gpu_memory_usage(&self) -> GpuMemoryStats {
+        self.state.memory_usage()
+    }
+
     /// Unloads texture from GPU memory.
     pub fn unload_texture(&mut self, texture: TextureResource) {
         self.texture_cache.unload(texture)
@@ -1330,6 +1648,12 @@ impl Renderer {
         &self.state
     }
 
+    /// Returns hardware limits and capabilities of the graphics device, such as maximum texture
+    /// size, maximum amount of color attachments, etc.
+    pub fn capabilities(&self) -> &PipelineCapabilities {
+        self.state.capabilities()
+    }
+
     /// Sets new frame size. You should call the same method on [`crate::engine::Engine`]
     /// instead, which will update the size for the user interface and rendering context
     /// as well as this one.
@@ -1342,12 +1666,31 @@ impl Renderer {
         self.frame_size.0 = new_size.0.max(1);
         self.frame_size.1 = new_size.1.max(1);
 
+        // Screen relative textures must be resized first, since the renderers may rely on their
+        // new size.
+        self.screen_textures
+            .set_frame_size(&self.state, self.frame_size)?;
+
         self.deferred_light_renderer
             .set_frame_size(&self.state, new_size)?;
 
         Ok(())
     }
 
+    /// Creates a new render target texture, which size is the size of the screen multiplied by the
+    /// given scale (`0.5` means half resolution, for example). The texture is resized automatically
+    /// every time the screen size changes, so there's no need to handle resizing manually. See
+    /// [`ScreenRelativeTexture`] docs for the exact auto-resize semantics. Returns an error if the
+    /// scale is not a positive number.
+    pub fn create_screen_relative_texture(
+        &mut self,
+        scale: f32,
+        pixel_kind: PixelKind,
+    ) -> Result<ScreenRelativeTexture, FrameworkError> {
+        self.screen_textures
+            .create(&self.state, self.frame_size, scale, pixel_kind)
+    }
+
     /// Returns current (width, height) pair of back buffer size.
     pub fn get_frame_size(&self) -> (u32, u32) {
         self.frame_size
@@ -1375,6 +1718,281 @@ impl Renderer {
         self.quality_settings
     }
 
+    /// Applies the full set of renderer settings at once. See [`RendererSettings`] docs for more
+    /// info.
+    pub fn apply_settings(&mut self, settings: &RendererSettings) -> Result<(), FrameworkError> {
+        self.set_quality_settings(&settings.quality)?;
+        self.set_ssao_options(&settings.ssao)?;
+        self.set_ssao_pixel_kind(if settings.ssao_half_precision {
+            PixelKind::R16F
+        } else {
+            PixelKind::R32F
+        })?;
+        self.set_light_intensity_scale(settings.light_intensity_scale);
+        Ok(())
+    }
+
+    /// Returns the full set of current renderer settings, it could be saved to disk and applied
+    /// back later using [`Self::apply_settings`].
+    pub fn current_settings(&self) -> RendererSettings {
+        RendererSettings {
+            version: RendererSettings::CURRENT_VERSION,
+            quality: self.quality_settings,
+            ssao: self.ssao_options(),
+            ssao_half_precision: self.ssao_pixel_kind() == PixelKind::R16F,
+            light_intensity_scale: self.point_light_intensity_scale,
+        }
+    }
+
+    /// Sets new debug view, that will be shown instead of the final frame of every scene. Use
+    /// [`DebugView::None`] to render scenes normally. See [`DebugView`] docs for more info.
+    pub fn set_debug_view(&mut self, debug_view: DebugView) {
+        self.debug_view = debug_view;
+    }
+
+    /// Returns current debug view.
+    pub fn debug_view(&self) -> DebugView {
+        self.debug_view
+    }
+
+    /// Sets a global multiplier for the intensity of every point light in every scene. It could
+    /// be used to tune brightness of point lights (for example, during day/night cycle) without
+    /// editing every light. Default value is `1.0`. Negative values are clamped to zero.
+    pub fn set_light_intensity_scale(&mut self, scale: f32) {
+        self.point_light_intensity_scale = scale.max(0.0);
+    }
+
+    /// Returns current global multiplier for the intensity of point lights.
+    pub fn light_intensity_scale(&self) -> f32 {
+        self.point_light_intensity_scale
+    }
+
+    /// Returns a reference to the chain of post effects, that is applied to the final frame of
+    /// every scene.
+    pub fn effect_chain(&self) -> &EffectChain {
+        &self.effect_chain
+    }
+
+    /// Returns a reference to the chain of post effects, that is applied to the final frame of
+    /// every scene. It could be used to add, remove or reorder effects.
+    pub fn effect_chain_mut(&mut self) -> &mut EffectChain {
+        &mut self.effect_chain
+    }
+
+    /// Returns information about every point light that contributed to the last rendered frame
+    /// (after culling). If there are multiple cameras, then the information is related to the last
+    /// rendered camera. Could be used to build lighting debug overlays.
+    pub fn point_lights_debug_info(&self) -> &[PointLightDebugInfo] {
+        self.deferred_light_renderer.point_lights_debug_info()
+    }
+
+    /// Returns `true` if the given light of the given scene passed culling for at least one camera
+    /// of the scene. The result reflects the previous completed frame, so it will be `false` for
+    /// lights that were added after the last rendered frame, and for every light of a scene that
+    /// was not rendered yet. Lights are culled by camera frustums only, there is no
+    /// occlusion culling for lights. Could be used to skip gameplay logic for lights that are not
+    /// visible (for example - flickering).
+    pub fn is_light_visible(&self, scene: Handle<Scene>, light: Handle<Node>) -> bool {
+        self.scene_data_map
+            .get(&scene)
+            .is_some_and(|data| data.visible_lights.contains(&light))
+    }
+
+    /// Requests a screenshot of the next rendered frame. The frame is read back right before it is
+    /// presented, so the screenshot contains every scene (except ones rendered to textures) and
+    /// the UI exactly as they're shown on the screen. Use [`Self::capture_screenshot`] to fetch
+    /// the result after the next frame.
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    /// Returns the screenshot, that was captured after [`Self::request_screenshot`] call, or
+    /// `None` if the frame was not rendered yet. The screenshot is returned only once.
+    ///
+    /// The image is in RGBA8 format with the origin at the top-left corner, its colors are
+    /// tone mapped and gamma corrected (sRGB) and alpha channel is always fully opaque, so it
+    /// could be saved to PNG as is and look the same as on the screen:
+    ///
+    /// ```rust,no_run
+    /// # use fyrox_impl::renderer::Renderer;
+    /// fn save_screenshot(renderer: &mut Renderer) {
+    ///     if let Some(screenshot) = renderer.capture_screenshot() {
+    ///         screenshot.save("screenshot.png").unwrap();
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Reading the frame back stalls the GPU, so avoid requesting screenshots every frame.
+    pub fn capture_screenshot(&mut self) -> Option<RgbaImage> {
+        self.screenshot.take()
+    }
+
+    /// Returns current values of every tunable parameter of screen-space ambient occlusion. It
+    /// could be used to build editor or in-game sliders, that reflect current state of the effect.
+    pub fn ssao_options(&self) -> SsaoOptions {
+        self.deferred_light_renderer.ssao_renderer.options()
+    }
+
+    /// Applies every tunable parameter of screen-space ambient occlusion at once. The radius of the
+    /// effect is not a part of the options, use [`QualitySettings::ssao_radius`] to change it.
+    /// Returns an error if the noise texture could not be regenerated, the rest of the options are
+    /// applied anyway.
+    pub fn set_ssao_options(&mut self, options: &SsaoOptions) -> Result<(), FrameworkError> {
+        self.deferred_light_renderer
+            .ssao_renderer
+            .apply_options(&self.state, options)
+    }
+
+    /// Sets the format of the occlusion maps of screen-space ambient occlusion, it must be either
+    /// [`PixelKind::R32F`] (default) or [`PixelKind::R16F`]. Half precision halves the bandwidth of
+    /// the effect with negligible quality loss, since occlusion is in `[0; 1]` range. Only the
+    /// occlusion maps and their frame buffers are recreated (tunable parameters and pending warm-up
+    /// iterations are kept, but temporal history starts over), so this method should not be called
+    /// every frame. Returns an error if the format is not
+    /// supported, in this case current occlusion maps are kept as is.
+    pub fn set_ssao_pixel_kind(&mut self, pixel_kind: PixelKind) -> Result<(), FrameworkError> {
+        self.deferred_light_renderer.ssao_renderer.set_pixel_kind(
+            &self.state,
+            &mut self.screen_textures,
+            self.frame_size.0 as usize,
+            self.frame_size.1 as usize,
+            pixel_kind,
+        )
+    }
+
+    /// Returns the format of the occlusion maps of screen-space ambient occlusion. See
+    /// [`Self::set_ssao_pixel_kind`] for more info.
+    pub fn ssao_pixel_kind(&self) -> PixelKind {
+        self.deferred_light_renderer.ssao_renderer.pixel_kind()
+    }
+
+    /// Returns width and height of the occlusion map of screen-space ambient occlusion. SSAO is
+    /// calculated at reduced resolution, so this method should be used to correctly scale texture
+    /// coordinates when sampling the map in custom shaders.
+    pub fn ssao_resolution(&self) -> Vector2<i32> {
+        self.deferred_light_renderer.ssao_renderer.ao_resolution()
+    }
+
+    /// Sets new size (in pixels along each axis) of the noise texture of screen-space ambient
+    /// occlusion. Larger textures (8x8, for example) reduce repeating patterns, that could be
+    /// visible on large flat surfaces. The value is clamped to the range from `1` to
+    /// [`MAX_NOISE_SIZE`], default value is [`DEFAULT_NOISE_SIZE`]. The texture is regenerated only
+    /// when the size changes.
+    pub fn set_ssao_noise_size(&mut self, size: usize) -> Result<(), FrameworkError> {
+        self.deferred_light_renderer
+            .ssao_renderer
+            .set_noise_size(&self.state, size)
+    }
+
+    /// Returns current size of the noise texture of screen-space ambient occlusion. See
+    /// [`Self::set_ssao_noise_size`] for more info.
+    pub fn ssao_noise_size(&self) -> usize {
+        self.deferred_light_renderer.ssao_renderer.noise_size()
+    }
+
+    /// Sets the maximum amount of frames, that could be processed by the GPU while the CPU prepares
+    /// the next frame. Every frame is marked with a fence and the renderer waits for the oldest
+    /// frame when the limit is reached. Lower values reduce input latency at the cost of less
+    /// parallelism between the CPU and the GPU. The data, that is updated every frame (bone
+    /// matrices and other matrix storages, transient geometry of the user interface, debug drawing
+    /// and instanced rectangles) is stored in ring buffers with this amount of copies, so the
+    /// renderer never overwrites the data that is still used by the GPU and the driver does not
+    /// have to stall until the previous frames are finished. The value must be in `[1; 3]` range,
+    /// default is [`Self::DEFAULT_FRAMES_IN_FLIGHT`] which matches the usual limit of video
+    /// drivers. Returns an error if the value is out of the range, in this case
+    /// current value is kept as is.
+    pub fn set_frames_in_flight(&mut self, frames_in_flight: usize) -> Result<(), FrameworkError> {
+        if !(1..=Self::MAX_FRAMES_IN_FLIGHT).contains(&frames_in_flight) {
+            return Err(FrameworkError::Custom(format!(
+                "Amount of frames in flight must be in [1; {}] range, got {}!",
+                Self::MAX_FRAMES_IN_FLIGHT,
+                frames_in_flight
+            )));
+        }
+
+        // Wait before resizing the rings, so the data of the unfinished frames stays intact.
+        self.frames_in_flight = frames_in_flight;
+        self.wait_for_frames_in_flight();
+
+        self.ui_renderer
+            .set_frames_in_flight(&self.state, frames_in_flight)?;
+        self.debug_renderer
+            .set_frames_in_flight(&self.state, frames_in_flight)?;
+        self.forward_renderer
+            .set_frames_in_flight(&self.state, frames_in_flight)?;
+        self.matrix_storage.set_frames_in_flight(frames_in_flight);
+
+        Ok(())
+    }
+
+    /// Returns the maximum amount of frames, that could be processed by the GPU while the CPU
+    /// prepares the next frame. See [`Self::set_frames_in_flight`] for more info.
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames_in_flight
+    }
+
+    /// Enables or disables the depth prepass. When enabled, opaque geometry of the G-Buffer pass is
+    /// rendered twice: the first time it only fills the depth buffer using the depth-only
+    /// `DepthPrepass` render pass of its shader, the second time it is shaded by the `GBuffer`
+    /// render pass with depth test set to "equal". This way every pixel is shaded only once, no
+    /// matter how many surfaces overlap it. Objects, whose shaders do not have the `DepthPrepass`
+    /// render pass, are rendered as usual (the standard shaders have it).
+    ///
+    /// The prepass reduces the cost of overdraw-heavy scenes (dense foliage, complex interiors,
+    /// etc.) with expensive materials, but doubles the amount of processed vertices and draw calls
+    /// of the G-Buffer pass, so it could make simple scenes slower. The benefit depends on the
+    /// hardware and the scene, so measure it: compare [`Statistics::pure_frame_time`] of your
+    /// scene with and without the prepass, while [`Statistics::geometry`] shows the extra draw
+    /// calls and triangles of the prepass. Disabled by default.
+    pub fn set_depth_prepass(&mut self, enabled: bool) {
+        self.depth_prepass = enabled;
+    }
+
+    /// Returns `true` if the depth prepass is enabled. See [`Self::set_depth_prepass`] for more
+    /// info.
+    pub fn is_depth_prepass_enabled(&self) -> bool {
+        self.depth_prepass
+    }
+
+    // Blocks until the amount of unfinished frames is within the limit.
+    fn wait_for_frames_in_flight(&mut self) {
+        while self.frame_fences.len() > self.frames_in_flight {
+            if let Some(fence) = self.frame_fences.pop_front() {
+                fence.wait(&self.state);
+            }
+        }
+    }
+
+    fn end_frame_in_flight(&mut self) -> Result<(), FrameworkError> {
+        self.frame_fences.push_back(GpuFence::new(&self.state)?);
+        self.wait_for_frames_in_flight();
+        Ok(())
+    }
+
+    /// Discards the history of screen-space ambient occlusion, so the next frame starts fresh.
+    /// The history is valid only for smooth camera movement, so this method should be called on
+    /// camera cuts, teleports, respawns and scene loads, otherwise occlusion of the previous view
+    /// will be visible for a few frames ("ghosting"). It is a no-op if neither temporal
+    /// accumulation, nor [`SsaoClearMode::PreserveSkipped`] is used.
+    pub fn reset_ssao_history(&mut self) {
+        self.deferred_light_renderer.ssao_renderer.reset_history()
+    }
+
+    /// Requests the given amount of additional screen-space ambient occlusion iterations to be
+    /// rendered on the next frame, so the effect is stable from the very first frame. It could be
+    /// useful for screenshots and cutscene starts. SSAO techniques without history are stable from
+    /// the first frame, so for them this method is a no-op.
+    pub fn warm_up_ssao(&mut self, frames: usize) {
+        self.deferred_light_renderer.ssao_renderer.warm_up(frames)
+    }
+
+    /// Returns a 1x1 "fully lit" cube map, that is bound as a shadow map for point lights that do
+    /// not have a rendered shadow map (for example, when shadows are disabled or the light is
+    /// too far from the camera). It guarantees that such lights are rendered without shadows.
+    pub fn point_shadow_fallback_texture(&self) -> &Rc<RefCell<GpuTexture>> {
+        self.deferred_light_renderer.point_shadow_fallback_texture()
+    }
+
     /// Removes all cached GPU data, forces renderer to re-upload data to GPU.
     /// Do not call this method until you absolutely need! It may cause **significant**
     /// performance lag!
@@ -1453,6 +2071,42 @@ impl Renderer {
         Ok(())
     }
 
+    /// Renders the given formatted text into a new texture of the given size. It could be used to bake
+    /// dynamic text into textures, for example to show labels on sprites or to create dynamic signs.
+    /// Multi-line text, wrapping and alignment are defined by the formatted text itself (see
+    /// [`crate::gui::formatted_text::FormattedTextBuilder`]), its constraint will be set to the
+    /// size of the texture. The background of the texture is fully transparent.
+    ///
+    /// Keep in mind, that the glyphs of the font must be rasterized on CPU first, so the font must
+    /// be fully loaded before calling this method.
+    pub fn render_text_to_texture(
+        &mut self,
+        mut formatted_text: FormattedText,
+        size: Vector2<f32>,
+    ) -> Result<TextureResource, FrameworkError> {
+        let render_target = TextureResource::new_render_target(size.x as u32, size.y as u32);
+
+        formatted_text.set_constraint(size);
+        formatted_text.build();
+
+        let mut drawing_context = DrawingContext::new();
+        drawing_context.draw_text(
+            Rect::new(0.0, 0.0, size.x, size.y),
+            Vector2::default(),
+            &formatted_text,
+        );
+
+        self.render_ui_to_texture(
+            render_target.clone(),
+            size,
+            &drawing_context,
+            Color::TRANSPARENT,
+            PixelKind::RGBA8,
+        )?;
+
+        Ok(render_target)
+    }
+
     fn update_texture_cache(&mut self, dt: f32) {
         // Maximum amount of textures uploaded to GPU per frame. This defines throughput **only** for
         // requests from resource manager. This is needed to prevent huge lag when there are tons of
@@ -1495,9 +2149,91 @@ impl Renderer {
             }
         }
 
+        self.precompile_queued_shaders();
+
         self.shader_cache.update(dt)
     }
 
+    fn precompile_queued_shaders(&mut self) {
+        let start_time = instant::Instant::now();
+
+        // Amount of consecutive shaders, that are still loading. Such shaders are moved to the end
+        // of the queue, so they do not block the rest of the queue. The loop stops when every
+        // remaining shader is still loading, they're tried again on next update.
+        let mut pending = 0;
+        while pending < self.shader_precompilation_queue.len() {
+            let Some(task) = self.shader_precompilation_queue.pop_front() else {
+                break;
+            };
+
+            match task {
+                ShaderPrecompilationTask::Shader(shader) if shader.is_loading() => {
+                    self.shader_precompilation_queue
+                        .push_back(ShaderPrecompilationTask::Shader(shader));
+                    pending += 1;
+                    continue;
+                }
+                ShaderPrecompilationTask::Shader(shader) => {
+                    // Failed shaders are counted as processed too, the error is written to the
+                    // log by the cache.
+                    let _ = self.shader_cache.get(&self.state, &shader);
+                }
+                ShaderPrecompilationTask::BuiltInPrograms => {
+                    Log::verify(
+                        self.deferred_light_renderer
+                            .ssao_renderer
+                            .precompile_programs(&self.state),
+                    );
+                }
+            }
+
+            pending = 0;
+            self.shader_precompilation_progress.processed += 1;
+
+            if instant::Instant::now()
+                .duration_since(start_time)
+                .as_secs_f32()
+                >= SHADER_PRECOMPILATION_TIME_BUDGET
+            {
+                break;
+            }
+        }
+    }
+
+    /// Queues the given shaders for precompilation. Material shaders are compiled lazily on first
+    /// use by default, which may cause noticeable hitches when an object with a new shader appears
+    /// on screen for the first time. This method allows you to compile the shaders up front, for
+    /// example while a loading screen is shown. Standard material shaders and most of the built-in
+    /// programs are always compiled when the renderer is created, the rest of the built-in programs
+    /// (the ones of optional effects, such as temporal accumulation or interleaved sampling of
+    /// SSAO) are queued by this method as well.
+    ///
+    /// Compilation is performed incrementally in [`Self::update_caches`] with a small time budget
+    /// per call, so the loading screen can stay responsive. Shaders that are still loading do not
+    /// block the rest of the queue, they will be compiled as soon as they're loaded. Use
+    /// [`Self::shader_precompilation_progress`] to track the progress.
+    pub fn precompile_shaders<I>(&mut self, shaders: I)
+    where
+        I: IntoIterator<Item = ShaderResource>,
+    {
+        if self.shader_precompilation_progress.is_finished() {
+            self.shader_precompilation_progress = Default::default();
+        }
+
+        let tasks = std::iter::once(ShaderPrecompilationTask::BuiltInPrograms)
+            .chain(shaders.into_iter().map(ShaderPrecompilationTask::Shader));
+        for task in tasks {
+            self.shader_precompilation_progress.total += 1;
+            self.shader_precompilation_queue.push_back(task);
+        }
+    }
+
+    /// Returns current progress of shader precompilation. See [`Self::precompile_shaders`] for
+    /// more info.
+    pub fn shader_precompilation_progress(&self) -> ShaderPrecompilationProgress {
+        self.shader_precompilation_progress
+    }
+
     /// Update caches - this will remove timed out resources.
     ///
     /// Normally, this is called from `Engine::update()`.
@@ -1577,6 +2313,7 @@ impl Renderer {
 
         let pipeline_stats = state.pipeline_statistics();
         scene_associated_data.statistics = Default::default();
+        scene_associated_data.visible_lights.clear();
 
         // If we specified a texture to draw to, we have to register it in texture cache
         // so it can be used in later on as texture. This is useful in case if you need
@@ -1609,6 +2346,7 @@ impl Renderer {
                     z_far: camera.projection().z_far(),
                     view_matrix: camera.view_matrix(),
                     projection_matrix: camera.projection_matrix(),
+                    viewport,
                 },
                 GBUFFER_PASS_NAME.clone(),
             );
@@ -1628,6 +2366,7 @@ impl Renderer {
                     shader_cache: &mut self.shader_cache,
                     environment_dummy: self.environment_dummy.clone(),
                     use_parallax_mapping: self.quality_settings.use_parallax_mapping,
+                    depth_prepass: self.depth_prepass,
                     normal_dummy: self.normal_dummy.clone(),
                     white_dummy: self.white_dummy.clone(),
                     black_dummy: self.black_dummy.clone(),
@@ -1671,6 +2410,10 @@ impl Renderer {
                         black_dummy: self.black_dummy.clone(),
                         volume_dummy: self.volume_dummy.clone(),
                         matrix_storage: &mut self.matrix_storage,
+                        point_light_intensity_scale: self.point_light_intensity_scale,
+                        visible_lights: &mut scene_associated_data.visible_lights,
+                        point_shadow_cache: &mut scene_associated_data.point_shadow_cache,
+                        ssao_preserved_maps: &mut scene_associated_data.ssao_preserved_maps,
                     })?;
 
             scene_associated_data.statistics += light_stats;
@@ -1753,22 +2496,45 @@ impl Renderer {
                 &mut self.texture_cache,
             )?;
 
-            // Apply FXAA if needed.
-            if self.quality_settings.fxaa {
-                scene_associated_data.statistics += self.fxaa_renderer.render(
-                    state,
-                    viewport,
-                    scene_associated_data.ldr_scene_frame_texture(),
-                    &mut scene_associated_data.ldr_temp_framebuffer,
-                )?;
+            // Apply post effects (FXAA, user-defined effects, etc.).
+            scene_associated_data.statistics += self.effect_chain.run(
+                state,
+                quad,
+                &self.flat_shader,
+                viewport,
+                &self.quality_settings,
+                &mut scene_associated_data.ldr_scene_framebuffer,
+                &mut scene_associated_data.ldr_temp_framebuffer,
+            )?;
 
-                let quad = &self.quad;
-                let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
-                scene_associated_data.statistics += blit_pixels(
+            // Replace the final frame with intermediate results of the renderer, if requested.
+            let debug_view_texture = match self.debug_view {
+                DebugView::None => None,
+                DebugView::Ssao => Some(self.deferred_light_renderer.ssao_renderer.ao_map()),
+                DebugView::SsaoRaw => Some(self.deferred_light_renderer.ssao_renderer.raw_ao_map()),
+                DebugView::Normals => Some(scene_associated_data.gbuffer.normal_texture()),
+                DebugView::Depth => Some(scene_associated_data.gbuffer.depth()),
+                DebugView::Albedo => Some(scene_associated_data.gbuffer.diffuse_texture()),
+                DebugView::Overdraw => {
+                    let (stats, counter_texture) = self.overdraw_renderer.render(
+                        state,
+                        &bundle_storage,
+                        camera,
+                        viewport,
+                        &mut self.geometry_cache,
+                        &mut self.shader_cache,
+                    )?;
+                    scene_associated_data.statistics += stats;
+                    Some(counter_texture)
+                }
+            };
+            if let Some(debug_view_texture) = debug_view_texture {
+                scene_associated_data.statistics += self.debug_view_shader.render(
                     state,
+                    self.debug_view,
+                    debug_view_texture,
+                    camera,
                     &mut scene_associated_data.ldr_scene_framebuffer,
-                    temp_frame_texture,
-                    &self.flat_shader,
                     viewport,
                     quad,
                 )?;
@@ -1845,6 +2611,9 @@ impl Renderer {
         }
 
         self.matrix_storage.begin_frame();
+        self.ui_renderer.begin_frame();
+        self.debug_renderer.begin_frame();
+        self.forward_renderer.begin_frame();
 
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
@@ -1891,6 +2660,18 @@ impl Renderer {
             })?;
         }
 
+        // Back buffer contents is undefined after swapping, so the screenshot must be taken here.
+        if std::mem::take(&mut self.screenshot_requested) {
+            let pixels = self
+                .backbuffer
+                .read_rgba8_pixels(&self.state, window_viewport);
+            self.screenshot = Some(screenshot::back_buffer_pixels_to_image(
+                self.frame_size.0,
+                self.frame_size.1,
+                pixels,
+            ));
+        }
+
         Ok(())
     }
 
@@ -1907,6 +2688,7 @@ impl Renderer {
         self.statistics.end_frame();
         window.pre_present_notify();
         surface.swap_buffers(context)?;
+        self.end_frame_in_flight()?;
         self.statistics.finalize();
         self.statistics.pipeline = self.state.pipeline_statistics();
         Ok(())
@@ -1920,6 +2702,7 @@ impl Renderer {
     ) -> Result<(), FrameworkError> {
         self.render_frame(scenes, drawing_contexts)?;
         self.statistics.end_frame();
+        self.end_frame_in_flight()?;
         self.statistics.finalize();
         self.statistics.pipeline = self.state.pipeline_statistics();
         Ok(())
@@ -0,0 +1,55 @@
+//! Conversion of the raw pixels of the back buffer to an image, that could be saved to disk.
+
+use image::RgbaImage;
+
+/// Converts raw RGBA8 pixels of the back buffer into an image. OpenGL stores rows from bottom to
+/// top, so the rows are flipped. The back buffer already contains tone mapped and gamma corrected
+/// colors, so no color conversion is needed. Alpha channel of the back buffer holds blending
+/// leftovers (premultiplied alpha of the UI, for example) and does not affect what is shown on
+/// the screen, so it is replaced with fully opaque value.
+pub(crate) fn back_buffer_pixels_to_image(
+    width: u32,
+    height: u32,
+    mut pixels: Vec<u8>,
+) -> RgbaImage {
+    let row_size = 4 * width as usize;
+    let height = height as usize;
+
+    for y in 0..height / 2 {
+        let (top, bottom) = pixels.split_at_mut((height - y - 1) * row_size);
+        top[y * row_size..(y + 1) * row_size].swap_with_slice(&mut bottom[..row_size]);
+    }
+
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel[3] = 255;
+    }
+
+    RgbaImage::from_raw(width, height as u32, pixels)
+        .expect("Pixel buffer must match the size of the image!")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::screenshot::back_buffer_pixels_to_image;
+
+    #[test]
+    fn test_back_buffer_pixels_to_image() {
+        #[rustfmt::skip]
+        let pixels = vec![
+            // Bottom row.
+            1, 2, 3, 0, 4, 5, 6, 10,
+            // Middle row.
+            7, 8, 9, 20, 10, 11, 12, 30,
+            // Top row.
+            13, 14, 15, 40, 16, 17, 18, 50,
+        ];
+
+        let image = back_buffer_pixels_to_image(2, 3, pixels);
+
+        assert_eq!(image.dimensions(), (2, 3));
+        assert_eq!(image.get_pixel(0, 0).0, [13, 14, 15, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [16, 17, 18, 255]);
+        assert_eq!(image.get_pixel(0, 1).0, [7, 8, 9, 255]);
+        assert_eq!(image.get_pixel(1, 2).0, [4, 5, 6, 255]);
+    }
+}
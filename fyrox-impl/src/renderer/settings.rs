@@ -0,0 +1,131 @@
+//! Renderer settings is a full set of options of the renderer, that could be saved to disk and
+//! applied back. See [`RendererSettings`] docs for more info.
+
+use crate::{
+    core::{reflect::prelude::*, visitor::prelude::*},
+    renderer::{QualitySettings, SsaoOptions},
+};
+use serde::{Deserialize, Serialize};
+
+/// A full set of options of the renderer: quality settings (shadows, SSAO, post effects), every
+/// tunable parameter of screen-space ambient occlusion and a few global parameters that are not
+/// part of [`QualitySettings`]. It is intended to back graphics options menus, use
+/// [`super::Renderer::current_settings`] to fetch the settings, save them using any `serde` format
+/// (or [`Visitor`]) and then restore them using [`super::Renderer::apply_settings`].
+///
+/// # Versioning
+///
+/// Every field is optional for deserialization, missing fields take their default values. It
+/// allows you to load settings, that were saved by older versions of the engine. [`Self::version`]
+/// stores the version of the format, that was used to save the settings.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use fyrox_impl::renderer::{settings::RendererSettings, Renderer};
+/// fn save_settings(renderer: &Renderer) -> String {
+///     ron::ser::to_string_pretty(&renderer.current_settings(), Default::default()).unwrap()
+/// }
+///
+/// fn load_settings(renderer: &mut Renderer, ron: &str) {
+///     let settings = ron::de::from_str::<RendererSettings>(ron).unwrap_or_default();
+///     renderer.apply_settings(&settings).unwrap();
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Visit)]
+#[serde(default)]
+pub struct RendererSettings {
+    /// Version of the settings format. It is always set to [`Self::CURRENT_VERSION`] when the
+    /// settings are created by the engine.
+    #[reflect(hidden)]
+    #[visit(optional)]
+    pub version: u32,
+
+    /// Quality settings of the renderer.
+    #[visit(optional)]
+    pub quality: QualitySettings,
+
+    /// Every tunable parameter of screen-space ambient occlusion, except the radius, which is
+    /// [`QualitySettings::ssao_radius`].
+    #[visit(optional)]
+    pub ssao: SsaoOptions,
+
+    /// Stores the occlusion maps of SSAO with half precision. See
+    /// [`super::Renderer::set_ssao_pixel_kind`] for more info.
+    #[visit(optional)]
+    pub ssao_half_precision: bool,
+
+    /// A global multiplier for the intensity of every point light.
+    #[visit(optional)]
+    pub light_intensity_scale: f32,
+}
+
+impl RendererSettings {
+    /// Current version of the settings format.
+    pub const CURRENT_VERSION: u32 = 1;
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self {
+            version: Self::CURRENT_VERSION,
+            quality: Default::default(),
+            ssao: Default::default(),
+            ssao_half_precision: false,
+            light_intensity_scale: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::{Visit, Visitor},
+        renderer::{
+            settings::RendererSettings, QualitySettings, SsaoClearMode, SsaoMode, SsaoOptions,
+        },
+    };
+
+    fn non_default_settings() -> RendererSettings {
+        RendererSettings {
+            quality: QualitySettings::low(),
+            ssao: SsaoOptions {
+                power: 2.0,
+                bias: 0.1,
+                sample_count: 16,
+                temporal: true,
+                interleave_factor: 2,
+                clear_mode: SsaoClearMode::PreserveSkipped,
+                blur_depth_threshold: 0.5,
+                mode: SsaoMode::HorizonBased,
+                hbao_direction_count: 4,
+                hbao_step_count: 8,
+                noise_size: 8,
+            },
+            ssao_half_precision: true,
+            light_intensity_scale: 0.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_renderer_settings_serde_round_trip() {
+        let settings = non_default_settings();
+        let string = ron::ser::to_string(&settings).unwrap();
+        let loaded = ron::de::from_str::<RendererSettings>(&string).unwrap();
+        assert_eq!(settings, loaded);
+    }
+
+    #[test]
+    fn test_renderer_settings_visit_round_trip() {
+        let mut settings = non_default_settings();
+        let mut visitor = Visitor::new();
+        settings.visit("Settings", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut loaded = RendererSettings::default();
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        loaded.visit("Settings", &mut visitor).unwrap();
+        assert_eq!(settings, loaded);
+    }
+}
@@ -1,6 +1,7 @@
 uniform sampler2D diffuseTexture;
 uniform sampler2D aoSampler;
 uniform sampler2D ambientTexture;
+uniform sampler2D materialTexture;
 uniform vec4 ambientColor;
 
 out vec4 FragColor;
@@ -11,7 +12,9 @@ void main()
     float ambientOcclusion = texture(aoSampler, texCoord).r;
     vec4 ambientPixel = texture(ambientTexture, texCoord);
     FragColor = (ambientColor + ambientPixel) * S_SRGBToLinear(texture(diffuseTexture, texCoord));
-    FragColor.rgb *= ambientOcclusion;
+    // Alpha channel of the material texture defines how much of the ambient occlusion the surface receives.
+    float aoFactor = texture(materialTexture, texCoord).a;
+    FragColor.rgb *= mix(1.0, ambientOcclusion, aoFactor);
     FragColor.a = ambientPixel.a;
 
     // TODO: Implement IBL.
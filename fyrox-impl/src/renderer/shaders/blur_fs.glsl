@@ -1,22 +1,47 @@
-// Simple 4x4 box blur.
+// Depth-aware (bilateral) 4x4 box blur. Negative samples are left by interleaved sampling of SSAO,
+// they're skipped and the gaps are filled using valid neighbours. Neighbours are weighted by their
+// view space depth difference to prevent occlusion leaking through the edges.
+// View space depth is in world units, so the threshold does not depend on clipping planes.
 
 uniform sampler2D inputTexture;
+uniform sampler2D depthSampler;
+uniform mat4 inverseProjectionMatrix;
+// Maximum view space depth difference (in world units) of blended neighbours.
+uniform float depthThreshold;
 
 out float FragColor;
 
 in vec2 texCoord;
 
+float GetViewSpaceDepth(vec2 screenCoord) {
+    return S_UnProject(vec3(screenCoord, texture(depthSampler, screenCoord).r), inverseProjectionMatrix).z;
+}
+
 void main()
 {
     vec2 texelSize = 1.0 / vec2(textureSize(inputTexture, 0));
+    float centerDepth = GetViewSpaceDepth(texCoord);
     float result = 0.0;
+    float totalWeight = 0.0;
     for (int y = -2; y < 2; ++y)
     {
         for (int x = -2; x < 2; ++x)
         {
             vec2 offset = vec2(float(x), float(y)) * texelSize;
-            result += texture(inputTexture, texCoord + offset).r;
+            float value = texture(inputTexture, texCoord + offset).r;
+            if (value >= 0.0) {
+                float depthDifference = abs(centerDepth - GetViewSpaceDepth(texCoord + offset));
+                float weight = max(1.0 - depthDifference / depthThreshold, 0.0);
+                result += value * weight;
+                totalWeight += weight;
+            }
         }
     }
-    FragColor = result / 16.0;
-}
\ No newline at end of file
+    if (totalWeight > 0.0) {
+        FragColor = result / totalWeight;
+    } else {
+        // No valid neighbours, keep the centre sample (unless it was skipped too).
+        float center = texture(inputTexture, texCoord).r;
+        FragColor = center >= 0.0 ? center : 1.0;
+    }
+}
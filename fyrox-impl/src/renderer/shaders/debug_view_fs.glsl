@@ -0,0 +1,46 @@
+uniform sampler2D sourceTexture;
+// 0 - show as is, 1 - single channel, 2 - non-linear depth, 3 - overdraw heatmap.
+uniform int mode;
+// (zNear, zFar)
+uniform vec2 depthRange;
+// Amount of overdraw, that is shown as the "hottest" color of the heatmap.
+uniform float maxOverdraw;
+
+out vec4 FragColor;
+
+in vec2 texCoord;
+
+vec3 Heatmap(float t)
+{
+    // Black -> blue -> green -> yellow -> red -> white.
+    const int count = 6;
+    const vec3 colors[count] = vec3[count] (
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(1.0, 1.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(1.0, 1.0, 1.0)
+    );
+    float position = clamp(t, 0.0, 1.0) * float(count - 1);
+    int index = min(int(position), count - 2);
+    return mix(colors[index], colors[index + 1], position - float(index));
+}
+
+void main()
+{
+    vec4 source = texture(sourceTexture, texCoord);
+    if (mode == 1) {
+        FragColor = vec4(vec3(source.r), 1.0);
+    } else if (mode == 2) {
+        float zNear = depthRange.x;
+        float zFar = depthRange.y;
+        float ndcDepth = source.r * 2.0 - 1.0;
+        float linearDepth = (2.0 * zNear * zFar) / (zFar + zNear - ndcDepth * (zFar - zNear));
+        FragColor = vec4(vec3((linearDepth - zNear) / (zFar - zNear)), 1.0);
+    } else if (mode == 3) {
+        FragColor = vec4(Heatmap(source.r / maxOverdraw), 1.0);
+    } else {
+        FragColor = vec4(source.rgb, 1.0);
+    }
+}
@@ -0,0 +1,76 @@
+// Renders every batched point light (without shadows) in a single pass. The screen is split into
+// tiles, every pixel loops over the lights of its tile only.
+
+uniform sampler2D depthTexture;
+uniform sampler2D colorTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform sampler2D aoSampler;
+
+// Two texels per light: (position, radius) and (color * intensity, source radius).
+uniform sampler2D lightStorage;
+// Header (offset, count) for every tile, followed by light indices of all tiles.
+uniform sampler2D gridStorage;
+
+uniform int tileSize;
+uniform int tileCountX;
+uniform mat4 invViewProj;
+uniform vec3 cameraPosition;
+
+in vec2 texCoord;
+out vec4 FragColor;
+
+float FetchGridValue(int index) {
+    return texelFetch(gridStorage, S_LinearIndexToPosition(index, textureSize(gridStorage, 0).x), 0).r;
+}
+
+vec4 FetchLightValue(int index) {
+    return texelFetch(lightStorage, S_LinearIndexToPosition(index, textureSize(lightStorage, 0).x), 0);
+}
+
+void main()
+{
+    ivec2 tile = ivec2(gl_FragCoord.xy) / tileSize;
+    int tileIndex = 2 * (tile.y * tileCountX + tile.x);
+    int offset = int(FetchGridValue(tileIndex));
+    int count = int(FetchGridValue(tileIndex + 1));
+
+    if (count == 0) {
+        discard;
+    }
+
+    vec4 material = texture(materialTexture, texCoord);
+    // Alpha channel of the material texture defines how much of the ambient occlusion the surface receives.
+    float ambientOcclusion = mix(1.0, texture(aoSampler, texCoord).r, material.a);
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
+    vec4 diffuseColor = texture(colorTexture, texCoord);
+
+    TPBRContext ctx;
+    ctx.albedo = S_SRGBToLinear(diffuseColor).rgb;
+    ctx.fragmentNormal = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    ctx.metallic = material.x;
+    ctx.roughness = material.y;
+    ctx.viewVector = normalize(cameraPosition - fragmentPosition);
+
+    vec3 lighting = vec3(0.0);
+    for (int i = 0; i < count; ++i) {
+        int lightIndex = int(FetchGridValue(offset + i));
+        vec4 positionRadius = FetchLightValue(2 * lightIndex);
+        vec4 colorSourceRadius = FetchLightValue(2 * lightIndex + 1);
+
+        vec3 fragmentToLight = positionRadius.xyz - fragmentPosition;
+        float distance = length(fragmentToLight);
+        float distanceAttenuation = S_LightDistanceAttenuation(distance, positionRadius.w);
+        if (distanceAttenuation <= 0.0) {
+            continue;
+        }
+
+        ctx.fragmentToLight = fragmentToLight / distance;
+        ctx.lightColor = colorSourceRadius.rgb;
+
+        lighting += distanceAttenuation * S_PBR_CalculateSphereLight(ctx, fragmentToLight, colorSourceRadius.w);
+    }
+
+    FragColor = vec4(ambientOcclusion * lighting, diffuseColor.a);
+}
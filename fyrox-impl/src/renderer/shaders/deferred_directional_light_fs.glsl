@@ -2,6 +2,7 @@ uniform sampler2D depthTexture;
 uniform sampler2D colorTexture;
 uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
+uniform sampler2D aoSampler;
 
 uniform vec3 lightDirection;
 uniform vec4 lightColor;
@@ -35,7 +36,9 @@ float CsmGetShadow(in sampler2D sampler, in vec3 fragmentPosition, in mat4 light
 
 void main()
 {
-    vec3 material = texture(materialTexture, texCoord).rgb;
+    vec4 material = texture(materialTexture, texCoord);
+    // Alpha channel of the material texture defines how much of the ambient occlusion the surface receives.
+    float ambientOcclusion = mix(1.0, texture(aoSampler, texCoord).r, material.a);
 
     vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
     vec4 diffuseColor = texture(colorTexture, texCoord);
@@ -62,5 +65,5 @@ void main()
         shadow = CsmGetShadow(shadowCascade2, fragmentPosition, lightViewProjMatrices[2]);
     }
 
-    FragColor = shadow * vec4(lightIntensity * lighting, diffuseColor.a);
+    FragColor = shadow * vec4(lightIntensity * ambientOcclusion * lighting, diffuseColor.a);
 }
@@ -2,25 +2,38 @@ uniform sampler2D depthTexture;
 uniform sampler2D colorTexture;
 uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
+// Distance-based shadow cascades, the first one is the closest to the light.
 uniform samplerCube pointShadowTexture;
+uniform samplerCube pointShadowTexture1;
+uniform samplerCube pointShadowTexture2;
+uniform samplerCube staticShadowTexture;
+uniform sampler2D aoSampler;
 
 uniform vec3 lightPos;
 uniform float lightRadius;
+uniform float lightSourceRadius;
 uniform vec4 lightColor;
 uniform mat4 invViewProj;
 uniform vec3 cameraPosition;
 uniform bool softShadows;
+uniform int pcfKernelRadius;
 uniform bool shadowsEnabled;
+uniform bool useStaticShadows;
 uniform float shadowBias;
 uniform float lightIntensity;
 uniform float shadowAlpha;
+uniform int shadowCascadeCount;
+// Far distances of the first two cascades.
+uniform vec2 shadowCascadeSplits;
 
 in vec2 texCoord;
 out vec4 FragColor;
 
 void main()
 {
-    vec3 material = texture(materialTexture, texCoord).rgb;
+    vec4 material = texture(materialTexture, texCoord);
+    // Alpha channel of the material texture defines how much of the ambient occlusion the surface receives.
+    float ambientOcclusion = mix(1.0, texture(aoSampler, texCoord).r, material.a);
 
     vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
     vec3 fragmentToLight = lightPos - fragmentPosition;
@@ -37,13 +50,31 @@ void main()
     ctx.roughness = material.y;
     ctx.viewVector = normalize(cameraPosition - fragmentPosition);
 
-    vec3 lighting = S_PBR_CalculateLight(ctx);
+    vec3 lighting = S_PBR_CalculateSphereLight(ctx, fragmentToLight, lightSourceRadius);
 
     float distanceAttenuation = S_LightDistanceAttenuation(distance, lightRadius);
 
-    float shadow = S_PointShadow(
-        shadowsEnabled, softShadows, distance, shadowBias, ctx.fragmentToLight, pointShadowTexture);
+    // Samplers cannot be selected dynamically, so each cascade has its own branch.
+    float shadow;
+    if (shadowCascadeCount < 2 || distance < shadowCascadeSplits.x)
+    {
+        shadow = S_PointShadow(
+            shadowsEnabled, softShadows, pcfKernelRadius, distance, shadowBias, ctx.fragmentToLight,
+            pointShadowTexture, useStaticShadows, staticShadowTexture);
+    }
+    else if (shadowCascadeCount < 3 || distance < shadowCascadeSplits.y)
+    {
+        shadow = S_PointShadow(
+            shadowsEnabled, softShadows, pcfKernelRadius, distance, shadowBias, ctx.fragmentToLight,
+            pointShadowTexture1, useStaticShadows, staticShadowTexture);
+    }
+    else
+    {
+        shadow = S_PointShadow(
+            shadowsEnabled, softShadows, pcfKernelRadius, distance, shadowBias, ctx.fragmentToLight,
+            pointShadowTexture2, useStaticShadows, staticShadowTexture);
+    }
     float finalShadow = mix(1.0, shadow, shadowAlpha);
 
-    FragColor = vec4(lightIntensity * distanceAttenuation * finalShadow * lighting, diffuseColor.a);
+    FragColor = vec4(lightIntensity * distanceAttenuation * finalShadow * ambientOcclusion * lighting, diffuseColor.a);
 }
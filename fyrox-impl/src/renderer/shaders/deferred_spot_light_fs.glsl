@@ -4,6 +4,7 @@ uniform sampler2D normalTexture;
 uniform sampler2D materialTexture;
 uniform sampler2D spotShadowTexture;
 uniform sampler2D cookieTexture;
+uniform sampler2D aoSampler;
 
 uniform mat4 lightViewProjMatrix;
 uniform vec3 lightPos;
@@ -27,7 +28,9 @@ out vec4 FragColor;
 
 void main()
 {
-    vec3 material = texture(materialTexture, texCoord).rgb;
+    vec4 material = texture(materialTexture, texCoord);
+    // Alpha channel of the material texture defines how much of the ambient occlusion the surface receives.
+    float ambientOcclusion = mix(1.0, texture(aoSampler, texCoord).r, material.a);
 
     vec3 fragmentPosition = S_UnProject(vec3(texCoord, texture(depthTexture, texCoord).r), invViewProj);
     vec3 fragmentToLight = lightPos - fragmentPosition;
@@ -61,5 +64,5 @@ void main()
         cookieAttenuation = texture(cookieTexture, texCoords);
     }
 
-    FragColor = cookieAttenuation * vec4(distanceAttenuation * lightIntensity * coneFactor * finalShadow * lighting, diffuseColor.a);
+    FragColor = cookieAttenuation * vec4(distanceAttenuation * lightIntensity * coneFactor * finalShadow * ambientOcclusion * lighting, diffuseColor.a);
 }
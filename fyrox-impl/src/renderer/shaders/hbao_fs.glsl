@@ -0,0 +1,83 @@
+// Horizon-based ambient occlusion. For every pixel the depth buffer is marched in a few directions
+// around the pixel, every direction tracks the highest horizon (relative to the tangent plane of
+// the pixel) and the occlusion is proportional to the elevation of the horizon.
+
+uniform sampler2D depthSampler;
+uniform sampler2D normalSampler;
+uniform sampler2D noiseSampler;
+
+uniform float radius;
+uniform float power;
+// Sine of the minimal elevation of the horizon, prevents self-occlusion of flat surfaces.
+uniform float bias;
+uniform mat4 inverseProjectionMatrix;
+uniform mat4 projectionMatrix;
+uniform int directionCount;
+uniform int stepCount;
+uniform vec2 noiseScale;
+uniform vec2 noiseOffset;
+uniform mat3 viewMatrix;
+// Amount of frames (1, 2 or 4) that is needed to calculate occlusion of every pixel.
+uniform int interleaveFactor;
+// Index of the pixel subset, that is calculated in the current frame. The subset is rendered at
+// reduced resolution and then scattered to the occlusion map.
+uniform int interleavePhase;
+// Size of the full occlusion map.
+uniform vec2 mapSize;
+
+out float finalOcclusion;
+
+vec3 GetViewSpacePosition(vec2 screenCoord) {
+    return S_UnProject(vec3(screenCoord, texture(depthSampler, screenCoord).r), inverseProjectionMatrix);
+}
+
+vec2 ProjectToScreen(vec3 viewSpacePosition) {
+    vec4 clipSpace = projectionMatrix * vec4(viewSpacePosition, 1.0);
+    return clipSpace.xy / clipSpace.w * 0.5 + 0.5;
+}
+
+void main() {
+    ivec2 pixel = S_InterleavedSubsetToPixel(ivec2(gl_FragCoord.xy), interleaveFactor, interleavePhase);
+    vec2 texCoord = (vec2(pixel) + 0.5) / mapSize;
+
+    vec3 fragPos = GetViewSpacePosition(texCoord);
+    vec3 worldSpaceNormal = texture(normalSampler, texCoord).xyz * 2.0 - 1.0;
+    vec3 viewSpaceNormal = normalize(viewMatrix * worldSpaceNormal);
+    // Random rotation of the directions and random offset of the first step hide banding.
+    vec2 random = texture(noiseSampler, texCoord * noiseScale + noiseOffset).xy;
+
+    // Radius of the sampling area in texture coordinates along both axes.
+    vec2 screenRadius = abs(ProjectToScreen(fragPos + vec3(radius, radius, 0.0)) - texCoord);
+    float radiusSquared = radius * radius;
+    float angleStep = 2.0 * PI / float(directionCount);
+
+    float occlusion = 0.0;
+    for (int i = 0; i < directionCount; ++i) {
+        float angle = angleStep * (float(i) + random.x);
+        vec2 direction = vec2(cos(angle), sin(angle)) * screenRadius;
+
+        float horizonSine = bias;
+        for (int j = 0; j < stepCount; ++j) {
+            vec2 sampleCoord = texCoord + direction * ((float(j) + random.y) / float(stepCount));
+            if (sampleCoord.x < 0.0 || sampleCoord.x > 1.0 || sampleCoord.y < 0.0 || sampleCoord.y > 1.0) {
+                break;
+            }
+
+            vec3 horizonVector = GetViewSpacePosition(sampleCoord) - fragPos;
+            float distanceSquared = dot(horizonVector, horizonVector);
+            if (distanceSquared < 0.000001 || distanceSquared > radiusSquared) {
+                continue;
+            }
+
+            float elevationSine = dot(viewSpaceNormal, horizonVector) * inversesqrt(distanceSquared);
+            if (elevationSine > horizonSine) {
+                // Distant samples occlude less, so there's no hard edge at the radius.
+                float falloff = 1.0 - distanceSquared / radiusSquared;
+                occlusion += (elevationSine - horizonSine) * falloff;
+                horizonSine = elevationSine;
+            }
+        }
+    }
+
+    finalOcclusion = pow(clamp(1.0 - occlusion / float(directionCount), 0.0, 1.0), power);
+}
@@ -0,0 +1,7 @@
+out float FragColor;
+
+void main()
+{
+    // Every fragment increments the counter, the counters are accumulated using additive blending.
+    FragColor = 1.0;
+}
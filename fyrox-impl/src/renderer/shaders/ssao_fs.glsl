@@ -1,36 +1,49 @@
-#define KERNEL_SIZE 32
+// Keep in sync with MAX_KERNEL_SIZE.
+#define MAX_KERNEL_SIZE 64
 
 uniform sampler2D depthSampler;
 uniform sampler2D normalSampler;
 uniform sampler2D noiseSampler;
 
 uniform float radius;
+uniform float power;
+uniform float bias;
 uniform mat4 inverseProjectionMatrix;
 uniform mat4 projectionMatrix;
-uniform vec3 kernel[KERNEL_SIZE];
+uniform vec3 kernel[MAX_KERNEL_SIZE];
+uniform int kernelSize;
 uniform vec2 noiseScale;
+uniform vec2 noiseOffset;
 uniform mat3 viewMatrix;
+// Amount of frames (1, 2 or 4) that is needed to calculate occlusion of every pixel.
+uniform int interleaveFactor;
+// Index of the pixel subset, that is calculated in the current frame. The subset is rendered at
+// reduced resolution and then scattered to the occlusion map.
+uniform int interleavePhase;
+// Size of the full occlusion map.
+uniform vec2 mapSize;
 
 out float finalOcclusion;
 
-in vec2 texCoord;
-
 vec3 GetViewSpacePosition(vec2 screenCoord) {
     return S_UnProject(vec3(screenCoord, texture(depthSampler, screenCoord).r), inverseProjectionMatrix);
 }
 
 void main() {
+    ivec2 pixel = S_InterleavedSubsetToPixel(ivec2(gl_FragCoord.xy), interleaveFactor, interleavePhase);
+    vec2 texCoord = (vec2(pixel) + 0.5) / mapSize;
+
     vec3 fragPos = GetViewSpacePosition(texCoord);
     vec3 worldSpaceNormal = texture(normalSampler, texCoord).xyz * 2.0 - 1.0;
     vec3 viewSpaceNormal = normalize(viewMatrix * worldSpaceNormal);
-    vec3 randomVec = normalize(texture(noiseSampler, texCoord * noiseScale).xyz * 2.0 - 1.0);
+    vec3 randomVec = normalize(texture(noiseSampler, texCoord * noiseScale + noiseOffset).xyz * 2.0 - 1.0);
 
     vec3 tangent = normalize(randomVec - viewSpaceNormal * dot(randomVec, viewSpaceNormal));
     vec3 bitangent = normalize(cross(viewSpaceNormal, tangent));
     mat3 TBN = mat3(tangent, bitangent, viewSpaceNormal);
 
     float occlusion = 0.0;
-    for (int i = 0; i < KERNEL_SIZE; ++i) {
+    for (int i = 0; i < kernelSize; ++i) {
         vec3 samplePoint = fragPos.xyz + TBN * kernel[i] * radius;
 
         vec4 offset = projectionMatrix * vec4(samplePoint, 1.0);
@@ -40,8 +53,8 @@ void main() {
         vec3 position = GetViewSpacePosition(offset.xy);
 
         float rangeCheck = smoothstep(0.0, 1.0, radius / abs(fragPos.z - position.z));
-        occlusion += rangeCheck * ((position.z > samplePoint.z + 0.04) ? 1.0 : 0.0);
+        occlusion += rangeCheck * ((position.z > samplePoint.z + bias) ? 1.0 : 0.0);
     }
 
-    finalOcclusion = 1.0 - occlusion / float(KERNEL_SIZE);
+    finalOcclusion = pow(1.0 - occlusion / float(kernelSize), power);
 }
\ No newline at end of file
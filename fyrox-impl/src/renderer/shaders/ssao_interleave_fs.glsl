@@ -0,0 +1,28 @@
+// Scatters the subset of pixels, that was calculated by interleaved sampling of SSAO at reduced
+// resolution, to the full occlusion map.
+
+uniform sampler2D subsetSampler;
+// Amount of pixel subsets (2 or 4).
+uniform int interleaveFactor;
+// Index of the subset, that is calculated in the current frame.
+uniform int interleavePhase;
+// Skipped pixels keep the occlusion calculated on previous frames.
+uniform bool preserveSkipped;
+
+out float finalOcclusion;
+
+void main() {
+    ivec2 pixel = ivec2(gl_FragCoord.xy);
+    int subset = interleaveFactor == 2 ? (pixel.x + pixel.y) % 2 : (pixel.x % 2) + 2 * (pixel.y % 2);
+    if (subset != interleavePhase) {
+        if (preserveSkipped) {
+            discard;
+        }
+        // Negative value marks pixels without occlusion, they're reconstructed later.
+        finalOcclusion = -1.0;
+        return;
+    }
+
+    ivec2 subsetPixel = interleaveFactor == 2 ? ivec2(pixel.x / 2, pixel.y) : pixel / 2;
+    finalOcclusion = texelFetch(subsetSampler, subsetPixel, 0).r;
+}
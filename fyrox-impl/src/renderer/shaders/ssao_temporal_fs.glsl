@@ -0,0 +1,64 @@
+// Temporal accumulation of raw SSAO map. Reprojects every pixel to the previous frame and blends
+// current occlusion with accumulated history.
+
+uniform sampler2D currentSampler;
+uniform sampler2D historySampler;
+uniform sampler2D depthSampler;
+uniform mat4 inverseViewProjection;
+uniform mat4 previousViewProjection;
+uniform float blendFactor;
+uniform bool historyValid;
+
+out float FragColor;
+
+in vec2 texCoord;
+
+void main()
+{
+    // Current occlusion is negative for pixels that were skipped by interleaved sampling.
+    float current = texture(currentSampler, texCoord).r;
+
+    if (!historyValid) {
+        FragColor = current;
+        return;
+    }
+
+    vec3 worldPosition = S_UnProject(vec3(texCoord, texture(depthSampler, texCoord).r), inverseViewProjection);
+    vec2 previousTexCoord = S_Project(worldPosition, previousViewProjection).xy;
+
+    // Pixel was off-screen on previous frame, there's no history for it.
+    if (previousTexCoord.x < 0.0 || previousTexCoord.x > 1.0 || previousTexCoord.y < 0.0 || previousTexCoord.y > 1.0) {
+        FragColor = current;
+        return;
+    }
+
+    // Clamp history by the neighbourhood of the current pixel to reduce ghosting on disocclusions.
+    vec2 texelSize = 1.0 / vec2(textureSize(currentSampler, 0));
+    float minOcclusion = 1.0;
+    float maxOcclusion = 0.0;
+    for (int y = -1; y <= 1; ++y)
+    {
+        for (int x = -1; x <= 1; ++x)
+        {
+            float neighbour = texture(currentSampler, texCoord + vec2(float(x), float(y)) * texelSize).r;
+            if (neighbour >= 0.0) {
+                minOcclusion = min(minOcclusion, neighbour);
+                maxOcclusion = max(maxOcclusion, neighbour);
+            }
+        }
+    }
+
+    float history = texture(historySampler, previousTexCoord).r;
+
+    if (history < 0.0) {
+        FragColor = current;
+        return;
+    }
+
+    if (minOcclusion <= maxOcclusion) {
+        history = clamp(history, minOcclusion, maxOcclusion);
+    }
+
+    // The pixel was skipped by interleaved sampling in this frame, use the history only.
+    FragColor = current < 0.0 ? history : mix(history, current, blendFactor);
+}
@@ -5,6 +5,7 @@ uniform sampler2D diffuseTexture;
 uniform bool isFont;
 uniform vec4 solidColor;
 uniform float opacity;
+uniform vec4 globalTint;
 
 uniform int brushType;
 
@@ -86,4 +87,6 @@ void main()
     fragColor.a *= opacity;
 
     fragColor *= color;
+
+    fragColor *= globalTint;
 }
\ No newline at end of file
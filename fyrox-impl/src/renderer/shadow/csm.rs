@@ -257,6 +257,7 @@ impl CsmRenderer {
                     z_far,
                     view_matrix: light_view_matrix,
                     projection_matrix: cascade_projection_matrix,
+                    viewport,
                 },
                 DIRECTIONAL_SHADOW_PASS_NAME.clone(),
             );
@@ -308,6 +309,7 @@ impl CsmRenderer {
                         |mut program_binding| {
                             apply_material(MaterialContext {
                                 material,
+                                material_overrides: &bundle.material_overrides,
                                 program_binding: &mut program_binding,
                                 texture_cache,
                                 matrix_storage,
@@ -316,6 +318,7 @@ impl CsmRenderer {
                                 wvp_matrix: &(light_view_projection * instance.world_transform),
                                 bone_matrices: &instance.bone_matrices,
                                 use_skeletal_animation: bundle.is_skinned,
+                                use_instancing: false,
                                 camera_position: &camera.global_position(),
                                 camera_up_vector: &camera_up,
                                 camera_side_vector: &camera_side,
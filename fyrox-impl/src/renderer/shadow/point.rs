@@ -2,9 +2,11 @@ use crate::{
     core::{
         algebra::{Matrix4, Point3, Vector3},
         color::Color,
-        math::Rect,
+        math::{Matrix4Ext, Rect},
+        pool::Handle,
         scope_profile,
     },
+    graph::{BaseSceneGraph, SceneGraph},
     renderer::{
         apply_material,
         bundle::{ObserverInfo, RenderDataBundleStorage},
@@ -23,15 +25,135 @@ use crate::{
         GeometryCache, MaterialContext, RenderPassStatistics, ShadowMapPrecision,
         POINT_SHADOW_PASS_NAME,
     },
-    scene::graph::Graph,
+    scene::{base::Mobility, graph::Graph, light::point::PointLight, node::Node},
 };
-use fyrox_core::math::Matrix4Ext;
-use std::{cell::RefCell, rc::Rc};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc};
+
+fn make_cube_map_framebuffer(
+    state: &PipelineState,
+    size: usize,
+    precision: ShadowMapPrecision,
+) -> Result<FrameBuffer, FrameworkError> {
+    let depth = {
+        let kind = GpuTextureKind::Rectangle {
+            width: size,
+            height: size,
+        };
+        let mut texture = GpuTexture::new(
+            state,
+            kind,
+            match precision {
+                ShadowMapPrecision::Full => PixelKind::D32F,
+                ShadowMapPrecision::Half => PixelKind::D16,
+            },
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+        texture
+            .bind_mut(state, 0)
+            .set_minification_filter(MinificationFilter::Nearest)
+            .set_magnification_filter(MagnificationFilter::Nearest)
+            .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+        texture
+    };
+
+    let cube_map = {
+        let kind = GpuTextureKind::Cube {
+            width: size,
+            height: size,
+        };
+        let mut texture = GpuTexture::new(
+            state,
+            kind,
+            PixelKind::R16F,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+        texture
+            .bind_mut(state, 0)
+            .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::T, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::R, WrapMode::ClampToEdge);
+        texture
+    };
+
+    FrameBuffer::new(
+        state,
+        Some(Attachment {
+            kind: AttachmentKind::Depth,
+            texture: Rc::new(RefCell::new(depth)),
+        }),
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: Rc::new(RefCell::new(cube_map)),
+        }],
+    )
+}
+
+fn make_cascades(
+    state: &PipelineState,
+    size: usize,
+    precision: ShadowMapPrecision,
+) -> Result<[FrameBuffer; 3], FrameworkError> {
+    Ok([
+        make_cube_map_framebuffer(state, cascade_size(size, 0), precision)?,
+        make_cube_map_framebuffer(state, cascade_size(size, 1), precision)?,
+        make_cube_map_framebuffer(state, cascade_size(size, 2), precision)?,
+    ])
+}
+
+/// Amount of light rendering passes, after which unused custom-sized or distance-based cascades are
+/// destroyed.
+const CASCADE_TIME_TO_LIVE: u64 = 120;
+
+// Cube maps have the same angular resolution at any distance from the light, so the closer
+// cascades have higher resolution instead: each previous cascade has two times more resolution
+// than the next one. The farthest cascade always has the size of the regular shadow map.
+fn distance_cascade_size(
+    size: usize,
+    distance_cascade: usize,
+    distance_cascade_count: usize,
+    max_size: usize,
+) -> usize {
+    let scale = distance_cascade_count.saturating_sub(distance_cascade + 1);
+    size.checked_shl(scale as u32)
+        .unwrap_or(usize::MAX)
+        .min(max_size.max(size))
+        .max(1)
+}
+
+struct DistanceCascade {
+    framebuffer: FrameBuffer,
+    last_used: u64,
+}
+
+struct CustomCascades {
+    framebuffers: [FrameBuffer; 3],
+    last_used: u64,
+}
 
 pub struct PointShadowMapRenderer {
     precision: ShadowMapPrecision,
     cascades: [FrameBuffer; 3],
     size: usize,
+    /// Cascades for the lights, that override the base size of the shadow map (see
+    /// [`PointLight::set_shadow_map_size`]), grouped by their size. The cascades are created on
+    /// demand and destroyed when they weren't used for [`CASCADE_TIME_TO_LIVE`] light rendering
+    /// passes.
+    custom_cascades: FxHashMap<usize, CustomCascades>,
+    /// Shadow maps of the distance-based cascades of lights (see
+    /// [`PointLight::set_shadow_cascade_splits`]), except the farthest one, which is stored in
+    /// `cascades`. Grouped by the index of the distance cascade and the size. The maps are created
+    /// on demand and destroyed when they weren't used for [`CASCADE_TIME_TO_LIVE`] light rendering
+    /// passes.
+    distance_cascades: FxHashMap<(usize, usize), DistanceCascade>,
+    pass_index: u64,
     faces: [PointShadowCubeMapFace; 6],
 }
 
@@ -55,6 +177,88 @@ pub(crate) struct PointShadowMapRenderContext<'a> {
     pub black_dummy: Rc<RefCell<GpuTexture>>,
     pub volume_dummy: Rc<RefCell<GpuTexture>>,
     pub matrix_storage: &'a mut MatrixStorageCache,
+    pub caster_filter: ShadowCasterFilter,
+    /// Per-light size of the shadow map, `None` means that the base size of the renderer is used.
+    pub shadow_map_size: Option<usize>,
+    /// Index of the distance-based cascade of the light. `light_radius` must be the far distance
+    /// of the cascade.
+    pub distance_cascade: usize,
+    /// Total amount of the distance-based cascades of the light.
+    pub distance_cascade_count: usize,
+}
+
+/// Defines which shadow casters are rendered into a point shadow map.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum ShadowCasterFilter {
+    /// Every shadow caster.
+    All,
+    /// Only the shadow casters with [`Mobility::Static`] mobility.
+    Static,
+    /// Every shadow caster, except the ones with [`Mobility::Static`] mobility.
+    Dynamic,
+}
+
+impl ShadowCasterFilter {
+    fn accepts(self, graph: &Graph, node: Handle<Node>) -> bool {
+        let is_static = || {
+            graph
+                .try_get(node)
+                .is_some_and(|node| node.mobility() == Mobility::Static)
+        };
+
+        match self {
+            ShadowCasterFilter::All => true,
+            ShadowCasterFilter::Static => is_static(),
+            ShadowCasterFilter::Dynamic => !is_static(),
+        }
+    }
+}
+
+struct StaticPointShadowMap {
+    framebuffer: FrameBuffer,
+    light_position: Vector3<f32>,
+    light_radius: f32,
+    size: usize,
+    precision: ShadowMapPrecision,
+}
+
+/// A set of cached shadow maps of static objects for point lights, that have static shadows
+/// caching enabled (see [`crate::scene::light::point::PointLight::set_cache_static_shadows`]).
+/// A shadow map is re-rendered automatically, when its light is moved or its radius is changed.
+#[derive(Default)]
+pub struct PointShadowCache {
+    maps: FxHashMap<Handle<Node>, StaticPointShadowMap>,
+}
+
+impl PointShadowCache {
+    /// Returns a cube map with distances from the given light to the nearest static objects, if
+    /// it was rendered.
+    pub fn texture(&self, light: Handle<Node>) -> Option<Rc<RefCell<GpuTexture>>> {
+        self.maps
+            .get(&light)
+            .map(|map| map.framebuffer.color_attachments()[0].texture.clone())
+    }
+
+    /// Removes the cached shadow map of the given light, it will be re-rendered on the next frame.
+    /// Use it when static objects around the light were changed.
+    pub fn invalidate(&mut self, light: Handle<Node>) {
+        self.maps.remove(&light);
+    }
+
+    /// Removes every cached shadow map.
+    pub fn clear(&mut self) {
+        self.maps.clear();
+    }
+
+    /// Removes cached shadow maps of the lights, that were deleted or have static shadows caching
+    /// disabled.
+    pub(crate) fn remove_unused(&mut self, graph: &Graph) {
+        self.maps.retain(|light, _| {
+            graph
+                .try_get_of_type::<PointLight>(*light)
+                .is_some_and(|light| light.is_caching_static_shadows())
+        });
+    }
 }
 
 impl PointShadowMapRenderer {
@@ -63,80 +267,13 @@ impl PointShadowMapRenderer {
         size: usize,
         precision: ShadowMapPrecision,
     ) -> Result<Self, FrameworkError> {
-        fn make_cascade(
-            state: &PipelineState,
-            size: usize,
-            precision: ShadowMapPrecision,
-        ) -> Result<FrameBuffer, FrameworkError> {
-            let depth = {
-                let kind = GpuTextureKind::Rectangle {
-                    width: size,
-                    height: size,
-                };
-                let mut texture = GpuTexture::new(
-                    state,
-                    kind,
-                    match precision {
-                        ShadowMapPrecision::Full => PixelKind::D32F,
-                        ShadowMapPrecision::Half => PixelKind::D16,
-                    },
-                    MinificationFilter::Nearest,
-                    MagnificationFilter::Nearest,
-                    1,
-                    None,
-                )?;
-                texture
-                    .bind_mut(state, 0)
-                    .set_minification_filter(MinificationFilter::Nearest)
-                    .set_magnification_filter(MagnificationFilter::Nearest)
-                    .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
-                    .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
-                texture
-            };
-
-            let cube_map = {
-                let kind = GpuTextureKind::Cube {
-                    width: size,
-                    height: size,
-                };
-                let mut texture = GpuTexture::new(
-                    state,
-                    kind,
-                    PixelKind::R16F,
-                    MinificationFilter::Nearest,
-                    MagnificationFilter::Nearest,
-                    1,
-                    None,
-                )?;
-                texture
-                    .bind_mut(state, 0)
-                    .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
-                    .set_wrap(Coordinate::T, WrapMode::ClampToEdge)
-                    .set_wrap(Coordinate::R, WrapMode::ClampToEdge);
-                texture
-            };
-
-            FrameBuffer::new(
-                state,
-                Some(Attachment {
-                    kind: AttachmentKind::Depth,
-                    texture: Rc::new(RefCell::new(depth)),
-                }),
-                vec![Attachment {
-                    kind: AttachmentKind::Color,
-                    texture: Rc::new(RefCell::new(cube_map)),
-                }],
-            )
-        }
-
         Ok(Self {
             precision,
-            cascades: [
-                make_cascade(state, cascade_size(size, 0), precision)?,
-                make_cascade(state, cascade_size(size, 1), precision)?,
-                make_cascade(state, cascade_size(size, 2), precision)?,
-            ],
+            cascades: make_cascades(state, size, precision)?,
             size,
+            custom_cascades: Default::default(),
+            distance_cascades: Default::default(),
+            pass_index: 0,
             faces: [
                 PointShadowCubeMapFace {
                     face: CubeMapFace::PositiveX,
@@ -180,147 +317,331 @@ impl PointShadowMapRenderer {
         self.precision
     }
 
-    pub fn cascade_texture(&self, cascade: usize) -> Rc<RefCell<GpuTexture>> {
-        self.cascades[cascade].color_attachments()[0]
-            .texture
-            .clone()
+    /// Returns the shadow map size, that will be used for a light with the given size override.
+    /// The size is limited by the maximum size of cube maps, that is supported by the GPU.
+    pub fn effective_size(&self, state: &PipelineState, shadow_map_size: Option<usize>) -> usize {
+        shadow_map_size
+            .map_or(self.size, |size| size.max(1))
+            .min(state.capabilities().max_cube_map_texture_size.max(1))
+    }
+
+    /// Returns the texture of the given cascade for a light with the given size override. If the
+    /// cascades of the requested size weren't created yet, the texture of the cascade with the base
+    /// size will be returned.
+    pub fn cascade_texture(
+        &self,
+        state: &PipelineState,
+        cascade: usize,
+        shadow_map_size: Option<usize>,
+    ) -> Rc<RefCell<GpuTexture>> {
+        let size = self.effective_size(state, shadow_map_size);
+        let cascades = if size == self.size {
+            &self.cascades
+        } else {
+            self.custom_cascades
+                .get(&size)
+                .map_or(&self.cascades, |custom| &custom.framebuffers)
+        };
+        cascades[cascade].color_attachments()[0].texture.clone()
+    }
+
+    /// Returns the size of the given distance-based cascade (see
+    /// [`PointLight::set_shadow_cascade_splits`]) for a light with the given size override.
+    pub fn distance_cascade_size(
+        &self,
+        state: &PipelineState,
+        cascade: usize,
+        distance_cascade: usize,
+        distance_cascade_count: usize,
+        shadow_map_size: Option<usize>,
+    ) -> usize {
+        distance_cascade_size(
+            cascade_size(self.effective_size(state, shadow_map_size), cascade),
+            distance_cascade,
+            distance_cascade_count,
+            state.capabilities().max_cube_map_texture_size,
+        )
+    }
+
+    /// Returns the texture of the given distance-based cascade (see
+    /// [`PointLight::set_shadow_cascade_splits`]) for a light with the given size override. If the
+    /// texture wasn't created yet, the texture of the farthest distance cascade will be returned.
+    pub fn distance_cascade_texture(
+        &self,
+        state: &PipelineState,
+        cascade: usize,
+        distance_cascade: usize,
+        distance_cascade_count: usize,
+        shadow_map_size: Option<usize>,
+    ) -> Rc<RefCell<GpuTexture>> {
+        if distance_cascade + 1 < distance_cascade_count {
+            let size = self.distance_cascade_size(
+                state,
+                cascade,
+                distance_cascade,
+                distance_cascade_count,
+                shadow_map_size,
+            );
+            if let Some(cached) = self.distance_cascades.get(&(distance_cascade, size)) {
+                return cached.framebuffer.color_attachments()[0].texture.clone();
+            }
+        }
+        self.cascade_texture(state, cascade, shadow_map_size)
+    }
+
+    /// Destroys the custom-sized and distance-based cascades, that weren't used for a while. Must
+    /// be called once per light rendering pass.
+    pub(crate) fn remove_unused_cascades(&mut self) {
+        self.pass_index += 1;
+        let pass_index = self.pass_index;
+        self.custom_cascades
+            .retain(|_, custom| pass_index - custom.last_used <= CASCADE_TIME_TO_LIVE);
+        self.distance_cascades.retain(|_, distance_cascade| {
+            pass_index - distance_cascade.last_used <= CASCADE_TIME_TO_LIVE
+        });
     }
 
     pub(crate) fn render(
         &mut self,
         args: PointShadowMapRenderContext,
     ) -> Result<RenderPassStatistics, FrameworkError> {
-        scope_profile!();
+        let cascade = args.cascade;
+        let size = self.effective_size(args.state, args.shadow_map_size);
+
+        if args.distance_cascade + 1 < args.distance_cascade_count {
+            let size = self.distance_cascade_size(
+                args.state,
+                cascade,
+                args.distance_cascade,
+                args.distance_cascade_count,
+                args.shadow_map_size,
+            );
+            let key = (args.distance_cascade, size);
+            let distance_cascade = match self.distance_cascades.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(DistanceCascade {
+                    framebuffer: make_cube_map_framebuffer(args.state, size, self.precision)?,
+                    last_used: self.pass_index,
+                }),
+            };
+            distance_cascade.last_used = self.pass_index;
+            return render_cube_map(&self.faces, &mut distance_cascade.framebuffer, size, args);
+        }
+
+        let cascades = if size == self.size {
+            &mut self.cascades
+        } else {
+            let custom = match self.custom_cascades.entry(size) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(CustomCascades {
+                    framebuffers: make_cascades(args.state, size, self.precision)?,
+                    last_used: self.pass_index,
+                }),
+            };
+            custom.last_used = self.pass_index;
+            &mut custom.framebuffers
+        };
+        render_cube_map(
+            &self.faces,
+            &mut cascades[cascade],
+            cascade_size(size, cascade),
+            args,
+        )
+    }
+
+    /// Renders static shadow casters of the given light into its cached shadow map, if the map is
+    /// missing or outdated. Static shadow maps always have the size of the first cascade, the
+    /// cascade of the given context is ignored.
+    pub(crate) fn render_static(
+        &mut self,
+        cache: &mut PointShadowCache,
+        light: Handle<Node>,
+        args: PointShadowMapRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        let size = self.effective_size(args.state, args.shadow_map_size);
+
+        if let Some(map) = cache.maps.get(&light) {
+            if map.light_position == args.light_pos
+                && map.light_radius == args.light_radius
+                && map.size == size
+                && map.precision == self.precision
+            {
+                return Ok(Default::default());
+            }
+        }
+
+        let map = match cache.maps.entry(light) {
+            Entry::Occupied(entry) => {
+                let map = entry.into_mut();
+                if map.size != size || map.precision != self.precision {
+                    map.framebuffer = make_cube_map_framebuffer(args.state, size, self.precision)?;
+                    map.size = size;
+                    map.precision = self.precision;
+                }
+                map
+            }
+            Entry::Vacant(entry) => entry.insert(StaticPointShadowMap {
+                framebuffer: make_cube_map_framebuffer(args.state, size, self.precision)?,
+                light_position: args.light_pos,
+                light_radius: args.light_radius,
+                size,
+                precision: self.precision,
+            }),
+        };
+        map.light_position = args.light_pos;
+        map.light_radius = args.light_radius;
 
-        let mut statistics = RenderPassStatistics::default();
+        render_cube_map(
+            &self.faces,
+            &mut map.framebuffer,
+            size,
+            PointShadowMapRenderContext {
+                caster_filter: ShadowCasterFilter::Static,
+                ..args
+            },
+        )
+    }
+}
+
+fn render_cube_map(
+    faces: &[PointShadowCubeMapFace; 6],
+    framebuffer: &mut FrameBuffer,
+    size: usize,
+    args: PointShadowMapRenderContext,
+) -> Result<RenderPassStatistics, FrameworkError> {
+    scope_profile!();
+
+    let mut statistics = RenderPassStatistics::default();
+
+    let PointShadowMapRenderContext {
+        state,
+        graph,
+        light_pos,
+        light_radius,
+        geom_cache,
+        shader_cache,
+        texture_cache,
+        normal_dummy,
+        white_dummy,
+        black_dummy,
+        volume_dummy,
+        matrix_storage,
+        caster_filter,
+        ..
+    } = args;
 
-        let PointShadowMapRenderContext {
+    let viewport = Rect::new(0, 0, size as i32, size as i32);
+
+    let z_near = 0.01;
+    let z_far = light_radius;
+    let light_projection_matrix =
+        Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, z_near, z_far);
+
+    for face in faces.iter() {
+        framebuffer.set_cubemap_face(state, 0, face.face).clear(
             state,
+            viewport,
+            Some(Color::WHITE),
+            Some(1.0),
+            None,
+        );
+
+        let light_look_at = light_pos + face.look;
+        let light_view_matrix = Matrix4::look_at_rh(
+            &Point3::from(light_pos),
+            &Point3::from(light_look_at),
+            &face.up,
+        );
+        let light_view_projection_matrix = light_projection_matrix * light_view_matrix;
+
+        let inv_view = light_view_matrix.try_inverse().unwrap();
+        let camera_up = inv_view.up();
+        let camera_side = inv_view.side();
+
+        let bundle_storage = RenderDataBundleStorage::from_graph(
             graph,
-            light_pos,
-            light_radius,
-            geom_cache,
-            cascade,
-            shader_cache,
-            texture_cache,
-            normal_dummy,
-            white_dummy,
-            black_dummy,
-            volume_dummy,
-            matrix_storage,
-        } = args;
-
-        let framebuffer = &mut self.cascades[cascade];
-        let cascade_size = cascade_size(self.size, cascade);
-
-        let viewport = Rect::new(0, 0, cascade_size as i32, cascade_size as i32);
-
-        let z_near = 0.01;
-        let z_far = light_radius;
-        let light_projection_matrix =
-            Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, z_near, z_far);
-
-        for face in self.faces.iter() {
-            framebuffer.set_cubemap_face(state, 0, face.face).clear(
-                state,
+            ObserverInfo {
+                observer_position: light_pos,
+                z_near,
+                z_far,
+                view_matrix: light_view_matrix,
+                projection_matrix: light_projection_matrix,
                 viewport,
-                Some(Color::WHITE),
-                Some(1.0),
-                None,
-            );
+            },
+            POINT_SHADOW_PASS_NAME.clone(),
+        );
 
-            let light_look_at = light_pos + face.look;
-            let light_view_matrix = Matrix4::look_at_rh(
-                &Point3::from(light_pos),
-                &Point3::from(light_look_at),
-                &face.up,
-            );
-            let light_view_projection_matrix = light_projection_matrix * light_view_matrix;
-
-            let inv_view = light_view_matrix.try_inverse().unwrap();
-            let camera_up = inv_view.up();
-            let camera_side = inv_view.side();
-
-            let bundle_storage = RenderDataBundleStorage::from_graph(
-                graph,
-                ObserverInfo {
-                    observer_position: light_pos,
-                    z_near,
-                    z_far,
-                    view_matrix: light_view_matrix,
-                    projection_matrix: light_projection_matrix,
-                },
-                POINT_SHADOW_PASS_NAME.clone(),
-            );
+        for bundle in bundle_storage.bundles.iter() {
+            let mut material_state = bundle.material.state();
+            let Some(material) = material_state.data() else {
+                continue;
+            };
+            let Some(geometry) = geom_cache.get(state, &bundle.data, bundle.time_to_live) else {
+                continue;
+            };
 
-            for bundle in bundle_storage.bundles.iter() {
-                let mut material_state = bundle.material.state();
-                let Some(material) = material_state.data() else {
-                    continue;
-                };
-                let Some(geometry) = geom_cache.get(state, &bundle.data, bundle.time_to_live)
-                else {
-                    continue;
-                };
-
-                let blend_shapes_storage = bundle
-                    .data
-                    .data_ref()
-                    .blend_shapes_container
-                    .as_ref()
-                    .and_then(|c| c.blend_shape_storage.clone());
-
-                let Some(render_pass) = shader_cache
-                    .get(state, material.shader())
-                    .and_then(|shader_set| shader_set.render_passes.get(&POINT_SHADOW_PASS_NAME))
-                else {
-                    continue;
-                };
-
-                for instance in bundle.instances.iter() {
-                    statistics += framebuffer.draw(
-                        geometry,
-                        state,
-                        viewport,
-                        &render_pass.program,
-                        &render_pass.draw_params,
-                        instance.element_range,
-                        |mut program_binding| {
-                            apply_material(MaterialContext {
-                                material,
-                                program_binding: &mut program_binding,
-                                texture_cache,
-                                matrix_storage,
-                                world_matrix: &instance.world_transform,
-                                view_projection_matrix: &light_view_projection_matrix,
-                                wvp_matrix: &(light_view_projection_matrix
-                                    * instance.world_transform),
-                                bone_matrices: &instance.bone_matrices,
-                                use_skeletal_animation: bundle.is_skinned,
-                                camera_position: &Default::default(),
-                                camera_up_vector: &camera_up,
-                                camera_side_vector: &camera_side,
-                                z_near,
-                                use_pom: false,
-                                light_position: &light_pos,
-                                blend_shapes_storage: blend_shapes_storage.as_ref(),
-                                blend_shapes_weights: &instance.blend_shapes_weights,
-                                normal_dummy: &normal_dummy,
-                                white_dummy: &white_dummy,
-                                black_dummy: &black_dummy,
-                                volume_dummy: &volume_dummy,
-                                persistent_identifier: instance.persistent_identifier,
-                                light_data: None,            // TODO
-                                ambient_light: Color::WHITE, // TODO
-                                scene_depth: None,
-                                z_far,
-                            });
-                        },
-                    )?;
-                }
+            let blend_shapes_storage = bundle
+                .data
+                .data_ref()
+                .blend_shapes_container
+                .as_ref()
+                .and_then(|c| c.blend_shape_storage.clone());
+
+            let Some(render_pass) = shader_cache
+                .get(state, material.shader())
+                .and_then(|shader_set| shader_set.render_passes.get(&POINT_SHADOW_PASS_NAME))
+            else {
+                continue;
+            };
+
+            for instance in bundle
+                .instances
+                .iter()
+                .filter(|instance| caster_filter.accepts(graph, instance.node_handle))
+            {
+                statistics += framebuffer.draw(
+                    geometry,
+                    state,
+                    viewport,
+                    &render_pass.program,
+                    &render_pass.draw_params,
+                    instance.element_range,
+                    |mut program_binding| {
+                        apply_material(MaterialContext {
+                            material,
+                            material_overrides: &bundle.material_overrides,
+                            program_binding: &mut program_binding,
+                            texture_cache,
+                            matrix_storage,
+                            world_matrix: &instance.world_transform,
+                            view_projection_matrix: &light_view_projection_matrix,
+                            wvp_matrix: &(light_view_projection_matrix * instance.world_transform),
+                            bone_matrices: &instance.bone_matrices,
+                            use_skeletal_animation: bundle.is_skinned,
+                            use_instancing: false,
+                            camera_position: &Default::default(),
+                            camera_up_vector: &camera_up,
+                            camera_side_vector: &camera_side,
+                            z_near,
+                            use_pom: false,
+                            light_position: &light_pos,
+                            blend_shapes_storage: blend_shapes_storage.as_ref(),
+                            blend_shapes_weights: &instance.blend_shapes_weights,
+                            normal_dummy: &normal_dummy,
+                            white_dummy: &white_dummy,
+                            black_dummy: &black_dummy,
+                            volume_dummy: &volume_dummy,
+                            persistent_identifier: instance.persistent_identifier,
+                            light_data: None,            // TODO
+                            ambient_light: Color::WHITE, // TODO
+                            scene_depth: None,
+                            z_far,
+                        });
+                    },
+                )?;
             }
         }
-
-        Ok(statistics)
     }
+
+    Ok(statistics)
 }
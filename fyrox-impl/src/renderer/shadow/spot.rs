@@ -155,6 +155,7 @@ impl SpotShadowMapRenderer {
                 z_far,
                 view_matrix: light_view_matrix,
                 projection_matrix: light_projection_matrix,
+                viewport,
             },
             SPOT_SHADOW_PASS_NAME.clone(),
         );
@@ -206,6 +207,7 @@ impl SpotShadowMapRenderer {
                     |mut program_binding| {
                         apply_material(MaterialContext {
                             material,
+                            material_overrides: &bundle.material_overrides,
                             program_binding: &mut program_binding,
                             texture_cache,
                             matrix_storage,
@@ -214,6 +216,7 @@ impl SpotShadowMapRenderer {
                             wvp_matrix: &(light_view_projection * instance.world_transform),
                             bone_matrices: &instance.bone_matrices,
                             use_skeletal_animation: bundle.is_skinned,
+                            use_instancing: false,
                             camera_position: &Default::default(),
                             camera_up_vector: &camera_up,
                             camera_side_vector: &camera_side,
@@ -1,6 +1,11 @@
 use crate::renderer::framework::geometry_buffer::{DrawCallStatistics, ElementRange};
 use crate::{
-    core::{math::Rect, scope_profile, sstorage::ImmutableString},
+    core::{
+        algebra::{Matrix4, Vector2},
+        math::Rect,
+        scope_profile,
+        sstorage::ImmutableString,
+    },
     renderer::{
         framework::{
             error::FrameworkError,
@@ -23,6 +28,9 @@ struct Shader {
     program: GpuProgram,
     world_view_projection_matrix: UniformLocation,
     input_texture: UniformLocation,
+    depth_sampler: UniformLocation,
+    inverse_projection_matrix: UniformLocation,
+    depth_threshold: UniformLocation,
 }
 
 impl Shader {
@@ -36,6 +44,12 @@ impl Shader {
                 .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
             input_texture: program
                 .uniform_location(state, &ImmutableString::new("inputTexture"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthSampler"))?,
+            inverse_projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("inverseProjectionMatrix"))?,
+            depth_threshold: program
+                .uniform_location(state, &ImmutableString::new("depthThreshold"))?,
             program,
         })
     }
@@ -50,13 +64,18 @@ pub struct Blur {
 }
 
 impl Blur {
-    pub fn new(state: &PipelineState, width: usize, height: usize) -> Result<Self, FrameworkError> {
+    pub fn new(
+        state: &PipelineState,
+        width: usize,
+        height: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<Self, FrameworkError> {
         let frame = {
             let kind = GpuTextureKind::Rectangle { width, height };
             let mut texture = GpuTexture::new(
                 state,
                 kind,
-                PixelKind::R32F,
+                pixel_kind,
                 MinificationFilter::Nearest,
                 MagnificationFilter::Nearest,
                 1,
@@ -93,10 +112,22 @@ impl Blur {
         self.framebuffer.color_attachments()[0].texture.clone()
     }
 
+    /// Returns width and height of the result texture.
+    pub fn resolution(&self) -> Vector2<i32> {
+        Vector2::new(self.width as i32, self.height as i32)
+    }
+
+    /// Blurs the input texture. Negative values of the input are treated as gaps, that are filled
+    /// using valid neighbours. The neighbours are weighted by their view space depth difference,
+    /// which preserves edges. The neighbours with the difference larger than `depth_threshold` (in
+    /// world units) are ignored.
     pub(crate) fn render(
         &mut self,
         state: &PipelineState,
         input: Rc<RefCell<GpuTexture>>,
+        depth: Rc<RefCell<GpuTexture>>,
+        inverse_projection: Matrix4<f32>,
+        depth_threshold: f32,
     ) -> Result<DrawCallStatistics, FrameworkError> {
         scope_profile!();
 
@@ -124,7 +155,10 @@ impl Blur {
                         &shader.world_view_projection_matrix,
                         &(make_viewport_matrix(viewport)),
                     )
-                    .set_texture(&shader.input_texture, &input);
+                    .set_texture(&shader.input_texture, &input)
+                    .set_texture(&shader.depth_sampler, &depth)
+                    .set_matrix4(&shader.inverse_projection_matrix, &inverse_projection)
+                    .set_f32(&shader.depth_threshold, depth_threshold);
             },
         )
     }
@@ -0,0 +1,190 @@
+use crate::renderer::framework::geometry_buffer::{DrawCallStatistics, ElementRange};
+use crate::{
+    core::{math::Rect, scope_profile, sstorage::ImmutableString},
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+                PixelKind, WrapMode,
+            },
+            state::PipelineState,
+        },
+        make_viewport_matrix,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub struct InterleaveShader {
+    program: GpuProgram,
+    world_view_projection_matrix: UniformLocation,
+    subset_sampler: UniformLocation,
+    interleave_factor: UniformLocation,
+    interleave_phase: UniformLocation,
+    preserve_skipped: UniformLocation,
+}
+
+impl InterleaveShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/ssao_interleave_fs.glsl");
+        let vertex_source = include_str!("../shaders/blur_vs.glsl");
+
+        let program = GpuProgram::from_source(
+            state,
+            "SsaoInterleaveShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            world_view_projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            subset_sampler: program
+                .uniform_location(state, &ImmutableString::new("subsetSampler"))?,
+            interleave_factor: program
+                .uniform_location(state, &ImmutableString::new("interleaveFactor"))?,
+            interleave_phase: program
+                .uniform_location(state, &ImmutableString::new("interleavePhase"))?,
+            preserve_skipped: program
+                .uniform_location(state, &ImmutableString::new("preserveSkipped"))?,
+            program,
+        })
+    }
+}
+
+/// Returns the size of a pixel subset of interleaved sampling for the map with the given size.
+/// The subsets of the factor `2` contain every second pixel of each row (checkerboard pattern),
+/// the subsets of the factor `4` contain one pixel of every 2x2 block.
+pub fn subset_size(width: usize, height: usize, factor: usize) -> (usize, usize) {
+    match factor {
+        2 => ((width + 1) / 2, height),
+        4 => ((width + 1) / 2, (height + 1) / 2),
+        _ => (width, height),
+    }
+}
+
+/// A reduced-resolution render target for a single pixel subset of interleaved sampling of SSAO.
+/// Occlusion is calculated only for the pixels of the subset, so the cost of the occlusion pass
+/// is reduced proportionally to the interleave factor. The subset is then scattered to the full
+/// occlusion map by a cheap pass, that only copies pixels.
+pub struct InterleavedSubset {
+    shader: Rc<InterleaveShader>,
+    framebuffer: FrameBuffer,
+    quad: GeometryBuffer,
+    factor: usize,
+    width: usize,
+    height: usize,
+}
+
+impl InterleavedSubset {
+    pub fn new(
+        state: &PipelineState,
+        shader: Rc<InterleaveShader>,
+        map_width: usize,
+        map_height: usize,
+        factor: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<Self, FrameworkError> {
+        let (width, height) = subset_size(map_width, map_height, factor);
+
+        let frame = {
+            let kind = GpuTextureKind::Rectangle { width, height };
+            let mut texture = GpuTexture::new(
+                state,
+                kind,
+                pixel_kind,
+                MinificationFilter::Nearest,
+                MagnificationFilter::Nearest,
+                1,
+                None,
+            )?;
+            texture
+                .bind_mut(state, 0)
+                .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+                .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+            texture
+        };
+
+        Ok(Self {
+            shader,
+            framebuffer: FrameBuffer::new(
+                state,
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(frame)),
+                }],
+            )?,
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            )?,
+            factor,
+            width,
+            height,
+        })
+    }
+
+    /// Returns `true` if the subset could be used for the map with the given parameters.
+    pub fn is_compatible(&self, map_width: usize, map_height: usize, factor: usize) -> bool {
+        self.factor == factor
+            && (self.width, self.height) == subset_size(map_width, map_height, factor)
+    }
+
+    pub fn framebuffer_mut(&mut self) -> &mut FrameBuffer {
+        &mut self.framebuffer
+    }
+
+    pub fn viewport(&self) -> Rect<i32> {
+        Rect::new(0, 0, self.width as i32, self.height as i32)
+    }
+
+    /// Copies the pixels of the subset with the given index to their places in the occlusion
+    /// map. The rest of the pixels is either marked as invalid (negative) or preserved.
+    pub(crate) fn scatter(
+        &mut self,
+        state: &PipelineState,
+        map: &mut FrameBuffer,
+        map_viewport: Rect<i32>,
+        phase: usize,
+        preserve_skipped: bool,
+    ) -> Result<DrawCallStatistics, FrameworkError> {
+        scope_profile!();
+
+        let subset = self.framebuffer.color_attachments()[0].texture.clone();
+        let factor = self.factor as i32;
+
+        let shader = &self.shader;
+        map.draw(
+            &self.quad,
+            state,
+            map_viewport,
+            &shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(
+                        &shader.world_view_projection_matrix,
+                        &(make_viewport_matrix(map_viewport)),
+                    )
+                    .set_texture(&shader.subset_sampler, &subset)
+                    .set_i32(&shader.interleave_factor, factor)
+                    .set_i32(&shader.interleave_phase, phase as i32)
+                    .set_bool(&shader.preserve_skipped, preserve_skipped);
+            },
+        )
+    }
+}
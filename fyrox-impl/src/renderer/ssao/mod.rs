@@ -4,8 +4,12 @@ use crate::{
         algebra::{Matrix3, Matrix4, Vector2, Vector3},
         color::Color,
         math::{lerpf, Rect},
+        pool::Handle,
+        reflect::prelude::*,
         scope_profile,
         sstorage::ImmutableString,
+        uuid_provider,
+        visitor::prelude::*,
     },
     rand::Rng,
     renderer::{
@@ -18,23 +22,64 @@ use crate::{
                 Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
                 PixelKind, WrapMode,
             },
+            screen_texture::{screen_relative_size, ScreenRelativeTextureStorage},
             state::PipelineState,
         },
         gbuffer::GBuffer,
-        ssao::blur::Blur,
-        RenderPassStatistics,
+        make_viewport_matrix,
+        ssao::{
+            blur::Blur,
+            interleave::{InterleaveShader, InterleavedSubset},
+            temporal::{TemporalAccumulation, TemporalShader},
+        },
+        RenderPassStatistics, SsaoDepthFilter,
     },
-    scene::mesh::surface::SurfaceData,
+    scene::{mesh::surface::SurfaceData, node::Node},
 };
+use serde::{Deserialize, Serialize};
 use std::{cell::RefCell, rc::Rc};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 mod blur;
+mod interleave;
+mod preserve;
+mod temporal;
+
+pub use preserve::PreservedOcclusionMaps;
+
+/// Default amount of samples in the SSAO kernel.
+pub const DEFAULT_KERNEL_SIZE: usize = 32;
+
+/// Maximum amount of samples in the SSAO kernel. Keep in sync with shader define.
+pub const MAX_KERNEL_SIZE: usize = 64;
+
+/// Default size (in pixels along each axis) of the noise texture, that is used to randomly rotate
+/// the sampling kernel.
+pub const DEFAULT_NOISE_SIZE: usize = 4;
+
+/// Maximum size (in pixels along each axis) of the noise texture.
+pub const MAX_NOISE_SIZE: usize = 64;
+
+/// Default amount of directions, that are marched by horizon-based ambient occlusion.
+pub const DEFAULT_HBAO_DIRECTION_COUNT: usize = 8;
 
-// Keep in sync with shader define.
-const KERNEL_SIZE: usize = 32;
+/// Maximum amount of directions, that are marched by horizon-based ambient occlusion.
+pub const MAX_HBAO_DIRECTION_COUNT: usize = 16;
 
-// Size of noise texture.
-const NOISE_SIZE: usize = 4;
+/// Default amount of steps per direction of horizon-based ambient occlusion.
+pub const DEFAULT_HBAO_STEP_COUNT: usize = 4;
+
+/// Maximum amount of steps per direction of horizon-based ambient occlusion.
+pub const MAX_HBAO_STEP_COUNT: usize = 16;
+
+// Minimal exponent of the occlusion factor.
+const MIN_POWER: f32 = 0.1;
+
+/// Default depth threshold (in world units) of the depth-aware blur of the occlusion map.
+pub const DEFAULT_BLUR_DEPTH_THRESHOLD: f32 = 0.5;
+
+// Minimal depth threshold of the blur, prevents division by zero in the shader.
+const MIN_BLUR_DEPTH_THRESHOLD: f32 = 0.001;
 
 struct Shader {
     program: GpuProgram,
@@ -42,12 +87,19 @@ struct Shader {
     normal_sampler: UniformLocation,
     noise_sampler: UniformLocation,
     radius: UniformLocation,
+    power: UniformLocation,
+    bias: UniformLocation,
     kernel: UniformLocation,
+    kernel_size: UniformLocation,
     projection_matrix: UniformLocation,
     noise_scale: UniformLocation,
+    noise_offset: UniformLocation,
     inv_proj_matrix: UniformLocation,
     world_view_proj_matrix: UniformLocation,
     view_matrix: UniformLocation,
+    interleave_factor: UniformLocation,
+    interleave_phase: UniformLocation,
+    map_size: UniformLocation,
 }
 
 impl Shader {
@@ -63,73 +115,396 @@ impl Shader {
             noise_sampler: program
                 .uniform_location(state, &ImmutableString::new("noiseSampler"))?,
             kernel: program.uniform_location(state, &ImmutableString::new("kernel"))?,
+            kernel_size: program.uniform_location(state, &ImmutableString::new("kernelSize"))?,
+            radius: program.uniform_location(state, &ImmutableString::new("radius"))?,
+            power: program.uniform_location(state, &ImmutableString::new("power"))?,
+            bias: program.uniform_location(state, &ImmutableString::new("bias"))?,
+            projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("projectionMatrix"))?,
+            inv_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("inverseProjectionMatrix"))?,
+            noise_scale: program.uniform_location(state, &ImmutableString::new("noiseScale"))?,
+            noise_offset: program.uniform_location(state, &ImmutableString::new("noiseOffset"))?,
+            world_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            interleave_factor: program
+                .uniform_location(state, &ImmutableString::new("interleaveFactor"))?,
+            interleave_phase: program
+                .uniform_location(state, &ImmutableString::new("interleavePhase"))?,
+            map_size: program.uniform_location(state, &ImmutableString::new("mapSize"))?,
+            program,
+        })
+    }
+}
+
+struct HbaoShader {
+    program: GpuProgram,
+    depth_sampler: UniformLocation,
+    normal_sampler: UniformLocation,
+    noise_sampler: UniformLocation,
+    radius: UniformLocation,
+    power: UniformLocation,
+    bias: UniformLocation,
+    direction_count: UniformLocation,
+    step_count: UniformLocation,
+    projection_matrix: UniformLocation,
+    noise_scale: UniformLocation,
+    noise_offset: UniformLocation,
+    inv_proj_matrix: UniformLocation,
+    world_view_proj_matrix: UniformLocation,
+    view_matrix: UniformLocation,
+    interleave_factor: UniformLocation,
+    interleave_phase: UniformLocation,
+    map_size: UniformLocation,
+}
+
+impl HbaoShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/hbao_fs.glsl");
+        let vertex_source = include_str!("../shaders/ssao_vs.glsl");
+        let program = GpuProgram::from_source(state, "HbaoShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthSampler"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalSampler"))?,
+            noise_sampler: program
+                .uniform_location(state, &ImmutableString::new("noiseSampler"))?,
             radius: program.uniform_location(state, &ImmutableString::new("radius"))?,
+            power: program.uniform_location(state, &ImmutableString::new("power"))?,
+            bias: program.uniform_location(state, &ImmutableString::new("bias"))?,
+            direction_count: program
+                .uniform_location(state, &ImmutableString::new("directionCount"))?,
+            step_count: program.uniform_location(state, &ImmutableString::new("stepCount"))?,
             projection_matrix: program
                 .uniform_location(state, &ImmutableString::new("projectionMatrix"))?,
             inv_proj_matrix: program
                 .uniform_location(state, &ImmutableString::new("inverseProjectionMatrix"))?,
             noise_scale: program.uniform_location(state, &ImmutableString::new("noiseScale"))?,
+            noise_offset: program.uniform_location(state, &ImmutableString::new("noiseOffset"))?,
             world_view_proj_matrix: program
                 .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
             view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            interleave_factor: program
+                .uniform_location(state, &ImmutableString::new("interleaveFactor"))?,
+            interleave_phase: program
+                .uniform_location(state, &ImmutableString::new("interleavePhase"))?,
+            map_size: program.uniform_location(state, &ImmutableString::new("mapSize"))?,
             program,
         })
     }
 }
 
+fn make_kernel(size: usize) -> Vec<Vector3<f32>> {
+    let mut rng = crate::rand::thread_rng();
+    (0..size)
+        .map(|i| {
+            let k = i as f32 / size as f32;
+            let scale = lerpf(0.1, 1.0, k * k);
+            Vector3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(0.0..1.0),
+            )
+            // Make sphere
+            .try_normalize(f32::EPSILON)
+            .unwrap()
+            // Use non-uniform distribution to shuffle points inside hemisphere.
+            .scale(scale)
+        })
+        .collect()
+}
+
+// Noise texture must be tiled over the occlusion map, so it uses repeat wrapping mode.
+fn make_noise_texture(state: &PipelineState, size: usize) -> Result<GpuTexture, FrameworkError> {
+    const RGB_PIXEL_SIZE: usize = 3;
+    let mut rng = crate::rand::thread_rng();
+    let mut pixels = vec![0u8; RGB_PIXEL_SIZE * size * size];
+    for pixel in pixels.chunks_exact_mut(RGB_PIXEL_SIZE) {
+        pixel[0] = rng.gen_range(0u8..255u8); // R
+        pixel[1] = rng.gen_range(0u8..255u8); // G
+        pixel[2] = 0u8; // B
+    }
+    let kind = GpuTextureKind::Rectangle {
+        width: size,
+        height: size,
+    };
+    let mut texture = GpuTexture::new(
+        state,
+        kind,
+        PixelKind::RGB8,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        Some(&pixels),
+    )?;
+    texture
+        .bind_mut(state, 0)
+        .set_wrap(Coordinate::S, WrapMode::Repeat)
+        .set_wrap(Coordinate::T, WrapMode::Repeat);
+    Ok(texture)
+}
+
+// The occlusion map is a screen relative texture, so it is resized by the renderer automatically and
+// the frame buffer stays valid.
+fn make_occlusion_framebuffer(
+    state: &PipelineState,
+    screen_textures: &mut ScreenRelativeTextureStorage,
+    frame_width: usize,
+    frame_height: usize,
+    pixel_kind: PixelKind,
+) -> Result<FrameBuffer, FrameworkError> {
+    let texture = screen_textures.create(
+        state,
+        (frame_width as u32, frame_height as u32),
+        SSAO_MAP_SCALE,
+        pixel_kind,
+    )?;
+
+    FrameBuffer::new(
+        state,
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: texture.texture().clone(),
+        }],
+    )
+}
+
+fn check_pixel_kind(pixel_kind: PixelKind) -> Result<(), FrameworkError> {
+    if matches!(pixel_kind, PixelKind::R16F | PixelKind::R32F) {
+        Ok(())
+    } else {
+        Err(FrameworkError::Custom(format!(
+            "Unsupported pixel kind of SSAO map: {:?}. Only R16F and R32F are supported.",
+            pixel_kind
+        )))
+    }
+}
+
+// It is good balance between quality and performance, no need to do SSAO in full resolution.
+// This SSAO map size reduction was taken from DOOM (2016).
+const SSAO_MAP_SCALE: f32 = 0.5;
+
+fn ssao_map_size(frame_width: usize, frame_height: usize) -> (usize, usize) {
+    screen_relative_size((frame_width as u32, frame_height as u32), SSAO_MAP_SCALE)
+}
+
+/// Defines what happens with the raw occlusion map before the occlusion pass. It matters only for
+/// interleaved sampling (see [`SsaoOptions::interleave_factor`]), since otherwise every pixel of
+/// the map is recalculated every frame.
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialEq,
+    Eq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum SsaoClearMode {
+    /// The map is fully cleared every frame, pixels that were skipped by interleaved sampling are
+    /// marked as invalid and reconstructed from their neighbours by the blur pass (and from the
+    /// history, if temporal accumulation is enabled).
+    #[default]
+    Full,
+    /// The map is cleared only when it is (re)created, pixels that were skipped by interleaved
+    /// sampling keep the occlusion calculated on previous frames (at most `interleave_factor - 1`
+    /// frames old). It gives sharper edges than the reconstruction, but the occlusion of moving
+    /// objects may lag behind for a few frames. Every camera preserves its own occlusion, see
+    /// [`PreservedOcclusionMaps`] for more info.
+    PreserveSkipped,
+}
+
+uuid_provider!(SsaoClearMode = "0f7d3c1e-8a52-4c1b-9e6d-2b47a5c39d18");
+
+/// Defines the algorithm, that is used to calculate the raw occlusion map. Both algorithms share
+/// the rest of the effect (interleaved sampling, temporal accumulation, blur).
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialEq,
+    Eq,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum SsaoMode {
+    /// Occlusion is calculated by testing random points of a hemisphere around every pixel against
+    /// the depth buffer. See [`ScreenSpaceAmbientOcclusionRenderer::set_sample_count`].
+    #[default]
+    Kernel,
+    /// Horizon-based ambient occlusion (HBAO). The depth buffer is marched in a few directions
+    /// around every pixel and the occlusion is proportional to the elevation of the highest horizon
+    /// in each direction. It gives more contrast occlusion in creases and contact areas, that looks
+    /// less flat than the kernel-based occlusion, at similar cost. See
+    /// [`ScreenSpaceAmbientOcclusionRenderer::set_hbao_direction_count`] and
+    /// [`ScreenSpaceAmbientOcclusionRenderer::set_hbao_step_count`].
+    HorizonBased,
+}
+
+uuid_provider!(SsaoMode = "5b2e8f71-3c4d-4a9e-b6f0-9d1a7c2e4b83");
+
+/// A full set of tunable parameters of screen-space ambient occlusion, except the radius, which
+/// is defined by [`crate::renderer::QualitySettings::ssao_radius`]. It could be fetched using
+/// [`crate::renderer::Renderer::ssao_options`] and applied back at once using
+/// [`crate::renderer::Renderer::set_ssao_options`].
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Visit)]
+#[serde(default)]
+pub struct SsaoOptions {
+    /// An exponent of the final occlusion factor.
+    #[reflect(min_value = 0.1, step = 0.05)]
+    #[visit(optional)]
+    pub power: f32,
+
+    /// A depth bias (in view space units), that is used to prevent self-occlusion of flat
+    /// surfaces.
+    #[reflect(min_value = 0.0, step = 0.005)]
+    #[visit(optional)]
+    pub bias: f32,
+
+    /// Amount of samples per pixel, the value is clamped to `[1; MAX_KERNEL_SIZE]` range.
+    #[reflect(min_value = 1.0, max_value = 64.0)]
+    #[visit(optional)]
+    pub sample_count: usize,
+
+    /// Enables temporal accumulation of the occlusion.
+    #[visit(optional)]
+    pub temporal: bool,
+
+    /// Amount of frames (1, 2 or 4), that is needed to calculate occlusion of every pixel. `1`
+    /// disables interleaved sampling.
+    #[reflect(min_value = 1.0, max_value = 4.0)]
+    #[visit(optional)]
+    pub interleave_factor: usize,
+
+    /// Defines what happens with pixels, that were skipped by interleaved sampling.
+    #[visit(optional)]
+    pub clear_mode: SsaoClearMode,
+
+    /// Maximum depth difference (in world units) between neighbouring pixels, that are blended
+    /// together by the depth-aware blur.
+    #[reflect(min_value = 0.001, step = 0.01)]
+    #[visit(optional)]
+    pub blur_depth_threshold: f32,
+
+    /// An algorithm, that is used to calculate the occlusion.
+    #[visit(optional)]
+    pub mode: SsaoMode,
+
+    /// Amount of directions per pixel of horizon-based occlusion, the value is clamped to
+    /// `[1; MAX_HBAO_DIRECTION_COUNT]` range.
+    #[reflect(min_value = 1.0, max_value = 16.0)]
+    #[visit(optional)]
+    pub hbao_direction_count: usize,
+
+    /// Amount of steps per direction of horizon-based occlusion, the value is clamped to
+    /// `[1; MAX_HBAO_STEP_COUNT]` range.
+    #[reflect(min_value = 1.0, max_value = 16.0)]
+    #[visit(optional)]
+    pub hbao_step_count: usize,
+
+    /// Size (in pixels along each axis) of the noise texture, the value is clamped to
+    /// `[1; MAX_NOISE_SIZE]` range.
+    #[reflect(min_value = 1.0, max_value = 64.0)]
+    #[visit(optional)]
+    pub noise_size: usize,
+}
+
+impl Default for SsaoOptions {
+    fn default() -> Self {
+        Self {
+            power: 1.0,
+            bias: 0.025,
+            sample_count: DEFAULT_KERNEL_SIZE,
+            temporal: false,
+            interleave_factor: 1,
+            clear_mode: SsaoClearMode::Full,
+            blur_depth_threshold: DEFAULT_BLUR_DEPTH_THRESHOLD,
+            mode: SsaoMode::Kernel,
+            hbao_direction_count: DEFAULT_HBAO_DIRECTION_COUNT,
+            hbao_step_count: DEFAULT_HBAO_STEP_COUNT,
+            noise_size: DEFAULT_NOISE_SIZE,
+        }
+    }
+}
+
 pub struct ScreenSpaceAmbientOcclusionRenderer {
     blur: Blur,
     shader: Shader,
+    hbao_shader: HbaoShader,
     framebuffer: FrameBuffer,
     quad: GeometryBuffer,
     width: i32,
     height: i32,
     noise: Rc<RefCell<GpuTexture>>,
-    kernel: [Vector3<f32>; KERNEL_SIZE],
+    noise_size: usize,
+    kernel: Vec<Vector3<f32>>,
     radius: f32,
+    power: f32,
+    bias: f32,
+    warm_up_frames: usize,
+    temporal: bool,
+    accumulation: Option<TemporalAccumulation>,
+    interleaved_subset: Option<InterleavedSubset>,
+    // Programs of optional passes are compiled on first use (or by `Self::precompile_programs`)
+    // and then shared by every instance of the respective pass.
+    temporal_shader: Option<Rc<TemporalShader>>,
+    interleave_shader: Option<Rc<InterleaveShader>>,
+    frame_index: usize,
+    interleave_factor: usize,
+    clear_mode: SsaoClearMode,
+    blur_depth_threshold: f32,
+    pixel_kind: PixelKind,
+    mode: SsaoMode,
+    hbao_direction_count: usize,
+    hbao_step_count: usize,
+    // Incremented every time the preserved occlusion (see `SsaoClearMode::PreserveSkipped`)
+    // becomes invalid (the map was resized, for example), so every camera must start over.
+    history_generation: u64,
 }
 
 impl ScreenSpaceAmbientOcclusionRenderer {
+    /// Creates a new SSAO renderer. `pixel_kind` defines the format of every occlusion map, it
+    /// must be either [`PixelKind::R32F`] (default) or [`PixelKind::R16F`]. Half precision halves
+    /// the bandwidth of the effect with negligible quality loss, since occlusion is in `[0; 1]`
+    /// range.
     pub fn new(
         state: &PipelineState,
+        screen_textures: &mut ScreenRelativeTextureStorage,
         frame_width: usize,
         frame_height: usize,
+        pixel_kind: PixelKind,
     ) -> Result<Self, FrameworkError> {
-        // It is good balance between quality and performance, no need to do SSAO in full resolution.
-        // This SSAO map size reduction was taken from DOOM (2016).
-        let width = (frame_width / 2).max(1);
-        let height = (frame_height / 2).max(1);
-
-        let occlusion = {
-            let kind = GpuTextureKind::Rectangle { width, height };
-            let mut texture = GpuTexture::new(
-                state,
-                kind,
-                PixelKind::R32F,
-                MinificationFilter::Nearest,
-                MagnificationFilter::Nearest,
-                1,
-                None,
-            )?;
-            texture
-                .bind_mut(state, 0)
-                .set_minification_filter(MinificationFilter::Nearest)
-                .set_magnification_filter(MagnificationFilter::Nearest);
-            texture
-        };
+        check_pixel_kind(pixel_kind)?;
 
-        let mut rng = crate::rand::thread_rng();
+        let (width, height) = ssao_map_size(frame_width, frame_height);
 
         Ok(Self {
-            blur: Blur::new(state, width, height)?,
+            blur: Blur::new(state, width, height, pixel_kind)?,
             shader: Shader::new(state)?,
-            framebuffer: FrameBuffer::new(
+            hbao_shader: HbaoShader::new(state)?,
+            framebuffer: make_occlusion_framebuffer(
                 state,
-                None,
-                vec![Attachment {
-                    kind: AttachmentKind::Color,
-                    texture: Rc::new(RefCell::new(occlusion)),
-                }],
+                screen_textures,
+                frame_width,
+                frame_height,
+                pixel_kind,
             )?,
             quad: GeometryBuffer::from_surface_data(
                 &SurfaceData::make_unit_xy_quad(),
@@ -138,60 +513,333 @@ impl ScreenSpaceAmbientOcclusionRenderer {
             )?,
             width: width as i32,
             height: height as i32,
-            kernel: {
-                let mut kernel = [Default::default(); KERNEL_SIZE];
-                for (i, v) in kernel.iter_mut().enumerate() {
-                    let k = i as f32 / KERNEL_SIZE as f32;
-                    let scale = lerpf(0.1, 1.0, k * k);
-                    *v = Vector3::new(
-                        rng.gen_range(-1.0..1.0),
-                        rng.gen_range(-1.0..1.0),
-                        rng.gen_range(0.0..1.0),
-                    )
-                    // Make sphere
-                    .try_normalize(f32::EPSILON)
-                    .unwrap()
-                    // Use non-uniform distribution to shuffle points inside hemisphere.
-                    .scale(scale);
-                }
-                kernel
-            },
-            noise: Rc::new(RefCell::new({
-                const RGB_PIXEL_SIZE: usize = 3;
-                let mut pixels = [0u8; RGB_PIXEL_SIZE * NOISE_SIZE * NOISE_SIZE];
-                for pixel in pixels.chunks_exact_mut(RGB_PIXEL_SIZE) {
-                    pixel[0] = rng.gen_range(0u8..255u8); // R
-                    pixel[1] = rng.gen_range(0u8..255u8); // G
-                    pixel[2] = 0u8; // B
-                }
-                let kind = GpuTextureKind::Rectangle {
-                    width: NOISE_SIZE,
-                    height: NOISE_SIZE,
-                };
-                let mut texture = GpuTexture::new(
-                    state,
-                    kind,
-                    PixelKind::RGB8,
-                    MinificationFilter::Nearest,
-                    MagnificationFilter::Nearest,
-                    1,
-                    Some(&pixels),
-                )?;
-                texture
-                    .bind_mut(state, 0)
-                    .set_wrap(Coordinate::S, WrapMode::Repeat)
-                    .set_wrap(Coordinate::T, WrapMode::Repeat);
-                texture
-            })),
+            kernel: make_kernel(DEFAULT_KERNEL_SIZE),
+            noise: Rc::new(RefCell::new(make_noise_texture(state, DEFAULT_NOISE_SIZE)?)),
+            noise_size: DEFAULT_NOISE_SIZE,
             radius: 0.5,
+            power: 1.0,
+            bias: 0.025,
+            warm_up_frames: 0,
+            temporal: false,
+            accumulation: None,
+            interleaved_subset: None,
+            temporal_shader: None,
+            interleave_shader: None,
+            frame_index: 0,
+            interleave_factor: 1,
+            clear_mode: SsaoClearMode::Full,
+            blur_depth_threshold: DEFAULT_BLUR_DEPTH_THRESHOLD,
+            pixel_kind,
+            mode: SsaoMode::Kernel,
+            hbao_direction_count: DEFAULT_HBAO_DIRECTION_COUNT,
+            hbao_step_count: DEFAULT_HBAO_STEP_COUNT,
+            history_generation: 0,
         })
     }
 
+    /// Recreates every frame size dependent resource, the rest of the parameters (radius, power,
+    /// etc.) are kept as is. Temporal history is discarded. The occlusion map itself is resized by
+    /// the renderer, since it is a screen relative texture.
+    pub fn set_frame_size(
+        &mut self,
+        state: &PipelineState,
+        frame_width: usize,
+        frame_height: usize,
+    ) -> Result<(), FrameworkError> {
+        let (width, height) = ssao_map_size(frame_width, frame_height);
+        self.blur = Blur::new(state, width, height, self.pixel_kind)?;
+        self.width = width as i32;
+        self.height = height as i32;
+        self.accumulation = None;
+        self.interleaved_subset = None;
+        self.history_generation = self.history_generation.wrapping_add(1);
+        Ok(())
+    }
+
+    fn temporal_shader(
+        &mut self,
+        state: &PipelineState,
+    ) -> Result<Rc<TemporalShader>, FrameworkError> {
+        if let Some(shader) = self.temporal_shader.as_ref() {
+            return Ok(shader.clone());
+        }
+        let shader = Rc::new(TemporalShader::new(state)?);
+        self.temporal_shader = Some(shader.clone());
+        Ok(shader)
+    }
+
+    fn interleave_shader(
+        &mut self,
+        state: &PipelineState,
+    ) -> Result<Rc<InterleaveShader>, FrameworkError> {
+        if let Some(shader) = self.interleave_shader.as_ref() {
+            return Ok(shader.clone());
+        }
+        let shader = Rc::new(InterleaveShader::new(state)?);
+        self.interleave_shader = Some(shader.clone());
+        Ok(shader)
+    }
+
+    /// Compiles the programs of optional passes (temporal accumulation and interleaved sampling),
+    /// that are otherwise compiled on first use. Does nothing if the programs are compiled already.
+    pub(crate) fn precompile_programs(
+        &mut self,
+        state: &PipelineState,
+    ) -> Result<(), FrameworkError> {
+        self.temporal_shader(state)?;
+        self.interleave_shader(state)?;
+        Ok(())
+    }
+
+    /// Requests the given amount of additional SSAO iterations to be rendered on the next frame,
+    /// so the effect is stable from the first frame. Temporal accumulation (see
+    /// [`Self::set_temporal`]) uses these iterations to pre-fill the history. Without temporal
+    /// accumulation this is a no-op, since the result is stable from the first frame.
+    pub fn warm_up(&mut self, frames: usize) {
+        self.warm_up_frames = frames;
+    }
+
+    /// Enables or disables temporal accumulation. When enabled, raw occlusion of every frame is
+    /// calculated with a different noise pattern, reprojected to the previous frame using previous
+    /// view-projection matrix of the camera and blended with the accumulated result of previous
+    /// frames. It significantly reduces noise and shimmering of slowly moving cameras at the cost
+    /// of two extra textures and one extra full screen pass. The history is shared between every
+    /// camera, so the accumulation works best when there's a single camera rendered per frame.
+    /// Disabling the accumulation frees the history. Default value is `false`.
+    pub fn set_temporal(&mut self, temporal: bool) {
+        self.temporal = temporal;
+        if !temporal {
+            self.accumulation = None;
+        }
+    }
+
+    /// Discards the history of the effect, so the next frame starts fresh: temporal accumulation
+    /// (see [`Self::set_temporal`]) uses the occlusion of the next frame only and pixels, that were
+    /// preserved by [`SsaoClearMode::PreserveSkipped`], are cleared. Reprojection can't handle
+    /// sudden changes of the view, so the history becomes invalid and produces ghosting. Call this
+    /// method on camera cuts, teleports, respawns and scene loads.
+    pub fn reset_history(&mut self) {
+        if let Some(accumulation) = self.accumulation.as_mut() {
+            accumulation.reset();
+        }
+        self.history_generation = self.history_generation.wrapping_add(1);
+    }
+
+    /// Sets the amount of frames, that is needed to calculate occlusion of every pixel. Every
+    /// frame only a subset of pixels is calculated: every second pixel in checkerboard pattern
+    /// for the factor `2` and one pixel of every 2x2 block for the factor `4`. The subset is
+    /// rendered into a separate map of reduced resolution (a half or a quarter of the occlusion
+    /// map) and then scattered to the occlusion map. The rest of the pixels is reconstructed by
+    /// the blur pass, using valid neighbours and their depth. It reduces the amount of the
+    /// occlusion calculations 2 or 4 times at the cost of quality and an extra (cheap) pass, so
+    /// it pairs well with temporal accumulation (see [`Self::set_temporal`]), which fills the
+    /// gaps using previous frames. The factor is rounded down to the nearest supported value, `1`
+    /// (default) disables interleaved sampling.
+    pub fn set_interleaved(&mut self, factor: usize) {
+        self.interleave_factor = match factor {
+            0 | 1 => 1,
+            2 | 3 => 2,
+            _ => 4,
+        };
+    }
+
+    /// Sets what happens with the raw occlusion map before the occlusion pass. See
+    /// [`SsaoClearMode`] docs for more info. Default value is [`SsaoClearMode::Full`].
+    pub fn set_clear_mode(&mut self, clear_mode: SsaoClearMode) {
+        if self.clear_mode != clear_mode {
+            self.clear_mode = clear_mode;
+            // Skipped pixels contain invalid occlusion after the full clear mode.
+            self.history_generation = self.history_generation.wrapping_add(1);
+        }
+    }
+
+    /// Returns the format of the occlusion maps. See [`Self::new`] for more info.
+    pub fn pixel_kind(&self) -> PixelKind {
+        self.pixel_kind
+    }
+
+    /// Changes the format of the occlusion maps, see [`Self::new`] for more info. Only the maps
+    /// and their frame buffers are recreated, every tunable parameter and pending warm-up
+    /// iterations (see [`Self::warm_up`]) are kept, but temporal history starts over. Returns an
+    /// error if the format is not supported, in this case current maps are kept as is.
+    pub fn set_pixel_kind(
+        &mut self,
+        state: &PipelineState,
+        screen_textures: &mut ScreenRelativeTextureStorage,
+        frame_width: usize,
+        frame_height: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<(), FrameworkError> {
+        check_pixel_kind(pixel_kind)?;
+        if self.pixel_kind == pixel_kind {
+            return Ok(());
+        }
+
+        let (width, height) = ssao_map_size(frame_width, frame_height);
+        let blur = Blur::new(state, width, height, pixel_kind)?;
+        let framebuffer = make_occlusion_framebuffer(
+            state,
+            screen_textures,
+            frame_width,
+            frame_height,
+            pixel_kind,
+        )?;
+
+        self.blur = blur;
+        self.framebuffer = framebuffer;
+        self.pixel_kind = pixel_kind;
+        // Both are created on demand with the new format.
+        self.accumulation = None;
+        self.interleaved_subset = None;
+        self.history_generation = self.history_generation.wrapping_add(1);
+
+        Ok(())
+    }
+
+    /// Sets new amount of samples in the kernel, that is used to calculate occlusion of every
+    /// pixel. More samples give smoother occlusion, but it is more expensive to calculate. The
+    /// value is clamped to `[1; MAX_KERNEL_SIZE]` range, default value is [`DEFAULT_KERNEL_SIZE`].
+    /// The kernel is regenerated only when the amount of samples changes.
+    pub fn set_sample_count(&mut self, count: usize) {
+        let count = count.clamp(1, MAX_KERNEL_SIZE);
+        if count != self.kernel.len() {
+            self.kernel = make_kernel(count);
+        }
+    }
+
+    /// Returns current amount of samples in the kernel.
+    pub fn sample_count(&self) -> usize {
+        self.kernel.len()
+    }
+
+    /// Sets the algorithm, that is used to calculate the occlusion. See [`SsaoMode`] docs for more
+    /// info. Default value is [`SsaoMode::Kernel`]. Keep in mind, that [`Self::set_bias`] has
+    /// different meaning for horizon-based occlusion: it is a sine of the minimal elevation of the
+    /// horizon, so it could require different values.
+    pub fn set_mode(&mut self, mode: SsaoMode) {
+        self.mode = mode;
+    }
+
+    /// Sets new amount of directions per pixel, that are marched by horizon-based occlusion (see
+    /// [`SsaoMode::HorizonBased`]). More directions give smoother occlusion, but it is more
+    /// expensive to calculate. The value is clamped to `[1; MAX_HBAO_DIRECTION_COUNT]` range,
+    /// default value is [`DEFAULT_HBAO_DIRECTION_COUNT`].
+    pub fn set_hbao_direction_count(&mut self, count: usize) {
+        self.hbao_direction_count = count.clamp(1, MAX_HBAO_DIRECTION_COUNT);
+    }
+
+    /// Sets new amount of steps per direction of horizon-based occlusion (see
+    /// [`SsaoMode::HorizonBased`]). More steps allow the effect to find thin occluders, but it
+    /// is more expensive to calculate. The value is clamped to `[1; MAX_HBAO_STEP_COUNT]` range,
+    /// default value is [`DEFAULT_HBAO_STEP_COUNT`].
+    pub fn set_hbao_step_count(&mut self, count: usize) {
+        self.hbao_step_count = count.clamp(1, MAX_HBAO_STEP_COUNT);
+    }
+
+    /// Sets new size (in pixels along each axis) of the noise texture, that is used to randomly
+    /// rotate the sampling kernel (or the marching directions of horizon-based occlusion). The
+    /// texture is tiled over the occlusion map, so larger textures (8x8, for example) reduce
+    /// repeating patterns, that could be visible on large flat surfaces, but they require stronger
+    /// blur to hide the noise. The value is clamped to `[1; MAX_NOISE_SIZE]` range, default value
+    /// is [`DEFAULT_NOISE_SIZE`]. The texture is regenerated only when the size changes.
+    pub fn set_noise_size(
+        &mut self,
+        state: &PipelineState,
+        size: usize,
+    ) -> Result<(), FrameworkError> {
+        let size = size.clamp(1, MAX_NOISE_SIZE);
+        if size != self.noise_size {
+            self.noise = Rc::new(RefCell::new(make_noise_texture(state, size)?));
+            self.noise_size = size;
+        }
+        Ok(())
+    }
+
+    /// Returns current size of the noise texture. See [`Self::set_noise_size`] for more info.
+    pub fn noise_size(&self) -> usize {
+        self.noise_size
+    }
+
     pub fn set_radius(&mut self, radius: f32) {
         self.radius = radius.abs();
     }
 
-    fn raw_ao_map(&self) -> Rc<RefCell<GpuTexture>> {
+    /// Returns current values of every tunable parameter. Individual parameters are not exposed
+    /// by separate getters, use the respective fields of [`SsaoOptions`] instead.
+    pub fn options(&self) -> SsaoOptions {
+        SsaoOptions {
+            power: self.power,
+            bias: self.bias,
+            sample_count: self.sample_count(),
+            temporal: self.temporal,
+            interleave_factor: self.interleave_factor,
+            clear_mode: self.clear_mode,
+            blur_depth_threshold: self.blur_depth_threshold,
+            mode: self.mode,
+            hbao_direction_count: self.hbao_direction_count,
+            hbao_step_count: self.hbao_step_count,
+            noise_size: self.noise_size,
+        }
+    }
+
+    /// Applies every tunable parameter at once. The values are validated the same way as in the
+    /// respective setters. Returns an error if the noise texture could not be regenerated, the
+    /// rest of the parameters are applied anyway.
+    pub fn apply_options(
+        &mut self,
+        state: &PipelineState,
+        options: &SsaoOptions,
+    ) -> Result<(), FrameworkError> {
+        self.set_power(options.power);
+        self.set_bias(options.bias);
+        self.set_sample_count(options.sample_count);
+        self.set_temporal(options.temporal);
+        self.set_interleaved(options.interleave_factor);
+        self.set_clear_mode(options.clear_mode);
+        self.set_blur_depth_threshold_world(options.blur_depth_threshold);
+        self.set_mode(options.mode);
+        self.set_hbao_direction_count(options.hbao_direction_count);
+        self.set_hbao_step_count(options.hbao_step_count);
+        self.set_noise_size(state, options.noise_size)
+    }
+
+    /// Sets an exponent, that is applied to the final occlusion factor. Values greater than one
+    /// make occlusion darker, values less than one - softer. Default value is `1.0`, the value
+    /// is clamped to `0.1` at minimum to prevent occlusion from disappearing completely.
+    pub fn set_power(&mut self, power: f32) {
+        self.power = power.max(MIN_POWER);
+    }
+
+    /// Sets a depth bias (in view space units), that is used when a sample point is compared
+    /// with the depth buffer. Larger values remove banding and self-occlusion on flat surfaces
+    /// viewed at grazing angles, but too large values hide occlusion of small details. Default
+    /// value is `0.025`.
+    pub fn set_bias(&mut self, bias: f32) {
+        self.bias = bias.abs();
+    }
+
+    /// Sets maximum depth difference (in world units) between neighbouring pixels, that are
+    /// blended together by the depth-aware (bilateral) blur of the occlusion map. The blur is
+    /// always depth-aware, regardless of interleaved sampling (see [`Self::set_interleaved`]).
+    /// Neighbours are weighted by `1 - difference / threshold`, so the neighbours with larger
+    /// depth difference are ignored, which prevents occlusion from leaking through the edges of
+    /// objects.
+    ///
+    /// The depth buffer stores non-linear depth, which depends on the near and far clipping
+    /// planes of the camera, so the blur converts it to view space depth (in world units) using
+    /// the inverse projection matrix of the camera before the comparison. It makes the threshold
+    /// independent of the clipping planes and the projection mode. Default value is
+    /// [`DEFAULT_BLUR_DEPTH_THRESHOLD`], the value is clamped to `0.001` at minimum.
+    pub fn set_blur_depth_threshold_world(&mut self, threshold: f32) {
+        self.blur_depth_threshold = threshold.abs().max(MIN_BLUR_DEPTH_THRESHOLD);
+    }
+
+    /// Returns the raw occlusion map, before it is blurred. It is meant to be used for debugging
+    /// only (for example, to visualize the effect of radius and bias tweaks in a debug overlay),
+    /// use [`Self::ao_map`] for rendering. The contents of the map is valid only after the
+    /// occlusion pass of the current frame and it could contain stale pixels when interleaved
+    /// rendering is used (see [`Self::set_interleaved`]).
+    pub fn raw_ao_map(&self) -> Rc<RefCell<GpuTexture>> {
         self.framebuffer.color_attachments()[0].texture.clone()
     }
 
@@ -199,54 +847,173 @@ impl ScreenSpaceAmbientOcclusionRenderer {
         self.blur.result()
     }
 
+    /// Returns width and height of the occlusion map, that is returned by [`Self::ao_map`]. SSAO is
+    /// calculated at reduced resolution, so this method should be used to correctly scale texture
+    /// coordinates when sampling the map in custom shaders.
+    pub fn ao_resolution(&self) -> Vector2<i32> {
+        self.blur.resolution()
+    }
+
     pub(crate) fn render(
         &mut self,
         state: &PipelineState,
         gbuffer: &GBuffer,
         projection_matrix: Matrix4<f32>,
         view_matrix: Matrix3<f32>,
+        view_projection: Matrix4<f32>,
+        depth_filter: SsaoDepthFilter,
+        camera: Handle<Node>,
+        preserved_maps: &mut PreservedOcclusionMaps,
     ) -> Result<RenderPassStatistics, FrameworkError> {
         scope_profile!();
 
         let mut stats = RenderPassStatistics::default();
 
+        // Without temporal accumulation every frame is already converged and there's nothing
+        // to warm up.
+        let iterations = if self.temporal {
+            1 + self.warm_up_frames
+        } else {
+            1
+        };
+        self.warm_up_frames = 0;
+
+        if self.temporal && self.accumulation.is_none() {
+            let shader = self.temporal_shader(state)?;
+            self.accumulation = Some(TemporalAccumulation::new(
+                state,
+                shader,
+                self.width as usize,
+                self.height as usize,
+                self.pixel_kind,
+            )?);
+        }
+
         let viewport = Rect::new(0, 0, self.width, self.height);
 
-        let frame_matrix = Matrix4::new_orthographic(
-            0.0,
-            viewport.w() as f32,
-            viewport.h() as f32,
-            0.0,
-            -1.0,
-            1.0,
-        ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
-            viewport.w() as f32,
-            viewport.h() as f32,
-            0.0,
-        ));
-
-        self.framebuffer.clear(
-            state,
-            viewport,
-            Some(Color::from_rgba(0, 0, 0, 0)),
-            Some(1.0),
-            None,
-        );
-
-        let shader = &self.shader;
-        let noise = &self.noise;
-        let kernel = &self.kernel;
-        let noise_scale = Vector2::new(
-            self.width as f32 / NOISE_SIZE as f32,
-            self.height as f32 / NOISE_SIZE as f32,
-        );
-        let radius = self.radius;
-        stats += self.framebuffer.draw(
-            &self.quad,
-            state,
-            viewport,
-            &shader.program,
-            &DrawParameters {
+        // Depth texture is shared with other passes, so the filter is switched only for the time
+        // of the SSAO pass and restored right after it.
+        let depth = gbuffer.depth();
+        let (min_filter, mag_filter) = match depth_filter {
+            SsaoDepthFilter::Nearest => (MinificationFilter::Nearest, MagnificationFilter::Nearest),
+            SsaoDepthFilter::Linear => (MinificationFilter::Linear, MagnificationFilter::Linear),
+        };
+        let (prev_min_filter, prev_mag_filter) = {
+            let depth = depth.borrow();
+            (depth.minification_filter(), depth.magnification_filter())
+        };
+        let filter_changed = prev_min_filter != min_filter || prev_mag_filter != mag_filter;
+        if filter_changed {
+            depth
+                .borrow_mut()
+                .bind_mut(state, 0)
+                .set_minification_filter(min_filter)
+                .set_magnification_filter(mag_filter);
+        }
+
+        let preserve_skipped =
+            self.clear_mode == SsaoClearMode::PreserveSkipped && self.interleave_factor > 1;
+
+        if self.interleave_factor > 1 {
+            let (width, height) = (self.width as usize, self.height as usize);
+            if !self
+                .interleaved_subset
+                .as_ref()
+                .is_some_and(|subset| subset.is_compatible(width, height, self.interleave_factor))
+            {
+                let shader = self.interleave_shader(state)?;
+                self.interleaved_subset = Some(InterleavedSubset::new(
+                    state,
+                    shader,
+                    width,
+                    height,
+                    self.interleave_factor,
+                    self.pixel_kind,
+                )?);
+            }
+        } else {
+            self.interleaved_subset = None;
+        }
+        let map_size = Vector2::new(self.width as f32, self.height as f32);
+
+        // Every camera preserves its own occlusion, the maps are not needed in other modes.
+        let mut preserved_map = if preserve_skipped {
+            Some(preserved_maps.get_or_create(
+                state,
+                camera,
+                self.width as usize,
+                self.height as usize,
+                self.pixel_kind,
+            )?)
+        } else {
+            preserved_maps.clear();
+            None
+        };
+
+        if let Some(preserved_map) = preserved_map.as_ref() {
+            if !preserved_map.restore(state, &self.framebuffer, self.history_generation) {
+                // There's no occlusion calculated yet, so the skipped pixels are unoccluded until
+                // they're calculated.
+                self.framebuffer
+                    .clear(state, viewport, Some(Color::WHITE), Some(1.0), None);
+            }
+        }
+
+        for _ in 0..iterations {
+            if !preserve_skipped {
+                self.framebuffer.clear(
+                    state,
+                    viewport,
+                    Some(Color::from_rgba(0, 0, 0, 0)),
+                    Some(1.0),
+                    None,
+                );
+            }
+
+            let noise = &self.noise;
+            let noise_size = self.noise_size;
+            let noise_scale = Vector2::new(
+                self.width as f32 / noise_size as f32,
+                self.height as f32 / noise_size as f32,
+            );
+            let radius = self.radius;
+            let power = self.power;
+            let bias = self.bias;
+            let noise_offset = if self.temporal {
+                // Shift the noise texture by a whole texel every frame, so every pixel uses
+                // different rotation of the kernel on each of noise_size^2 consecutive frames.
+                let index = self.frame_index % (noise_size * noise_size);
+                Vector2::new(
+                    (index % noise_size) as f32 / noise_size as f32,
+                    (index / noise_size) as f32 / noise_size as f32,
+                )
+            } else {
+                Vector2::default()
+            };
+            let interleave_factor = self.interleave_factor as i32;
+            // Preserved occlusion is per camera and so is the pixel subset, otherwise a camera
+            // could get the same subset every frame and the rest of its pixels would never be
+            // calculated.
+            let frame_index = match preserved_map.as_mut() {
+                Some(preserved_map) => preserved_map.advance_frame(),
+                None => self.frame_index,
+            };
+            let interleave_phase = frame_index % self.interleave_factor;
+            self.frame_index = self.frame_index.wrapping_add(1);
+            let inv_projection_matrix = projection_matrix.try_inverse().unwrap_or_default();
+
+            // Interleaved sampling calculates the occlusion of the current pixel subset only, at
+            // reduced resolution.
+            let (target, target_viewport) = match self.interleaved_subset.as_mut() {
+                Some(subset) => {
+                    let subset_viewport = subset.viewport();
+                    (subset.framebuffer_mut(), subset_viewport)
+                }
+                None => (&mut self.framebuffer, viewport),
+            };
+            let target_matrix = make_viewport_matrix(target_viewport);
+
+            let draw_params = DrawParameters {
                 cull_face: None,
                 color_write: Default::default(),
                 depth_write: false,
@@ -254,27 +1021,115 @@ impl ScreenSpaceAmbientOcclusionRenderer {
                 depth_test: false,
                 blend: None,
                 stencil_op: Default::default(),
-            },
-            ElementRange::Full,
-            |mut program_binding| {
-                program_binding
-                    .set_texture(&shader.depth_sampler, &gbuffer.depth())
-                    .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
-                    .set_texture(&shader.noise_sampler, noise)
-                    .set_vector3_slice(&shader.kernel, kernel)
-                    .set_vector2(&shader.noise_scale, &noise_scale)
-                    .set_f32(&shader.radius, radius)
-                    .set_matrix4(&shader.world_view_proj_matrix, &frame_matrix)
-                    .set_matrix4(&shader.projection_matrix, &projection_matrix)
-                    .set_matrix4(
-                        &shader.inv_proj_matrix,
-                        &projection_matrix.try_inverse().unwrap_or_default(),
-                    )
-                    .set_matrix3(&shader.view_matrix, &view_matrix);
-            },
-        )?;
+            };
 
-        self.blur.render(state, self.raw_ao_map())?;
+            stats += match self.mode {
+                SsaoMode::Kernel => {
+                    let shader = &self.shader;
+                    let kernel = &self.kernel;
+                    target.draw(
+                        &self.quad,
+                        state,
+                        target_viewport,
+                        &shader.program,
+                        &draw_params,
+                        ElementRange::Full,
+                        |mut program_binding| {
+                            program_binding
+                                .set_texture(&shader.depth_sampler, &depth)
+                                .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                                .set_texture(&shader.noise_sampler, noise)
+                                .set_vector3_slice(&shader.kernel, kernel)
+                                .set_i32(&shader.kernel_size, kernel.len() as i32)
+                                .set_vector2(&shader.noise_scale, &noise_scale)
+                                .set_vector2(&shader.noise_offset, &noise_offset)
+                                .set_f32(&shader.radius, radius)
+                                .set_f32(&shader.power, power)
+                                .set_f32(&shader.bias, bias)
+                                .set_matrix4(&shader.world_view_proj_matrix, &target_matrix)
+                                .set_matrix4(&shader.projection_matrix, &projection_matrix)
+                                .set_matrix4(&shader.inv_proj_matrix, &inv_projection_matrix)
+                                .set_matrix3(&shader.view_matrix, &view_matrix)
+                                .set_i32(&shader.interleave_factor, interleave_factor)
+                                .set_i32(&shader.interleave_phase, interleave_phase as i32)
+                                .set_vector2(&shader.map_size, &map_size);
+                        },
+                    )?
+                }
+                SsaoMode::HorizonBased => {
+                    let shader = &self.hbao_shader;
+                    let direction_count = self.hbao_direction_count as i32;
+                    let step_count = self.hbao_step_count as i32;
+                    target.draw(
+                        &self.quad,
+                        state,
+                        target_viewport,
+                        &shader.program,
+                        &draw_params,
+                        ElementRange::Full,
+                        |mut program_binding| {
+                            program_binding
+                                .set_texture(&shader.depth_sampler, &depth)
+                                .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                                .set_texture(&shader.noise_sampler, noise)
+                                .set_i32(&shader.direction_count, direction_count)
+                                .set_i32(&shader.step_count, step_count)
+                                .set_vector2(&shader.noise_scale, &noise_scale)
+                                .set_vector2(&shader.noise_offset, &noise_offset)
+                                .set_f32(&shader.radius, radius)
+                                .set_f32(&shader.power, power)
+                                .set_f32(&shader.bias, bias)
+                                .set_matrix4(&shader.world_view_proj_matrix, &target_matrix)
+                                .set_matrix4(&shader.projection_matrix, &projection_matrix)
+                                .set_matrix4(&shader.inv_proj_matrix, &inv_projection_matrix)
+                                .set_matrix3(&shader.view_matrix, &view_matrix)
+                                .set_i32(&shader.interleave_factor, interleave_factor)
+                                .set_i32(&shader.interleave_phase, interleave_phase as i32)
+                                .set_vector2(&shader.map_size, &map_size);
+                        },
+                    )?
+                }
+            };
+
+            if let Some(subset) = self.interleaved_subset.as_mut() {
+                stats += subset.scatter(
+                    state,
+                    &mut self.framebuffer,
+                    viewport,
+                    interleave_phase,
+                    preserve_skipped,
+                )?;
+            }
+
+            if let Some(accumulation) = self.accumulation.as_mut() {
+                let raw_ao_map = self.framebuffer.color_attachments()[0].texture.clone();
+                stats += accumulation.render(state, raw_ao_map, depth.clone(), view_projection)?;
+            }
+        }
+
+        if let Some(preserved_map) = preserved_map {
+            preserved_map.store(state, &self.framebuffer, self.history_generation);
+        }
+
+        if filter_changed {
+            depth
+                .borrow_mut()
+                .bind_mut(state, 0)
+                .set_minification_filter(prev_min_filter)
+                .set_magnification_filter(prev_mag_filter);
+        }
+
+        let blur_input = match self.accumulation.as_ref() {
+            Some(accumulation) => accumulation.result(),
+            None => self.raw_ao_map(),
+        };
+        stats += self.blur.render(
+            state,
+            blur_input,
+            depth,
+            projection_matrix.try_inverse().unwrap_or_default(),
+            self.blur_depth_threshold,
+        )?;
 
         Ok(stats)
     }
@@ -0,0 +1,158 @@
+use crate::{
+    core::pool::Handle,
+    graph::SceneGraph,
+    renderer::framework::{
+        error::FrameworkError,
+        framebuffer::{Attachment, AttachmentKind, FrameBuffer},
+        gpu_texture::{
+            Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+            PixelKind, WrapMode,
+        },
+        state::{BlitFilter, PipelineState},
+    },
+    scene::{camera::Camera, graph::Graph, node::Node},
+};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, rc::Rc};
+
+pub(super) struct PreservedOcclusionMap {
+    framebuffer: FrameBuffer,
+    width: usize,
+    height: usize,
+    pixel_kind: PixelKind,
+    // Generation of the history of the SSAO renderer, that the contents of the map belongs to.
+    // `None` means that the map was just created and its contents is undefined.
+    generation: Option<u64>,
+    // Index of the next frame of the camera, it defines the pixel subset of interleaved sampling.
+    frame_index: usize,
+}
+
+impl PreservedOcclusionMap {
+    fn new(
+        state: &PipelineState,
+        width: usize,
+        height: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<Self, FrameworkError> {
+        let mut texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            pixel_kind,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+        texture
+            .bind_mut(state, 0)
+            .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+            .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+        Ok(Self {
+            framebuffer: FrameBuffer::new(
+                state,
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(texture)),
+                }],
+            )?,
+            width,
+            height,
+            pixel_kind,
+            generation: None,
+            frame_index: 0,
+        })
+    }
+
+    /// Returns the index of the current frame of the camera and advances to the next one.
+    pub(super) fn advance_frame(&mut self) -> usize {
+        let frame_index = self.frame_index;
+        self.frame_index = self.frame_index.wrapping_add(1);
+        frame_index
+    }
+
+    /// Copies preserved occlusion to the given map. Returns `false` if there's nothing to copy,
+    /// because the preserved occlusion belongs to another generation of the history.
+    pub(super) fn restore(
+        &self,
+        state: &PipelineState,
+        map: &FrameBuffer,
+        generation: u64,
+    ) -> bool {
+        if self.generation != Some(generation) {
+            return false;
+        }
+        self.copy(state, &self.framebuffer, map);
+        true
+    }
+
+    /// Copies occlusion of the given map, so it could be restored on the next frame of the camera.
+    pub(super) fn store(&mut self, state: &PipelineState, map: &FrameBuffer, generation: u64) {
+        self.copy(state, map, &self.framebuffer);
+        self.generation = Some(generation);
+    }
+
+    fn copy(&self, state: &PipelineState, source: &FrameBuffer, dest: &FrameBuffer) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        state.blit_framebuffer(
+            source.id(),
+            dest.id(),
+            0,
+            0,
+            width,
+            height,
+            0,
+            0,
+            width,
+            height,
+            true,
+            false,
+            false,
+            BlitFilter::Nearest,
+        );
+    }
+}
+
+/// A set of raw occlusion maps of every camera of a scene, that keep the pixels skipped by
+/// interleaved sampling between frames (see [`super::SsaoClearMode::PreserveSkipped`]). Every
+/// camera preserves its own occlusion, so cameras do not see the occlusion of each other.
+#[derive(Default)]
+pub struct PreservedOcclusionMaps {
+    maps: FxHashMap<Handle<Node>, PreservedOcclusionMap>,
+}
+
+impl PreservedOcclusionMaps {
+    /// Removes every preserved occlusion map, the maps are created again on demand.
+    pub fn clear(&mut self) {
+        self.maps.clear();
+    }
+
+    /// Removes preserved occlusion maps of the cameras, that were deleted.
+    pub(crate) fn remove_unused(&mut self, graph: &Graph) {
+        self.maps
+            .retain(|camera, _| graph.try_get_of_type::<Camera>(*camera).is_some());
+    }
+
+    /// Returns a preserved occlusion map of the given camera. The map is (re)created if there's
+    /// no map yet or if its size or format does not match the given ones.
+    pub(super) fn get_or_create(
+        &mut self,
+        state: &PipelineState,
+        camera: Handle<Node>,
+        width: usize,
+        height: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<&mut PreservedOcclusionMap, FrameworkError> {
+        let is_compatible = self.maps.get(&camera).is_some_and(|map| {
+            map.width == width && map.height == height && map.pixel_kind == pixel_kind
+        });
+        if !is_compatible {
+            self.maps.insert(
+                camera,
+                PreservedOcclusionMap::new(state, width, height, pixel_kind)?,
+            );
+        }
+        Ok(self.maps.get_mut(&camera).unwrap())
+    }
+}
@@ -0,0 +1,198 @@
+use crate::renderer::framework::geometry_buffer::{DrawCallStatistics, ElementRange};
+use crate::{
+    core::{algebra::Matrix4, math::Rect, scope_profile, sstorage::ImmutableString},
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+                PixelKind, WrapMode,
+            },
+            state::PipelineState,
+        },
+        make_viewport_matrix,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+// Weight of the current frame in the accumulated result.
+const BLEND_FACTOR: f32 = 0.1;
+
+pub struct TemporalShader {
+    program: GpuProgram,
+    world_view_projection_matrix: UniformLocation,
+    current_sampler: UniformLocation,
+    history_sampler: UniformLocation,
+    depth_sampler: UniformLocation,
+    inverse_view_projection: UniformLocation,
+    previous_view_projection: UniformLocation,
+    blend_factor: UniformLocation,
+    history_valid: UniformLocation,
+}
+
+impl TemporalShader {
+    pub fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/ssao_temporal_fs.glsl");
+        let vertex_source = include_str!("../shaders/blur_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "SsaoTemporalShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            world_view_projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            current_sampler: program
+                .uniform_location(state, &ImmutableString::new("currentSampler"))?,
+            history_sampler: program
+                .uniform_location(state, &ImmutableString::new("historySampler"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthSampler"))?,
+            inverse_view_projection: program
+                .uniform_location(state, &ImmutableString::new("inverseViewProjection"))?,
+            previous_view_projection: program
+                .uniform_location(state, &ImmutableString::new("previousViewProjection"))?,
+            blend_factor: program.uniform_location(state, &ImmutableString::new("blendFactor"))?,
+            history_valid: program
+                .uniform_location(state, &ImmutableString::new("historyValid"))?,
+            program,
+        })
+    }
+}
+
+fn make_history_framebuffer(
+    state: &PipelineState,
+    width: usize,
+    height: usize,
+    pixel_kind: PixelKind,
+) -> Result<FrameBuffer, FrameworkError> {
+    let kind = GpuTextureKind::Rectangle { width, height };
+    let mut texture = GpuTexture::new(
+        state,
+        kind,
+        pixel_kind,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+    texture
+        .bind_mut(state, 0)
+        .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+        .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+    FrameBuffer::new(
+        state,
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: Rc::new(RefCell::new(texture)),
+        }],
+    )
+}
+
+/// Blends raw SSAO map with the accumulated result of previous frames. Uses two frame buffers,
+/// that are swapped every frame: one holds the history, another one receives the new result.
+pub struct TemporalAccumulation {
+    shader: Rc<TemporalShader>,
+    framebuffers: [FrameBuffer; 2],
+    current: usize,
+    quad: GeometryBuffer,
+    width: usize,
+    height: usize,
+    previous_view_projection: Option<Matrix4<f32>>,
+}
+
+impl TemporalAccumulation {
+    pub fn new(
+        state: &PipelineState,
+        shader: Rc<TemporalShader>,
+        width: usize,
+        height: usize,
+        pixel_kind: PixelKind,
+    ) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader,
+            framebuffers: [
+                make_history_framebuffer(state, width, height, pixel_kind)?,
+                make_history_framebuffer(state, width, height, pixel_kind)?,
+            ],
+            current: 0,
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            )?,
+            width,
+            height,
+            previous_view_projection: None,
+        })
+    }
+
+    /// Discards accumulated history, the next frame will use current occlusion only.
+    pub fn reset(&mut self) {
+        self.previous_view_projection = None;
+    }
+
+    pub fn result(&self) -> Rc<RefCell<GpuTexture>> {
+        self.framebuffers[self.current].color_attachments()[0]
+            .texture
+            .clone()
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        state: &PipelineState,
+        input: Rc<RefCell<GpuTexture>>,
+        depth: Rc<RefCell<GpuTexture>>,
+        view_projection: Matrix4<f32>,
+    ) -> Result<DrawCallStatistics, FrameworkError> {
+        scope_profile!();
+
+        let viewport = Rect::new(0, 0, self.width as i32, self.height as i32);
+
+        let history = self.result();
+        self.current = (self.current + 1) % self.framebuffers.len();
+
+        let history_valid = self.previous_view_projection.is_some();
+        let previous_view_projection = self
+            .previous_view_projection
+            .replace(view_projection)
+            .unwrap_or(view_projection);
+        let inverse_view_projection = view_projection.try_inverse().unwrap_or_default();
+
+        let shader = &self.shader;
+        self.framebuffers[self.current].draw(
+            &self.quad,
+            state,
+            viewport,
+            &shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            ElementRange::Full,
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(
+                        &shader.world_view_projection_matrix,
+                        &(make_viewport_matrix(viewport)),
+                    )
+                    .set_texture(&shader.current_sampler, &input)
+                    .set_texture(&shader.history_sampler, &history)
+                    .set_texture(&shader.depth_sampler, &depth)
+                    .set_matrix4(&shader.inverse_view_projection, &inverse_view_projection)
+                    .set_matrix4(&shader.previous_view_projection, &previous_view_projection)
+                    .set_f32(&shader.blend_factor, BLEND_FACTOR)
+                    .set_bool(&shader.history_valid, history_valid);
+            },
+        )
+    }
+}
@@ -18,6 +18,9 @@ pub struct PipelineStatistics {
     pub framebuffer_binding_changes: usize,
     /// Total amount of programs was used in the pipeline during the rendering.
     pub program_binding_changes: usize,
+    /// Total amount of draw state changes that were skipped, because the requested state was
+    /// already applied.
+    pub skipped_state_changes: usize,
 }
 
 impl std::ops::AddAssign for PipelineStatistics {
@@ -28,6 +31,7 @@ impl std::ops::AddAssign for PipelineStatistics {
         self.blend_state_changes += rhs.blend_state_changes;
         self.framebuffer_binding_changes += rhs.framebuffer_binding_changes;
         self.program_binding_changes += rhs.program_binding_changes;
+        self.skipped_state_changes += rhs.skipped_state_changes;
     }
 }
 
@@ -43,6 +47,7 @@ impl std::ops::Sub for PipelineStatistics {
             framebuffer_binding_changes: self.framebuffer_binding_changes
                 - rhs.framebuffer_binding_changes,
             program_binding_changes: self.program_binding_changes - rhs.program_binding_changes,
+            skipped_state_changes: self.skipped_state_changes - rhs.skipped_state_changes,
         }
     }
 }
@@ -57,13 +62,15 @@ impl Display for PipelineStatistics {
             \tVAO: {},\n\
             \tFBO: {},\n\
             \tShaders: {},\n\
-            \tBlend: {}",
+            \tBlend: {},\n\
+            \tSkipped: {}",
             self.texture_binding_changes,
             self.vbo_binding_changes,
             self.vao_binding_changes,
             self.framebuffer_binding_changes,
             self.program_binding_changes,
-            self.blend_state_changes
+            self.blend_state_changes,
+            self.skipped_state_changes
         )
     }
 }
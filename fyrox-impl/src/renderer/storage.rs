@@ -14,7 +14,11 @@ use crate::{
     },
 };
 use fxhash::FxHashMap;
-use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, VecDeque},
+    rc::Rc,
+};
 
 /// Generic, texture-based, storage for matrices with somewhat unlimited capacity.
 ///
@@ -97,10 +101,14 @@ impl MatrixStorage {
 
 /// A cache for matrix storages. It supplies the renderer with textures filled with matrices, usually
 /// it is used to give unique storage for every entity that has bone matrices. Every storage in the
-/// cache is re-used in the next frame.
+/// cache is re-used only after the frame, that used it, is finished by the GPU: the storages of a
+/// frame are kept in a ring sized by the amount of frames in flight (see
+/// [`crate::renderer::Renderer::set_frames_in_flight`]).
 pub struct MatrixStorageCache {
     empty: MatrixStorage,
     active_set: FxHashMap<PersistentIdentifier, MatrixStorage>,
+    in_flight: VecDeque<Vec<MatrixStorage>>,
+    frames_in_flight: usize,
     cache: Vec<MatrixStorage>,
 }
 
@@ -110,14 +118,36 @@ impl MatrixStorageCache {
         Ok(Self {
             empty: MatrixStorage::new(state)?,
             active_set: Default::default(),
+            in_flight: Default::default(),
+            frames_in_flight: 1,
             cache: Default::default(),
         })
     }
 
+    /// Sets the amount of frames, that could be processed by the GPU at the same time. The storages
+    /// of every such frame are not re-used until the frame is finished.
+    pub fn set_frames_in_flight(&mut self, frames_in_flight: usize) {
+        self.frames_in_flight = frames_in_flight.max(1);
+        self.recycle();
+    }
+
     /// Clears active set of the cache and prepares the cache for the a new frame.
     pub fn begin_frame(&mut self) {
-        for (_, storage) in self.active_set.drain() {
-            self.cache.push(storage);
+        self.in_flight.push_back(
+            self.active_set
+                .drain()
+                .map(|(_, storage)| storage)
+                .collect(),
+        );
+        self.recycle();
+    }
+
+    // Moves the storages of the finished frames to the cache.
+    fn recycle(&mut self) {
+        while self.in_flight.len() >= self.frames_in_flight {
+            if let Some(storages) = self.in_flight.pop_front() {
+                self.cache.extend(storages);
+            }
         }
     }
 
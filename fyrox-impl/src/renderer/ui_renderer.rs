@@ -14,6 +14,7 @@ use crate::{
         draw::{CommandTexture, DrawingContext},
     },
     renderer::{
+        frame_ring::FrameRing,
         framework::{
             error::FrameworkError,
             framebuffer::{BlendParameters, DrawParameters, FrameBuffer},
@@ -53,6 +54,7 @@ struct UiShader {
     bounds_min: UniformLocation,
     bounds_max: UniformLocation,
     opacity: UniformLocation,
+    global_tint: UniformLocation,
 }
 
 impl UiShader {
@@ -81,6 +83,7 @@ impl UiShader {
             bounds_max: program.uniform_location(state, &ImmutableString::new("boundsMax"))?,
             resolution: program.uniform_location(state, &ImmutableString::new("resolution"))?,
             opacity: program.uniform_location(state, &ImmutableString::new("opacity"))?,
+            global_tint: program.uniform_location(state, &ImmutableString::new("globalTint"))?,
             program,
         })
     }
@@ -89,8 +92,9 @@ impl UiShader {
 /// User interface renderer allows you to render drawing context in specified render target.
 pub struct UiRenderer {
     shader: UiShader,
-    geometry_buffer: GeometryBuffer,
-    clipping_geometry_buffer: GeometryBuffer,
+    // One copy of each buffer per frame in flight.
+    geometry_buffer: FrameRing<GeometryBuffer>,
+    clipping_geometry_buffer: FrameRing<GeometryBuffer>,
 }
 
 /// A set of parameters to render a specified user interface drawing context.
@@ -113,14 +117,10 @@ pub struct UiRenderContext<'a, 'b, 'c> {
     pub texture_cache: &'a mut TextureCache,
 }
 
-impl UiRenderer {
-    pub(in crate::renderer) fn new(state: &PipelineState) -> Result<Self, FrameworkError> {
-        let geometry_buffer = GeometryBufferBuilder::new(ElementKind::Triangle)
-            .with_buffer_builder(
-                BufferBuilder::new::<crate::gui::draw::Vertex>(
-                    GeometryBufferKind::DynamicDraw,
-                    None,
-                )
+fn make_geometry_buffer(state: &PipelineState) -> Result<GeometryBuffer, FrameworkError> {
+    GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(
+            BufferBuilder::new::<crate::gui::draw::Vertex>(GeometryBufferKind::DynamicDraw, None)
                 .with_attribute(AttributeDefinition {
                     location: 0,
                     kind: AttributeKind::Float2,
@@ -139,15 +139,14 @@ impl UiRenderer {
                     normalized: true, // Make sure [0; 255] -> [0; 1]
                     divisor: 0,
                 }),
-            )
-            .build(state)?;
-
-        let clipping_geometry_buffer = GeometryBufferBuilder::new(ElementKind::Triangle)
-            .with_buffer_builder(
-                BufferBuilder::new::<crate::gui::draw::Vertex>(
-                    GeometryBufferKind::DynamicDraw,
-                    None,
-                )
+        )
+        .build(state)
+}
+
+fn make_clipping_geometry_buffer(state: &PipelineState) -> Result<GeometryBuffer, FrameworkError> {
+    GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(
+            BufferBuilder::new::<crate::gui::draw::Vertex>(GeometryBufferKind::DynamicDraw, None)
                 // We're interested only in position. Fragment shader won't run for clipping geometry anyway.
                 .with_attribute(AttributeDefinition {
                     location: 0,
@@ -155,16 +154,40 @@ impl UiRenderer {
                     normalized: false,
                     divisor: 0,
                 }),
-            )
-            .build(state)?;
+        )
+        .build(state)
+}
 
+impl UiRenderer {
+    pub(in crate::renderer) fn new(
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<Self, FrameworkError> {
         Ok(Self {
-            geometry_buffer,
-            clipping_geometry_buffer,
+            geometry_buffer: FrameRing::new(frames_in_flight, || make_geometry_buffer(state))?,
+            clipping_geometry_buffer: FrameRing::new(frames_in_flight, || {
+                make_clipping_geometry_buffer(state)
+            })?,
             shader: UiShader::new(state)?,
         })
     }
 
+    pub(in crate::renderer) fn set_frames_in_flight(
+        &mut self,
+        state: &PipelineState,
+        frames_in_flight: usize,
+    ) -> Result<(), FrameworkError> {
+        self.geometry_buffer
+            .resize(frames_in_flight, || make_geometry_buffer(state))?;
+        self.clipping_geometry_buffer
+            .resize(frames_in_flight, || make_clipping_geometry_buffer(state))
+    }
+
+    pub(in crate::renderer) fn begin_frame(&mut self) {
+        self.geometry_buffer.advance();
+        self.clipping_geometry_buffer.advance();
+    }
+
     /// Renders given UI's drawing context to specified frame buffer.
     pub fn render(
         &mut self,
@@ -185,14 +208,17 @@ impl UiRenderer {
 
         let mut statistics = RenderPassStatistics::default();
 
-        self.geometry_buffer
-            .set_buffer_data(state, 0, drawing_context.get_vertices());
+        let geometry_buffer = self.geometry_buffer.current_mut();
+        let clipping_geometry_buffer = self.clipping_geometry_buffer.current_mut();
 
-        let geometry_buffer = self.geometry_buffer.bind(state);
-        geometry_buffer.set_triangles(drawing_context.get_triangles());
+        geometry_buffer.set_buffer_data(state, 0, drawing_context.get_vertices());
+        geometry_buffer
+            .bind(state)
+            .set_triangles(drawing_context.get_triangles());
 
         let ortho = Matrix4::new_orthographic(0.0, frame_width, frame_height, 0.0, -1.0, 1.0);
         let resolution = Vector2::new(frame_width, frame_height);
+        let global_tint = drawing_context.global_tint();
 
         state.set_scissor_test(true);
 
@@ -221,18 +247,18 @@ impl UiRenderer {
             if let Some(clipping_geometry) = cmd.clipping_geometry.as_ref() {
                 frame_buffer.clear(state, viewport, None, None, Some(0));
 
-                self.clipping_geometry_buffer.set_buffer_data(
+                clipping_geometry_buffer.set_buffer_data(
                     state,
                     0,
                     &clipping_geometry.vertex_buffer,
                 );
-                self.clipping_geometry_buffer
+                clipping_geometry_buffer
                     .bind(state)
                     .set_triangles(&clipping_geometry.triangle_buffer);
 
                 // Draw
                 statistics += frame_buffer.draw(
-                    &self.clipping_geometry_buffer,
+                    clipping_geometry_buffer,
                     state,
                     viewport,
                     &self.shader.program,
@@ -341,7 +367,7 @@ impl UiRenderer {
 
             let shader = &self.shader;
             statistics += frame_buffer.draw(
-                &self.geometry_buffer,
+                geometry_buffer,
                 state,
                 viewport,
                 &self.shader.program,
@@ -409,7 +435,8 @@ impl UiRenderer {
                                 }
                             },
                         )
-                        .set_f32(&shader.opacity, cmd.opacity);
+                        .set_f32(&shader.opacity, cmd.opacity)
+                        .set_srgb_color(&shader.global_tint, &global_tint);
                 },
             )?;
         }
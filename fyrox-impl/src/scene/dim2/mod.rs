@@ -4,5 +4,6 @@
 pub mod collider;
 pub mod joint;
 pub mod physics;
+pub mod polygon;
 pub mod rectangle;
 pub mod rigidbody;
@@ -0,0 +1,535 @@
+//! Polygon is a "2D" node, that renders an arbitrary (convex or concave) filled polygon. It uses
+//! the same vertex format and material as [`super::rectangle::Rectangle`].
+//!
+//! See [`Polygon`] docs for more info.
+
+use crate::scene::node::RdcControlFlow;
+use crate::{
+    core::{
+        algebra::{Point3, Vector2, Vector3, Vector4},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, triangulator::triangulate, Rect, TriangleDefinition},
+        parking_lot::Mutex,
+        pool::Handle,
+        reflect::prelude::*,
+        sstorage::ImmutableString,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    material::{Material, MaterialPropertyOverrides, MaterialResource, PropertyValue},
+    renderer::{
+        self,
+        bundle::{BundleParameters, RenderContext, SortingMode},
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        dim2::rectangle::RectangleVertex,
+        graph::Graph,
+        mesh::{buffer::VertexTrait, RenderPath},
+        node::{Node, NodeTrait},
+    },
+};
+use fyrox_core::value_as_u8_slice;
+use fyrox_graph::BaseSceneGraph;
+use std::ops::{Deref, DerefMut};
+
+/// Polygon is a "2D" node, that renders an arbitrary filled polygon defined by a list of points in
+/// local coordinates (XY plane). The polygon could be convex or concave (it is triangulated using
+/// ear clipping), but it must not be self-intersecting and it must not have holes. It could be
+/// used for health bars with cut corners, custom sprites, simple 2D terrain, etc.
+///
+/// ## Material and colors
+///
+/// Polygons use the same vertex format and the same materials as rectangles, so polygons and
+/// rectangles with the same material are rendered in a single batch. The texture is stretched
+/// over the bounding rectangle of the polygon, the region of the texture could be specified using
+/// [`Self::set_uv_rect`]. Every point could have its own color (see [`Self::set_vertex_colors`]),
+/// otherwise uniform [`Self::color`] is used. Material properties could be overridden for a single
+/// polygon without cloning the material, see [`Self::set_material_overrides`].
+///
+/// ## Sorting
+///
+/// Polygons are sorted the same way as rectangles, see [`Self::set_sorting_mode`] and
+/// [`super::rectangle::Rectangle`] docs for more info.
+///
+/// ## Performance
+///
+/// The triangulation of the polygon is cached, it is recalculated only when the points change.
+/// The triangulation of concave polygons is `O(n^2)`, so avoid changing the points of polygons
+/// with hundreds of points every frame.
+///
+/// ## Example
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::{algebra::Vector2, pool::Handle},
+/// #     scene::{base::BaseBuilder, dim2::polygon::PolygonBuilder, graph::Graph, node::Node},
+/// # };
+/// #
+/// fn create_bar_with_cut_corners(graph: &mut Graph) -> Handle<Node> {
+///     PolygonBuilder::new(BaseBuilder::new())
+///         .with_points(vec![
+///             Vector2::new(-1.0, -0.1),
+///             Vector2::new(0.9, -0.1),
+///             Vector2::new(1.0, 0.0),
+///             Vector2::new(1.0, 0.1),
+///             Vector2::new(-0.9, 0.1),
+///             Vector2::new(-1.0, 0.0),
+///         ])
+///         .build(graph)
+/// }
+/// ```
+#[derive(Reflect, Debug, Clone)]
+pub struct Polygon {
+    base: Base,
+
+    #[reflect(setter = "set_points")]
+    points: InheritableVariable<Vec<Vector2<f32>>>,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[reflect(setter = "set_vertex_colors")]
+    vertex_colors: InheritableVariable<Vec<Color>>,
+
+    #[reflect(setter = "set_uv_rect")]
+    uv_rect: InheritableVariable<Rect<f32>>,
+
+    material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_sorting_mode")]
+    sorting_mode: InheritableVariable<SortingMode>,
+
+    #[reflect(hidden)]
+    material_overrides: InheritableVariable<MaterialPropertyOverrides>,
+
+    #[reflect(hidden)]
+    triangulation: TriangulationCache,
+}
+
+// Triangulation of the points of a polygon, `None` means that the triangulation must be
+// recalculated.
+#[derive(Debug, Default)]
+struct TriangulationCache(Mutex<Option<Triangulation>>);
+
+impl Clone for TriangulationCache {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().clone()))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Triangulation {
+    points: Vec<Vector2<f32>>,
+    triangles: Vec<TriangleDefinition>,
+}
+
+impl Triangulation {
+    fn new(points: &[Vector2<f32>]) -> Self {
+        let points_3d = points
+            .iter()
+            .map(|point| Vector3::new(point.x, point.y, 0.0))
+            .collect::<Vec<_>>();
+        let mut triangles = Vec::new();
+        triangulate(&points_3d, &mut triangles);
+        Self {
+            points: points.to_vec(),
+            triangles: triangles
+                .iter()
+                .map(|[a, b, c]| TriangleDefinition([*a as u32, *b as u32, *c as u32]))
+                .collect(),
+        }
+    }
+}
+
+impl Visit for Polygon {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.base.visit("Base", &mut region)?;
+        self.points.visit("Points", &mut region)?;
+        self.color.visit("Color", &mut region)?;
+        self.vertex_colors.visit("VertexColors", &mut region)?;
+        self.uv_rect.visit("UvRect", &mut region)?;
+        self.material.visit("Material", &mut region)?;
+        let _ = self.sorting_mode.visit("SortingMode", &mut region);
+        let _ = self
+            .material_overrides
+            .visit("MaterialOverrides", &mut region);
+
+        if region.is_reading() {
+            *self.triangulation.0.get_mut() = None;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Polygon {
+    fn default() -> Self {
+        PolygonBuilder::new(BaseBuilder::new()).build_polygon()
+    }
+}
+
+impl Deref for Polygon {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Polygon {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Polygon {
+    fn type_uuid() -> Uuid {
+        uuid!("5c0e7d43-1a9b-4f26-8d3c-b7e2f4a91c58")
+    }
+}
+
+impl Polygon {
+    /// Sets new points of the polygon in local coordinates. The points could be in clockwise or
+    /// counter-clockwise order, at least three points are required for the polygon to be visible.
+    pub fn set_points(&mut self, points: Vec<Vector2<f32>>) -> Vec<Vector2<f32>> {
+        *self.triangulation.0.get_mut() = None;
+        self.points.set_value_and_mark_modified(points)
+    }
+
+    /// Returns current points of the polygon in local coordinates.
+    pub fn points(&self) -> &[Vector2<f32>] {
+        &self.points
+    }
+
+    /// Sets color of the polygon. It is used for every point, that does not have its own color.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the polygon.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Sets individual colors of the points of the polygon, the colors are interpolated across
+    /// the polygon. `i`-th color is used for `i`-th point, points without a color use
+    /// [`Self::color`]. Empty list (default) makes the polygon use the uniform color.
+    pub fn set_vertex_colors(&mut self, vertex_colors: Vec<Color>) -> Vec<Color> {
+        self.vertex_colors
+            .set_value_and_mark_modified(vertex_colors)
+    }
+
+    /// Returns current colors of the points of the polygon. See [`Self::set_vertex_colors`] for
+    /// more info.
+    pub fn vertex_colors(&self) -> &[Color] {
+        &self.vertex_colors
+    }
+
+    /// Sets a region of the texture, that is stretched over the bounding rectangle of the
+    /// polygon. See [`super::rectangle::Rectangle::set_uv_rect`] for more info.
+    pub fn set_uv_rect(&mut self, uv_rect: Rect<f32>) -> Rect<f32> {
+        self.uv_rect.set_value_and_mark_modified(uv_rect)
+    }
+
+    /// Returns a region of the texture, that is used by the polygon.
+    pub fn uv_rect(&self) -> Rect<f32> {
+        *self.uv_rect
+    }
+
+    /// Returns a reference to the current material used by the polygon.
+    pub fn material(&self) -> &InheritableVariable<MaterialResource> {
+        &self.material
+    }
+
+    /// Returns a reference to the current material used by the polygon.
+    pub fn material_mut(&mut self) -> &mut InheritableVariable<MaterialResource> {
+        &mut self.material
+    }
+
+    /// Sets new sorting mode of the polygon. It defines the order of rendering of the polygon
+    /// relative to other transparent objects. Polygons with any mode other than
+    /// [`SortingMode::Distance`] are not batched with each other, even if they share a material.
+    /// See [`SortingMode`] docs for more info.
+    pub fn set_sorting_mode(&mut self, sorting_mode: SortingMode) -> SortingMode {
+        self.sorting_mode.set_value_and_mark_modified(sorting_mode)
+    }
+
+    /// Returns current sorting mode of the polygon.
+    pub fn sorting_mode(&self) -> SortingMode {
+        *self.sorting_mode
+    }
+
+    /// Sets new set of material property overrides. See [`MaterialPropertyOverrides`] docs for more
+    /// info.
+    pub fn set_material_overrides(
+        &mut self,
+        overrides: MaterialPropertyOverrides,
+    ) -> MaterialPropertyOverrides {
+        self.material_overrides
+            .set_value_and_mark_modified(overrides)
+    }
+
+    /// Returns current set of material property overrides.
+    pub fn material_overrides(&self) -> &MaterialPropertyOverrides {
+        &self.material_overrides
+    }
+
+    /// Overrides a value of the material property with the given name for this polygon only, the
+    /// material itself is left unchanged. Returns `false` if the type of the value is not supported
+    /// as an override (see [`MaterialPropertyOverrides`] docs).
+    pub fn set_material_property_override(
+        &mut self,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) -> bool {
+        self.material_overrides
+            .get_value_mut_and_mark_modified()
+            .set(name, value)
+    }
+
+    /// Removes an override of the material property with the given name and returns its value
+    /// (if any). The value of the material will be used for rendering.
+    pub fn remove_material_property_override(
+        &mut self,
+        name: &ImmutableString,
+    ) -> Option<PropertyValue> {
+        self.material_overrides
+            .get_value_mut_and_mark_modified()
+            .remove(name)
+    }
+}
+
+impl NodeTrait for Polygon {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        if self.points.is_empty() {
+            AxisAlignedBoundingBox::collapsed()
+        } else {
+            let mut aabb = AxisAlignedBoundingBox::default();
+            for point in self.points.iter() {
+                aabb.add_point(Vector3::new(point.x, point.y, 0.0));
+            }
+            aabb
+        }
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn collect_render_data(&self, ctx: &mut RenderContext) -> RdcControlFlow {
+        if self.points.len() < 3
+            || !self.global_visibility()
+            || !self.is_globally_enabled()
+            || (self.frustum_culling()
+                && !ctx
+                    .frustum
+                    .map_or(true, |f| f.is_intersects_aabb(&self.world_bounding_box())))
+        {
+            return RdcControlFlow::Continue;
+        }
+
+        if renderer::is_shadow_pass(ctx.render_pass_name) {
+            return RdcControlFlow::Continue;
+        }
+
+        let mut cache = self.triangulation.0.lock();
+        // The points could be changed without the setter (for example, by inheritance), so the
+        // cached triangulation is validated by the points as well.
+        let triangulation = match cache.take() {
+            Some(triangulation) if triangulation.points == *self.points => triangulation,
+            _ => Triangulation::new(&self.points),
+        };
+        let triangulation = cache.insert(triangulation);
+        let triangles = &triangulation.triangles;
+        if triangles.is_empty() {
+            return RdcControlFlow::Continue;
+        }
+
+        let global_transform = self.global_transform();
+        let bounds = self.local_bounding_box();
+        let size = (bounds.max - bounds.min).xy();
+        let uv_rect = *self.uv_rect;
+
+        let vertices = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                // Texture is mapped the same way as on rectangles: left side of the texture is
+                // on the positive X axis and top side is on the positive Y axis.
+                let relative = Vector2::new(
+                    if size.x > f32::EPSILON {
+                        (bounds.max.x - point.x) / size.x
+                    } else {
+                        0.0
+                    },
+                    if size.y > f32::EPSILON {
+                        (bounds.max.y - point.y) / size.y
+                    } else {
+                        0.0
+                    },
+                );
+
+                RectangleVertex {
+                    position: global_transform
+                        .transform_point(&Point3::new(point.x, point.y, 0.0))
+                        .coords,
+                    tex_coord: Vector2::new(
+                        uv_rect.x() + relative.x * uv_rect.w(),
+                        uv_rect.y() + relative.y * uv_rect.h(),
+                    ),
+                    color: self
+                        .vertex_colors
+                        .get(index)
+                        .cloned()
+                        .unwrap_or(*self.color),
+                    clip_rect: Vector4::new(-f32::MAX, -f32::MAX, f32::MAX, f32::MAX),
+                    wrap_mode: Vector2::repeat(-1.0),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let sort_index =
+            ctx.calculate_sorting_index_with_mode(*self.sorting_mode, self.global_position());
+
+        ctx.storage.push_triangles(
+            RectangleVertex::layout(),
+            &BundleParameters::new(&self.material, RenderPath::Forward, sort_index)
+                .with_material_overrides(&self.material_overrides)
+                .with_separation_by_sort_index(*self.sorting_mode != SortingMode::Distance),
+            self.self_handle,
+            &mut move |mut vertex_buffer, mut triangle_buffer| {
+                let start_vertex_index = vertex_buffer.vertex_count();
+
+                for vertex in vertices.iter() {
+                    vertex_buffer
+                        .push_vertex_raw(value_as_u8_slice(vertex))
+                        .unwrap();
+                }
+
+                triangle_buffer
+                    .push_triangles_iter_with_offset(start_vertex_index, triangles.iter().cloned());
+            },
+        );
+
+        RdcControlFlow::Continue
+    }
+}
+
+/// Allows you to create polygon in declarative manner.
+pub struct PolygonBuilder {
+    base_builder: BaseBuilder,
+    points: Vec<Vector2<f32>>,
+    color: Color,
+    vertex_colors: Vec<Color>,
+    uv_rect: Rect<f32>,
+    material: MaterialResource,
+    sorting_mode: SortingMode,
+    material_overrides: MaterialPropertyOverrides,
+}
+
+impl PolygonBuilder {
+    /// Creates new polygon builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            points: vec![
+                Vector2::new(-0.5, -0.5),
+                Vector2::new(0.5, -0.5),
+                Vector2::new(0.0, 0.5),
+            ],
+            color: Color::WHITE,
+            vertex_colors: Default::default(),
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            material: MaterialResource::new_ok(Default::default(), Material::standard_2d()),
+            sorting_mode: Default::default(),
+            material_overrides: Default::default(),
+        }
+    }
+
+    /// Sets desired points of the polygon. See [`Polygon::set_points`] for more info.
+    pub fn with_points(mut self, points: Vec<Vector2<f32>>) -> Self {
+        self.points = points;
+        self
+    }
+
+    /// Sets desired color of the polygon.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets desired colors of the points of the polygon. See [`Polygon::set_vertex_colors`] for
+    /// more info.
+    pub fn with_vertex_colors(mut self, vertex_colors: Vec<Color>) -> Self {
+        self.vertex_colors = vertex_colors;
+        self
+    }
+
+    /// Sets desired portion of the texture for the polygon. See [`Polygon::set_uv_rect`] for
+    /// more info.
+    pub fn with_uv_rect(mut self, uv_rect: Rect<f32>) -> Self {
+        self.uv_rect = uv_rect;
+        self
+    }
+
+    /// Sets the desired material of the polygon.
+    pub fn with_material(mut self, material: MaterialResource) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Sets the desired sorting mode of the polygon. See [`Polygon::set_sorting_mode`] for more
+    /// info.
+    pub fn with_sorting_mode(mut self, sorting_mode: SortingMode) -> Self {
+        self.sorting_mode = sorting_mode;
+        self
+    }
+
+    /// Sets the desired set of material property overrides. See
+    /// [`Polygon::set_material_overrides`] for more info.
+    pub fn with_material_overrides(
+        mut self,
+        material_overrides: MaterialPropertyOverrides,
+    ) -> Self {
+        self.material_overrides = material_overrides;
+        self
+    }
+
+    /// Creates new [`Polygon`] instance.
+    pub fn build_polygon(self) -> Polygon {
+        Polygon {
+            base: self.base_builder.build_base(),
+            points: self.points.into(),
+            color: self.color.into(),
+            vertex_colors: self.vertex_colors.into(),
+            uv_rect: self.uv_rect.into(),
+            material: self.material.into(),
+            sorting_mode: self.sorting_mode.into(),
+            material_overrides: self.material_overrides.into(),
+            triangulation: Default::default(),
+        }
+    }
+
+    /// Creates new [`Polygon`] instance.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_polygon())
+    }
+
+    /// Creates new [`Polygon`] instance and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
@@ -6,18 +6,23 @@
 use crate::scene::node::RdcControlFlow;
 use crate::{
     core::{
-        algebra::{Point3, Vector2, Vector3},
+        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
         color::Color,
-        math::{aabb::AxisAlignedBoundingBox, Rect, TriangleDefinition},
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext, Rect, TriangleDefinition},
         pool::Handle,
         reflect::prelude::*,
+        sstorage::ImmutableString,
         uuid::{uuid, Uuid},
         variable::InheritableVariable,
         visitor::prelude::*,
         TypeUuidProvider,
     },
-    material::{self, Material, MaterialResource},
-    renderer::{self, bundle::RenderContext},
+    material::{self, Material, MaterialPropertyOverrides, MaterialResource, PropertyValue},
+    renderer::{
+        self,
+        bundle::{BundleParameters, RenderContext, SortingMode},
+    },
+    resource::texture::TextureWrapMode,
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -45,6 +50,12 @@ pub struct RectangleVertex {
     pub tex_coord: Vector2<f32>,
     /// Diffuse color.
     pub color: Color,
+    /// Clipping rectangle in world space, stored as `(min_x, min_y, max_x, max_y)`. Every fragment
+    /// outside of the rectangle will be discarded.
+    pub clip_rect: Vector4<f32>,
+    /// Wrapping modes of texture coordinates along both axes, stored as [`TextureWrapMode`] index
+    /// or `-1.0` if texture coordinates must not be modified.
+    pub wrap_mode: Vector2<f32>,
 }
 
 impl VertexTrait for RectangleVertex {
@@ -74,6 +85,22 @@ impl VertexTrait for RectangleVertex {
                 shader_location: 2,
                 normalized: true,
             },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom0,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 0,
+                shader_location: 3,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom1,
+                data_type: VertexAttributeDataType::F32,
+                size: 2,
+                divisor: 0,
+                shader_location: 4,
+                normalized: false,
+            },
         ]
     }
 }
@@ -83,6 +110,8 @@ impl PartialEq for RectangleVertex {
         self.position == other.position
             && self.tex_coord == other.tex_coord
             && self.color == other.color
+            && self.clip_rect == other.clip_rect
+            && self.wrap_mode == other.wrap_mode
     }
 }
 
@@ -102,6 +131,131 @@ impl Hash for RectangleVertex {
     }
 }
 
+/// Per-instance data of an instanced rectangle. Every instance is drawn as a unit quad (built with
+/// [`RectangleVertex`] layout), that is transformed by the world matrix of the instance. See
+/// [`crate::renderer::bundle::RenderDataBundleStorageTrait::push_rectangle_instances`] for more
+/// info.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(C)] // OpenGL expects this structure packed as in C
+pub struct RectangleInstance {
+    /// World transform of the instance. The unit quad is centered at the origin and lies in XY
+    /// plane, so the scale of the matrix defines the size of the rectangle.
+    pub world_transform: Matrix4<f32>,
+    /// Diffuse color of the instance.
+    pub color: Color,
+    /// Texture coordinates rectangle, stored as `(x, y, width, height)`.
+    pub uv_rect: Vector4<f32>,
+}
+
+impl Default for RectangleInstance {
+    fn default() -> Self {
+        Self {
+            world_transform: Matrix4::identity(),
+            color: Color::WHITE,
+            uv_rect: Vector4::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl RectangleInstance {
+    /// Creates a new instance with the given world transform, color and texture coordinates
+    /// rectangle.
+    pub fn new(world_transform: Matrix4<f32>, color: Color, uv_rect: Rect<f32>) -> Self {
+        Self {
+            world_transform,
+            color,
+            uv_rect: Vector4::new(
+                uv_rect.position.x,
+                uv_rect.position.y,
+                uv_rect.size.x,
+                uv_rect.size.y,
+            ),
+        }
+    }
+
+    /// Returns vertices of the unit quad, that is used as the base geometry for every instance.
+    /// Vertices are ordered as left-top, right-top, left-bottom, right-bottom.
+    pub fn unit_quad() -> [RectangleVertex; 4] {
+        let vertex = |x: f32, y: f32| RectangleVertex {
+            // Left side of the texture is mapped to the positive X axis, the same as for
+            // rectangles.
+            position: Vector3::new(0.5 - x, 0.5 - y, 0.0),
+            tex_coord: Vector2::new(x, y),
+            color: Color::WHITE,
+            clip_rect: Vector4::new(-f32::MAX, -f32::MAX, f32::MAX, f32::MAX),
+            wrap_mode: Vector2::repeat(-1.0),
+        };
+        [
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(0.0, 1.0),
+            vertex(1.0, 1.0),
+        ]
+    }
+
+    /// Returns triangles of the unit quad, see [`Self::unit_quad`].
+    pub fn unit_quad_triangles() -> [TriangleDefinition; 2] {
+        [TriangleDefinition([1, 0, 2]), TriangleDefinition([2, 3, 1])]
+    }
+}
+
+impl VertexTrait for RectangleInstance {
+    fn layout() -> &'static [VertexAttributeDescriptor] {
+        // Instance attributes are placed right after the attributes of `RectangleVertex`, the
+        // world matrix occupies four consecutive locations.
+        &[
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom0,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 1,
+                shader_location: 5,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom1,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 1,
+                shader_location: 6,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom2,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 1,
+                shader_location: 7,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Custom3,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 1,
+                shader_location: 8,
+                normalized: false,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::Color,
+                data_type: VertexAttributeDataType::U8,
+                size: 4,
+                divisor: 1,
+                shader_location: 9,
+                normalized: true,
+            },
+            VertexAttributeDescriptor {
+                usage: VertexAttributeUsage::TexCoord0,
+                data_type: VertexAttributeDataType::F32,
+                size: 4,
+                divisor: 1,
+                shader_location: 10,
+                normalized: false,
+            },
+        ]
+    }
+}
+
 /// Rectangle is the simplest "2D" node, it can be used to create "2D" graphics. 2D is in quotes
 /// here because the node is actually a 3D node, like everything else in the engine.
 ///
@@ -151,6 +305,56 @@ impl Hash for RectangleVertex {
 /// image, but just changing portion for rendering. Keep in mind that the coordinates are normalized
 /// which means `[0; 0]` corresponds to top-left corner of the texture and `[1; 1]` corresponds to
 /// right-bottom corner.
+///
+/// ## Pixel-perfect rendering
+///
+/// Pixel-art games usually require the vertices of every sprite to be aligned with the pixel grid of
+/// the screen, otherwise sprites will "shimmer" when moving. Use [`Self::set_pixel_snap`] to snap
+/// the vertices of the rectangle to the nearest pixel of the viewport.
+///
+/// ## Sorting
+///
+/// Rectangles are rendered using forward renderer and they're sorted by their distance to the camera
+/// by default. This could be changed using [`Self::set_sorting_mode`], for example isometric games
+/// could use [`SortingMode::IsometricY`] to sort rectangles by their layer and Y coordinate.
+///
+/// ## Clipping
+///
+/// Every rectangle could be clipped by an arbitrary rectangle in world space (see [`Self::set_clip_rect`]).
+/// Every part of the rectangle outside of the clipping rectangle won't be rendered. It could be used to
+/// create scrollable views in world space without using stencil buffer. Clipping is performed by the
+/// standard 2D shader, custom shaders must read `vertexClipRect` attribute (`location = 3`) and discard
+/// the fragments themselves.
+///
+/// ## Texture wrapping
+///
+/// UV rectangle could exceed `[0; 1]` range to tile the texture, in this case texture coordinates
+/// are wrapped according to wrap modes of the texture. Since textures are shared, it is not always
+/// possible to change their wrap modes, so each rectangle could override them using
+/// [`Self::set_wrap_mode`]. The override is applied by the standard 2D shader, custom shaders must
+/// read `vertexWrapMode` attribute (`location = 4`) and wrap texture coordinates themselves.
+///
+/// ## Material property overrides
+///
+/// Materials are usually shared across many rectangles, and cloning a material just to change a
+/// single value (for example, dissolve amount) breaks batching. Instead, each rectangle could
+/// override values of simple (non-array, non-texture) material properties using
+/// [`Self::set_material_property_override`]. Rectangles with the same material and the same set of
+/// overrides are still rendered in a single draw call. See [`MaterialPropertyOverrides`] docs for
+/// the list of supported property types.
+///
+/// ```rust
+/// # use fyrox_impl::{
+/// #     core::sstorage::ImmutableString, material::PropertyValue,
+/// #     scene::dim2::rectangle::Rectangle,
+/// # };
+/// fn set_dissolve_amount(rect: &mut Rectangle, amount: f32) {
+///     rect.set_material_property_override(
+///         ImmutableString::new("dissolveAmount"),
+///         PropertyValue::Float(amount),
+///     );
+/// }
+/// ```
 #[derive(Reflect, Debug, Clone)]
 pub struct Rectangle {
     base: Base,
@@ -158,10 +362,129 @@ pub struct Rectangle {
     #[reflect(setter = "set_color")]
     color: InheritableVariable<Color>,
 
+    #[reflect(setter = "set_corner_colors")]
+    corner_colors: InheritableVariable<Option<[Color; 4]>>,
+
     #[reflect(setter = "set_uv_rect")]
     uv_rect: InheritableVariable<Rect<f32>>,
 
     material: InheritableVariable<MaterialResource>,
+
+    #[reflect(setter = "set_nine_slice")]
+    nine_slice: InheritableVariable<Option<NineSlice>>,
+
+    #[reflect(setter = "set_flip_x")]
+    flip_x: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_flip_y")]
+    flip_y: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_pixel_snap")]
+    pixel_snap: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_sorting_mode")]
+    sorting_mode: InheritableVariable<SortingMode>,
+
+    #[reflect(setter = "set_render_path")]
+    render_path: InheritableVariable<RenderPath>,
+
+    #[reflect(setter = "set_clip_rect")]
+    clip_rect: InheritableVariable<Option<Rect<f32>>>,
+
+    #[reflect(setter = "set_wrap_mode")]
+    wrap_mode: InheritableVariable<Option<RectangleWrapMode>>,
+
+    #[reflect(setter = "set_pivot")]
+    pivot: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(hidden)]
+    material_overrides: InheritableVariable<MaterialPropertyOverrides>,
+}
+
+/// Wrapping modes of texture coordinates of a rectangle along both axes. See
+/// [`Rectangle::set_wrap_mode`] for more info.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Reflect, Visit)]
+pub struct RectangleWrapMode {
+    /// Wrapping mode along horizontal axis.
+    pub s: TextureWrapMode,
+    /// Wrapping mode along vertical axis.
+    pub t: TextureWrapMode,
+}
+
+impl RectangleWrapMode {
+    /// Creates new wrapping modes.
+    pub fn new(s: TextureWrapMode, t: TextureWrapMode) -> Self {
+        Self { s, t }
+    }
+
+    /// Creates new wrapping modes, that are the same along both axes.
+    pub fn uniform(mode: TextureWrapMode) -> Self {
+        Self { s: mode, t: mode }
+    }
+}
+
+impl TypeUuidProvider for RectangleWrapMode {
+    fn type_uuid() -> Uuid {
+        uuid!("8a4c0f2e-6d1b-4f7a-b3e5-2c9d7e1f0a64")
+    }
+}
+
+/// Border insets of a rectangle in nine-slice mode. See [`Rectangle::set_nine_slice`] for more
+/// info.
+#[derive(Copy, Clone, Debug, PartialEq, Reflect, Visit)]
+pub struct NineSlice {
+    /// Width of the left border as a fraction of the width of the texture region (uv rect), must
+    /// be in `[0; 1]` range.
+    pub left: f32,
+    /// Width of the right border as a fraction of the width of the texture region (uv rect), must
+    /// be in `[0; 1]` range.
+    pub right: f32,
+    /// Height of the top border as a fraction of the height of the texture region (uv rect), must
+    /// be in `[0; 1]` range.
+    pub top: f32,
+    /// Height of the bottom border as a fraction of the height of the texture region (uv rect),
+    /// must be in `[0; 1]` range.
+    pub bottom: f32,
+    /// Size of the entire texture region in world units. It defines the size of the borders, for
+    /// example the left border will always be `left * texture_size.x` units wide. Default is
+    /// `(1, 1)`, which means that an unscaled rectangle looks the same as without nine-slice mode.
+    pub texture_size: Vector2<f32>,
+}
+
+impl Default for NineSlice {
+    fn default() -> Self {
+        Self {
+            left: 0.0,
+            right: 0.0,
+            top: 0.0,
+            bottom: 0.0,
+            texture_size: Vector2::new(1.0, 1.0),
+        }
+    }
+}
+
+impl NineSlice {
+    /// Creates new nine-slice insets with the given size of the texture region in world units.
+    pub fn new(left: f32, right: f32, top: f32, bottom: f32, texture_size: Vector2<f32>) -> Self {
+        Self {
+            left,
+            right,
+            top,
+            bottom,
+            texture_size,
+        }
+    }
+
+    /// Creates new nine-slice insets, that are the same on every side.
+    pub fn uniform(inset: f32, texture_size: Vector2<f32>) -> Self {
+        Self::new(inset, inset, inset, inset, texture_size)
+    }
+}
+
+impl TypeUuidProvider for NineSlice {
+    fn type_uuid() -> Uuid {
+        uuid!("3f6e2b91-7c4d-4a85-9e1f-d2b8c5a70e36")
+    }
 }
 
 impl Visit for Rectangle {
@@ -182,7 +505,20 @@ impl Visit for Rectangle {
 
         self.base.visit("Base", &mut region)?;
         self.color.visit("Color", &mut region)?;
+        let _ = self.corner_colors.visit("CornerColors", &mut region);
         let _ = self.uv_rect.visit("UvRect", &mut region);
+        let _ = self.nine_slice.visit("NineSlice", &mut region);
+        let _ = self.flip_x.visit("FlipX", &mut region);
+        let _ = self.flip_y.visit("FlipY", &mut region);
+        let _ = self.pixel_snap.visit("PixelSnap", &mut region);
+        let _ = self.sorting_mode.visit("SortingMode", &mut region);
+        let _ = self.render_path.visit("RenderPath", &mut region);
+        let _ = self.clip_rect.visit("ClipRect", &mut region);
+        let _ = self.wrap_mode.visit("WrapMode", &mut region);
+        let _ = self.pivot.visit("Pivot", &mut region);
+        let _ = self
+            .material_overrides
+            .visit("MaterialOverrides", &mut region);
 
         Ok(())
     }
@@ -193,11 +529,22 @@ impl Default for Rectangle {
         Self {
             base: Default::default(),
             color: Default::default(),
+            corner_colors: Default::default(),
             uv_rect: InheritableVariable::new_modified(Rect::new(0.0, 0.0, 1.0, 1.0)),
             material: InheritableVariable::new_modified(MaterialResource::new_ok(
                 Default::default(),
                 Material::standard_2d(),
             )),
+            nine_slice: Default::default(),
+            flip_x: InheritableVariable::new_modified(false),
+            flip_y: InheritableVariable::new_modified(false),
+            pixel_snap: InheritableVariable::new_modified(false),
+            sorting_mode: Default::default(),
+            render_path: InheritableVariable::new_modified(RenderPath::Forward),
+            clip_rect: Default::default(),
+            wrap_mode: Default::default(),
+            pivot: InheritableVariable::new_modified(Vector2::repeat(0.5)),
+            material_overrides: Default::default(),
         }
     }
 }
@@ -243,6 +590,31 @@ impl Rectangle {
         self.color.set_value_and_mark_modified(color)
     }
 
+    /// Sets individual colors for every corner of the rectangle, the colors are bilinearly
+    /// interpolated across the whole rectangle (including every slice in nine-slice mode). It could
+    /// be used for gradients and simple lighting effects. The corners are defined in local space of
+    /// the rectangle in the following order:
+    ///
+    /// - `[0]` - left-top corner, `+X +Y` side of the local space.
+    /// - `[1]` - right-top corner, `-X +Y` side of the local space.
+    /// - `[2]` - left-bottom corner, `+X -Y` side of the local space.
+    /// - `[3]` - right-bottom corner, `-X -Y` side of the local space.
+    ///
+    /// The colors are attached to the corners of the rectangle and not to the texture, so
+    /// [`Self::set_flip_x`], [`Self::set_flip_y`] and [`Self::set_uv_rect`] do not affect them.
+    /// When set, the colors are used instead of [`Self::color`], `None` (default) makes the
+    /// rectangle use the uniform color again.
+    pub fn set_corner_colors(&mut self, corner_colors: Option<[Color; 4]>) -> Option<[Color; 4]> {
+        self.corner_colors
+            .set_value_and_mark_modified(corner_colors)
+    }
+
+    /// Returns current colors of the corners of the rectangle, if any. See
+    /// [`Self::set_corner_colors`] for more info.
+    pub fn corner_colors(&self) -> Option<[Color; 4]> {
+        *self.corner_colors
+    }
+
     /// Returns a rectangle that defines the region in texture which will be rendered. The coordinates are normalized
     /// which means `[0; 0]` corresponds to top-left corner of the texture and `[1; 1]` corresponds to right-bottom
     /// corner.
@@ -261,13 +633,270 @@ impl Rectangle {
     pub fn set_uv_rect(&mut self, uv_rect: Rect<f32>) -> Rect<f32> {
         self.uv_rect.set_value_and_mark_modified(uv_rect)
     }
+
+    /// Selects a frame of a sprite sheet, that is a uniform grid of `columns x rows` frames of the
+    /// same size. Frames are numbered row by row, starting from the left-top corner of the texture.
+    /// Indices, that are out of range, wrap around, so an ever-increasing counter could be used to
+    /// loop an animation. Zero amount of columns or rows is treated as one. The method just
+    /// calculates and sets [`Self::uv_rect`], so the rectangle could be overridden at any time using
+    /// [`Self::set_uv_rect`]. Returns the previous uv rectangle.
+    pub fn set_frame(&mut self, columns: u32, rows: u32, index: u32) -> Rect<f32> {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let index = index % columns.saturating_mul(rows);
+        let width = 1.0 / columns as f32;
+        let height = 1.0 / rows as f32;
+        self.set_uv_rect(Rect::new(
+            (index % columns) as f32 * width,
+            (index / columns) as f32 * height,
+            width,
+            height,
+        ))
+    }
+
+    /// Enables or disables nine-slice (9-patch) mode. In this mode the rectangle is split into 3x3
+    /// quads using the given border insets: the corners keep their size when the rectangle is
+    /// scaled, the edges are stretched along one axis and the center is stretched along both axes.
+    /// It allows you to use a single rectangle for scalable 2D frames and panels. If the
+    /// rectangle is too small for its borders, the borders are shrunk proportionally. `None`
+    /// (default) disables the mode.
+    pub fn set_nine_slice(&mut self, nine_slice: Option<NineSlice>) -> Option<NineSlice> {
+        self.nine_slice.set_value_and_mark_modified(nine_slice)
+    }
+
+    /// Returns current nine-slice insets of the rectangle, if any. See [`Self::set_nine_slice`]
+    /// for more info.
+    pub fn nine_slice(&self) -> Option<NineSlice> {
+        *self.nine_slice
+    }
+
+    /// Mirrors the texture of the rectangle horizontally. The flip is applied on top of the current
+    /// [`Self::uv_rect`], so sprite sheet animations could keep changing the rectangle while the
+    /// flip stays the same. It is useful for 2D characters facing left or right. Disabled by
+    /// default.
+    pub fn set_flip_x(&mut self, flip_x: bool) -> bool {
+        self.flip_x.set_value_and_mark_modified(flip_x)
+    }
+
+    /// Returns `true` if the texture of the rectangle is mirrored horizontally, `false` - otherwise.
+    pub fn is_flip_x(&self) -> bool {
+        *self.flip_x
+    }
+
+    /// Mirrors the texture of the rectangle vertically. The flip is applied on top of the current
+    /// [`Self::uv_rect`], see [`Self::set_flip_x`] for more info. Disabled by default.
+    pub fn set_flip_y(&mut self, flip_y: bool) -> bool {
+        self.flip_y.set_value_and_mark_modified(flip_y)
+    }
+
+    /// Returns `true` if the texture of the rectangle is mirrored vertically, `false` - otherwise.
+    pub fn is_flip_y(&self) -> bool {
+        *self.flip_y
+    }
+
+    /// Enables or disables snapping of the vertices of the rectangle to the nearest pixel of the
+    /// viewport. It is useful for pixel-art games, to prevent sprites from "shimmering" when moving.
+    /// Disabled by default.
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) -> bool {
+        self.pixel_snap.set_value_and_mark_modified(pixel_snap)
+    }
+
+    /// Returns `true` if the vertices of the rectangle are snapped to the pixel grid, `false` -
+    /// otherwise.
+    pub fn is_pixel_snap(&self) -> bool {
+        *self.pixel_snap
+    }
+
+    /// Sets new sorting mode of the rectangle. It defines the order of rendering of the rectangle
+    /// relative to other transparent objects. Rectangles with any mode other than
+    /// [`SortingMode::Distance`] are not batched with each other, even if they share a material,
+    /// because their order is defined by their own sort indices. See [`SortingMode`] docs for more
+    /// info.
+    pub fn set_sorting_mode(&mut self, sorting_mode: SortingMode) -> SortingMode {
+        self.sorting_mode.set_value_and_mark_modified(sorting_mode)
+    }
+
+    /// Returns current sorting mode of the rectangle.
+    pub fn sorting_mode(&self) -> SortingMode {
+        *self.sorting_mode
+    }
+
+    /// Sets new render path for the rectangle. Default is [`RenderPath::Forward`], which supports
+    /// translucency and is used by the standard 2D material. [`RenderPath::Deferred`] allows the
+    /// rectangle to receive deferred lighting, but the shader of its material must have a
+    /// `GBuffer` render pass, otherwise the rectangle won't be rendered at all.
+    pub fn set_render_path(&mut self, render_path: RenderPath) -> RenderPath {
+        self.render_path.set_value_and_mark_modified(render_path)
+    }
+
+    /// Returns current render path of the rectangle.
+    pub fn render_path(&self) -> RenderPath {
+        *self.render_path
+    }
+
+    /// Sets new clipping rectangle in world space (XY plane), where the position of the rectangle
+    /// defines its bottom-left corner. Every part of the rectangle outside of the clipping rectangle
+    /// will be discarded. `None` disables clipping, which is the default value.
+    pub fn set_clip_rect(&mut self, clip_rect: Option<Rect<f32>>) -> Option<Rect<f32>> {
+        self.clip_rect.set_value_and_mark_modified(clip_rect)
+    }
+
+    /// Returns current clipping rectangle in world space. See [`Self::set_clip_rect`] for more info.
+    pub fn clip_rect(&self) -> Option<Rect<f32>> {
+        *self.clip_rect
+    }
+
+    /// Sets new wrapping modes of texture coordinates, that will be used instead of the wrapping
+    /// modes of the texture. It does not modify the texture, so it is safe to use with shared
+    /// textures. `None` means that the wrapping modes of the texture will be used, which is the
+    /// default value. [`TextureWrapMode::ClampToBorder`] acts like [`TextureWrapMode::ClampToEdge`],
+    /// since there's no way to specify border color per rectangle.
+    pub fn set_wrap_mode(
+        &mut self,
+        wrap_mode: Option<RectangleWrapMode>,
+    ) -> Option<RectangleWrapMode> {
+        self.wrap_mode.set_value_and_mark_modified(wrap_mode)
+    }
+
+    /// Returns current wrapping modes of texture coordinates. See [`Self::set_wrap_mode`] for more
+    /// info.
+    pub fn wrap_mode(&self) -> Option<RectangleWrapMode> {
+        *self.wrap_mode
+    }
+
+    /// Sets new pivot point of the rectangle in normalized coordinates, where `[0; 0]` corresponds
+    /// to the left-top corner and `[1; 1]` corresponds to the right-bottom corner (as they are seen
+    /// on the unflipped texture). The pivot point is placed at the position of the node, so the
+    /// rectangle is rotated and scaled around it. It is useful for sprites, that should be pinned
+    /// at a corner or at a custom point. The default value is `[0.5; 0.5]`, which is the center of
+    /// the rectangle.
+    pub fn set_pivot(&mut self, pivot: Vector2<f32>) -> Vector2<f32> {
+        self.pivot.set_value_and_mark_modified(pivot)
+    }
+
+    /// Returns current pivot point of the rectangle. See [`Self::set_pivot`] for more info.
+    pub fn pivot(&self) -> Vector2<f32> {
+        *self.pivot
+    }
+
+    /// Sets new set of material property overrides. See [`MaterialPropertyOverrides`] docs for more
+    /// info.
+    pub fn set_material_overrides(
+        &mut self,
+        overrides: MaterialPropertyOverrides,
+    ) -> MaterialPropertyOverrides {
+        self.material_overrides
+            .set_value_and_mark_modified(overrides)
+    }
+
+    /// Returns current set of material property overrides.
+    pub fn material_overrides(&self) -> &MaterialPropertyOverrides {
+        &self.material_overrides
+    }
+
+    /// Overrides a value of the material property with the given name for this rectangle only,
+    /// the material itself is left unchanged. Returns `false` if the type of the value is not
+    /// supported as an override (see [`MaterialPropertyOverrides`] docs).
+    pub fn set_material_property_override(
+        &mut self,
+        name: ImmutableString,
+        value: PropertyValue,
+    ) -> bool {
+        self.material_overrides
+            .get_value_mut_and_mark_modified()
+            .set(name, value)
+    }
+
+    /// Removes an override of the material property with the given name and returns its value
+    /// (if any). The value of the material will be used for rendering.
+    pub fn remove_material_property_override(
+        &mut self,
+        name: &ImmutableString,
+    ) -> Option<PropertyValue> {
+        self.material_overrides
+            .get_value_mut_and_mark_modified()
+            .remove(name)
+    }
+}
+
+/// Returns texture coordinates of the corners of the given uv rectangle in the following order:
+/// left-top, right-top, left-bottom, right-bottom. Flipped axes swap the respective corners.
+fn uv_corners(uv_rect: Rect<f32>, flip_x: bool, flip_y: bool) -> [Vector2<f32>; 4] {
+    let (mut left, mut right) = (uv_rect.x(), uv_rect.x() + uv_rect.w());
+    let (mut top, mut bottom) = (uv_rect.y(), uv_rect.y() + uv_rect.h());
+    if flip_x {
+        std::mem::swap(&mut left, &mut right);
+    }
+    if flip_y {
+        std::mem::swap(&mut top, &mut bottom);
+    }
+    [
+        Vector2::new(left, top),
+        Vector2::new(right, top),
+        Vector2::new(left, bottom),
+        Vector2::new(right, bottom),
+    ]
+}
+
+/// Returns positions of the slice lines along one axis of the rectangle in nine-slice mode, both as
+/// fractions of the texture region and as fractions of the rectangle. `scale` is the size of the
+/// rectangle along the axis in world units.
+fn slice_axis(start: f32, end: f32, texture_size: f32, scale: f32) -> ([f32; 4], [f32; 4]) {
+    let (start, end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+    let mut geometry_start = start * texture_size / scale.max(f32::EPSILON);
+    let mut geometry_end = end * texture_size / scale.max(f32::EPSILON);
+    let sum = geometry_start + geometry_end;
+    if sum > 1.0 {
+        geometry_start /= sum;
+        geometry_end /= sum;
+    }
+    (
+        [0.0, start, 1.0 - end, 1.0],
+        [0.0, geometry_start, 1.0 - geometry_end, 1.0],
+    )
+}
+
+/// Snaps the given world-space position to the nearest pixel of the viewport.
+fn snap_to_pixel(
+    position: Vector3<f32>,
+    view_projection: &Matrix4<f32>,
+    inv_view_projection: &Matrix4<f32>,
+    viewport_size: Vector2<f32>,
+) -> Vector3<f32> {
+    let clip_space = view_projection * Point3::from(position).to_homogeneous();
+    if clip_space.w <= f32::EPSILON || viewport_size.x <= 0.0 || viewport_size.y <= 0.0 {
+        return position;
+    }
+
+    let half_size = viewport_size.scale(0.5);
+    let ndc = clip_space.xy().scale(1.0 / clip_space.w);
+    let snapped_x = ((ndc.x + 1.0) * half_size.x).round() / half_size.x - 1.0;
+    let snapped_y = ((ndc.y + 1.0) * half_size.y).round() / half_size.y - 1.0;
+
+    let world_space = inv_view_projection
+        * Vector4::new(
+            snapped_x * clip_space.w,
+            snapped_y * clip_space.w,
+            clip_space.z,
+            clip_space.w,
+        );
+
+    if world_space.w.abs() <= f32::EPSILON {
+        position
+    } else {
+        world_space.xyz().scale(1.0 / world_space.w)
+    }
 }
 
 impl NodeTrait for Rectangle {
     crate::impl_query_component!();
 
     fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
-        AxisAlignedBoundingBox::unit()
+        // Vertices are placed at `pivot - [0; 1]` along both axes.
+        AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(self.pivot.x - 1.0, self.pivot.y - 1.0, -0.5),
+            Vector3::new(self.pivot.x, self.pivot.y, 0.5),
+        )
     }
 
     fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -296,62 +925,133 @@ impl NodeTrait for Rectangle {
 
         let global_transform = self.global_transform();
 
+        let clip_rect = self.clip_rect.map_or(
+            Vector4::new(-f32::MAX, -f32::MAX, f32::MAX, f32::MAX),
+            |clip_rect| {
+                Vector4::new(
+                    clip_rect.x(),
+                    clip_rect.y(),
+                    clip_rect.x() + clip_rect.w(),
+                    clip_rect.y() + clip_rect.h(),
+                )
+            },
+        );
+
+        let wrap_mode = self.wrap_mode.map_or(Vector2::repeat(-1.0), |wrap_mode| {
+            Vector2::new(wrap_mode.s as u32 as f32, wrap_mode.t as u32 as f32)
+        });
+
+        let [left_top, right_top, left_bottom, _] =
+            uv_corners(*self.uv_rect, *self.flip_x, *self.flip_y);
+
+        let [left_top_color, right_top_color, left_bottom_color, right_bottom_color] =
+            self.corner_colors.unwrap_or([*self.color; 4]);
+
+        // The rectangle is a grid of quads, a single quad by default or 3x3 quads in nine-slice
+        // mode. Slice lines are stored as fractions of the texture region and of the rectangle.
+        let (line_count, texture_lines, geometry_lines) =
+            if let Some(nine_slice) = self.nine_slice.as_ref() {
+                let (texture_x, geometry_x) = slice_axis(
+                    nine_slice.left,
+                    nine_slice.right,
+                    nine_slice.texture_size.x,
+                    global_transform.side().norm(),
+                );
+                let (texture_y, geometry_y) = slice_axis(
+                    nine_slice.top,
+                    nine_slice.bottom,
+                    nine_slice.texture_size.y,
+                    global_transform.up().norm(),
+                );
+                (4, [texture_x, texture_y], [geometry_x, geometry_y])
+            } else {
+                let lines = [0.0, 1.0, 1.0, 1.0];
+                (2, [lines; 2], [lines; 2])
+            };
+
+        let pivot = *self.pivot;
+
         type Vertex = RectangleVertex;
 
-        let vertices = [
-            Vertex {
-                position: global_transform
-                    .transform_point(&Point3::new(-0.5, 0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.right_top_corner(),
-                color: *self.color,
-            },
-            Vertex {
-                position: global_transform
-                    .transform_point(&Point3::new(0.5, 0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.left_top_corner(),
-                color: *self.color,
-            },
-            Vertex {
-                position: global_transform
-                    .transform_point(&Point3::new(0.5, -0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.left_bottom_corner(),
-                color: *self.color,
-            },
-            Vertex {
-                position: global_transform
-                    .transform_point(&Point3::new(-0.5, -0.5, 0.0))
-                    .coords,
-                tex_coord: self.uv_rect.right_bottom_corner(),
-                color: *self.color,
-            },
-        ];
+        let mut vertices = [Vertex::default(); 16];
+        for y in 0..line_count {
+            for x in 0..line_count {
+                let texture = Vector2::new(texture_lines[0][x], texture_lines[1][y]);
+                let geometry = Vector2::new(geometry_lines[0][x], geometry_lines[1][y]);
+                let top_color = left_top_color.lerp(right_top_color, geometry.x);
+                let bottom_color = left_bottom_color.lerp(right_bottom_color, geometry.x);
+                vertices[y * line_count + x] = Vertex {
+                    // Left side of the texture is mapped to the positive X axis.
+                    position: global_transform
+                        .transform_point(&Point3::new(
+                            pivot.x - geometry.x,
+                            pivot.y - geometry.y,
+                            0.0,
+                        ))
+                        .coords,
+                    tex_coord: Vector2::new(
+                        left_top.x + (right_top.x - left_top.x) * texture.x,
+                        left_top.y + (left_bottom.y - left_top.y) * texture.y,
+                    ),
+                    color: top_color.lerp(bottom_color, geometry.y),
+                    clip_rect,
+                    wrap_mode,
+                };
+            }
+        }
+        let vertex_count = line_count * line_count;
 
-        let triangles = [TriangleDefinition([0, 1, 2]), TriangleDefinition([2, 3, 0])];
+        if *self.pixel_snap {
+            let view_projection = ctx.projection_matrix * ctx.view_matrix;
+            let inv_view_projection = view_projection.try_inverse().unwrap_or_default();
+            let viewport_size = Vector2::new(ctx.viewport.w() as f32, ctx.viewport.h() as f32);
+            for vertex in vertices[..vertex_count].iter_mut() {
+                vertex.position = snap_to_pixel(
+                    vertex.position,
+                    &view_projection,
+                    &inv_view_projection,
+                    viewport_size,
+                );
+            }
+        }
 
-        let sort_index = ctx.calculate_sorting_index(self.global_position());
+        let mut triangles = [TriangleDefinition::default(); 18];
+        let mut triangle_count = 0;
+        for y in 0..line_count - 1 {
+            for x in 0..line_count - 1 {
+                let index = |x: usize, y: usize| (y * line_count + x) as u32;
+                triangles[triangle_count] =
+                    TriangleDefinition([index(x + 1, y), index(x, y), index(x, y + 1)]);
+                triangles[triangle_count + 1] =
+                    TriangleDefinition([index(x, y + 1), index(x + 1, y + 1), index(x + 1, y)]);
+                triangle_count += 2;
+            }
+        }
+
+        let sort_index =
+            ctx.calculate_sorting_index_with_mode(*self.sorting_mode, self.global_position());
 
         ctx.storage.push_triangles(
             Vertex::layout(),
-            &self.material,
-            RenderPath::Forward,
-            0,
-            sort_index,
-            false,
+            &BundleParameters::new(&self.material, *self.render_path, sort_index)
+                .with_material_overrides(&self.material_overrides)
+                // Rectangles with the same material must keep their relative order, which is
+                // defined only by their own sort indices.
+                .with_separation_by_sort_index(*self.sorting_mode != SortingMode::Distance),
             self.self_handle,
             &mut move |mut vertex_buffer, mut triangle_buffer| {
                 let start_vertex_index = vertex_buffer.vertex_count();
 
-                for vertex in vertices.iter() {
+                for vertex in vertices[..vertex_count].iter() {
                     vertex_buffer
                         .push_vertex_raw(value_as_u8_slice(vertex))
                         .unwrap();
                 }
 
-                triangle_buffer
-                    .push_triangles_iter_with_offset(start_vertex_index, triangles.into_iter());
+                triangle_buffer.push_triangles_iter_with_offset(
+                    start_vertex_index,
+                    triangles[..triangle_count].iter().cloned(),
+                );
             },
         );
 
@@ -363,8 +1063,19 @@ impl NodeTrait for Rectangle {
 pub struct RectangleBuilder {
     base_builder: BaseBuilder,
     color: Color,
+    corner_colors: Option<[Color; 4]>,
     uv_rect: Rect<f32>,
     material: MaterialResource,
+    nine_slice: Option<NineSlice>,
+    flip_x: bool,
+    flip_y: bool,
+    pixel_snap: bool,
+    sorting_mode: SortingMode,
+    render_path: RenderPath,
+    clip_rect: Option<Rect<f32>>,
+    wrap_mode: Option<RectangleWrapMode>,
+    pivot: Vector2<f32>,
+    material_overrides: MaterialPropertyOverrides,
 }
 
 impl RectangleBuilder {
@@ -373,8 +1084,19 @@ impl RectangleBuilder {
         Self {
             base_builder,
             color: Color::WHITE,
+            corner_colors: None,
             uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
             material: MaterialResource::new_ok(Default::default(), Material::standard_2d()),
+            nine_slice: None,
+            flip_x: false,
+            flip_y: false,
+            pixel_snap: false,
+            sorting_mode: Default::default(),
+            render_path: RenderPath::Forward,
+            clip_rect: None,
+            wrap_mode: None,
+            pivot: Vector2::repeat(0.5),
+            material_overrides: Default::default(),
         }
     }
 
@@ -384,6 +1106,13 @@ impl RectangleBuilder {
         self
     }
 
+    /// Sets desired colors of the corners of the rectangle. See [`Rectangle::set_corner_colors`]
+    /// for more info.
+    pub fn with_corner_colors(mut self, corner_colors: Option<[Color; 4]>) -> Self {
+        self.corner_colors = corner_colors;
+        self
+    }
+
     /// Sets desired portion of the texture for the rectangle. See [`Rectangle::set_uv_rect`]
     /// for more info.
     pub fn with_uv_rect(mut self, uv_rect: Rect<f32>) -> Self {
@@ -397,13 +1126,96 @@ impl RectangleBuilder {
         self
     }
 
+    /// Sets the desired nine-slice insets of the rectangle. See [`Rectangle::set_nine_slice`] for
+    /// more info.
+    pub fn with_nine_slice(mut self, nine_slice: Option<NineSlice>) -> Self {
+        self.nine_slice = nine_slice;
+        self
+    }
+
+    /// Sets whether the texture of the rectangle should be mirrored horizontally or not. See
+    /// [`Rectangle::set_flip_x`] for more info.
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    /// Sets whether the texture of the rectangle should be mirrored vertically or not. See
+    /// [`Rectangle::set_flip_y`] for more info.
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Sets whether the vertices of the rectangle should be snapped to the pixel grid or not. See
+    /// [`Rectangle::set_pixel_snap`] for more info.
+    pub fn with_pixel_snap(mut self, pixel_snap: bool) -> Self {
+        self.pixel_snap = pixel_snap;
+        self
+    }
+
+    /// Sets the desired sorting mode of the rectangle. See [`Rectangle::set_sorting_mode`] for more
+    /// info.
+    pub fn with_sorting_mode(mut self, sorting_mode: SortingMode) -> Self {
+        self.sorting_mode = sorting_mode;
+        self
+    }
+
+    /// Sets the desired render path of the rectangle. See [`Rectangle::set_render_path`] for more
+    /// info.
+    pub fn with_render_path(mut self, render_path: RenderPath) -> Self {
+        self.render_path = render_path;
+        self
+    }
+
+    /// Sets the desired clipping rectangle in world space. See [`Rectangle::set_clip_rect`] for more
+    /// info.
+    pub fn with_clip_rect(mut self, clip_rect: Option<Rect<f32>>) -> Self {
+        self.clip_rect = clip_rect;
+        self
+    }
+
+    /// Sets the desired wrapping modes of texture coordinates. See [`Rectangle::set_wrap_mode`] for
+    /// more info.
+    pub fn with_wrap_mode(mut self, wrap_mode: Option<RectangleWrapMode>) -> Self {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    /// Sets the desired pivot point of the rectangle. See [`Rectangle::set_pivot`] for more info.
+    pub fn with_pivot(mut self, pivot: Vector2<f32>) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Sets the desired set of material property overrides. See [`Rectangle::set_material_overrides`]
+    /// for more info.
+    pub fn with_material_overrides(
+        mut self,
+        material_overrides: MaterialPropertyOverrides,
+    ) -> Self {
+        self.material_overrides = material_overrides;
+        self
+    }
+
     /// Creates new [`Rectangle`] instance.
     pub fn build_rectangle(self) -> Rectangle {
         Rectangle {
             base: self.base_builder.build_base(),
             color: self.color.into(),
+            corner_colors: self.corner_colors.into(),
             uv_rect: self.uv_rect.into(),
             material: self.material.into(),
+            nine_slice: self.nine_slice.into(),
+            flip_x: self.flip_x.into(),
+            flip_y: self.flip_y.into(),
+            pixel_snap: self.pixel_snap.into(),
+            sorting_mode: self.sorting_mode.into(),
+            render_path: self.render_path.into(),
+            clip_rect: self.clip_rect.into(),
+            wrap_mode: self.wrap_mode.into(),
+            pivot: self.pivot.into(),
+            material_overrides: self.material_overrides.into(),
         }
     }
 
@@ -417,3 +1229,64 @@ impl RectangleBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            math::Rect,
+            sstorage::ImmutableString,
+        },
+        material::{Material, MaterialResource},
+        renderer::bundle::{ObserverInfo, RenderDataBundleStorage, SortingMode},
+        scene::{
+            base::BaseBuilder, dim2::rectangle::RectangleBuilder, graph::Graph,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn collect_bundles(sorting_mode: SortingMode) -> RenderDataBundleStorage {
+        let mut graph = Graph::new();
+        let material = MaterialResource::new_ok(Default::default(), Material::standard_2d());
+        for y in [1.0, -1.0] {
+            RectangleBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, y, 0.0))
+                        .build(),
+                ),
+            )
+            .with_material(material.clone())
+            .with_sorting_mode(sorting_mode)
+            .build(&mut graph);
+        }
+        graph.update_hierarchical_data();
+
+        RenderDataBundleStorage::from_graph(
+            &graph,
+            ObserverInfo {
+                observer_position: Vector3::new(0.0, 0.0, 5.0),
+                z_near: 0.1,
+                z_far: 10.0,
+                view_matrix: Matrix4::new_translation(&Vector3::new(0.0, 0.0, -5.0)),
+                projection_matrix: Matrix4::new_orthographic(-4.0, 4.0, -4.0, 4.0, 0.1, 10.0),
+                viewport: Rect::new(0, 0, 100, 100),
+            },
+            ImmutableString::new("Forward"),
+        )
+    }
+
+    #[test]
+    fn test_rectangles_with_same_material_are_batched_by_distance() {
+        let storage = collect_bundles(SortingMode::Distance);
+        assert_eq!(storage.bundles.len(), 1);
+    }
+
+    #[test]
+    fn test_rectangles_with_same_material_are_separated_by_sort_index() {
+        let storage = collect_bundles(SortingMode::IsometricY { layer: 0 });
+        assert_eq!(storage.bundles.len(), 2);
+        assert!(storage.bundles[0].sort_index() < storage.bundles[1].sort_index());
+    }
+}
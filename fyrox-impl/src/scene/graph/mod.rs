@@ -721,6 +721,33 @@ impl Graph {
         self.lightmap.as_ref()
     }
 
+    /// Collects every unique material, that is used by the surfaces of meshes and by rectangles of
+    /// the graph. Materials are returned in the order of their first occurrence in the graph. It
+    /// could be used for bulk operations on materials, for example to find unused textures.
+    pub fn collect_materials(&self) -> Vec<MaterialResource> {
+        let mut unique = FxHashSet::default();
+        let mut materials = Vec::new();
+        let mut add = |material: &MaterialResource| {
+            if unique.insert(material.key()) {
+                materials.push(material.clone());
+            }
+        };
+
+        for node in self.linear_iter() {
+            if let Some(mesh) = node.cast::<Mesh>() {
+                for surface in mesh.surfaces() {
+                    add(surface.material());
+                }
+            } else if let Some(rectangle) = node.cast::<dim2::rectangle::Rectangle>() {
+                add(rectangle.material());
+            } else if let Some(polygon) = node.cast::<dim2::polygon::Polygon>() {
+                add(polygon.material());
+            }
+        }
+
+        materials
+    }
+
     fn apply_lightmap(&mut self) {
         // Re-apply lightmap if any. This has to be done after resolve because we must patch surface
         // data at this stage, but if we'd do this before we wouldn't be able to do this because
@@ -1658,9 +1685,11 @@ mod test {
         },
         engine::{self, SerializationContext},
         graph::{BaseSceneGraph, SceneGraph},
+        material::{Material, MaterialResource},
         resource::model::{Model, ModelResourceExtension},
         scene::{
             base::BaseBuilder,
+            dim2::rectangle::RectangleBuilder,
             graph::Graph,
             mesh::{
                 surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
@@ -1801,6 +1830,35 @@ mod test {
         assert_eq!(graph.pool.alive_count(), 4);
     }
 
+    #[test]
+    fn test_collect_materials() {
+        let mut graph = Graph::new();
+
+        let material = MaterialResource::new_ok(Default::default(), Material::standard());
+        let other_material = MaterialResource::new_ok(Default::default(), Material::standard_2d());
+
+        let surface = |material: &MaterialResource| {
+            SurfaceBuilder::new(SurfaceResource::new_ok(
+                ResourceKind::Embedded,
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_material(material.clone())
+            .build()
+        };
+
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![surface(&material), surface(&material)])
+            .build(&mut graph);
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![surface(&other_material)])
+            .build(&mut graph);
+        RectangleBuilder::new(BaseBuilder::new())
+            .with_material(material.clone())
+            .build(&mut graph);
+
+        assert_eq!(graph.collect_materials(), vec![material, other_material]);
+    }
+
     #[test]
     fn test_graph_search() {
         let mut graph = Graph::new();
@@ -3,6 +3,14 @@
 //! parameter - radius at which intensity will be zero. Intensity of light will
 //! be calculated using inverse square root law.
 //!
+//! # Source radius
+//!
+//! By default, point light is an infinitely small (punctual) light source, which produces
+//! sharp specular highlights. Real light sources have physical size, which makes highlights
+//! softer and bigger. Use [`PointLight::set_source_radius`] to set the radius of the light
+//! emitting sphere, specular lighting will then be calculated using spherical area light
+//! approximation.
+//!
 //! # Light scattering
 //!
 //! Point light support light scattering feature - it means that you'll see light
@@ -16,6 +24,29 @@
 //! Point lights supports shadows, but keep in mind - they're very expensive and
 //! can easily ruin performance of your game, especially on low-end hardware. Light
 //! scattering is relatively heavy too.
+//!
+//! # Static shadows caching
+//!
+//! Shadows of a point light could be split in two layers: static and dynamic. Static layer
+//! contains every object with [`crate::scene::base::Mobility::Static`] mobility, it is rendered
+//! once and then reused while the light stays at the same place. Dynamic layer contains every other
+//! object and it is rendered every frame. Both layers are combined when the shadows are sampled.
+//! It makes shadows much cheaper in mostly static scenes with a few moving objects. Use
+//! [`PointLight::set_cache_static_shadows`] to enable it. Keep in mind, that static objects must
+//! not move, otherwise their shadows will stay at the old place.
+//!
+//! # Shadow cascades
+//!
+//! A point shadow map is a cube map, so its angular resolution is the same at any distance from
+//! the light, and shadows close to the light, which occupy large part of the screen, could look
+//! blurry. The shadow map could be split in up to [`PointLight::MAX_SHADOW_CASCADES`]
+//! distance-based cascades, where each cascade covers a range of distances from the light. The
+//! last cascade (the farthest from the light) has the resolution of the shadow map and each
+//! previous cascade has two times more resolution than the next one (limited by the maximum cube
+//! map size of the device). Closer cascades contain only the shadow casters within their range, so
+//! they're usually cheap to render. Use [`PointLight::set_shadow_cascade_splits`] to define the
+//! split distances. By default, there's only one cascade, that covers the whole radius of the
+//! light.
 
 use crate::{
     core::{
@@ -51,6 +82,23 @@ pub struct PointLight {
     #[reflect(min_value = 0.0, step = 0.1)]
     #[reflect(setter = "set_radius")]
     radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.01)]
+    #[reflect(setter = "set_source_radius")]
+    #[visit(optional)]
+    source_radius: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_cache_static_shadows")]
+    #[visit(optional)]
+    cache_static_shadows: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_shadow_map_size")]
+    #[visit(optional)]
+    shadow_map_size: InheritableVariable<Option<usize>>,
+
+    #[reflect(setter = "set_shadow_cascade_splits")]
+    #[visit(optional)]
+    shadow_cascade_splits: InheritableVariable<Vec<f32>>,
 }
 
 impl Deref for PointLight {
@@ -74,6 +122,9 @@ impl TypeUuidProvider for PointLight {
 }
 
 impl PointLight {
+    /// Maximum amount of shadow cascades. See [module docs](self#shadow-cascades) for more info.
+    pub const MAX_SHADOW_CASCADES: usize = 3;
+
     /// Returns a reference to base light.    
     pub fn base_light_ref(&self) -> &BaseLight {
         &self.base_light
@@ -97,6 +148,20 @@ impl PointLight {
         *self.radius
     }
 
+    /// Sets radius of the light emitting sphere. Larger values produce softer and bigger
+    /// specular highlights. Zero radius (default) means that the light is punctual. The value
+    /// should be much smaller than the radius of the light (see [`Self::set_radius`]).
+    #[inline]
+    pub fn set_source_radius(&mut self, radius: f32) -> f32 {
+        self.source_radius.set_value_and_mark_modified(radius.abs())
+    }
+
+    /// Returns radius of the light emitting sphere.
+    #[inline]
+    pub fn source_radius(&self) -> f32 {
+        *self.source_radius
+    }
+
     /// Sets new shadow bias value. Bias will be used to offset fragment's depth before
     /// compare it with shadow map value, it is used to remove "shadow acne".
     pub fn set_shadow_bias(&mut self, bias: f32) -> f32 {
@@ -107,6 +172,74 @@ impl PointLight {
     pub fn shadow_bias(&self) -> f32 {
         *self.shadow_bias
     }
+
+    /// Enables or disables caching of the shadows of static objects. See
+    /// [module docs](self#static-shadows-caching) for more info.
+    pub fn set_cache_static_shadows(&mut self, cache: bool) -> bool {
+        self.cache_static_shadows.set_value_and_mark_modified(cache)
+    }
+
+    /// Returns `true` if the shadows of static objects are cached, `false` - otherwise.
+    pub fn is_caching_static_shadows(&self) -> bool {
+        *self.cache_static_shadows
+    }
+
+    /// Sets the size (in pixels) of a face of the shadow cube map of the light. `None` means that
+    /// the global point shadow map size from the renderer settings will be used. It could be useful
+    /// to increase the resolution of shadows of a few important lights, without increasing it for
+    /// every other light. The size is used for the first (closest) shadow cascade, every other
+    /// cascade is smaller just as with the global size. The size is limited by the maximum size of
+    /// cube maps, that is supported by the GPU.
+    pub fn set_shadow_map_size(&mut self, size: Option<usize>) -> Option<usize> {
+        self.shadow_map_size.set_value_and_mark_modified(size)
+    }
+
+    /// Returns the size of a face of the shadow cube map of the light, `None` means that the global
+    /// point shadow map size from the renderer settings is used.
+    pub fn shadow_map_size(&self) -> Option<usize> {
+        *self.shadow_map_size
+    }
+
+    /// Sets the distances, that split the shadow map of the light in cascades. Each distance is
+    /// a fraction of the radius of the light in `(0; 1)` range and the distances must be sorted in
+    /// ascending order. `N` splits create `N + 1` cascades, but no more than
+    /// [`Self::MAX_SHADOW_CASCADES`], extra splits are ignored. Empty list (default) means that
+    /// there is only one cascade. See [module docs](self#shadow-cascades) for more info.
+    pub fn set_shadow_cascade_splits(&mut self, splits: Vec<f32>) -> Vec<f32> {
+        self.shadow_cascade_splits
+            .set_value_and_mark_modified(splits)
+    }
+
+    /// Returns the distances, that split the shadow map of the light in cascades. See
+    /// [`Self::set_shadow_cascade_splits`] for more info.
+    pub fn shadow_cascade_splits(&self) -> &[f32] {
+        &self.shadow_cascade_splits
+    }
+
+    /// Returns the amount of shadow cascades of the light, it is always in
+    /// `[1; Self::MAX_SHADOW_CASCADES]` range.
+    pub fn shadow_cascade_count(&self) -> usize {
+        self.shadow_cascade_distances(1.0).len()
+    }
+
+    /// Returns the far distance of each shadow cascade for the given (world space) radius of the
+    /// light. Invalid splits (out of `(0; 1)` range or not in ascending order) are skipped. The
+    /// last distance is always equal to the radius.
+    pub fn shadow_cascade_distances(&self, radius: f32) -> Vec<f32> {
+        let mut distances = Vec::with_capacity(Self::MAX_SHADOW_CASCADES);
+        let mut previous = 0.0;
+        for &split in self.shadow_cascade_splits.iter() {
+            if distances.len() + 1 >= Self::MAX_SHADOW_CASCADES {
+                break;
+            }
+            if split > previous && split < 1.0 {
+                distances.push(split * radius);
+                previous = split;
+            }
+        }
+        distances.push(radius);
+        distances
+    }
 }
 
 impl NodeTrait for PointLight {
@@ -136,6 +269,10 @@ impl Default for PointLight {
             base_light: Default::default(),
             shadow_bias: InheritableVariable::new_modified(0.025),
             radius: InheritableVariable::new_modified(10.0),
+            source_radius: InheritableVariable::new_modified(0.0),
+            cache_static_shadows: InheritableVariable::new_modified(false),
+            shadow_map_size: InheritableVariable::new_modified(None),
+            shadow_cascade_splits: InheritableVariable::new_modified(Vec::new()),
         }
     }
 }
@@ -145,6 +282,10 @@ pub struct PointLightBuilder {
     base_light_builder: BaseLightBuilder,
     shadow_bias: f32,
     radius: f32,
+    source_radius: f32,
+    cache_static_shadows: bool,
+    shadow_map_size: Option<usize>,
+    shadow_cascade_splits: Vec<f32>,
 }
 
 impl PointLightBuilder {
@@ -154,6 +295,10 @@ impl PointLightBuilder {
             base_light_builder,
             shadow_bias: 0.025,
             radius: 10.0,
+            source_radius: 0.0,
+            cache_static_shadows: false,
+            shadow_map_size: None,
+            shadow_cascade_splits: Vec::new(),
         }
     }
 
@@ -163,18 +308,48 @@ impl PointLightBuilder {
         self
     }
 
+    /// Sets desired radius of the light emitting sphere.
+    pub fn with_source_radius(mut self, source_radius: f32) -> Self {
+        self.source_radius = source_radius;
+        self
+    }
+
     /// Sets desired shadow bias.
     pub fn with_shadow_bias(mut self, bias: f32) -> Self {
         self.shadow_bias = bias;
         self
     }
 
+    /// Sets whether the shadows of static objects should be cached or not.
+    pub fn with_cache_static_shadows(mut self, cache: bool) -> Self {
+        self.cache_static_shadows = cache;
+        self
+    }
+
+    /// Sets desired size of a face of the shadow cube map, `None` means that the global size from the
+    /// renderer settings will be used.
+    pub fn with_shadow_map_size(mut self, size: Option<usize>) -> Self {
+        self.shadow_map_size = size;
+        self
+    }
+
+    /// Sets desired distances, that split the shadow map in cascades. See
+    /// [`PointLight::set_shadow_cascade_splits`] for more info.
+    pub fn with_shadow_cascade_splits(mut self, splits: Vec<f32>) -> Self {
+        self.shadow_cascade_splits = splits;
+        self
+    }
+
     /// Builds new instance of point light.
     pub fn build_point_light(self) -> PointLight {
         PointLight {
             base_light: self.base_light_builder.build(),
             radius: self.radius.into(),
+            source_radius: self.source_radius.into(),
             shadow_bias: self.shadow_bias.into(),
+            cache_static_shadows: self.cache_static_shadows.into(),
+            shadow_map_size: self.shadow_map_size.into(),
+            shadow_cascade_splits: self.shadow_cascade_splits.into(),
         }
     }
 
@@ -188,3 +363,31 @@ impl PointLightBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        light::{point::PointLightBuilder, BaseLightBuilder},
+    };
+
+    #[test]
+    fn test_shadow_cascade_distances() {
+        let light =
+            PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new())).build_point_light();
+        assert_eq!(light.shadow_cascade_distances(10.0), vec![10.0]);
+        assert_eq!(light.shadow_cascade_count(), 1);
+
+        let light = PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()))
+            .with_shadow_cascade_splits(vec![0.25, 0.5])
+            .build_point_light();
+        assert_eq!(light.shadow_cascade_distances(10.0), vec![2.5, 5.0, 10.0]);
+        assert_eq!(light.shadow_cascade_count(), 3);
+
+        // Invalid and extra splits are ignored.
+        let light = PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new()))
+            .with_shadow_cascade_splits(vec![0.5, 0.25, 1.5, 0.75, 0.9])
+            .build_point_light();
+        assert_eq!(light.shadow_cascade_distances(10.0), vec![5.0, 7.5, 10.0]);
+    }
+}
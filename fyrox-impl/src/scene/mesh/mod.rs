@@ -15,13 +15,14 @@ use crate::{
         TypeUuidProvider,
     },
     graph::{BaseSceneGraph, SceneGraph},
-    material::MaterialResource,
+    material::{MaterialPropertyOverrides, MaterialResource},
     renderer::{
         self,
         bundle::{
-            PersistentIdentifier, RenderContext, RenderDataBundleStorageTrait, SurfaceInstanceData,
+            BundleParameters, PersistentIdentifier, RenderContext, RenderDataBundleStorageTrait,
+            SurfaceInstanceData,
         },
-        framework::geometry_buffer::ElementRange,
+        framework::{geometry_buffer::ElementRange, state::PolygonFillMode},
     },
     scene::{
         base::{Base, BaseBuilder},
@@ -138,6 +139,34 @@ pub enum BatchingMode {
 struct Batch {
     data: SurfaceResource,
     material: MaterialResource,
+    material_overrides: MaterialPropertyOverrides,
+    polygon_fill_mode: PolygonFillMode,
+}
+
+impl Batch {
+    // Render path, decal layer index and sort index of a batch are defined by the mesh, that
+    // owns the batch container, so the batches are separated only by the rest of the parameters.
+    fn key(layout: impl Hash, params: &BundleParameters) -> u64 {
+        let mut hasher = FxHasher::default();
+        layout.hash(&mut hasher);
+        hasher.write_u64(params.material.key());
+        if let Some(material_overrides) = params.material_overrides {
+            if !material_overrides.is_empty() {
+                hasher.write_u64(material_overrides.content_hash());
+            }
+        }
+        hasher.write_u32(params.polygon_fill_mode as u32);
+        hasher.finish()
+    }
+
+    fn new(data: SurfaceResource, params: &BundleParameters) -> Self {
+        Self {
+            data,
+            material: params.material.clone(),
+            material_overrides: params.material_overrides.cloned().unwrap_or_default(),
+            polygon_fill_mode: params.polygon_fill_mode,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -163,6 +192,7 @@ impl BatchContainer {
                 storage: self,
                 graph: ctx.graph,
                 render_pass_name: ctx.render_pass_name,
+                viewport: ctx.viewport,
             });
         }
     }
@@ -181,29 +211,24 @@ impl RenderDataBundleStorageTrait for BatchContainer {
     fn push_triangles(
         &mut self,
         layout: &[VertexAttributeDescriptor],
-        material: &MaterialResource,
-        _render_path: RenderPath,
-        _decal_layer_index: u8,
-        _sort_index: u64,
-        _is_skinned: bool,
+        params: &BundleParameters,
         _node_handle: Handle<Node>,
         func: &mut dyn FnMut(VertexBufferRefMut, TriangleBufferRefMut),
     ) {
-        let mut hasher = FxHasher::default();
-        layout.hash(&mut hasher);
-        hasher.write_u64(material.key());
-        let batch_hash = hasher.finish();
-
-        let batch = self.batches.entry(batch_hash).or_insert_with(|| Batch {
-            data: SurfaceResource::new_ok(
-                ResourceKind::Embedded,
-                SurfaceData::new(
-                    VertexBuffer::new_with_layout(layout, 0, BytesStorage::with_capacity(4096))
-                        .unwrap(),
-                    TriangleBuffer::new(Vec::with_capacity(4096)),
+        let batch_hash = Batch::key(layout, params);
+
+        let batch = self.batches.entry(batch_hash).or_insert_with(|| {
+            Batch::new(
+                SurfaceResource::new_ok(
+                    ResourceKind::Embedded,
+                    SurfaceData::new(
+                        VertexBuffer::new_with_layout(layout, 0, BytesStorage::with_capacity(4096))
+                            .unwrap(),
+                        TriangleBuffer::new(Vec::with_capacity(4096)),
+                    ),
                 ),
-            ),
-            material: material.clone(),
+                params,
+            )
         });
 
         let mut batch_data_guard = batch.data.data_ref();
@@ -218,28 +243,24 @@ impl RenderDataBundleStorageTrait for BatchContainer {
     fn push(
         &mut self,
         data: &SurfaceResource,
-        material: &MaterialResource,
-        _render_path: RenderPath,
-        _decal_layer_index: u8,
-        _sort_index: u64,
+        params: &BundleParameters,
         instance_data: SurfaceInstanceData,
     ) {
         let src_data = data.data_ref();
 
-        let mut hasher = FxHasher::default();
-        src_data.vertex_buffer.layout().hash(&mut hasher);
-        hasher.write_u64(material.key());
-        let batch_hash = hasher.finish();
-
-        let batch = self.batches.entry(batch_hash).or_insert_with(|| Batch {
-            data: SurfaceResource::new_ok(
-                ResourceKind::Embedded,
-                SurfaceData::new(
-                    src_data.vertex_buffer.clone_empty(4096),
-                    TriangleBuffer::new(Vec::with_capacity(4096)),
+        let batch_hash = Batch::key(src_data.vertex_buffer.layout(), params);
+
+        let batch = self.batches.entry(batch_hash).or_insert_with(|| {
+            Batch::new(
+                SurfaceResource::new_ok(
+                    ResourceKind::Embedded,
+                    SurfaceData::new(
+                        src_data.vertex_buffer.clone_empty(4096),
+                        TriangleBuffer::new(Vec::with_capacity(4096)),
+                    ),
                 ),
-            ),
-            material: material.clone(),
+                params,
+            )
         });
 
         let mut batch_data_guard = batch.data.data_ref();
@@ -624,10 +645,14 @@ impl NodeTrait for Mesh {
             for (index, batch) in container.batches.values().enumerate() {
                 ctx.storage.push(
                     &batch.data,
-                    &batch.material,
-                    self.render_path(),
-                    self.decal_layer_index(),
-                    batch.material.key(),
+                    &BundleParameters::new(
+                        &batch.material,
+                        self.render_path(),
+                        batch.material.key(),
+                    )
+                    .with_decal_layer_index(self.decal_layer_index())
+                    .with_material_overrides(&batch.material_overrides)
+                    .with_polygon_fill_mode(batch.polygon_fill_mode),
                     SurfaceInstanceData {
                         world_transform: Matrix4::identity(),
                         bone_matrices: Default::default(),
@@ -662,6 +687,7 @@ impl NodeTrait for Mesh {
                         let surface_data_guard = surface.data_ref().data_ref();
                         if self.blend_shapes().is_empty()
                             && surface.bones().is_empty()
+                            && surface.polygon_fill_mode() == PolygonFillMode::Fill
                             && surface_data_guard.vertex_buffer.vertex_count() < 256
                         {
                             BatchingMode::Dynamic
@@ -675,10 +701,13 @@ impl NodeTrait for Mesh {
                     BatchingMode::None => {
                         ctx.storage.push(
                             surface.data_ref(),
-                            surface.material(),
-                            self.render_path(),
-                            self.decal_layer_index(),
-                            surface.material().key(),
+                            &BundleParameters::new(
+                                surface.material(),
+                                self.render_path(),
+                                surface.material().key(),
+                            )
+                            .with_decal_layer_index(self.decal_layer_index())
+                            .with_polygon_fill_mode(surface.polygon_fill_mode()),
                             SurfaceInstanceData {
                                 world_transform: world,
                                 bone_matrices: surface
@@ -717,11 +746,8 @@ impl NodeTrait for Mesh {
                                 .vertex_buffer
                                 .layout_descriptor()
                                 .collect::<Vec<_>>(),
-                            surface.material(),
-                            *self.render_path,
-                            self.decal_layer_index(),
-                            0,
-                            false,
+                            &BundleParameters::new(surface.material(), *self.render_path, 0)
+                                .with_decal_layer_index(self.decal_layer_index()),
                             self.self_handle,
                             &mut move |mut vertex_buffer, mut triangle_buffer| {
                                 let start_vertex_index = vertex_buffer.vertex_count();
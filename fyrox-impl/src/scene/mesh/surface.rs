@@ -24,6 +24,7 @@ use crate::{
         Uuid,
     },
     material::{self, Material, MaterialResource, MaterialResourceExtension},
+    renderer::framework::state::PolygonFillMode,
     resource::texture::{TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension},
     scene::{
         mesh::{
@@ -1245,6 +1246,12 @@ pub struct Surface {
     )]
     unique_material: InheritableVariable<bool>,
 
+    #[reflect(
+        description = "Defines how polygons of the surface will be rasterized. Could be used to inspect \
+        individual surfaces of complex meshes in wireframe mode."
+    )]
+    polygon_fill_mode: InheritableVariable<PolygonFillMode>,
+
     // Temporal array for FBX conversion needs, it holds skinning data (weight + bone handle)
     // and will be used to fill actual bone indices and weight in vertices that will be
     // sent to GPU. The idea is very simple: GPU needs to know only indices of matrices of
@@ -1270,6 +1277,7 @@ impl Clone for Surface {
             },
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
+            polygon_fill_mode: self.polygon_fill_mode.clone(),
             vertex_weights: self.vertex_weights.clone(),
         }
     }
@@ -1293,6 +1301,7 @@ impl Visit for Surface {
         self.data.visit("Data", &mut region)?;
         self.bones.visit("Bones", &mut region)?;
         let _ = self.unique_material.visit("UniqueMaterial", &mut region); // Backward compatibility.
+        let _ = self.polygon_fill_mode.visit("PolygonFillMode", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -1310,6 +1319,7 @@ impl Default for Surface {
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            polygon_fill_mode: Default::default(),
         }
     }
 }
@@ -1374,6 +1384,19 @@ impl Surface {
     pub fn set_unique_material(&mut self, unique: bool) {
         self.unique_material.set_value_and_mark_modified(unique);
     }
+
+    /// Returns current polygon fill mode of the surface.
+    pub fn polygon_fill_mode(&self) -> PolygonFillMode {
+        *self.polygon_fill_mode
+    }
+
+    /// Sets how polygons of the surface will be rasterized. [`PolygonFillMode::Line`] could be used
+    /// to show the surface in wireframe mode. Default value is [`PolygonFillMode::Fill`]. The mode
+    /// could be toggled in the editor using the "Toggle Wireframe" item of the context menu of a
+    /// mesh in the world viewer.
+    pub fn set_polygon_fill_mode(&mut self, mode: PolygonFillMode) {
+        self.polygon_fill_mode.set_value_and_mark_modified(mode);
+    }
 }
 
 /// Surface builder allows you to create surfaces in declarative manner.
@@ -1382,6 +1405,7 @@ pub struct SurfaceBuilder {
     material: Option<MaterialResource>,
     bones: Vec<Handle<Node>>,
     unique_material: bool,
+    polygon_fill_mode: PolygonFillMode,
 }
 
 impl SurfaceBuilder {
@@ -1392,6 +1416,7 @@ impl SurfaceBuilder {
             material: None,
             bones: Default::default(),
             unique_material: false,
+            polygon_fill_mode: Default::default(),
         }
     }
 
@@ -1413,6 +1438,12 @@ impl SurfaceBuilder {
         self
     }
 
+    /// Sets desired polygon fill mode of the surface.
+    pub fn with_polygon_fill_mode(mut self, mode: PolygonFillMode) -> Self {
+        self.polygon_fill_mode = mode;
+        self
+    }
+
     /// Creates new instance of surface.
     pub fn build(self) -> Surface {
         Surface {
@@ -1426,6 +1457,7 @@ impl SurfaceBuilder {
             vertex_weights: Default::default(),
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
+            polygon_fill_mode: self.polygon_fill_mode.into(),
         }
     }
 }
@@ -48,6 +48,7 @@ impl NodeConstructorContainer {
 
         container.add::<dim2::collider::Collider>();
         container.add::<dim2::joint::Joint>();
+        container.add::<dim2::polygon::Polygon>();
         container.add::<Rectangle>();
         container.add::<dim2::rigidbody::RigidBody>();
         container.add::<DirectionalLight>();
@@ -19,7 +19,10 @@ use crate::{
     },
     material::{self, Material, MaterialResource, PropertyValue},
     rand::{prelude::StdRng, Error, RngCore, SeedableRng},
-    renderer::{self, bundle::RenderContext},
+    renderer::{
+        self,
+        bundle::{BundleParameters, RenderContext},
+    },
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -511,11 +514,7 @@ impl NodeTrait for ParticleSystem {
 
         ctx.storage.push_triangles(
             Vertex::layout(),
-            &self.material,
-            RenderPath::Forward,
-            0,
-            sort_index,
-            false,
+            &BundleParameters::new(&self.material, RenderPath::Forward, sort_index),
             self.self_handle,
             &mut move |mut vertex_buffer, mut triangle_buffer| {
                 let vertices = sorted_particles.iter().flat_map(move |particle_index| {
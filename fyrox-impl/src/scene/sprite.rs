@@ -17,7 +17,10 @@ use crate::{
     },
     material,
     material::{Material, MaterialResource},
-    renderer::{self, bundle::RenderContext},
+    renderer::{
+        self,
+        bundle::{BundleParameters, RenderContext},
+    },
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -343,11 +346,7 @@ impl NodeTrait for Sprite {
 
         ctx.storage.push_triangles(
             Vertex::layout(),
-            &self.material,
-            RenderPath::Forward,
-            0,
-            sort_index,
-            false,
+            &BundleParameters::new(&self.material, RenderPath::Forward, sort_index),
             self.self_handle,
             &mut move |mut vertex_buffer, mut triangle_buffer| {
                 let start_vertex_index = vertex_buffer.vertex_count();
@@ -21,7 +21,7 @@ use crate::{
     material::{Material, MaterialResource, PropertyValue},
     renderer::{
         self,
-        bundle::{RenderContext, SurfaceInstanceData},
+        bundle::{BundleParameters, RenderContext, SurfaceInstanceData},
         framework::geometry_buffer::ElementRange,
     },
     resource::texture::{
@@ -1548,6 +1548,12 @@ impl NodeTrait for Terrain {
                     "Unable to set height map texture for terrain material.",
                 );
 
+                // Custom terrain shaders may not have this property, so the error is ignored.
+                let _ = material.set_property(
+                    &ImmutableString::new("isBaseLayer"),
+                    PropertyValue::Bool(layer_index == 0),
+                );
+
                 for node in selection {
                     let kx = node.position.x as f32 / self.height_map_size.x as f32;
                     let kz = node.position.y as f32 / self.height_map_size.y as f32;
@@ -1580,10 +1586,12 @@ impl NodeTrait for Terrain {
                     if node.is_draw_full() {
                         ctx.storage.push(
                             &self.geometry.data,
-                            &material,
-                            RenderPath::Deferred,
-                            self.decal_layer_index(),
-                            layer_index as u64,
+                            &BundleParameters::new(
+                                &material,
+                                RenderPath::Deferred,
+                                layer_index as u64,
+                            )
+                            .with_decal_layer_index(self.decal_layer_index()),
                             SurfaceInstanceData {
                                 world_transform: node_transform,
                                 bone_matrices: Default::default(),
@@ -1603,10 +1611,12 @@ impl NodeTrait for Terrain {
                             if *draw_quadrant {
                                 ctx.storage.push(
                                     &self.geometry.data,
-                                    &material,
-                                    RenderPath::Deferred,
-                                    self.decal_layer_index(),
-                                    layer_index as u64,
+                                    &BundleParameters::new(
+                                        &material,
+                                        RenderPath::Deferred,
+                                        layer_index as u64,
+                                    )
+                                    .with_decal_layer_index(self.decal_layer_index()),
                                     SurfaceInstanceData {
                                         world_transform: node_transform,
                                         bone_matrices: Default::default(),
@@ -4,7 +4,7 @@ use fyrox_sound::renderer::hrtf::{HrirSphereResource, HrirSphereResourceExt};
 use fyrox_sound::{
     algebra::{Point3, UnitQuaternion, Vector3},
     buffer::{DataSource, SoundBufferResource},
-    context::{self, SoundContext},
+    context::SoundContext,
     effects::{reverb::Reverb, Effect},
     engine::SoundEngine,
     futures::executor::block_on,
@@ -19,12 +19,12 @@ use std::{
 };
 
 fn main() {
-    let hrir_path = PathBuf::from("examples/data/IRC_1002_C.bin");
-    let hrir_sphere = HrirSphere::from_file(&hrir_path, context::SAMPLE_RATE).unwrap();
-
     // Initialize sound engine with default output device.
     let engine = SoundEngine::new().unwrap();
 
+    let hrir_path = PathBuf::from("examples/data/IRC_1002_C.bin");
+    let hrir_sphere = HrirSphere::from_file(&hrir_path, engine.state().sample_rate()).unwrap();
+
     // Initialize new sound context.
     let context = SoundContext::new();
 
@@ -9,14 +9,17 @@ use fyrox_sound::{
     source::{SoundSource, SoundSourceBuilder, Status},
 };
 
+// Sample rate of the output file. It could be any rate, 44100 and 48000 Hz are the most common ones.
+const SAMPLE_RATE: u32 = 48000;
+
 fn main() {
     // Initialize sound engine without output device.
-    let engine = SoundEngine::without_device();
+    let engine = SoundEngine::without_device_with_sample_rate(SAMPLE_RATE);
 
     // Create new context.
     let context = SoundContext::new();
 
-    // Register context in the engine.
+    // Register context in the engine, the engine sets its sample rate to the context.
     engine.state().add_context(context.clone());
 
     // Load sound buffer.
@@ -41,10 +44,11 @@ fn main() {
     // and returns pool handle to it by which it can be accessed later on if needed.
     let _source_handle: Handle<SoundSource> = context.state().add_source(source);
 
-    // Create output wav file. The sample rate is currently fixed.
+    // Create output wav file with the same sample rate as the engine. Use `fyrox_sound::dsp::pcm`
+    // helpers to convert the samples, if you need 16-bit PCM file.
     let wav_spec = hound::WavSpec {
         channels: 2,
-        sample_rate: fyrox_sound::context::SAMPLE_RATE,
+        sample_rate: SAMPLE_RATE,
         bits_per_sample: 32,
         sample_format: hound::SampleFormat::Float,
     };
@@ -56,7 +60,7 @@ fn main() {
     let mut samples_written = 0;
 
     // Wait until sound will play completely.
-    while samples_written < 3 * fyrox_sound::context::SAMPLE_RATE {
+    while samples_written < 3 * SAMPLE_RATE {
         engine.state().render(&mut buf);
         for &(l, r) in buf.iter() {
             wav_writer.write_sample(l).unwrap();
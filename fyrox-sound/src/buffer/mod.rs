@@ -13,7 +13,8 @@ use crate::{
     error::SoundError,
 };
 use fyrox_core::{
-    io::FileLoadError, reflect::prelude::*, uuid::Uuid, visitor::prelude::*, TypeUuidProvider,
+    io::FileLoadError, reflect::prelude::*, task::TaskPool, uuid::Uuid, visitor::prelude::*,
+    TypeUuidProvider,
 };
 use fyrox_resource::{
     io::{FileReader, ResourceIo},
@@ -26,6 +27,7 @@ use std::{
     io::{Cursor, Read, Seek, SeekFrom},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
+    sync::Arc,
     time::Duration,
 };
 
@@ -195,6 +197,33 @@ pub trait SoundBufferResourceExtension {
 
     /// Tries to create new generic sound buffer from a given data source.
     fn new_generic(data_source: DataSource) -> Result<Resource<SoundBuffer>, DataSource>;
+
+    /// Returns exact duration of the sound buffer, or `None` if the buffer is not loaded (yet).
+    /// Duration of streaming buffers is taken from the header of their data source, so it is
+    /// known without decoding the entire buffer.
+    fn duration(&self) -> Option<Duration>;
+
+    /// Returns amount of channels of the sound buffer, or `None` if the buffer is not loaded (yet).
+    fn channel_count(&self) -> Option<usize>;
+
+    /// Returns sample rate of the sound buffer, or `None` if the buffer is not loaded (yet).
+    fn sample_rate(&self) -> Option<usize>;
+
+    /// Loads and decodes multiple sound buffers concurrently using the given task pool. The
+    /// returned resources are in pending state and will be loaded in the background, every
+    /// resource could be `.await`ed to wait until it is loaded. A failure to load one buffer does
+    /// not affect the others, such buffer is put in error state with
+    /// [`SoundBufferResourceLoadError`] inside.
+    ///
+    /// Keep in mind, that the returned resources are not registered in a resource manager.
+    fn load_many<P>(
+        paths: &[P],
+        stream: bool,
+        io: Arc<dyn ResourceIo>,
+        task_pool: &TaskPool,
+    ) -> Vec<Resource<SoundBuffer>>
+    where
+        P: AsRef<Path>;
 }
 
 impl SoundBufferResourceExtension for SoundBufferResource {
@@ -213,6 +242,59 @@ impl SoundBufferResourceExtension for SoundBufferResource {
             SoundBuffer::Generic(GenericBuffer::new(data_source)?),
         ))
     }
+
+    fn duration(&self) -> Option<Duration> {
+        self.state().data().map(|buffer| buffer.duration())
+    }
+
+    fn channel_count(&self) -> Option<usize> {
+        self.state().data().map(|buffer| buffer.channel_count())
+    }
+
+    fn sample_rate(&self) -> Option<usize> {
+        self.state().data().map(|buffer| buffer.sample_rate())
+    }
+
+    fn load_many<P>(
+        paths: &[P],
+        stream: bool,
+        io: Arc<dyn ResourceIo>,
+        task_pool: &TaskPool,
+    ) -> Vec<Resource<SoundBuffer>>
+    where
+        P: AsRef<Path>,
+    {
+        paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref().to_path_buf();
+                let resource = SoundBufferResource::new_pending(path.clone().into());
+                let untyped = resource.clone().into_untyped();
+                let io = io.clone();
+
+                task_pool.spawn_task(async move {
+                    match DataSource::from_file(&path, io.as_ref()).await {
+                        Ok(source) => {
+                            let result = if stream {
+                                SoundBuffer::raw_streaming(source)
+                            } else {
+                                SoundBuffer::raw_generic(source)
+                            };
+
+                            match result {
+                                Ok(buffer) => untyped.commit_ok(buffer),
+                                Err(_) => untyped
+                                    .commit_error(SoundBufferResourceLoadError::UnsupportedFormat),
+                            }
+                        }
+                        Err(error) => untyped.commit_error(SoundBufferResourceLoadError::Io(error)),
+                    }
+                });
+
+                resource
+            })
+            .collect()
+    }
 }
 
 impl TypeUuidProvider for SoundBuffer {
@@ -286,3 +368,43 @@ impl ResourceData for SoundBuffer {
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension};
+    use fyrox_core::{futures::executor::block_on, task::TaskPool};
+    use fyrox_resource::io::FsResourceIo;
+    use std::{sync::Arc, time::Duration};
+
+    #[test]
+    fn test_sound_buffer_metadata() {
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 4,
+            channel_count: 2,
+            samples: vec![0.0; 16],
+        })
+        .unwrap();
+
+        assert_eq!(buffer.duration(), Some(Duration::from_secs(2)));
+        assert_eq!(buffer.channel_count(), Some(2));
+        assert_eq!(buffer.sample_rate(), Some(4));
+    }
+
+    #[test]
+    fn test_load_many() {
+        let task_pool = TaskPool::new();
+        let buffers = SoundBufferResource::load_many(
+            &["examples/data/drop.wav", "examples/data/does_not_exist.wav"],
+            false,
+            Arc::new(FsResourceIo),
+            &task_pool,
+        );
+
+        assert_eq!(buffers.len(), 2);
+        // A missing file must not prevent other buffers from loading.
+        assert!(block_on(buffers[0].clone()).is_ok());
+        assert!(buffers[0].channel_count().is_some());
+        assert!(block_on(buffers[1].clone()).is_err());
+        assert!(buffers[1].is_failed_to_load());
+    }
+}
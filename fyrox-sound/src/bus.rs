@@ -453,6 +453,14 @@ impl AudioBusGraph {
         self.buses.pair_iter_mut()
     }
 
+    pub(crate) fn set_sample_rate(&mut self, sample_rate: u32) {
+        for bus in self.buses.iter_mut() {
+            for effect in bus.effects.iter_mut() {
+                effect.set_sample_rate(sample_rate);
+            }
+        }
+    }
+
     pub(crate) fn begin_render(&mut self, output_device_buffer_size: usize) {
         for bus in self.buses.iter_mut() {
             bus.begin_render(output_device_buffer_size);
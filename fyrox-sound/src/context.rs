@@ -18,19 +18,22 @@ use crate::{
     source::{SoundSource, Status},
 };
 use fyrox_core::{
+    log::Log,
     pool::{Handle, Pool},
     reflect::prelude::*,
     uuid_provider,
     visitor::prelude::*,
 };
 use std::{
-    sync::{Arc, Mutex, MutexGuard},
+    fmt::{Debug, Formatter},
+    sync::{mpsc::Sender, Arc, Mutex, MutexGuard},
     time::Duration,
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
-/// Sample rate for output device.
-/// TODO: Make this configurable, for now its set to most commonly used sample rate of 44100 Hz.
+/// Default sample rate for output device. It is used by [`SoundContext::new`] and
+/// [`crate::engine::SoundEngine::new`], use [`SoundContext::with_sample_rate`] and
+/// [`crate::engine::SoundEngine::with_sample_rate`] to pick a different one.
 pub const SAMPLE_RATE: u32 = 44100;
 
 /// Distance model defines how volume of sound will decay when distance to listener changes.
@@ -86,6 +89,16 @@ impl Default for DistanceModel {
     }
 }
 
+/// An event, that is emitted by a sound context. See [`State::add_event_listener`] for more info.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SoundEvent {
+    /// A sound source has reached the end of its buffer and stopped. This event is not emitted for
+    /// looping sources, as well as for the sources that were stopped manually. Keep in mind, that
+    /// "play once" sources are removed from the context right after they're stopped, so the handle
+    /// could be invalid at the moment when the event is received.
+    PlaybackEnded(Handle<SoundSource>),
+}
+
 /// See module docs.
 #[derive(Clone, Default, Debug, Visit)]
 pub struct SoundContext {
@@ -108,8 +121,40 @@ pub struct SerializationOptions {
     pub skip_bus_graph: bool,
 }
 
+/// A callback, that receives every block of samples rendered by a sound context. See
+/// [`State::set_capture_sink`] for more info.
+pub type CaptureSink = Box<dyn FnMut(&[(f32, f32)]) + Send>;
+
+// A wrapper for capture sink, that does not clone the sink, so deep clones of a context won't
+// capture anything.
+#[derive(Default)]
+struct CaptureSinkSlot(Option<CaptureSink>);
+
+impl Debug for CaptureSinkSlot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CaptureSinkSlot({})", self.0.is_some())
+    }
+}
+
+impl Clone for CaptureSinkSlot {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+// A wrapper for event listeners, that does not clone the listeners, so deep clones of a context
+// won't send events with the handles of their sources to the listeners of the original context.
+#[derive(Default, Debug)]
+struct EventListeners(Vec<Sender<SoundEvent>>);
+
+impl Clone for EventListeners {
+    fn clone(&self) -> Self {
+        Self(Default::default())
+    }
+}
+
 /// Internal state of context.
-#[derive(Default, Debug, Clone, Reflect)]
+#[derive(Debug, Clone, Reflect)]
 pub struct State {
     sources: Pool<SoundSource>,
     listener: Listener,
@@ -118,12 +163,41 @@ pub struct State {
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
     paused: bool,
+    max_voices: Option<usize>,
+    #[reflect(hidden)]
+    sample_rate: u32,
+    #[reflect(hidden)]
+    event_listeners: EventListeners,
+    #[reflect(hidden)]
+    capture_sink: CaptureSinkSlot,
+    #[reflect(hidden)]
+    capture_buffer: Vec<(f32, f32)>,
     /// A set of flags, that can be used to define what should be skipped during the
     /// serialization of a sound context.
     #[reflect(hidden)]
     pub serialization_options: SerializationOptions,
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            sources: Default::default(),
+            listener: Default::default(),
+            render_duration: Default::default(),
+            renderer: Default::default(),
+            bus_graph: Default::default(),
+            distance_model: Default::default(),
+            paused: false,
+            max_voices: None,
+            sample_rate: SAMPLE_RATE,
+            event_listeners: Default::default(),
+            capture_sink: Default::default(),
+            capture_buffer: Default::default(),
+            serialization_options: Default::default(),
+        }
+    }
+}
+
 impl State {
     /// Extracts a source from the context and reserves its handle. It is used to temporarily take
     /// ownership over source, and then put node back using given ticket.
@@ -158,6 +232,128 @@ impl State {
         self.paused
     }
 
+    /// Sets the maximum amount of sources that can be played at the same time. When the limit is
+    /// exceeded, the context stops the sources with the lowest priority (see [`SoundSource::set_priority`]).
+    /// If there are multiple sources with the same priority, the oldest ones (the ones that have been
+    /// playing for the longest time, including every loop of looping sources) will be stopped first.
+    /// `None` means that there's no limit, which is the default value.
+    pub fn set_max_voices(&mut self, max_voices: Option<usize>) {
+        self.max_voices = max_voices;
+    }
+
+    /// Returns the maximum amount of sources that can be played at the same time. See [`Self::set_max_voices`]
+    /// for more info.
+    pub fn max_voices(&self) -> Option<usize> {
+        self.max_voices
+    }
+
+    /// Returns the amount of sources that are currently playing.
+    pub fn active_voice_count(&self) -> usize {
+        self.sources
+            .iter()
+            .filter(|s| s.status() == Status::Playing)
+            .count()
+    }
+
+    /// Adds a new event listener, that will receive every event emitted by the context. It could be
+    /// used, for example, to get notified when a specific sound source has finished playing, without
+    /// polling its status. Keep in mind, that the events are sent from the sound thread. Listeners
+    /// are removed automatically when their receiving part is dropped. Listeners are not copied
+    /// by [`SoundContext::deep_clone`], the copy has no listeners.
+    ///
+    /// ```no_run
+    /// use fyrox_sound::context::{SoundContext, SoundEvent};
+    /// use std::sync::mpsc::channel;
+    ///
+    /// let context = SoundContext::new();
+    /// let (sender, receiver) = channel();
+    /// context.state().add_event_listener(sender);
+    ///
+    /// // Somewhere in the game loop.
+    /// while let Ok(event) = receiver.try_recv() {
+    ///     match event {
+    ///         SoundEvent::PlaybackEnded(source) => println!("{:?} has finished playing", source),
+    ///     }
+    /// }
+    /// ```
+    pub fn add_event_listener(&mut self, listener: Sender<SoundEvent>) {
+        self.event_listeners.0.push(listener);
+    }
+
+    /// Sets a capture sink, that receives every block of samples rendered by the context right
+    /// before it is mixed into the output of the sound engine (which then goes to the output
+    /// device). It could be used to record the sound of the context, for example to implement
+    /// "save last 30 seconds" feature using a ring buffer. Returns previous capture sink (if any).
+    ///
+    /// The sink receives the output of this context only, the output of other contexts is not
+    /// included. Each block contains interleaved stereo frames, the length of the block is defined
+    /// by the sound engine and there's [`Self::sample_rate`] frames per second. Paused contexts
+    /// render nothing, so the sink is not called while the context is paused.
+    ///
+    /// # Threading
+    ///
+    /// The sink is called from the sound thread, while the context is locked. This means that it
+    /// must be fast and must not lock the context itself (it will cause a deadlock). Since the sink
+    /// is called while the context is locked, setting a new sink (or detaching the current one by
+    /// passing `None`) never happens in the middle of a block: the sink always receives whole
+    /// blocks and the sound produced by the context is not affected at all.
+    ///
+    /// ```no_run
+    /// use fyrox_sound::context::SoundContext;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let context = SoundContext::new();
+    /// let recording = Arc::new(Mutex::new(Vec::new()));
+    /// let sink_recording = recording.clone();
+    /// context
+    ///     .state()
+    ///     .set_capture_sink(Some(Box::new(move |block: &[(f32, f32)]| {
+    ///         sink_recording.lock().unwrap().extend_from_slice(block);
+    ///     })));
+    ///
+    /// // Later on.
+    /// context.state().set_capture_sink(None);
+    /// ```
+    pub fn set_capture_sink(&mut self, sink: Option<CaptureSink>) -> Option<CaptureSink> {
+        std::mem::replace(&mut self.capture_sink.0, sink)
+    }
+
+    /// Returns `true` if the context has a capture sink, `false` - otherwise.
+    pub fn has_capture_sink(&self) -> bool {
+        self.capture_sink.0.is_some()
+    }
+
+    fn steal_voices(&mut self) {
+        let Some(max_voices) = self.max_voices else {
+            return;
+        };
+
+        let mut playing = self
+            .sources
+            .pair_iter()
+            .filter(|(_, s)| s.status() == Status::Playing)
+            .map(|(handle, s)| (handle, s.priority(), s.play_time()))
+            .collect::<Vec<_>>();
+
+        if playing.len() <= max_voices {
+            return;
+        }
+
+        // Lowest priority first, then the oldest ones.
+        playing.sort_by(|(_, a_priority, a_time), (_, b_priority, b_time)| {
+            a_priority
+                .cmp(b_priority)
+                .then_with(|| b_time.total_cmp(a_time))
+        });
+
+        let excess = playing.len() - max_voices;
+        for (handle, _, _) in playing.into_iter().take(excess) {
+            if let Err(err) = self.sources[handle].stop() {
+                Log::err(format!("Unable to stop a sound source. Reason: {:?}", err));
+            }
+        }
+    }
+
     /// Sets new distance model.
     pub fn set_distance_model(&mut self, distance_model: DistanceModel) {
         self.distance_model = distance_model;
@@ -168,10 +364,28 @@ impl State {
         self.distance_model
     }
 
+    /// Sets the sample rate (in Hertz) of the output of the context. Sound sources are resampled
+    /// on the fly and filter effects are re-tuned to match the new rate. There's no need to call
+    /// this method manually for contexts that were added to a sound engine, the engine sets its
+    /// own sample rate to every added context.
+    ///
+    /// # Notes
+    ///
+    /// HRTF renderer uses HRIR sphere, that must be loaded with the same sample rate (see
+    /// [`crate::renderer::hrtf::HrirSphere::from_file`]).
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.max(1);
+    }
+
+    /// Returns the sample rate (in Hertz) of the output of the context.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
-        f / SAMPLE_RATE as f32
+        f / self.sample_rate as f32
     }
 
     /// Returns amount of time context spent on rendering all sound sources.
@@ -263,17 +477,28 @@ impl State {
                 !done
             });
 
+            self.steal_voices();
+
+            self.bus_graph.set_sample_rate(self.sample_rate);
             self.bus_graph.begin_render(output_device_buffer.len());
 
             // Render sounds to respective audio buses.
-            for source in self
+            for (handle, source) in self
                 .sources
-                .iter_mut()
-                .filter(|s| s.status() == Status::Playing)
+                .pair_iter_mut()
+                .filter(|(_, s)| s.status() == Status::Playing)
             {
                 if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
                 {
-                    source.render(output_device_buffer.len());
+                    source.render(output_device_buffer.len(), self.sample_rate);
+
+                    // Looping sources never stop by themselves, so the only way for a source to
+                    // stop during rendering is to reach the end of its buffer.
+                    if source.status() == Status::Stopped {
+                        self.event_listeners.0.retain(|listener| {
+                            listener.send(SoundEvent::PlaybackEnded(handle)).is_ok()
+                        });
+                    }
 
                     match self.renderer {
                         Renderer::Default => {
@@ -291,13 +516,33 @@ impl State {
                                 &self.listener,
                                 self.distance_model,
                                 bus_input_buffer,
+                                self.sample_rate,
                             );
                         }
                     }
                 }
             }
 
-            self.bus_graph.end_render(output_device_buffer);
+            if let Some(capture_sink) = self.capture_sink.0.as_mut() {
+                // Render the context separately, so the sink won't receive the output of the other
+                // contexts.
+                self.capture_buffer.clear();
+                self.capture_buffer
+                    .resize(output_device_buffer.len(), (0.0, 0.0));
+                self.bus_graph.end_render(&mut self.capture_buffer);
+
+                capture_sink(&self.capture_buffer);
+
+                for ((output_left, output_right), (left, right)) in output_device_buffer
+                    .iter_mut()
+                    .zip(self.capture_buffer.iter())
+                {
+                    *output_left += *left;
+                    *output_right += *right;
+                }
+            } else {
+                self.bus_graph.end_render(output_device_buffer);
+            }
         }
 
         self.render_duration = fyrox_core::instant::Instant::now() - last_time;
@@ -316,8 +561,15 @@ impl SoundContext {
 
     /// Creates new instance of context. Internally context starts new thread which will call render all
     /// sound source and send samples to default output device. This method returns `Arc<Mutex<Context>>`
-    /// because separate thread also uses context.
+    /// because separate thread also uses context. The context uses default [`SAMPLE_RATE`].
     pub fn new() -> Self {
+        Self::with_sample_rate(SAMPLE_RATE)
+    }
+
+    /// Creates new instance of context, that produces samples with the given sample rate (in
+    /// Hertz). Keep in mind, that the sound engine sets its own sample rate to every added
+    /// context, see [`State::set_sample_rate`] for more info.
+    pub fn with_sample_rate(sample_rate: u32) -> Self {
         Self {
             state: Some(Arc::new(Mutex::new(State {
                 sources: Pool::new(),
@@ -327,6 +579,11 @@ impl SoundContext {
                 bus_graph: AudioBusGraph::new(),
                 distance_model: DistanceModel::InverseDistance,
                 paused: false,
+                max_voices: None,
+                sample_rate: sample_rate.max(1),
+                event_listeners: Default::default(),
+                capture_sink: Default::default(),
+                capture_buffer: Default::default(),
                 serialization_options: Default::default(),
             }))),
         }
@@ -385,6 +642,7 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.max_voices.visit("MaxVoices", &mut region);
 
         Ok(())
     }
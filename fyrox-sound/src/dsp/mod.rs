@@ -11,6 +11,7 @@
 use fyrox_core::visitor::{PodVecView, Visit, VisitResult, Visitor};
 
 pub mod filters;
+pub mod pcm;
 
 #[derive(Debug, PartialEq, Clone)]
 struct SamplesContainer(pub Vec<f32>);
@@ -0,0 +1,78 @@
+//! Conversion of floating point samples to integer PCM formats, that are used by the most of audio
+//! files (16-bit WAV, for example).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use fyrox_sound::{dsp::pcm::convert_f32_to_i16_dithered, engine::SoundEngine};
+//! use fyrox_core::rand::thread_rng;
+//! use std::time::Duration;
+//!
+//! let engine = SoundEngine::without_device();
+//! // Add contexts with sound sources here.
+//! let mix = engine.state().render_full(Duration::from_secs(3));
+//!
+//! // Interleave stereo frames: L R L R ...
+//! let samples = mix.iter().flat_map(|(l, r)| [*l, *r]).collect::<Vec<_>>();
+//! let mut pcm = vec![0i16; samples.len()];
+//! convert_f32_to_i16_dithered(&samples, &mut pcm, &mut thread_rng());
+//! ```
+
+use fyrox_core::rand::Rng;
+
+const I16_SCALE: f32 = i16::MAX as f32;
+
+fn quantize(sample: f32) -> i16 {
+    // Clamping keeps the range symmetrical, NaN is cast to zero.
+    (sample * I16_SCALE).round().clamp(-I16_SCALE, I16_SCALE) as i16
+}
+
+/// Converts floating point samples in `[-1.0; 1.0]` range to 16-bit signed PCM samples. Values
+/// outside of the range are clamped. Only `min(input.len(), output.len())` samples are converted.
+/// The conversion does not use dithering, see [`convert_f32_to_i16_dithered`] for quieter signals.
+pub fn convert_f32_to_i16(input: &[f32], output: &mut [i16]) {
+    for (input, output) in input.iter().zip(output) {
+        *output = quantize(*input);
+    }
+}
+
+/// Converts floating point samples in `[-1.0; 1.0]` range to 16-bit signed PCM samples using
+/// triangular (TPDF) dither. Dither adds a tiny amount of noise (up to one least significant bit)
+/// before quantization, it replaces audible quantization distortion of quiet signals (fade outs,
+/// reverb tails) with a constant noise floor. Values outside of the range are clamped. Only
+/// `min(input.len(), output.len())` samples are converted.
+pub fn convert_f32_to_i16_dithered<R: Rng + ?Sized>(
+    input: &[f32],
+    output: &mut [i16],
+    rng: &mut R,
+) {
+    for (input, output) in input.iter().zip(output) {
+        // Sum of two uniform distributions gives triangular distribution in (-1; 1) LSB range.
+        let noise = rng.gen::<f32>() - rng.gen::<f32>();
+        *output = quantize(*input + noise / I16_SCALE);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dsp::pcm::{convert_f32_to_i16, convert_f32_to_i16_dithered};
+    use fyrox_core::rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_convert_f32_to_i16() {
+        let input = [0.0, 1.0, -1.0, 0.5, 2.0, -2.0, f32::NAN];
+        let mut output = [1; 7];
+        convert_f32_to_i16(&input, &mut output);
+        assert_eq!(output, [0, 32767, -32767, 16384, 32767, -32767, 0]);
+    }
+
+    #[test]
+    fn test_convert_f32_to_i16_dithered() {
+        let input = [0.0, 1.0, -1.0, 0.5];
+        let mut output = [0; 4];
+        convert_f32_to_i16_dithered(&input, &mut output, &mut StdRng::seed_from_u64(0));
+        for (dithered, exact) in output.iter().zip([0, 32767, -32767, 16384]) {
+            assert!((*dithered as i32 - exact).abs() <= 1);
+        }
+    }
+}
@@ -27,6 +27,10 @@ macro_rules! define_filter_effect {
             )]
             quality: f32,
 
+            #[reflect(hidden)]
+            #[visit(optional)]
+            sample_rate: u32,
+
             #[reflect(hidden)]
             left: Biquad,
             #[reflect(hidden)]
@@ -39,6 +43,7 @@ macro_rules! define_filter_effect {
                     cutoff_frequency_hz: 2200.0,
                     gain: 1.0,
                     quality: 0.5,
+                    sample_rate: SAMPLE_RATE,
                     left: Default::default(),
                     right: Default::default(),
                 };
@@ -54,6 +59,13 @@ macro_rules! define_filter_effect {
                     *output_right = self.right.feed(*input_right);
                 }
             }
+
+            fn set_sample_rate(&mut self, sample_rate: u32) {
+                if self.sample_rate != sample_rate {
+                    self.sample_rate = sample_rate;
+                    self.update();
+                }
+            }
         }
 
         impl $name {
@@ -101,14 +113,14 @@ macro_rules! define_filter_effect {
             fn update(&mut self) {
                 self.left.tune(
                     $kind,
-                    self.cutoff_frequency_hz / SAMPLE_RATE as f32,
+                    self.cutoff_frequency_hz / self.sample_rate as f32,
                     self.gain,
                     self.quality
                 );
 
                 self.right.tune(
                     $kind,
-                    self.cutoff_frequency_hz / SAMPLE_RATE as f32,
+                    self.cutoff_frequency_hz / self.sample_rate as f32,
                     self.gain,
                     self.quality
                 )
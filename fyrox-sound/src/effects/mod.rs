@@ -78,6 +78,10 @@ impl Default for Effect {
 
 pub(crate) trait EffectRenderTrait {
     fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]);
+
+    // Called before rendering with the sample rate of the context. Must be cheap if the rate
+    // did not change.
+    fn set_sample_rate(&mut self, _sample_rate: u32) {}
 }
 
 macro_rules! static_dispatch {
@@ -99,4 +103,17 @@ impl EffectRenderTrait for Effect {
     fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
         static_dispatch!(self, render, input, output)
     }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        match self {
+            Effect::Attenuate(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::Reverb(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::LowPassFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::HighPassFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::BandPassFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::AllPassFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::LowShelfFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+            Effect::HighShelfFilter(v) => EffectRenderTrait::set_sample_rate(v, sample_rate),
+        }
+    }
 }
@@ -78,6 +78,8 @@ impl ChannelReverb {
     }
 
     fn set_sample_rate(&mut self, sample_rate: usize) {
+        self.sample_rate = sample_rate as u32;
+
         let scale = sample_rate as f32 / DESIGN_SAMPLE_RATE as f32;
 
         let feedback = self.lp_fb_comb_filters[0].feedback();
@@ -229,6 +231,8 @@ impl Reverb {
     pub fn set_sample_rate(&mut self, sample_rate: usize) {
         self.left.set_sample_rate(sample_rate);
         self.right.set_sample_rate(sample_rate);
+        // Lengths of the comb filters were changed, so their feedback must be recalculated.
+        self.set_decay_time(self.decay_time);
     }
 
     /// Sets desired duration of reverberation, the more size your environment has,
@@ -283,4 +287,10 @@ impl EffectRenderTrait for Reverb {
             *out_right = processed_right * wet + processed_left * dry + self.dry * right;
         }
     }
+
+    fn set_sample_rate(&mut self, sample_rate: u32) {
+        if self.left.sample_rate != sample_rate {
+            Reverb::set_sample_rate(self, sample_rate as usize);
+        }
+    }
 }
@@ -8,6 +8,7 @@ use crate::context::{SoundContext, SAMPLE_RATE};
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
 use std::error::Error;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
 /// Sound engine manages contexts, feeds output device with data. Sound engine instance can be cloned,
 /// however this is always a "shallow" clone, because actual sound engine data is wrapped in Arc.
@@ -24,35 +25,55 @@ impl Default for SoundEngine {
 pub struct State {
     contexts: Vec<SoundContext>,
     output_device: Option<Box<dyn tinyaudio::BaseAudioOutputDevice>>,
+    sample_rate: u32,
+    // Intermediate stereo buffer for mono rendering.
+    stereo_buffer: Vec<(f32, f32)>,
 }
 
 impl SoundEngine {
     /// Creates new instance of the sound engine. It is possible to have multiple engines running at
     /// the same time, but you shouldn't do this because you can create multiple contexts which
-    /// should cover 99% of use cases.
+    /// should cover 99% of use cases. The engine uses default [`SAMPLE_RATE`].
     pub fn new() -> Result<Self, Box<dyn Error>> {
-        let engine = Self::without_device();
+        Self::with_sample_rate(SAMPLE_RATE)
+    }
+
+    /// Creates new instance of the sound engine, that runs the output device with the given sample
+    /// rate (in Hertz). Sound buffers with any sample rate could be played, they are resampled on
+    /// the fly.
+    pub fn with_sample_rate(sample_rate: u32) -> Result<Self, Box<dyn Error>> {
+        let engine = Self::without_device_with_sample_rate(sample_rate);
         engine.initialize_audio_output_device()?;
         Ok(engine)
     }
 
     /// Creates new instance of a sound engine without OS audio output device (so called headless mode).
     /// The user should periodically run [`State::render`] if they want to implement their own sample sending
-    /// method to an output device (or a file, etc.).
+    /// method to an output device (or a file, etc.). The engine uses default [`SAMPLE_RATE`].
     pub fn without_device() -> Self {
+        Self::without_device_with_sample_rate(SAMPLE_RATE)
+    }
+
+    /// Creates new instance of a sound engine without OS audio output device (so called headless
+    /// mode), that produces samples with the given sample rate (in Hertz). See
+    /// [`Self::without_device`] for more info.
+    pub fn without_device_with_sample_rate(sample_rate: u32) -> Self {
         Self(Arc::new(Mutex::new(State {
             contexts: Default::default(),
             output_device: None,
+            sample_rate: sample_rate.max(1),
+            stereo_buffer: Default::default(),
         })))
     }
 
     /// Tries to initialize default audio output device.
     pub fn initialize_audio_output_device(&self) -> Result<(), Box<dyn Error>> {
         let state = self.clone();
+        let sample_rate = self.state().sample_rate;
 
         let device = tinyaudio::run_output_device(
             tinyaudio::OutputDeviceParameters {
-                sample_rate: SAMPLE_RATE as usize,
+                sample_rate: sample_rate as usize,
                 channels_count: 2,
                 channel_sample_count: SoundContext::SAMPLES_PER_CHANNEL,
             },
@@ -89,11 +110,17 @@ impl SoundEngine {
 
 impl State {
     /// Adds new context to the engine. Each context must be added to the engine to emit
-    /// sounds.
+    /// sounds. The sample rate of the context is set to the sample rate of the engine.
     pub fn add_context(&mut self, context: SoundContext) {
+        context.state().set_sample_rate(self.sample_rate);
         self.contexts.push(context);
     }
 
+    /// Returns the sample rate (in Hertz) of the output of the engine.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
     /// Removes a context from the engine. Removed context will no longer produce any sound.
     pub fn remove_context(&mut self, context: SoundContext) {
         if let Some(position) = self.contexts.iter().position(|c| c == &context) {
@@ -118,7 +145,7 @@ impl State {
         &self.contexts
     }
 
-    /// Returns the length of buf to be passed to [`Self::render()`].
+    /// Returns the length of buf to be passed to [`Self::render()`] or [`Self::render_mono()`].
     pub fn render_buffer_len() -> usize {
         SoundContext::SAMPLES_PER_CHANNEL
     }
@@ -127,6 +154,12 @@ impl State {
     /// elements. This method must be used if and only if the engine was created via
     /// [`SoundEngine::without_device`].
     ///
+    /// ## Layout
+    ///
+    /// Each element of the buffer is a stereo frame `(left, right)`, so the buffer could be treated
+    /// as interleaved stereo samples `L R L R ...` with [`Self::sample_rate`] frames per second. Use
+    /// [`Self::render_mono`] if you need a single channel.
+    ///
     /// ## Deadlocks
     ///
     /// This method internally locks added sound contexts so it must be called when all the contexts
@@ -136,6 +169,63 @@ impl State {
         self.render_inner(buf);
     }
 
+    /// Renders the sound into buf and down-mixes it into a single channel, every sample is an
+    /// average of left and right channels. The buf must have at least
+    /// [`Self::render_buffer_len()`] elements, each element is a single sample and there's
+    /// [`Self::sample_rate`] samples per second. This method must be used if and only if
+    /// the engine was created via [`SoundEngine::without_device`]. It could be useful to record
+    /// voice-overs or to produce smaller files.
+    ///
+    /// ## Deadlocks
+    ///
+    /// This method internally locks added sound contexts so it must be called when all the contexts
+    /// are unlocked or you'll get a deadlock.
+    pub fn render_mono(&mut self, buf: &mut [f32]) {
+        let mut stereo_buffer = std::mem::take(&mut self.stereo_buffer);
+        stereo_buffer.clear();
+        stereo_buffer.resize(buf.len(), (0.0, 0.0));
+        self.render_inner(&mut stereo_buffer);
+        downmix_to_mono(&stereo_buffer, buf);
+        self.stereo_buffer = stereo_buffer;
+    }
+
+    /// Renders the sound of every added context for the given duration and returns the full mix. It
+    /// could be used for automated testing of audio or to render sounds offline. The mix has
+    /// [`Self::sample_rate`] stereo frames per second, see [`Self::render`] for more info about the
+    /// layout. This method must be used if and only if the engine was created via
+    /// [`SoundEngine::without_device`].
+    ///
+    /// Rendering stops early if there's no playing sound sources left and the mix (including the
+    /// tails of effects, such as reverb) has decayed to silence. This means that the returned mix
+    /// could be shorter than the requested duration.
+    ///
+    /// ## Deadlocks
+    ///
+    /// This method internally locks added sound contexts so it must be called when all the contexts
+    /// are unlocked or you'll get a deadlock.
+    pub fn render_full(&mut self, duration: Duration) -> Vec<(f32, f32)> {
+        let total_len = (duration.as_secs_f64() * self.sample_rate as f64).round() as usize;
+        let mut mix = Vec::with_capacity(total_len);
+        let mut buf = vec![(0.0, 0.0); Self::render_buffer_len()];
+
+        while mix.len() < total_len {
+            self.render(&mut buf);
+
+            let len = buf.len().min(total_len - mix.len());
+            mix.extend_from_slice(&buf[..len]);
+
+            let is_playing = self
+                .contexts
+                .iter()
+                .any(|context| context.state().active_voice_count() > 0);
+            if !is_playing && is_silent(&buf) {
+                break;
+            }
+        }
+
+        mix
+    }
+
     fn render_inner(&mut self, buf: &mut [(f32, f32)]) {
         for context in self.contexts.iter_mut() {
             context.state().render(buf);
@@ -143,6 +233,20 @@ impl State {
     }
 }
 
+// Every sample below this threshold (-100 dB) is considered as silence.
+const SILENCE_THRESHOLD: f32 = 1.0e-5;
+
+fn is_silent(buf: &[(f32, f32)]) -> bool {
+    buf.iter()
+        .all(|(left, right)| left.abs() < SILENCE_THRESHOLD && right.abs() < SILENCE_THRESHOLD)
+}
+
+fn downmix_to_mono(stereo: &[(f32, f32)], mono: &mut [f32]) {
+    for (mono, (left, right)) in mono.iter_mut().zip(stereo) {
+        *mono = (left + right) * 0.5;
+    }
+}
+
 impl Visit for State {
     fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
         if visitor.is_reading() {
@@ -153,6 +257,189 @@ impl Visit for State {
 
         self.contexts.visit("Contexts", &mut region)?;
 
+        if region.is_reading() {
+            for context in self.contexts.iter() {
+                context.state().set_sample_rate(self.sample_rate);
+            }
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{DataSource, SoundBufferResource, SoundBufferResourceExtension},
+        context::SoundContext,
+        engine::{downmix_to_mono, SoundEngine, State},
+        source::{SoundSourceBuilder, Status},
+    };
+    use std::{
+        sync::{mpsc::channel, Arc, Mutex},
+        time::Duration,
+    };
+
+    #[test]
+    fn test_downmix_to_mono() {
+        let stereo = [(1.0, 0.0), (0.5, 0.5), (-1.0, 1.0)];
+        let mut mono = [0.0; 3];
+        downmix_to_mono(&stereo, &mut mono);
+        assert_eq!(mono, [0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_render_mono_without_contexts() {
+        let engine = SoundEngine::without_device();
+        let mut buf = vec![1.0; State::render_buffer_len()];
+        engine.state().render_mono(&mut buf);
+        assert!(buf.iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn test_add_context_sets_sample_rate() {
+        let engine = SoundEngine::without_device_with_sample_rate(48000);
+        let context = SoundContext::new();
+        engine.state().add_context(context.clone());
+        assert_eq!(engine.state().sample_rate(), 48000);
+        assert_eq!(context.state().sample_rate(), 48000);
+        assert_eq!(context.state().normalize_frequency(24000.0), 0.5);
+    }
+
+    #[test]
+    fn test_render_full() {
+        let engine = SoundEngine::without_device();
+        let context = SoundContext::new();
+        engine.state().add_context(context.clone());
+
+        let mix = engine.state().render_full(Duration::from_secs(1));
+        assert_eq!(mix.len(), State::render_buffer_len());
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.5; 44100],
+        })
+        .unwrap();
+        let source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .with_play_once(true)
+            .with_spatial_blend_factor(0.0)
+            .build()
+            .unwrap();
+        context.state().add_source(source);
+
+        // The source plays for one second, so the rendering must stop right after that.
+        let mix = engine.state().render_full(Duration::from_secs(10));
+        assert!(mix.len() > 44100);
+        assert!(mix.len() <= 44100 + 2 * State::render_buffer_len());
+        assert!(mix[0].0 > 0.0);
+
+        let mix = engine.state().render_full(Duration::from_millis(10));
+        assert_eq!(mix.len(), 441);
+    }
+
+    #[test]
+    fn test_steal_voices_of_looping_source() {
+        let engine = SoundEngine::without_device();
+        let context = SoundContext::new();
+        engine.state().add_context(context.clone());
+
+        let make_source = |sample_count: usize, looping: bool| {
+            let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+                sample_rate: 44100,
+                channel_count: 1,
+                samples: vec![0.5; sample_count],
+            })
+            .unwrap();
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_status(Status::Playing)
+                .with_looping(looping)
+                .with_spatial_blend_factor(0.0)
+                .build()
+                .unwrap()
+        };
+
+        // The looping source wraps around many times, so its playback position is small, but it
+        // is still the oldest one.
+        let looping = context.state().add_source(make_source(100, true));
+        engine.state().render_full(Duration::from_millis(100));
+        let newer = context.state().add_source(make_source(44100, false));
+        engine.state().render_full(Duration::from_millis(10));
+
+        context.state().set_max_voices(Some(1));
+        engine.state().render_full(Duration::from_millis(10));
+
+        let state = context.state();
+        assert_eq!(state.source(looping).status(), Status::Stopped);
+        assert_eq!(state.source(newer).status(), Status::Playing);
+    }
+
+    #[test]
+    fn test_deep_clone_has_no_event_listeners() {
+        let engine = SoundEngine::without_device();
+        let context = SoundContext::new();
+
+        let (sender, receiver) = channel();
+        context.state().add_event_listener(sender);
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.5; 100],
+        })
+        .unwrap();
+        let source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .build()
+            .unwrap();
+        context.state().add_source(source);
+
+        // Only the copy is rendered, so the listener of the original context must not receive
+        // anything.
+        engine.state().add_context(context.deep_clone());
+        engine.state().render_full(Duration::from_millis(10));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_capture_sink() {
+        let engine = SoundEngine::without_device();
+        let context = SoundContext::new();
+        engine.state().add_context(context.clone());
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.5; 44100],
+        })
+        .unwrap();
+        let source = SoundSourceBuilder::new()
+            .with_buffer(buffer)
+            .with_status(Status::Playing)
+            .with_spatial_blend_factor(0.0)
+            .build()
+            .unwrap();
+        context.state().add_source(source);
+
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink_captured = captured.clone();
+        assert!(context
+            .state()
+            .set_capture_sink(Some(Box::new(move |block: &[(f32, f32)]| {
+                sink_captured.lock().unwrap().extend_from_slice(block)
+            })))
+            .is_none());
+
+        let mut buf = vec![(0.0, 0.0); State::render_buffer_len()];
+        engine.state().render(&mut buf);
+        assert_eq!(*captured.lock().unwrap(), buf);
+
+        assert!(context.state().set_capture_sink(None).is_some());
+        engine.state().render(&mut buf);
+        assert_eq!(captured.lock().unwrap().len(), State::render_buffer_len());
+    }
+}
@@ -19,7 +19,7 @@
 //! To use HRTF you need to change default renderer to HRTF renderer like so:
 //!
 //! ```no_run
-//! use fyrox_sound::context::{self, SoundContext};
+//! use fyrox_sound::context::SoundContext;
 //! use fyrox_sound::renderer::hrtf::{HrirSphereResource, HrirSphereResourceExt, HrtfRenderer};
 //! use fyrox_sound::renderer::Renderer;
 //! use std::path::{Path, PathBuf};
@@ -29,7 +29,8 @@
 //!     // IRC_1002_C.bin is HRIR sphere in binary format, can be any valid HRIR sphere
 //!     // from base mentioned above.
 //!     let hrir_path = PathBuf::from("examples/data/IRC_1002_C.bin");
-//!     let hrir_sphere = HrirSphere::from_file(&hrir_path, context::SAMPLE_RATE).unwrap();
+//!     let sample_rate = context.state().sample_rate();
+//!     let hrir_sphere = HrirSphere::from_file(&hrir_path, sample_rate).unwrap();
 //!
 //!     context.state().set_renderer(Renderer::HrtfRenderer(HrtfRenderer::new(HrirSphereResource::from_hrir_sphere(hrir_sphere, hrir_path.into()))));
 //! }
@@ -83,6 +84,9 @@ pub struct HrtfRenderer {
     hrir_resource: Option<HrirSphereResource>,
     #[reflect(hidden)]
     processor: Option<hrtf::HrtfProcessor>,
+    // Sample rate of the HRIR sphere used by the processor.
+    #[reflect(hidden)]
+    sample_rate: u32,
 }
 
 impl Visit for HrtfRenderer {
@@ -96,18 +100,14 @@ impl Visit for HrtfRenderer {
 }
 
 impl HrtfRenderer {
-    /// Creates new HRTF renderer using specified HRTF sphere. See module docs for more info.
+    /// Creates new HRTF renderer using specified HRTF sphere. See module docs for more info. The
+    /// HRIR sphere is resampled to the sample rate of the sound context, that uses the renderer, on
+    /// the first render call.
     pub fn new(hrir_sphere_resource: HrirSphereResource) -> Self {
         Self {
-            processor: Some(hrtf::HrtfProcessor::new(
-                {
-                    let sphere = hrir_sphere_resource.data_ref().hrir_sphere.clone().unwrap();
-                    sphere
-                },
-                SoundContext::HRTF_INTERPOLATION_STEPS,
-                SoundContext::HRTF_BLOCK_LEN,
-            )),
+            processor: None,
             hrir_resource: Some(hrir_sphere_resource),
+            sample_rate: 0,
         }
     }
 
@@ -129,18 +129,28 @@ impl HrtfRenderer {
         listener: &Listener,
         distance_model: DistanceModel,
         out_buf: &mut [(f32, f32)],
+        sample_rate: u32,
     ) {
+        // HRIR sphere must match the sample rate of the context, otherwise the impulse responses
+        // will be applied at wrong frequencies.
+        if self.sample_rate != sample_rate {
+            self.processor = None;
+        }
+
         // Re-create HRTF processor on the fly only when a respective HRIR sphere resource is fully loaded.
         // This is a poor-man's async support for crippled OSes such as WebAssembly.
         if self.processor.is_none() {
             if let Some(resource) = self.hrir_resource.as_ref() {
                 let mut header = resource.state();
                 if let Some(hrir) = header.data() {
-                    self.processor = Some(hrtf::HrtfProcessor::new(
-                        hrir.hrir_sphere.clone().unwrap(),
-                        SoundContext::HRTF_INTERPOLATION_STEPS,
-                        SoundContext::HRTF_BLOCK_LEN,
-                    ));
+                    if let Some(hrir_sphere) = hrir.hrir_sphere(sample_rate) {
+                        self.processor = Some(hrtf::HrtfProcessor::new(
+                            hrir_sphere,
+                            SoundContext::HRTF_INTERPOLATION_STEPS,
+                            SoundContext::HRTF_BLOCK_LEN,
+                        ));
+                        self.sample_rate = sample_rate;
+                    }
                 }
             }
         }
@@ -187,6 +197,38 @@ pub struct HrirSphereResourceData {
     #[reflect(hidden)]
     #[visit(skip)]
     hrir_sphere: Option<HrirSphere>,
+    // Sample rate of the sphere, `None` for pre-loaded spheres with unknown sample rate.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    sample_rate: Option<u32>,
+    // Raw data of the sphere, that is used to resample it to a different sample rate.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    source: Vec<u8>,
+}
+
+impl HrirSphereResourceData {
+    /// Returns the HRIR sphere resampled to the given sample rate. Spheres, that were loaded by the
+    /// resource manager, are resampled on demand and the last resampled sphere is cached.
+    /// Pre-loaded spheres (see [`HrirSphereResourceExt::from_hrir_sphere`]) cannot be resampled
+    /// and returned as is, so they must be created with the sample rate of the sound context that
+    /// uses them.
+    pub fn hrir_sphere(&mut self, sample_rate: u32) -> Option<HrirSphere> {
+        if self.sample_rate.is_some_and(|rate| rate != sample_rate) && !self.source.is_empty() {
+            match HrirSphere::new(self.source.as_slice(), sample_rate) {
+                Ok(hrir_sphere) => {
+                    self.hrir_sphere = Some(hrir_sphere);
+                    self.sample_rate = Some(sample_rate);
+                }
+                Err(err) => Log::err(format!(
+                    "Unable to resample HRIR sphere to {} Hz. Reason: {:?}",
+                    sample_rate, err
+                )),
+            }
+        }
+
+        self.hrir_sphere.clone()
+    }
 }
 
 impl Debug for HrirSphereResourceData {
@@ -237,11 +279,14 @@ impl ResourceLoader for HrirSphereLoader {
 
     fn load(&self, path: PathBuf, io: Arc<dyn ResourceIo>) -> BoxedLoaderFuture {
         Box::pin(async move {
-            let reader = io.file_reader(&path).await.map_err(LoadError::new)?;
+            let source = io.load_file(&path).await.map_err(LoadError::new)?;
+            // The sphere is resampled later if the sound context uses a different sample rate.
             let hrir_sphere =
-                HrirSphere::new(reader, context::SAMPLE_RATE).map_err(LoadError::new)?;
+                HrirSphere::new(source.as_slice(), context::SAMPLE_RATE).map_err(LoadError::new)?;
             Ok(LoaderPayload::new(HrirSphereResourceData {
                 hrir_sphere: Some(hrir_sphere),
+                sample_rate: Some(context::SAMPLE_RATE),
+                source,
             }))
         })
     }
@@ -253,7 +298,8 @@ pub type HrirSphereResource = Resource<HrirSphereResourceData>;
 /// A set of extension methods for [`HrirSphereResource`]
 pub trait HrirSphereResourceExt {
     /// Creates a new HRIR sphere resource directly from pre-loaded HRIR sphere. It could be used if you
-    /// do not use a resource manager, but want to load HRIR spheres manually.
+    /// do not use a resource manager, but want to load HRIR spheres manually. Such spheres are not
+    /// resampled, so they must be loaded with the sample rate of the sound context that uses them.
     fn from_hrir_sphere(hrir_sphere: HrirSphere, kind: ResourceKind) -> Self;
 }
 
@@ -263,6 +309,8 @@ impl HrirSphereResourceExt for HrirSphereResource {
             kind,
             HrirSphereResourceData {
                 hrir_sphere: Some(hrir_sphere),
+                sample_rate: None,
+                source: Default::default(),
             },
         )
     }
@@ -99,6 +99,8 @@ pub struct SoundSource {
     #[visit(optional)]
     pub(crate) bus: String,
     play_once: bool,
+    #[visit(optional)]
+    priority: u8,
     // Here we use Option because when source is just created it has no info about it
     // previous left and right channel gains. We can't set it to 1.0 for example
     // because it would give incorrect results: a sound would just start as loud as it
@@ -138,6 +140,11 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    // Time in seconds since the source has started playing. Unlike the playback position, it is
+    // not reset when a looping source wraps around, so it could be used as the age of the source.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    play_time: f64,
 }
 
 impl Default for SoundSource {
@@ -156,6 +163,7 @@ impl Default for SoundSource {
             status: Status::Stopped,
             bus: "Master".to_string(),
             play_once: false,
+            priority: SoundSource::DEFAULT_PRIORITY,
             last_left_gain: None,
             last_right_gain: None,
             frame_samples: Default::default(),
@@ -168,11 +176,15 @@ impl Default for SoundSource {
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            play_time: 0.0,
         }
     }
 }
 
 impl SoundSource {
+    /// Default priority of every sound source.
+    pub const DEFAULT_PRIORITY: u8 = 128;
+
     /// Sets new name of the sound source.
     pub fn set_name<N: AsRef<str>>(&mut self, name: N) {
         name.as_ref().clone_into(&mut self.name);
@@ -261,6 +273,20 @@ impl SoundSource {
         self.play_once
     }
 
+    /// Sets new priority of the source. Priority is used when the amount of playing sources in a
+    /// context exceeds its voice limit (see [`crate::context::State::set_max_voices`]) - sources
+    /// with the lowest priority will be stopped first. Higher values mean higher priority. Default
+    /// value is [`Self::DEFAULT_PRIORITY`].
+    pub fn set_priority(&mut self, priority: u8) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns current priority of the source.
+    pub fn priority(&self) -> u8 {
+        self.priority
+    }
+
     /// Sets new gain (volume) of sound. Value should be in 0..1 range, but it is not clamped
     /// and larger values can be used to "overdrive" sound.
     ///
@@ -330,12 +356,17 @@ impl SoundSource {
         self.pitch
     }
 
+    pub(crate) fn play_time(&self) -> f64 {
+        self.play_time
+    }
+
     /// Stops sound source. Automatically rewinds streaming buffers.
     pub fn stop(&mut self) -> Result<(), SoundError> {
         self.status = Status::Stopped;
 
         self.buf_read_pos = 0.0;
         self.playback_pos = 0.0;
+        self.play_time = 0.0;
 
         if let Some(buffer) = self.buffer.as_ref() {
             if let Some(SoundBuffer::Streaming(streaming)) = buffer.state().data() {
@@ -489,7 +520,7 @@ impl SoundSource {
         }
     }
 
-    pub(crate) fn render(&mut self, amount: usize) {
+    pub(crate) fn render(&mut self, amount: usize, device_sample_rate: u32) {
         if self.frame_samples.capacity() < amount {
             self.frame_samples = Vec::with_capacity(amount);
         }
@@ -500,7 +531,14 @@ impl SoundSource {
             let mut state = buffer.state();
             if let Some(buffer) = state.data() {
                 if self.status == Status::Playing && !buffer.is_empty() {
-                    self.render_playing(buffer, amount);
+                    // Resampling multiplier is calculated for the default sample rate, so it must
+                    // be scaled if the device runs at a different rate.
+                    let step = self.pitch
+                        * self.resampling_multiplier
+                        * f64::from(crate::context::SAMPLE_RATE)
+                        / f64::from(device_sample_rate);
+                    self.play_time += amount as f64 / f64::from(device_sample_rate);
+                    self.render_playing(buffer, amount, step);
                 }
             }
         }
@@ -508,10 +546,10 @@ impl SoundSource {
         self.frame_samples.resize(amount, (0.0, 0.0));
     }
 
-    fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize) {
+    fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize, step: f64) {
         let mut count = 0;
         loop {
-            count += self.render_until_block_end(buffer, amount - count);
+            count += self.render_until_block_end(buffer, amount - count, step);
             if count == amount {
                 break;
             }
@@ -534,6 +572,7 @@ impl SoundSource {
                 self.playback_pos = 0.0;
                 if !self.looping {
                     self.status = Status::Stopped;
+                    self.play_time = 0.0;
                     return;
                 }
             } else {
@@ -544,8 +583,12 @@ impl SoundSource {
 
     // Renders until the end of the block or until amount samples is written and returns
     // the number of written samples.
-    fn render_until_block_end(&mut self, buffer: &mut SoundBuffer, mut amount: usize) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
+    fn render_until_block_end(
+        &mut self,
+        buffer: &mut SoundBuffer,
+        mut amount: usize,
+        step: f64,
+    ) -> usize {
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -709,6 +752,7 @@ pub struct SoundSourceBuilder {
     looping: bool,
     status: Status,
     play_once: bool,
+    priority: u8,
     playback_time: Duration,
     radius: f32,
     position: Vector3<f32>,
@@ -736,6 +780,7 @@ impl SoundSourceBuilder {
             looping: false,
             status: Status::Stopped,
             play_once: false,
+            priority: SoundSource::DEFAULT_PRIORITY,
             playback_time: Default::default(),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
@@ -800,6 +845,12 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_priority`]
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Sets desired name of the source.
     pub fn with_name<N: AsRef<str>>(mut self, name: N) -> Self {
         name.as_ref().clone_into(&mut self.name);
@@ -849,6 +900,7 @@ impl SoundSourceBuilder {
             gain: self.gain,
             pitch: self.pitch,
             play_once: self.play_once,
+            priority: self.priority,
             panning: self.panning,
             status: self.status,
             looping: self.looping,
@@ -2,10 +2,10 @@
 //! more info.
 
 use crate::{
-    core::pool::Handle, font::FontResource, message::UiMessage, RestrictionEntry, UiNode,
-    UserInterface,
+    core::pool::Handle, font::FontResource, message::UiMessage, DeferredAction, RestrictionEntry,
+    UiNode, UserInterface,
 };
-use fyrox_graph::BaseSceneGraph;
+use fyrox_graph::{BaseSceneGraph, SceneGraph};
 use std::{
     ops::{Index, IndexMut},
     sync::mpsc::Sender,
@@ -113,6 +113,52 @@ impl<'a> BuildContext<'a> {
         self.ui.link_nodes(child, parent, false)
     }
 
+    /// Builds a subtree of widgets using the given closure and links every returned widget to the
+    /// given parent widget. Children are linked in the same order as they're returned from the
+    /// closure. Returns the parent handle, so the method can be chained with the parent builder.
+    /// It is a shortcut for building children and then calling [`Self::link`] for each of them.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use fyrox_ui::{
+    /// #     border::BorderBuilder, core::pool::Handle, text::TextBuilder, widget::WidgetBuilder,
+    /// #     BuildContext, UiNode,
+    /// # };
+    /// fn build_panel(ctx: &mut BuildContext) -> Handle<UiNode> {
+    ///     let panel = BorderBuilder::new(WidgetBuilder::new()).build(ctx);
+    ///     ctx.build_subtree(panel, |ctx| {
+    ///         vec![
+    ///             TextBuilder::new(WidgetBuilder::new())
+    ///                 .with_text("First")
+    ///                 .build(ctx),
+    ///             TextBuilder::new(WidgetBuilder::new())
+    ///                 .with_text("Second")
+    ///                 .build(ctx),
+    ///         ]
+    ///     })
+    /// }
+    /// ```
+    pub fn build_subtree<F>(&mut self, parent: Handle<UiNode>, func: F) -> Handle<UiNode>
+    where
+        F: FnOnce(&mut BuildContext) -> Vec<Handle<UiNode>>,
+    {
+        for child in func(self) {
+            self.link(child, parent);
+        }
+        parent
+    }
+
+    /// Searches for a widget with the given name down the tree starting from the given root widget
+    /// (the root widget is checked as well). Returns [`Handle::NONE`] if nothing is found. See
+    /// [`SceneGraph::find_by_name`] for more info.
+    pub fn find_by_name(&self, root: Handle<UiNode>, name: &str) -> Handle<UiNode> {
+        self.ui
+            .find_by_name(root, name)
+            .map(|(handle, _)| handle)
+            .unwrap_or_default()
+    }
+
     /// Copies a widget, adds it to the UI, links it to the root node of the UI and returns the handle to it.
     pub fn copy(&mut self, node: Handle<UiNode>) -> Handle<UiNode> {
         self.ui.copy_node(node)
@@ -140,6 +186,16 @@ impl<'a> BuildContext<'a> {
         self.ui.remove_picking_restriction(node)
     }
 
+    /// Queues the given closure to be executed once after the next layout pass of the UI. At this
+    /// moment every widget has its size and position calculated, so the closure can read computed
+    /// metrics of widgets (for example, [`crate::widget::Widget::actual_local_size`]) and adjust
+    /// other widgets accordingly. It is a lightweight alternative to sending a message for such
+    /// tasks. Keep in mind, that changes made by the closure will be taken into account only on
+    /// the next layout pass.
+    pub fn defer(&mut self, action: DeferredAction) {
+        self.ui.defer(action)
+    }
+
     /// Returns an immutable reference to the user interface.
     pub fn inner(&self) -> &UserInterface {
         self.ui
@@ -150,3 +206,74 @@ impl<'a> BuildContext<'a> {
         self.ui
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        border::BorderBuilder,
+        core::{algebra::Vector2, pool::Handle},
+        widget::WidgetBuilder,
+        UserInterface,
+    };
+    use fyrox_graph::BaseSceneGraph;
+
+    #[test]
+    fn test_build_subtree() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let parent = BorderBuilder::new(WidgetBuilder::new()).build(ctx);
+        let mut children = Vec::new();
+        let result = ctx.build_subtree(parent, |ctx| {
+            children = (0..3)
+                .map(|_| BorderBuilder::new(WidgetBuilder::new()).build(ctx))
+                .collect();
+            children.clone()
+        });
+
+        assert_eq!(result, parent);
+        assert_eq!(ui.node(parent).children(), children.as_slice());
+        for child in children {
+            assert_eq!(ui.node(child).parent(), parent);
+        }
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let mut child = Handle::NONE;
+        let root = BorderBuilder::new(WidgetBuilder::new().with_name("Root")).build(ctx);
+        ctx.build_subtree(root, |ctx| {
+            child = BorderBuilder::new(WidgetBuilder::new().with_name("Child")).build(ctx);
+            vec![child]
+        });
+
+        assert_eq!(ctx.find_by_name(root, "Root"), root);
+        assert_eq!(ctx.find_by_name(root, "Child"), child);
+        assert_eq!(ctx.find_by_name(child, "Root"), Handle::NONE);
+        assert_eq!(ctx.find_by_name(root, "Missing"), Handle::NONE);
+    }
+
+    #[test]
+    fn test_defer() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let child = BorderBuilder::new(WidgetBuilder::new().with_width(30.0)).build(ctx);
+        let parent = BorderBuilder::new(WidgetBuilder::new().with_child(child)).build(ctx);
+        ctx.defer(Box::new(move |ui| {
+            let width = ui.node(child).actual_local_size().x;
+            ui.node_mut(parent).set_width(width * 2.0);
+        }));
+
+        ui.update_layout(Vector2::new(100.0, 100.0));
+        assert_eq!(ui.node(parent).width(), 60.0);
+
+        // Deferred actions must run only once.
+        ui.node_mut(parent).set_width(10.0);
+        ui.update_layout(Vector2::new(100.0, 100.0));
+        assert_eq!(ui.node(parent).width(), 10.0);
+    }
+}
@@ -13,12 +13,15 @@ use crate::{
     },
     define_constructor,
     grid::{Column, GridBuilder, Row},
-    message::{KeyCode, MessageDirection, UiMessage},
+    message::{ButtonState, KeyCode, MessageDirection, UiMessage},
+    text::TextBuilder,
     vector_image::{Primitive, VectorImageBuilder},
     widget::{Widget, WidgetBuilder, WidgetMessage},
     BuildContext, Control, HorizontalAlignment, MouseButton, Thickness, UiNode, UserInterface,
-    VerticalAlignment, BRUSH_BRIGHT, BRUSH_BRIGHT_BLUE, BRUSH_DARKEST, BRUSH_LIGHT, BRUSH_TEXT,
+    VerticalAlignment, BRUSH_BRIGHT, BRUSH_BRIGHT_BLUE, BRUSH_DARKER, BRUSH_DARKEST, BRUSH_LIGHT,
+    BRUSH_TEXT,
 };
+use fyrox_graph::{BaseSceneGraph, SceneGraph};
 use std::ops::{Deref, DerefMut};
 
 /// A set of possible check box messages.
@@ -26,6 +29,26 @@ use std::ops::{Deref, DerefMut};
 pub enum CheckBoxMessage {
     /// Emitted when the check box changed its state. Could also be used to modify check box state.
     Check(Option<bool>),
+    /// Emitted by momentary check boxes (see [`CheckBox::momentary`]) when they were pressed and
+    /// released. Such check boxes never change their state, so this message is the only way to
+    /// react to user actions.
+    Pressed,
+    /// Could be used to disable or enable the check box. Disabled check boxes ignore mouse clicks
+    /// and Space key presses, but their state still could be changed from code using
+    /// [`CheckBoxMessage::Check`] message. The background of disabled check boxes is drawn using
+    /// [`CheckBox::disabled_brush`].
+    Disabled(bool),
+    /// Emitted when the check box was clicked or toggled by Space key press. Unlike
+    /// [`CheckBoxMessage::Check`], this message is never emitted when the state is changed from
+    /// code, so it could be used to react only to user actions. Contains the state requested by
+    /// the user, it is followed by [`CheckBoxMessage::Check`] message if the state has changed.
+    Clicked(Option<bool>),
+    /// Could be used to replace the content of the check box (a label, an icon, etc.) at runtime.
+    /// The new content is placed at the right of the check mark. The previous content is not
+    /// destroyed, it is unlinked from the check box (attached to the root of the user interface)
+    /// and the sender of the message owns it afterwards: it could be re-used or it must be removed
+    /// using [`WidgetMessage::remove`]. [`Handle::NONE`] just unlinks the content.
+    Content(Handle<UiNode>),
 }
 
 impl CheckBoxMessage {
@@ -33,6 +56,22 @@ impl CheckBoxMessage {
         /// Creates [`CheckBoxMessage::checked`] message.
         CheckBoxMessage:Check => fn checked(Option<bool>), layout: false
     );
+    define_constructor!(
+        /// Creates [`CheckBoxMessage::Pressed`] message.
+        CheckBoxMessage:Pressed => fn pressed(), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CheckBoxMessage::Disabled`] message.
+        CheckBoxMessage:Disabled => fn disabled(bool), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CheckBoxMessage::Clicked`] message.
+        CheckBoxMessage:Clicked => fn clicked(Option<bool>), layout: false
+    );
+    define_constructor!(
+        /// Creates [`CheckBoxMessage::Content`] message.
+        CheckBoxMessage:Content => fn content(Handle<UiNode>), layout: false
+    );
 }
 
 /// Checkbox is a UI widget that have three states - `Checked`, `Unchecked` and `Undefined`. In most cases it is used
@@ -59,7 +98,8 @@ impl CheckBoxMessage {
 /// The above code will create a checkbox without any textual info, but usually checkboxes have some useful info
 /// near them. To create such checkbox, you could use [`CheckBoxBuilder::with_content`] method which accepts any widget handle.
 /// For checkbox with text, you could use [`crate::text::TextBuilder`] to create textual content, for checkbox with image - use
-/// [`crate::image::ImageBuilder`]. As already said, you're free to use any widget handle there.
+/// [`crate::image::ImageBuilder`]. As already said, you're free to use any widget handle there. The content
+/// could be replaced at runtime using [`CheckBoxMessage::Content`] message (for example, to update localized text).
 ///
 /// Here's an example of checkbox with textual content.
 ///
@@ -115,6 +155,63 @@ impl CheckBoxMessage {
 /// # }
 /// ```
 ///
+/// [`CheckBoxMessage::Check`] is emitted on every state change, including the ones made from code.
+/// If you need to react only to user actions, handle [`CheckBoxMessage::Clicked`] message instead,
+/// it is emitted only when the check box is clicked or toggled by Space key press.
+///
+/// ## Momentary check boxes
+///
+/// A check box could also be used as a trigger that shows check mark only while it is pressed and
+/// never retains its checked state. Use [`CheckBoxBuilder::with_momentary`] to create such check box
+/// and handle [`CheckBoxMessage::Pressed`] message to react to user actions.
+///
+/// ## Drag toggle
+///
+/// Check boxes could be toggled by dragging the mouse over them with the left button pressed, which is
+/// useful for long lists of options. Use [`CheckBoxBuilder::with_drag_toggle`] to enable this mode. In
+/// this mode check box toggles its state when pressed (instead of released) and does not capture the
+/// mouse, so other check boxes could receive [`WidgetMessage::MouseEnter`] while the button is held.
+/// This mode has no effect on momentary check boxes.
+///
+/// ## Radio groups
+///
+/// Check boxes could be combined into radio groups, where only one check box could be checked at a
+/// time. Use [`CheckBoxBuilder::with_radio_group`] to assign the same group id to every check box
+/// of a group. When a check box of a group becomes checked, every other checked member of the group
+/// is unchecked automatically. Checked members of a group cannot be unchecked by clicking on them.
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     check_box::CheckBoxBuilder, core::{pool::Handle, uuid::Uuid}, widget::WidgetBuilder,
+/// #     BuildContext, UiNode,
+/// # };
+/// fn create_radio_buttons(ctx: &mut BuildContext) -> Vec<Handle<UiNode>> {
+///     let group = Uuid::new_v4();
+///     (0..3)
+///         .map(|i| {
+///             CheckBoxBuilder::new(WidgetBuilder::new())
+///                 .checked(Some(i == 0))
+///                 .with_radio_group(group)
+///                 .build(ctx)
+///         })
+///         .collect()
+/// }
+/// ```
+///
+/// ## Parent check boxes
+///
+/// A check box could derive its state from a set of other check boxes, which is useful for "select
+/// all" check boxes. Use [`CheckBoxBuilder::with_child_check_boxes`] to create such check box. Its
+/// state is `Some(true)` when every child is checked, `Some(false)` when none of them is checked
+/// and `None` otherwise. Toggling the parent check box checks or unchecks every child.
+///
+/// ## Disabled state
+///
+/// Check box could be disabled to prevent the user from changing its state, while keeping it fully
+/// visible (for example, when a form is inactive). Use [`CheckBoxBuilder::with_disabled`] or send
+/// [`CheckBoxMessage::Disabled`] message to change this state at runtime. Disabled check boxes draw
+/// their background using [`CheckBoxBuilder::with_disabled_brush`].
+///
 /// Keep in mind that checkbox (as any other widget) generates [`WidgetMessage`] instances. You can catch them too and
 /// do a custom handling if you need.
 ///
@@ -140,22 +237,264 @@ pub struct CheckBox {
     pub uncheck_mark: InheritableVariable<Handle<UiNode>>,
     /// Check mark that is used when the state is `None`.
     pub undefined_mark: InheritableVariable<Handle<UiNode>>,
+    /// A flag, that defines whether the check box acts like a momentary button or not. Momentary
+    /// check boxes show the check mark only while pressed and emit [`CheckBoxMessage::Pressed`]
+    /// instead of changing their state.
+    #[visit(optional)]
+    pub momentary: InheritableVariable<bool>,
+    /// A flag, that defines whether the check box could be toggled by dragging the mouse over it
+    /// with the left button pressed. See [`CheckBoxBuilder::with_drag_toggle`] for more info.
+    #[visit(optional)]
+    pub drag_toggle: InheritableVariable<bool>,
+    /// A flag, that defines whether the check box cycles through all three states on click or
+    /// Space key press. See [`CheckBoxBuilder::with_tristate_cycle`] for more info.
+    #[visit(optional)]
+    pub tristate_cycle: InheritableVariable<bool>,
+    /// An id of a radio group, the check box belongs to. Only one check box of a group could be
+    /// checked at a time. See [`CheckBoxBuilder::with_radio_group`] for more info.
+    #[visit(optional)]
+    pub radio_group: InheritableVariable<Option<Uuid>>,
+    /// A list of child check boxes, that defines the state of this check box. See
+    /// [`CheckBoxBuilder::with_child_check_boxes`] for more info.
+    #[visit(optional)]
+    pub child_check_boxes: InheritableVariable<Vec<Handle<UiNode>>>,
+    /// A flag, that defines whether the check box is disabled or not. Disabled check boxes ignore
+    /// user input. See [`CheckBoxMessage::Disabled`] for more info.
+    #[visit(optional)]
+    pub disabled: InheritableVariable<bool>,
+    /// A brush, that is used for the background of the check box when it is disabled.
+    #[visit(optional)]
+    pub disabled_brush: InheritableVariable<Brush>,
+    /// A brush, that is used for the background of the check box when it is enabled.
+    #[visit(optional)]
+    pub background_brush: InheritableVariable<Brush>,
+    /// A widget, that is used as a container for check box contents.
+    #[visit(optional)]
+    pub background: InheritableVariable<Handle<UiNode>>,
+    /// A widget, that is shown near the check mark (usually a label). See
+    /// [`CheckBoxMessage::Content`] for more info.
+    #[visit(optional)]
+    pub content: InheritableVariable<Handle<UiNode>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    drag_toggled: bool,
 }
 
 crate::define_widget_deref!(CheckBox);
 
+impl CheckBox {
+    fn is_drag_toggle(&self) -> bool {
+        *self.drag_toggle && !*self.momentary
+    }
+
+    fn toggle(&self, ui: &UserInterface) {
+        let new_value = if self.radio_group.is_some() {
+            // Members of a radio group could only be checked by the user, unchecking is done by
+            // checking another member of the group.
+            if *self.checked == Some(true) {
+                return;
+            }
+            Some(true)
+        } else if *self.tristate_cycle {
+            // None -> Some(true) -> Some(false) -> None.
+            match *self.checked {
+                None => Some(true),
+                Some(true) => Some(false),
+                Some(false) => None,
+            }
+        } else {
+            // Invert state if it is defined, switch from undefined state to checked otherwise.
+            Some(self.checked.map_or(true, |value| !value))
+        };
+
+        self.click(ui, new_value);
+
+        // Propagate defined state to the child check boxes, the undefined state of a parent check
+        // box is always derived from its children.
+        if new_value.is_some() {
+            for &child in self.child_check_boxes.iter() {
+                ui.send_message(CheckBoxMessage::checked(
+                    child,
+                    MessageDirection::ToWidget,
+                    new_value,
+                ));
+            }
+        }
+    }
+
+    fn click(&self, ui: &UserInterface, value: Option<bool>) {
+        ui.send_message(CheckBoxMessage::clicked(
+            self.handle(),
+            MessageDirection::FromWidget,
+            value,
+        ));
+        ui.send_message(CheckBoxMessage::checked(
+            self.handle(),
+            MessageDirection::ToWidget,
+            value,
+        ));
+    }
+
+    fn uncheck_radio_group_members(&self, ui: &UserInterface) {
+        let Some(radio_group) = *self.radio_group else {
+            return;
+        };
+
+        for (handle, node) in ui.nodes().pair_iter() {
+            if let Some(check_box) = node.query_component::<CheckBox>() {
+                if handle != self.handle()
+                    && *check_box.radio_group == Some(radio_group)
+                    && *check_box.checked == Some(true)
+                {
+                    ui.send_message(CheckBoxMessage::checked(
+                        handle,
+                        MessageDirection::ToWidget,
+                        Some(false),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn derive_state_from_children(&self, ui: &UserInterface) -> Option<bool> {
+        let mut states = self.child_check_boxes.iter().filter_map(|child| {
+            ui.try_get_of_type::<CheckBox>(*child)
+                .map(|check_box| *check_box.checked)
+        });
+
+        let first = states.next().flatten()?;
+        states.all(|state| state == Some(first)).then_some(first)
+    }
+
+    fn set_disabled(&mut self, ui: &UserInterface, disabled: bool) {
+        self.disabled.set_value_and_mark_modified(disabled);
+
+        if self.background.is_some() {
+            ui.send_message(WidgetMessage::background(
+                *self.background,
+                MessageDirection::ToWidget,
+                if disabled {
+                    (*self.disabled_brush).clone()
+                } else {
+                    (*self.background_brush).clone()
+                },
+            ));
+        }
+    }
+
+    fn set_content(&mut self, ui: &mut UserInterface, content: Handle<UiNode>) {
+        // The previous content is owned by the sender of the message from now on.
+        if self.content.is_some() {
+            ui.send_message(WidgetMessage::unlink(
+                *self.content,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        self.content.set_value_and_mark_modified(content);
+
+        // The content is placed in the second column of the grid, that contains the background.
+        let Some(grid) = ui
+            .try_get(*self.background)
+            .map(|background| background.parent())
+        else {
+            return;
+        };
+
+        if let Some(content_ref) = ui.try_get_mut(content) {
+            content_ref.set_row(0).set_column(1);
+            ui.send_message(WidgetMessage::link(
+                content,
+                MessageDirection::ToWidget,
+                grid,
+            ));
+        }
+
+        if let Some(grid_ref) = ui.try_get(grid) {
+            grid_ref.invalidate_layout();
+        }
+    }
+
+    fn set_marks_visibility(&self, ui: &UserInterface, value: Option<bool>) {
+        if self.check_mark.is_none() {
+            return;
+        }
+
+        let (check, uncheck, undefined) = match value {
+            None => (false, false, true),
+            Some(value) => (value, !value, false),
+        };
+
+        ui.send_message(WidgetMessage::visibility(
+            *self.check_mark,
+            MessageDirection::ToWidget,
+            check,
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            *self.uncheck_mark,
+            MessageDirection::ToWidget,
+            uncheck,
+        ));
+        ui.send_message(WidgetMessage::visibility(
+            *self.undefined_mark,
+            MessageDirection::ToWidget,
+            undefined,
+        ));
+    }
+}
+
 impl Control for CheckBox {
+    fn preview_message(&self, ui: &UserInterface, message: &mut UiMessage) {
+        if let Some(CheckBoxMessage::Check(_)) = message.data::<CheckBoxMessage>() {
+            if message.direction() == MessageDirection::FromWidget
+                && self.child_check_boxes.contains(&message.destination())
+            {
+                let derived_state = self.derive_state_from_children(ui);
+                if derived_state != *self.checked {
+                    ui.send_message(CheckBoxMessage::checked(
+                        self.handle(),
+                        MessageDirection::ToWidget,
+                        derived_state,
+                    ));
+                }
+            }
+        }
+    }
+
     fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
         self.widget.handle_routed_message(ui, message);
 
         if let Some(msg) = message.data::<WidgetMessage>() {
+            if *self.disabled {
+                if let WidgetMessage::MouseUp { .. } = msg {
+                    // The check box could be disabled while pressed.
+                    if ui.captured_node() == self.handle() {
+                        ui.release_mouse_capture();
+                    }
+                }
+                return;
+            }
+
             match msg {
                 WidgetMessage::MouseDown { button, .. } => {
                     if *button == MouseButton::Left
                         && (message.destination() == self.handle()
                             || self.widget.has_descendant(message.destination(), ui))
                     {
+                        if self.is_drag_toggle() {
+                            // Do not capture the mouse, otherwise other check boxes won't receive
+                            // mouse enter messages while dragging.
+                            self.drag_toggled = true;
+                            self.toggle(ui);
+                            return;
+                        }
+
                         ui.capture_mouse(self.handle());
+
+                        if *self.momentary {
+                            // Show the check mark only while the check box is pressed.
+                            self.set_marks_visibility(ui, Some(true));
+                        }
                     }
                 }
                 WidgetMessage::MouseUp { button, .. } => {
@@ -163,32 +502,49 @@ impl Control for CheckBox {
                         && (message.destination() == self.handle()
                             || self.widget.has_descendant(message.destination(), ui))
                     {
+                        if self.is_drag_toggle() {
+                            return;
+                        }
+
                         ui.release_mouse_capture();
 
-                        if let Some(value) = *self.checked {
-                            // Invert state if it is defined.
-                            ui.send_message(CheckBoxMessage::checked(
+                        if *self.momentary {
+                            self.set_marks_visibility(ui, *self.checked);
+                            ui.send_message(CheckBoxMessage::pressed(
                                 self.handle(),
-                                MessageDirection::ToWidget,
-                                Some(!value),
+                                MessageDirection::FromWidget,
                             ));
                         } else {
-                            // Switch from undefined state to checked.
-                            ui.send_message(CheckBoxMessage::checked(
-                                self.handle(),
-                                MessageDirection::ToWidget,
-                                Some(true),
-                            ));
+                            self.toggle(ui);
                         }
                     }
                 }
+                // Mouse enter is also received when the cursor moves between descendants of the
+                // check box, so toggle only once per entrance.
+                WidgetMessage::MouseEnter
+                    if self.is_drag_toggle()
+                        && !self.drag_toggled
+                        && ui.mouse_state().left == ButtonState::Pressed
+                        && ui.captured_node().is_none() =>
+                {
+                    self.drag_toggled = true;
+                    self.toggle(ui);
+                }
+                WidgetMessage::MouseLeave
+                    if !self.screen_bounds().contains(ui.cursor_position()) =>
+                {
+                    self.drag_toggled = false;
+                }
                 WidgetMessage::KeyDown(key_code) => {
                     if !message.handled() && *key_code == KeyCode::Space {
-                        ui.send_message(CheckBoxMessage::checked(
-                            self.handle,
-                            MessageDirection::ToWidget,
-                            self.checked.map(|checked| !checked),
-                        ));
+                        if *self.momentary {
+                            ui.send_message(CheckBoxMessage::pressed(
+                                self.handle,
+                                MessageDirection::FromWidget,
+                            ));
+                        } else {
+                            self.toggle(ui);
+                        }
                         message.set_handled(true);
                     }
                 }
@@ -198,50 +554,36 @@ impl Control for CheckBox {
             if message.direction() == MessageDirection::ToWidget
                 && message.destination() == self.handle()
                 && *self.checked != value
+                // Momentary check boxes never change their state.
+                && !*self.momentary
             {
                 self.checked.set_value_and_mark_modified(value);
 
                 ui.send_message(message.reverse());
 
-                if self.check_mark.is_some() {
-                    match value {
-                        None => {
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.check_mark,
-                                MessageDirection::ToWidget,
-                                false,
-                            ));
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.uncheck_mark,
-                                MessageDirection::ToWidget,
-                                false,
-                            ));
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.undefined_mark,
-                                MessageDirection::ToWidget,
-                                true,
-                            ));
-                        }
-                        Some(value) => {
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.check_mark,
-                                MessageDirection::ToWidget,
-                                value,
-                            ));
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.uncheck_mark,
-                                MessageDirection::ToWidget,
-                                !value,
-                            ));
-                            ui.send_message(WidgetMessage::visibility(
-                                *self.undefined_mark,
-                                MessageDirection::ToWidget,
-                                false,
-                            ));
-                        }
-                    }
+                self.set_marks_visibility(ui, value);
+
+                if value == Some(true) {
+                    self.uncheck_radio_group_members(ui);
                 }
             }
+        } else if let Some(&CheckBoxMessage::Disabled(disabled)) = message.data::<CheckBoxMessage>()
+        {
+            if message.direction() == MessageDirection::ToWidget
+                && message.destination() == self.handle()
+                && *self.disabled != disabled
+            {
+                self.set_disabled(ui, disabled);
+                ui.send_message(message.reverse());
+            }
+        } else if let Some(&CheckBoxMessage::Content(content)) = message.data::<CheckBoxMessage>() {
+            if message.direction() == MessageDirection::ToWidget
+                && message.destination() == self.handle()
+                && *self.content != content
+            {
+                self.set_content(ui, content);
+                ui.send_message(message.reverse());
+            }
         }
     }
 }
@@ -255,6 +597,13 @@ pub struct CheckBoxBuilder {
     undefined_mark: Option<Handle<UiNode>>,
     background: Option<Handle<UiNode>>,
     content: Handle<UiNode>,
+    momentary: bool,
+    drag_toggle: bool,
+    tristate_cycle: bool,
+    disabled: bool,
+    disabled_brush: Brush,
+    radio_group: Option<Uuid>,
+    child_check_boxes: Vec<Handle<UiNode>>,
 }
 
 impl CheckBoxBuilder {
@@ -268,6 +617,13 @@ impl CheckBoxBuilder {
             undefined_mark: None,
             content: Handle::NONE,
             background: None,
+            momentary: false,
+            drag_toggle: false,
+            tristate_cycle: false,
+            disabled: false,
+            disabled_brush: BRUSH_DARKER,
+            radio_group: None,
+            child_check_boxes: Default::default(),
         }
     }
 
@@ -308,8 +664,82 @@ impl CheckBoxBuilder {
         self
     }
 
+    /// Sets whether the check box should act like a momentary button or not. Momentary check boxes
+    /// are always unchecked, show the check mark only while pressed and emit [`CheckBoxMessage::Pressed`]
+    /// message when released.
+    pub fn with_momentary(mut self, momentary: bool) -> Self {
+        self.momentary = momentary;
+        self
+    }
+
+    /// Sets whether the check box could be toggled by dragging the mouse over it with the left
+    /// button pressed. Such check boxes toggle their state on press instead of release and do not
+    /// capture the mouse. Has no effect on momentary check boxes. Default is `false`.
+    pub fn with_drag_toggle(mut self, drag_toggle: bool) -> Self {
+        self.drag_toggle = drag_toggle;
+        self
+    }
+
+    /// Sets whether the check box should cycle through all three states (`None -> Some(true) ->
+    /// Some(false) -> None`) on click or Space key press. By default, the undefined state could
+    /// only be set from code. Default is `false`.
+    pub fn with_tristate_cycle(mut self, tristate_cycle: bool) -> Self {
+        self.tristate_cycle = tristate_cycle;
+        self
+    }
+
+    /// Adds the check box to a radio group with the given id. Only one check box of a group could
+    /// be checked at a time, checking a member of the group unchecks every other member of it.
+    pub fn with_radio_group(mut self, radio_group: Uuid) -> Self {
+        self.radio_group = Some(radio_group);
+        self
+    }
+
+    /// Makes the check box a parent of the given check boxes (a "select all" check box, for example).
+    /// The state of the parent check box is derived from its children: `Some(true)` if every child
+    /// is checked, `Some(false)` if none of them is checked and `None` otherwise. Toggling the
+    /// parent check box sets its new state to every child. By default, the list is empty.
+    pub fn with_child_check_boxes(mut self, child_check_boxes: Vec<Handle<UiNode>>) -> Self {
+        self.child_check_boxes = child_check_boxes;
+        self
+    }
+
+    /// Sets whether the check box should be disabled or not. Default is `false`.
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets the desired brush, that will be used for the background of the check box when it is
+    /// disabled. Default is [`BRUSH_DARKER`].
+    pub fn with_disabled_brush(mut self, brush: Brush) -> Self {
+        self.disabled_brush = brush;
+        self
+    }
+
     /// Finishes check box building and adds it to the user interface.
-    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+    pub fn build(mut self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        if self.momentary {
+            self.checked = Some(false);
+        }
+
+        if !self.child_check_boxes.is_empty() {
+            // Initial state of a parent check box is defined by its children.
+            let states = self
+                .child_check_boxes
+                .iter()
+                .filter_map(|child| ctx[*child].query_component::<CheckBox>())
+                .map(|check_box| *check_box.checked)
+                .collect::<Vec<_>>();
+            self.checked = match states.first() {
+                Some(&Some(first)) if states.iter().all(|state| *state == Some(first)) => {
+                    Some(first)
+                }
+                _ => None,
+            };
+            self.widget_builder = self.widget_builder.with_preview_messages(true);
+        }
+
         let check_mark = self.check_mark.unwrap_or_else(|| {
             BorderBuilder::new(
                 WidgetBuilder::new()
@@ -322,6 +752,7 @@ impl CheckBoxBuilder {
                                 .with_horizontal_alignment(HorizontalAlignment::Center)
                                 .with_foreground(BRUSH_TEXT),
                         )
+                        .with_antialiasing(true)
                         .with_primitives({
                             let size = 8.0;
                             let half_size = size * 0.5;
@@ -395,6 +826,10 @@ impl CheckBoxBuilder {
         });
 
         let background_ref = &mut ctx[background];
+        let background_brush = background_ref.background();
+        if self.disabled {
+            background_ref.set_background(self.disabled_brush.clone());
+        }
         background_ref.set_row(0).set_column(0);
         if background_ref.min_width() < 0.01 {
             background_ref.set_min_width(16.0);
@@ -427,20 +862,404 @@ impl CheckBoxBuilder {
             check_mark: check_mark.into(),
             uncheck_mark: uncheck_mark.into(),
             undefined_mark: undefined_mark.into(),
+            momentary: self.momentary.into(),
+            drag_toggle: self.drag_toggle.into(),
+            tristate_cycle: self.tristate_cycle.into(),
+            radio_group: self.radio_group.into(),
+            child_check_boxes: self.child_check_boxes.into(),
+            disabled: self.disabled.into(),
+            disabled_brush: self.disabled_brush.into(),
+            background_brush: background_brush.into(),
+            background: background.into(),
+            content: self.content.into(),
+            drag_toggled: false,
         };
         ctx.add_node(UiNode::new(cb))
     }
 }
 
+/// A settings menu row, created by [`SettingsCheckBoxBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsCheckBox {
+    /// A handle of the root widget of the row. Add it to a parent container (a stack panel, for
+    /// example) of a settings menu.
+    pub row: Handle<UiNode>,
+    /// A handle of the inner check box. Use it to handle [`CheckBoxMessage::Check`] messages.
+    pub check_box: Handle<UiNode>,
+    /// A binding of the check box to a property, if one was specified using
+    /// [`SettingsCheckBoxBuilder::with_binding`].
+    pub binding: Option<CheckBoxBinding>,
+}
+
+/// Creates a typical settings menu row: a label on the left and a check box on the right, with
+/// fixed height and consistent margins. The check box could optionally be bound to a property,
+/// see [`CheckBoxBinding`] docs for more info.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     check_box::{SettingsCheckBox, SettingsCheckBoxBuilder},
+/// #     widget::WidgetBuilder, BuildContext,
+/// # };
+/// fn create_vsync_row(ctx: &mut BuildContext) -> SettingsCheckBox {
+///     SettingsCheckBoxBuilder::new(WidgetBuilder::new())
+///         .with_label("Vertical Synchronization")
+///         .with_binding("vsync")
+///         .build(ctx)
+/// }
+/// ```
+pub struct SettingsCheckBoxBuilder {
+    widget_builder: WidgetBuilder,
+    label: String,
+    checked: Option<bool>,
+    row_height: f32,
+    binding: Option<String>,
+}
+
+impl SettingsCheckBoxBuilder {
+    /// Default height of a settings row.
+    pub const DEFAULT_ROW_HEIGHT: f32 = 24.0;
+
+    /// Creates new builder instance.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            label: Default::default(),
+            checked: Some(false),
+            row_height: Self::DEFAULT_ROW_HEIGHT,
+            binding: None,
+        }
+    }
+
+    /// Sets the text of the label.
+    pub fn with_label<P: AsRef<str>>(mut self, label: P) -> Self {
+        self.label = label.as_ref().to_owned();
+        self
+    }
+
+    /// Sets the initial state of the check box.
+    pub fn checked(mut self, value: Option<bool>) -> Self {
+        self.checked = value;
+        self
+    }
+
+    /// Sets the height of the row. Default is [`Self::DEFAULT_ROW_HEIGHT`].
+    pub fn with_row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Binds the check box to a property at the given reflection path. The binding will be
+    /// returned in [`SettingsCheckBox::binding`] field.
+    pub fn with_binding<P: Into<String>>(mut self, path: P) -> Self {
+        self.binding = Some(path.into());
+        self
+    }
+
+    /// Finishes row building and adds it to the user interface.
+    pub fn build(self, ctx: &mut BuildContext) -> SettingsCheckBox {
+        let label = TextBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(0)
+                .with_margin(Thickness::left_right(4.0)),
+        )
+        .with_text(self.label)
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .build(ctx);
+
+        let check_box = CheckBoxBuilder::new(
+            WidgetBuilder::new()
+                .on_row(0)
+                .on_column(1)
+                .with_margin(Thickness::left_right(4.0))
+                .with_vertical_alignment(VerticalAlignment::Center),
+        )
+        .checked(self.checked)
+        .build(ctx);
+
+        let row = GridBuilder::new(
+            self.widget_builder
+                .with_height(self.row_height)
+                .with_child(label)
+                .with_child(check_box),
+        )
+        .add_row(Row::stretch())
+        .add_column(Column::stretch())
+        .add_column(Column::auto())
+        .build(ctx);
+
+        SettingsCheckBox {
+            row,
+            check_box,
+            binding: self
+                .binding
+                .map(|path| CheckBoxBinding::new(check_box, path)),
+        }
+    }
+}
+
+/// Check box binding keeps the state of a check box in sync with a `bool` or `Option<bool>`
+/// property of some data, that implements [`Reflect`]. The property is defined by its reflection
+/// path (for example `settings.shadows.enabled`). `bool` properties are mapped to `Some(true)` and
+/// `Some(false)` states, `Option<bool>` properties are mapped to all three states of the check box.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use fyrox_ui::{
+/// #     check_box::CheckBoxBinding, core::reflect::prelude::*, message::UiMessage, UserInterface,
+/// # };
+/// #[derive(Reflect, Debug)]
+/// struct Settings {
+///     vsync: bool,
+/// }
+///
+/// fn on_ui_message(binding: &CheckBoxBinding, settings: &mut Settings, message: &UiMessage) {
+///     // Writes new state of the check box back to the property.
+///     binding.handle_message(message, settings);
+/// }
+///
+/// fn on_settings_changed(binding: &CheckBoxBinding, settings: &Settings, ui: &UserInterface) {
+///     // Updates the check box, if the property was changed elsewhere.
+///     binding.sync(settings, ui);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckBoxBinding {
+    /// A handle of the bound check box.
+    pub check_box: Handle<UiNode>,
+    /// A reflection path of the bound property.
+    pub path: String,
+}
+
+impl CheckBoxBinding {
+    /// Creates a new binding of the given check box to a property at the given reflection path.
+    pub fn new<P: Into<String>>(check_box: Handle<UiNode>, path: P) -> Self {
+        Self {
+            check_box,
+            path: path.into(),
+        }
+    }
+
+    /// Reads the bound property and sends [`CheckBoxMessage::Check`] message to the check box if
+    /// its state differs from the value of the property. Does nothing if the property does not
+    /// exist or has unsupported type.
+    pub fn sync(&self, data: &dyn Reflect, ui: &UserInterface) {
+        let Some(value) = read_bound_value(data, &self.path) else {
+            return;
+        };
+
+        if let Some(check_box) = ui.try_get_of_type::<CheckBox>(self.check_box) {
+            if *check_box.checked != value {
+                ui.send_message(CheckBoxMessage::checked(
+                    self.check_box,
+                    MessageDirection::ToWidget,
+                    value,
+                ));
+            }
+        }
+    }
+
+    /// Writes the new state of the check box to the bound property, if the message is
+    /// [`CheckBoxMessage::Check`] sent from the bound check box. The undefined (`None`) state can be
+    /// written to `Option<bool>` properties only. Returns `true` if the property was changed.
+    pub fn handle_message(&self, message: &UiMessage, data: &mut dyn Reflect) -> bool {
+        let Some(&CheckBoxMessage::Check(value)) = message.data::<CheckBoxMessage>() else {
+            return false;
+        };
+
+        if message.destination() != self.check_box
+            || message.direction() != MessageDirection::FromWidget
+        {
+            return false;
+        }
+
+        let mut changed = false;
+        data.resolve_path_mut(&self.path, &mut |result| {
+            let Ok(field) = result else {
+                return;
+            };
+
+            let new_value: Box<dyn Reflect> = match field_value(field) {
+                Some(BoundValue::Bool(current)) => match value {
+                    Some(value) if value != current => Box::new(value),
+                    _ => return,
+                },
+                Some(BoundValue::OptionBool(current)) if current != value => Box::new(value),
+                _ => return,
+            };
+
+            // Use `set` instead of direct modification, so inheritable variables will be marked
+            // as modified.
+            changed = field.set(new_value).is_ok();
+        });
+        changed
+    }
+}
+
+enum BoundValue {
+    Bool(bool),
+    OptionBool(Option<bool>),
+}
+
+fn field_value(field: &dyn Reflect) -> Option<BoundValue> {
+    let mut value = None;
+    field.downcast_ref::<bool>(&mut |result| value = result.map(|v| BoundValue::Bool(*v)));
+    if value.is_none() {
+        field.downcast_ref::<Option<bool>>(&mut |result| {
+            value = result.map(|v| BoundValue::OptionBool(*v))
+        });
+    }
+    value
+}
+
+fn read_bound_value(data: &dyn Reflect, path: &str) -> Option<Option<bool>> {
+    let mut value = None;
+    data.resolve_path(path, &mut |result| {
+        value = match result.ok().and_then(field_value) {
+            Some(BoundValue::Bool(v)) => Some(Some(v)),
+            Some(BoundValue::OptionBool(v)) => Some(v),
+            None => None,
+        }
+    });
+    value
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        check_box::{CheckBoxBuilder, CheckBoxMessage},
-        message::MessageDirection,
-        widget::WidgetBuilder,
-        UserInterface,
+        check_box::{
+            CheckBox, CheckBoxBinding, CheckBoxBuilder, CheckBoxMessage, SettingsCheckBoxBuilder,
+        },
+        message::{KeyCode, MessageDirection},
+        text::TextBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        MouseButton, UserInterface,
+    };
+    use fyrox_core::{
+        algebra::Vector2, reflect::prelude::*, uuid::Uuid, variable::InheritableVariable,
     };
-    use fyrox_core::algebra::Vector2;
+    use fyrox_graph::BaseSceneGraph;
+
+    #[derive(Reflect, Debug, Default)]
+    struct BindingData {
+        flag: InheritableVariable<bool>,
+        tri_state: Option<bool>,
+    }
+
+    #[test]
+    fn check_box_binding() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        let tri_state_check_box =
+            CheckBoxBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+
+        let mut data = BindingData::default();
+        let binding = CheckBoxBinding::new(check_box, "flag");
+        let tri_state_binding = CheckBoxBinding::new(tri_state_check_box, "tri_state");
+
+        // Data to UI.
+        data.flag.set_value_silent(true);
+        binding.sync(&data, &ui);
+        tri_state_binding.sync(&data, &ui);
+        while ui.poll_message().is_some() {}
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+        assert_eq!(
+            *ui.node(tri_state_check_box)
+                .cast::<CheckBox>()
+                .unwrap()
+                .checked,
+            None
+        );
+
+        // UI to data.
+        let unchecked =
+            CheckBoxMessage::checked(check_box, MessageDirection::FromWidget, Some(false));
+        assert!(binding.handle_message(&unchecked, &mut data));
+        assert!(!*data.flag);
+        assert!(data.flag.is_modified());
+
+        // Undefined state can't be written to a `bool` property.
+        let undefined = CheckBoxMessage::checked(check_box, MessageDirection::FromWidget, None);
+        assert!(!binding.handle_message(&undefined, &mut data));
+
+        let checked = CheckBoxMessage::checked(
+            tri_state_check_box,
+            MessageDirection::FromWidget,
+            Some(true),
+        );
+        assert!(tri_state_binding.handle_message(&checked, &mut data));
+        assert_eq!(data.tri_state, Some(true));
+
+        // Messages of other check boxes are ignored.
+        assert!(!binding.handle_message(&checked, &mut data));
+    }
+
+    #[test]
+    fn check_box_content() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+        let ctx = &mut ui.build_ctx();
+
+        let old_content = TextBuilder::new(WidgetBuilder::new())
+            .with_text("Old")
+            .build(ctx);
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new())
+            .with_content(old_content)
+            .build(ctx);
+        let new_content = TextBuilder::new(WidgetBuilder::new())
+            .with_text("New")
+            .build(ctx);
+        while ui.poll_message().is_some() {}
+
+        let grid = ui.node(old_content).parent();
+
+        ui.send_message(CheckBoxMessage::content(
+            check_box,
+            MessageDirection::ToWidget,
+            new_content,
+        ));
+        while ui.poll_message().is_some() {}
+
+        assert_eq!(ui.node(old_content).parent(), ui.root());
+        assert_eq!(ui.node(new_content).parent(), grid);
+        assert_eq!(ui.node(new_content).column(), 1);
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().content,
+            new_content
+        );
+    }
+
+    #[test]
+    fn settings_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let row = SettingsCheckBoxBuilder::new(WidgetBuilder::new())
+            .with_label("Flag")
+            .checked(Some(true))
+            .with_binding("flag")
+            .build(&mut ui.build_ctx());
+
+        assert!(ui.node(row.row).children().contains(&row.check_box));
+        assert_eq!(
+            *ui.node(row.check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+        assert_eq!(
+            row.binding,
+            Some(CheckBoxBinding::new(row.check_box, "flag"))
+        );
+
+        ui.force_layout();
+        assert_eq!(
+            ui.node(row.row).actual_local_size().y,
+            SettingsCheckBoxBuilder::DEFAULT_ROW_HEIGHT
+        );
+    }
 
     #[test]
     fn check_box() {
@@ -463,4 +1282,331 @@ mod test {
         // We must get response from check box.
         assert_eq!(ui.poll_message(), Some(input_message.reverse()));
     }
+
+    #[test]
+    fn momentary_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new())
+            .with_momentary(true)
+            .build(&mut ui.build_ctx());
+
+        ui.send_message(WidgetMessage::mouse_up(
+            check_box,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+
+        let mut pressed = false;
+        while let Some(message) = ui.poll_message() {
+            if message.destination() == check_box {
+                match message.data::<CheckBoxMessage>() {
+                    Some(CheckBoxMessage::Pressed) => pressed = true,
+                    Some(CheckBoxMessage::Check(_)) => panic!("momentary check box changed state"),
+                    _ => (),
+                }
+            }
+        }
+        assert!(pressed);
+    }
+
+    #[test]
+    fn clicked_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+
+        let poll_clicked = |ui: &mut UserInterface| {
+            let mut clicked = None;
+            while let Some(message) = ui.poll_message() {
+                if let Some(&CheckBoxMessage::Clicked(value)) = message.data::<CheckBoxMessage>() {
+                    assert_eq!(message.destination(), check_box);
+                    assert_eq!(message.direction(), MessageDirection::FromWidget);
+                    clicked = Some(value);
+                }
+            }
+            clicked
+        };
+
+        // State changes made from code must not be reported as clicks.
+        ui.send_message(CheckBoxMessage::checked(
+            check_box,
+            MessageDirection::ToWidget,
+            Some(true),
+        ));
+        assert_eq!(poll_clicked(&mut ui), None);
+
+        ui.send_message(WidgetMessage::mouse_up(
+            check_box,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+        assert_eq!(poll_clicked(&mut ui), Some(Some(false)));
+
+        ui.send_message(WidgetMessage::key_down(
+            check_box,
+            MessageDirection::FromWidget,
+            KeyCode::Space,
+        ));
+        assert_eq!(poll_clicked(&mut ui), Some(Some(true)));
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+
+        // Undefined state is switched to checked, the same as on click.
+        ui.send_message(CheckBoxMessage::checked(
+            check_box,
+            MessageDirection::ToWidget,
+            None,
+        ));
+        assert_eq!(poll_clicked(&mut ui), None);
+
+        ui.send_message(WidgetMessage::key_down(
+            check_box,
+            MessageDirection::FromWidget,
+            KeyCode::Space,
+        ));
+        assert_eq!(poll_clicked(&mut ui), Some(Some(true)));
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn drag_toggle_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new())
+            .with_drag_toggle(true)
+            .build(&mut ui.build_ctx());
+
+        // Drag toggle check boxes are toggled on press and do not capture the mouse.
+        ui.send_message(WidgetMessage::mouse_down(
+            check_box,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+        while ui.poll_message().is_some() {}
+
+        assert!(ui.captured_node().is_none());
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn tristate_cycle_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new())
+            .checked(None)
+            .with_tristate_cycle(true)
+            .build(&mut ui.build_ctx());
+
+        let state = |ui: &UserInterface| *ui.node(check_box).cast::<CheckBox>().unwrap().checked;
+
+        for expected in [Some(true), Some(false), None] {
+            ui.send_message(WidgetMessage::key_down(
+                check_box,
+                MessageDirection::FromWidget,
+                KeyCode::Space,
+            ));
+            while ui.poll_message().is_some() {}
+            assert_eq!(state(&ui), expected);
+        }
+
+        for expected in [Some(true), Some(false), None] {
+            ui.send_message(WidgetMessage::mouse_up(
+                check_box,
+                MessageDirection::FromWidget,
+                Vector2::default(),
+                MouseButton::Left,
+            ));
+            while ui.poll_message().is_some() {}
+            assert_eq!(state(&ui), expected);
+        }
+    }
+
+    #[test]
+    fn radio_group_check_boxes() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let group = Uuid::new_v4();
+        let ctx = &mut ui.build_ctx();
+        let first = CheckBoxBuilder::new(WidgetBuilder::new())
+            .checked(Some(true))
+            .with_radio_group(group)
+            .build(ctx);
+        let second = CheckBoxBuilder::new(WidgetBuilder::new())
+            .with_radio_group(group)
+            .build(ctx);
+        let other = CheckBoxBuilder::new(WidgetBuilder::new())
+            .checked(Some(true))
+            .build(ctx);
+
+        let state =
+            |ui: &UserInterface, check_box| *ui.node(check_box).cast::<CheckBox>().unwrap().checked;
+
+        ui.send_message(WidgetMessage::mouse_up(
+            second,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(state(&ui, first), Some(false));
+        assert_eq!(state(&ui, second), Some(true));
+        assert_eq!(state(&ui, other), Some(true));
+
+        // Checked member of a group cannot be unchecked by the user.
+        ui.send_message(WidgetMessage::key_down(
+            second,
+            MessageDirection::FromWidget,
+            KeyCode::Space,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(state(&ui, second), Some(true));
+    }
+
+    #[test]
+    fn parent_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let ctx = &mut ui.build_ctx();
+        let first = CheckBoxBuilder::new(WidgetBuilder::new())
+            .checked(Some(true))
+            .build(ctx);
+        let second = CheckBoxBuilder::new(WidgetBuilder::new()).build(ctx);
+        let parent = CheckBoxBuilder::new(WidgetBuilder::new())
+            .with_child_check_boxes(vec![first, second])
+            .build(ctx);
+
+        let state =
+            |ui: &UserInterface, check_box| *ui.node(check_box).cast::<CheckBox>().unwrap().checked;
+        assert_eq!(state(&ui, parent), None);
+
+        // Children to parent.
+        ui.send_message(CheckBoxMessage::checked(
+            second,
+            MessageDirection::ToWidget,
+            Some(true),
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(state(&ui, parent), Some(true));
+
+        ui.send_message(CheckBoxMessage::checked(
+            first,
+            MessageDirection::ToWidget,
+            Some(false),
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(state(&ui, parent), None);
+
+        // Parent to children.
+        for expected in [Some(true), Some(false)] {
+            ui.send_message(WidgetMessage::mouse_up(
+                parent,
+                MessageDirection::FromWidget,
+                Vector2::default(),
+                MouseButton::Left,
+            ));
+            while ui.poll_message().is_some() {}
+            assert_eq!(state(&ui, parent), expected);
+            assert_eq!(state(&ui, first), expected);
+            assert_eq!(state(&ui, second), expected);
+        }
+    }
+
+    #[test]
+    fn disabled_check_box() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+
+        ui.send_message(CheckBoxMessage::disabled(
+            check_box,
+            MessageDirection::ToWidget,
+            true,
+        ));
+        while ui.poll_message().is_some() {}
+
+        let check_box_ref = ui.node(check_box).cast::<CheckBox>().unwrap();
+        assert!(*check_box_ref.disabled);
+        assert_eq!(
+            ui.node(*check_box_ref.background).background(),
+            *check_box_ref.disabled_brush
+        );
+
+        // User input is ignored.
+        ui.send_message(WidgetMessage::mouse_up(
+            check_box,
+            MessageDirection::FromWidget,
+            Vector2::default(),
+            MouseButton::Left,
+        ));
+        ui.send_message(WidgetMessage::key_down(
+            check_box,
+            MessageDirection::FromWidget,
+            KeyCode::Space,
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(false)
+        );
+
+        // But the state still could be changed from code.
+        ui.send_message(CheckBoxMessage::checked(
+            check_box,
+            MessageDirection::ToWidget,
+            Some(true),
+        ));
+        while ui.poll_message().is_some() {}
+        assert_eq!(
+            *ui.node(check_box).cast::<CheckBox>().unwrap().checked,
+            Some(true)
+        );
+
+        ui.send_message(CheckBoxMessage::disabled(
+            check_box,
+            MessageDirection::ToWidget,
+            false,
+        ));
+        while ui.poll_message().is_some() {}
+
+        let check_box_ref = ui.node(check_box).cast::<CheckBox>().unwrap();
+        assert_eq!(
+            ui.node(*check_box_ref.background).background(),
+            *check_box_ref.background_brush
+        );
+    }
+
+    #[test]
+    fn check_mark_is_centered() {
+        let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new())
+            .checked(Some(true))
+            .build(&mut ui.build_ctx());
+
+        ui.force_layout();
+
+        let check_mark = *ui.node(check_box).cast::<CheckBox>().unwrap().check_mark;
+        let check_mark_ref = ui.node(check_mark);
+        let image_ref = ui.node(check_mark_ref.children()[0]);
+
+        assert!(check_mark_ref.actual_local_size().x > 0.0);
+        assert!(image_ref.actual_local_size().x > 0.0);
+
+        let check_mark_center =
+            check_mark_ref.screen_position() + check_mark_ref.actual_global_size().scale(0.5);
+        let image_center = image_ref.screen_position() + image_ref.actual_global_size().scale(0.5);
+        assert!((check_mark_center - image_center).norm() < 0.001);
+    }
 }
@@ -158,6 +158,99 @@ pub trait Draw {
         self.push_triangle(index + 2, index + 1, index + 3);
     }
 
+    /// Pushes a line with feathered edges. The line consists of a solid core and a thin outer
+    /// fringe of the `feather` width, which fades to fully transparent color, this way the edges of
+    /// the line look smooth without any multisampling.
+    fn push_line_antialiased(
+        &mut self,
+        a: Vector2<f32>,
+        b: Vector2<f32>,
+        thickness: f32,
+        feather: f32,
+    ) {
+        let Some(dir) = (b - a).try_normalize(f32::EPSILON) else {
+            return;
+        };
+        let feather = feather.max(0.0);
+        let half_feather = feather * 0.5;
+        let perp = Vector2::new(dir.y, -dir.x);
+        let inner_perp = perp.scale((thickness * 0.5 - half_feather).max(0.0));
+        let outer_perp = perp.scale(thickness * 0.5 + half_feather);
+        let along = dir.scale(half_feather);
+
+        let solid = Color::WHITE;
+        let transparent = Color::WHITE.with_new_alpha(0);
+
+        let index = self.last_vertex_index();
+        for (pos, color) in [
+            // Solid core.
+            (a + along - inner_perp, solid),
+            (b - along - inner_perp, solid),
+            (b - along + inner_perp, solid),
+            (a + along + inner_perp, solid),
+            // Transparent fringe.
+            (a - along - outer_perp, transparent),
+            (b + along - outer_perp, transparent),
+            (b + along + outer_perp, transparent),
+            (a - along + outer_perp, transparent),
+        ] {
+            self.push_vertex_raw(Vertex {
+                pos,
+                tex_coord: Vector2::default(),
+                color,
+            });
+        }
+
+        self.push_triangle(index, index + 1, index + 2);
+        self.push_triangle(index, index + 2, index + 3);
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            self.push_triangle(index + i, index + 4 + i, index + 4 + next);
+            self.push_triangle(index + i, index + 4 + next, index + next);
+        }
+    }
+
+    /// Pushes a filled triangle with feathered edges. Every edge of the triangle gets a thin outer
+    /// fringe of the `feather` width, which fades to fully transparent color.
+    fn push_triangle_filled_antialiased(&mut self, vertices: [Vector2<f32>; 3], feather: f32) {
+        self.push_triangle_filled(vertices);
+
+        let feather = feather.max(0.0);
+        if feather <= 0.0 {
+            return;
+        }
+
+        let center = (vertices[0] + vertices[1] + vertices[2]).scale(1.0 / 3.0);
+        for i in 0..3 {
+            let begin = vertices[i];
+            let end = vertices[(i + 1) % 3];
+            let Some(dir) = (end - begin).try_normalize(f32::EPSILON) else {
+                continue;
+            };
+            let mut normal = Vector2::new(dir.y, -dir.x);
+            if normal.dot(&(begin - center)) < 0.0 {
+                normal = -normal;
+            }
+            let offset = normal.scale(feather);
+
+            let index = self.last_vertex_index();
+            for (pos, color) in [
+                (begin, Color::WHITE),
+                (end, Color::WHITE),
+                (end + offset, Color::WHITE.with_new_alpha(0)),
+                (begin + offset, Color::WHITE.with_new_alpha(0)),
+            ] {
+                self.push_vertex_raw(Vertex {
+                    pos,
+                    tex_coord: Vector2::default(),
+                    color,
+                });
+            }
+            self.push_triangle(index, index + 1, index + 2);
+            self.push_triangle(index, index + 2, index + 3);
+        }
+    }
+
     fn push_rect(&mut self, rect: &Rect<f32>, thickness: f32) {
         let offset = thickness * 0.5;
 
@@ -710,6 +803,7 @@ pub struct DrawingContext {
     pub transform_stack: TransformStack,
     opacity_stack: Vec<f32>,
     triangles_to_commit: usize,
+    global_tint: Color,
 }
 
 fn get_line_thickness_vector(a: Vector2<f32>, b: Vector2<f32>, thickness: f32) -> Vector2<f32> {
@@ -759,6 +853,7 @@ impl DrawingContext {
             triangles_to_commit: 0,
             opacity_stack: vec![1.0],
             transform_stack: Default::default(),
+            global_tint: Color::WHITE,
         }
     }
 
@@ -787,6 +882,19 @@ impl DrawingContext {
         &self.command_buffer
     }
 
+    /// Sets a color that will be multiplied with the final color of every drawing command. White
+    /// color means no tint.
+    #[inline]
+    pub fn set_global_tint(&mut self, tint: Color) {
+        self.global_tint = tint;
+    }
+
+    /// Returns current global tint of the drawing context.
+    #[inline]
+    pub fn global_tint(&self) -> Color {
+        self.global_tint
+    }
+
     pub fn push_opacity(&mut self, opacity: f32) {
         self.opacity_stack.push(opacity);
     }
@@ -603,6 +603,18 @@ struct DoubleClickEntry {
 
 struct Clipboard(Option<RefCell<ClipboardContext>>);
 
+/// A closure, that will be executed once after the next layout pass. See [`BuildContext::defer`].
+pub type DeferredAction = Box<dyn FnOnce(&mut UserInterface) + Send>;
+
+#[derive(Default)]
+struct DeferredActions(Vec<DeferredAction>);
+
+impl Debug for DeferredActions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeferredActions({})", self.0.len())
+    }
+}
+
 impl Debug for Clipboard {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Clipboard")
@@ -694,6 +706,8 @@ pub struct UserInterface {
     #[reflect(hidden)]
     double_click_entries: FxHashMap<MouseButton, DoubleClickEntry>,
     pub double_click_time_slice: f32,
+    #[reflect(hidden)]
+    deferred_actions: DeferredActions,
 }
 
 impl Visit for UserInterface {
@@ -775,6 +789,8 @@ impl Clone for UserInterface {
             default_font: self.default_font.clone(),
             double_click_entries: self.double_click_entries.clone(),
             double_click_time_slice: self.double_click_time_slice,
+            // Closures cannot be cloned.
+            deferred_actions: Default::default(),
         }
     }
 }
@@ -1073,6 +1089,7 @@ impl UserInterface {
             default_font: BUILT_IN_FONT.clone(),
             double_click_entries: Default::default(),
             double_click_time_slice: 0.5, // 500 ms is standard in most operating systems.
+            deferred_actions: Default::default(),
         };
         ui.root_canvas = ui.add_node(UiNode::new(Canvas {
             widget: WidgetBuilder::new().build(),
@@ -1252,6 +1269,36 @@ impl UserInterface {
                 Rect::new(0.0, 0.0, self.screen_size.x, self.screen_size.y),
             );
         }
+
+        // Actions, that are deferred while running the current ones, will run after the next
+        // layout pass.
+        for action in std::mem::take(&mut self.deferred_actions.0) {
+            action(self);
+        }
+    }
+
+    /// Queues the given closure to be executed once after the next layout pass. See
+    /// [`BuildContext::defer`] for more info.
+    pub fn defer(&mut self, action: DeferredAction) {
+        self.deferred_actions.0.push(action);
+    }
+
+    /// Invalidates layout of every widget and immediately performs full layout pass (measurement and
+    /// arrangement) using current screen size of the user interface. After this call, every widget will
+    /// have its layout-related properties, such as [`Widget::actual_local_size`] or [`Widget::screen_position`],
+    /// calculated. It could be used to check layout metrics without rendering anything, for example in
+    /// tests.
+    ///
+    /// ## Important notes
+    ///
+    /// The layout is calculated using the current screen size of the user interface, so make sure it
+    /// is set (either via [`Self::new`] or [`Self::set_screen_size`]) before calling this method. Also,
+    /// this method does not process pending messages, so any changes made by messages (for example,
+    /// visibility changes) won't be taken into account until the messages are processed.
+    pub fn force_layout(&mut self) {
+        self.invalidate_layout();
+        self.need_update_global_transform = true;
+        self.update_layout(self.screen_size);
     }
 
     pub fn update(&mut self, screen_size: Vector2<f32>, dt: f32, switches: &UiUpdateSwitches) {
@@ -1299,6 +1346,18 @@ impl UserInterface {
         self.cursor_icon
     }
 
+    /// Sets a color that will be multiplied with the final color of every widget. It could be used
+    /// to darken or tint the entire user interface (for example, when a game is paused) without
+    /// changing brushes of every widget. Default value is [`Color::WHITE`], which means no tint.
+    pub fn set_global_tint(&mut self, tint: Color) {
+        self.drawing_context.set_global_tint(tint);
+    }
+
+    /// Returns current global tint of the user interface. See [`Self::set_global_tint`] for more info.
+    pub fn global_tint(&self) -> Color {
+        self.drawing_context.global_tint()
+    }
+
     pub fn draw(&mut self) -> &DrawingContext {
         scope_profile!();
 
@@ -2151,6 +2210,11 @@ impl UserInterface {
         self.captured_node
     }
 
+    /// Returns current state of the mouse buttons.
+    pub fn mouse_state(&self) -> &MouseState {
+        &self.mouse_state
+    }
+
     // Tries to set new picked node (a node under the cursor) and returns `true` if the node was
     // changed.
     fn try_set_picked_node(&mut self, node: Handle<UiNode>) -> bool {
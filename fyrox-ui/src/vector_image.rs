@@ -36,6 +36,21 @@ pub enum Primitive {
         /// Thickness of the line in absolute units.
         thickness: f32,
     },
+    /// A dashed line of fixed thickness between two points. Could also be used to create dotted lines,
+    /// if the dash length is equal to the thickness.
+    DashedLine {
+        /// Beginning of the line in local coordinates.
+        begin: Vector2<f32>,
+        /// End of the line in local coordinates.
+        end: Vector2<f32>,
+        /// Thickness of the line in absolute units.
+        thickness: f32,
+        /// Length of every dash in absolute units. Too short dashes are clamped to a small
+        /// minimal length.
+        dash_length: f32,
+        /// Length of the gap between two dashes in absolute units. Zero gap makes the line solid.
+        gap_length: f32,
+    },
     /// Solid circle primitive.
     Circle {
         /// Center of the circle in local coordinates.
@@ -92,6 +107,49 @@ fn line_thickness_vector(a: Vector2<f32>, b: Vector2<f32>, thickness: f32) -> Ve
     }
 }
 
+/// Minimal length of a dash of a dashed line in absolute units.
+const MIN_DASH_LENGTH: f32 = 0.5;
+
+/// Maximal amount of dashes of a single dashed line. Longer dashed lines get proportionally longer
+/// dashes and gaps, so the amount of generated geometry stays bounded.
+const MAX_DASH_COUNT: usize = 1024;
+
+/// Splits the line into a set of dashes and returns `(begin, end)` pairs for each dash. A line with
+/// zero (or negative) gap length is solid, so it consists of a single dash.
+fn dashes(
+    begin: Vector2<f32>,
+    end: Vector2<f32>,
+    dash_length: f32,
+    gap_length: f32,
+) -> impl Iterator<Item = (Vector2<f32>, Vector2<f32>)> {
+    let length = (end - begin).norm();
+    let dir = (end - begin)
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_default();
+    let (mut dash_length, mut step) = if gap_length > 0.0 {
+        let dash_length = dash_length.max(MIN_DASH_LENGTH);
+        (dash_length, dash_length + gap_length)
+    } else {
+        (length, length)
+    };
+    let mut count = if length > 0.0 {
+        (length / step).ceil() as usize
+    } else {
+        0
+    };
+    if count > MAX_DASH_COUNT {
+        let scale = length / (MAX_DASH_COUNT as f32 * step);
+        dash_length *= scale;
+        step *= scale;
+        count = MAX_DASH_COUNT;
+    }
+    (0..count).map(move |i| {
+        let dash_begin = i as f32 * step;
+        let dash_end = (dash_begin + dash_length).min(length);
+        (begin + dir.scale(dash_begin), begin + dir.scale(dash_end))
+    })
+}
+
 impl Primitive {
     /// Returns current bounds of the primitive as `min, max` tuple.
     pub fn bounds(&self) -> (Vector2<f32>, Vector2<f32>) {
@@ -109,6 +167,12 @@ impl Primitive {
                 begin,
                 end,
                 thickness,
+            }
+            | Primitive::DashedLine {
+                begin,
+                end,
+                thickness,
+                ..
             } => {
                 let tv = line_thickness_vector(*begin, *end, *thickness);
                 let mut min = begin + tv;
@@ -181,10 +245,34 @@ pub struct VectorImage {
     pub widget: Widget,
     /// Current set of primitives that will be drawn.
     pub primitives: InheritableVariable<Vec<Primitive>>,
+    /// Whether the edges of lines and triangles should be smoothed by a thin feathered fringe or not.
+    /// Anti-aliasing adds a few extra triangles per primitive, but makes thin lines (such as check marks)
+    /// look much crisper.
+    #[visit(optional)]
+    pub antialiasing: InheritableVariable<bool>,
 }
 
 crate::define_widget_deref!(VectorImage);
 
+/// Width of the feathered fringe (in local units) that is used for anti-aliased primitives.
+const FEATHER: f32 = 1.0;
+
+impl VectorImage {
+    fn push_line(
+        &self,
+        drawing_context: &mut DrawingContext,
+        begin: Vector2<f32>,
+        end: Vector2<f32>,
+        thickness: f32,
+    ) {
+        if *self.antialiasing {
+            drawing_context.push_line_antialiased(begin, end, thickness, FEATHER);
+        } else {
+            drawing_context.push_line(begin, end, thickness);
+        }
+    }
+}
+
 uuid_provider!(VectorImage = "7e535b65-0178-414e-b310-e208afc0eeb5");
 
 impl Control for VectorImage {
@@ -217,18 +305,37 @@ impl Control for VectorImage {
                         bounds.position + points[2],
                     ];
 
-                    drawing_context.push_triangle_filled(pts);
+                    if *self.antialiasing {
+                        drawing_context.push_triangle_filled_antialiased(pts, FEATHER);
+                    } else {
+                        drawing_context.push_triangle_filled(pts);
+                    }
                 }
                 Primitive::Line {
                     begin,
                     end,
                     thickness,
+                } => self.push_line(
+                    drawing_context,
+                    bounds.position + *begin,
+                    bounds.position + *end,
+                    *thickness,
+                ),
+                Primitive::DashedLine {
+                    begin,
+                    end,
+                    thickness,
+                    dash_length,
+                    gap_length,
                 } => {
-                    drawing_context.push_line(
-                        bounds.position + *begin,
-                        bounds.position + *end,
-                        *thickness,
-                    );
+                    for (dash_begin, dash_end) in dashes(*begin, *end, *dash_length, *gap_length) {
+                        self.push_line(
+                            drawing_context,
+                            bounds.position + dash_begin,
+                            bounds.position + dash_end,
+                            *thickness,
+                        );
+                    }
                 }
                 Primitive::Circle {
                     center,
@@ -274,6 +381,7 @@ impl Control for VectorImage {
 pub struct VectorImageBuilder {
     widget_builder: WidgetBuilder,
     primitives: Vec<Primitive>,
+    antialiasing: bool,
 }
 
 impl VectorImageBuilder {
@@ -282,6 +390,7 @@ impl VectorImageBuilder {
         Self {
             widget_builder,
             primitives: Default::default(),
+            antialiasing: false,
         }
     }
 
@@ -291,11 +400,19 @@ impl VectorImageBuilder {
         self
     }
 
+    /// Enables or disables anti-aliasing of lines and triangles of the image. See
+    /// [`VectorImage::antialiasing`] for more info. Disabled by default.
+    pub fn with_antialiasing(mut self, antialiasing: bool) -> Self {
+        self.antialiasing = antialiasing;
+        self
+    }
+
     /// Builds the vector image widget.
     pub fn build_node(self) -> UiNode {
         let image = VectorImage {
             widget: self.widget_builder.build(),
             primitives: self.primitives.into(),
+            antialiasing: self.antialiasing.into(),
         };
         UiNode::new(image)
     }
@@ -305,3 +422,56 @@ impl VectorImageBuilder {
         ctx.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        vector_image::{dashes, MAX_DASH_COUNT},
+    };
+
+    fn dashed_line(length: f32, dash_length: f32, gap_length: f32) -> Vec<(f32, f32)> {
+        dashes(
+            Vector2::new(0.0, 0.0),
+            Vector2::new(length, 0.0),
+            dash_length,
+            gap_length,
+        )
+        .map(|(begin, end)| (begin.x, end.x))
+        .collect()
+    }
+
+    #[test]
+    fn test_dashed_line() {
+        assert_eq!(
+            dashed_line(10.0, 2.0, 2.0),
+            vec![(0.0, 2.0), (4.0, 6.0), (8.0, 10.0)]
+        );
+        // The last dash is clipped by the end of the line.
+        assert_eq!(dashed_line(5.0, 2.0, 1.0), vec![(0.0, 2.0), (3.0, 5.0)]);
+        assert!(dashed_line(0.0, 2.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_dashed_line_with_zero_gap() {
+        assert_eq!(dashed_line(10.0, 2.0, 0.0), vec![(0.0, 10.0)]);
+        assert_eq!(dashed_line(10.0, 0.0, 0.0), vec![(0.0, 10.0)]);
+        assert_eq!(dashed_line(10.0, 2.0, -1.0), vec![(0.0, 10.0)]);
+    }
+
+    #[test]
+    fn test_dashed_line_with_zero_dash_length() {
+        let dashes = dashed_line(10.0, 0.0, 1.0);
+        assert!(!dashes.is_empty());
+        assert!(dashes.iter().all(|(begin, end)| end > begin));
+    }
+
+    #[test]
+    fn test_dashed_line_dash_count_is_bounded() {
+        let dashes = dashed_line(1.0e6, 0.0, f32::MIN_POSITIVE);
+        assert_eq!(dashes.len(), MAX_DASH_COUNT);
+        // The dashes still cover the whole line.
+        assert_eq!(dashes[0].0, 0.0);
+        assert!(dashes.last().unwrap().1 > 0.99e6);
+    }
+}